@@ -0,0 +1,300 @@
+use std::collections::{BTreeSet, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use atlaspack_core::asset_graph::{AssetGraph, AssetGraphNode};
+use atlaspack_core::bundle_graph::BundleGraph;
+use petgraph::Direction;
+use serde::Serialize;
+
+/// The entries, targets and bundles that depend on a set of changed files
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AffectedEntries {
+  pub entries: Vec<AffectedEntry>,
+  /// Names of the targets of the affected entries
+  pub targets: Vec<String>,
+  pub bundles: Vec<AffectedBundle>,
+  /// Changed files that are assets within the asset graph
+  pub changed_assets: Vec<PathBuf>,
+  /// Changed files that are not assets within the asset graph
+  ///
+  /// These may still affect the build, e.g. config files, lockfiles and files that were removed,
+  /// so callers can decide whether to treat them as affecting every entry.
+  ///
+  pub unmatched_files: Vec<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq, PartialOrd, Eq, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AffectedEntry {
+  /// The entry path relative to the project root
+  pub entry: String,
+  pub target: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AffectedBundle {
+  pub id: String,
+  pub name: Option<String>,
+  pub target: String,
+}
+
+/// Finds the entries that transitively depend on the changed files, and the bundles that contain
+/// them
+///
+/// Changed paths may be directories, such as `node_modules` packages whose resolution changed, in
+/// which case every asset within the directory is considered changed.
+///
+pub fn find_affected_entries(
+  asset_graph: &AssetGraph,
+  bundle_graph: &dyn BundleGraph,
+  changed_paths: &[PathBuf],
+) -> anyhow::Result<AffectedEntries> {
+  let changed_paths = changed_paths
+    .iter()
+    .map(PathBuf::as_path)
+    .collect::<HashSet<_>>();
+
+  let mut matched_paths = HashSet::new();
+  let mut changed_asset_ids = HashSet::new();
+  let mut changed_assets = BTreeSet::new();
+  let mut queue = VecDeque::new();
+
+  for node_index in asset_graph.graph.node_indices() {
+    let Some(AssetGraphNode::Asset(asset)) = asset_graph.get_node(&asset_graph.graph[node_index])
+    else {
+      continue;
+    };
+
+    let matches = asset
+      .file_path
+      .ancestors()
+      .filter(|path| changed_paths.contains(path))
+      .collect::<Vec<_>>();
+
+    if matches.is_empty() {
+      continue;
+    }
+
+    matched_paths.extend(matches);
+
+    changed_asset_ids.insert(asset.id.clone());
+    changed_assets.insert(asset.file_path.clone());
+    queue.push_back(node_index);
+  }
+
+  // Walk the incoming edges from each changed asset up to the entry dependencies that import it
+  let mut visited = queue.iter().copied().collect::<HashSet<_>>();
+  let mut entries = BTreeSet::new();
+
+  while let Some(node_index) = queue.pop_front() {
+    if let Some(AssetGraphNode::Dependency(dependency)) =
+      asset_graph.get_node(&asset_graph.graph[node_index])
+      && dependency.is_entry
+    {
+      entries.insert(AffectedEntry {
+        entry: dependency.specifier.clone(),
+        target: dependency
+          .target
+          .as_ref()
+          .map(|target| target.name.clone())
+          .unwrap_or_default(),
+      });
+    }
+
+    for parent in asset_graph
+      .graph
+      .neighbors_directed(node_index, Direction::Incoming)
+    {
+      if visited.insert(parent) {
+        queue.push_back(parent);
+      }
+    }
+  }
+
+  let mut bundles = Vec::new();
+  for bundle in bundle_graph.get_bundles() {
+    let assets = bundle_graph.get_bundle_assets(bundle)?;
+    if assets
+      .iter()
+      .any(|asset| changed_asset_ids.contains(&asset.id))
+    {
+      bundles.push(AffectedBundle {
+        id: bundle.id.clone(),
+        name: bundle.name.clone(),
+        target: bundle.target.name.clone(),
+      });
+    }
+  }
+
+  bundles.sort_by(|a, b| a.id.cmp(&b.id));
+
+  let targets = entries
+    .iter()
+    .map(|entry| entry.target.clone())
+    .collect::<BTreeSet<_>>();
+
+  let unmatched_files = changed_paths
+    .into_iter()
+    .filter(|path| !matched_paths.contains(path))
+    .map(Path::to_path_buf)
+    .collect::<BTreeSet<_>>();
+
+  Ok(AffectedEntries {
+    entries: entries.into_iter().collect(),
+    targets: targets.into_iter().collect(),
+    bundles,
+    changed_assets: changed_assets.into_iter().collect(),
+    unmatched_files: unmatched_files.into_iter().collect(),
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use atlaspack_core::bundle_graph::NativeBundleGraph;
+  use atlaspack_core::bundle_graph::native_bundle_graph::NativeBundleGraphEdgeType;
+  use atlaspack_core::types::{Asset, Dependency, Target};
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::requests::test_utils::bundle_graph::make_test_bundle;
+
+  fn add_asset(graph: &mut AssetGraph, parent: usize, file_path: &str) -> usize {
+    let asset = Asset {
+      id: file_path.to_string(),
+      file_path: PathBuf::from(file_path),
+      ..Asset::default()
+    };
+
+    let asset_node = graph.add_asset(Arc::new(asset), false);
+    graph.add_edge(&parent, &asset_node);
+    asset_node
+  }
+
+  fn add_dependency(graph: &mut AssetGraph, parent: usize, specifier: &str) -> usize {
+    let dependency = Dependency {
+      specifier: specifier.to_string(),
+      ..Dependency::default()
+    };
+
+    let dependency_node = graph.add_dependency(dependency, false);
+    graph.add_edge(&parent, &dependency_node);
+    dependency_node
+  }
+
+  fn add_entry(graph: &mut AssetGraph, entry: &str, target: &str) -> usize {
+    let target = Target {
+      name: target.to_string(),
+      ..Target::default()
+    };
+
+    let entry_node =
+      graph.add_entry_dependency(Dependency::entry(entry.to_string(), target), false);
+    graph.add_edge(&graph.root_node(), &entry_node);
+    add_asset(graph, entry_node, &format!("/repo/{entry}"))
+  }
+
+  #[test]
+  fn finds_the_entries_and_bundles_that_depend_on_changed_files() {
+    let mut graph = AssetGraph::new();
+
+    let app = add_entry(&mut graph, "apps/app/index.js", "app");
+    let admin = add_entry(&mut graph, "apps/admin/index.js", "admin");
+
+    let dependency = add_dependency(&mut graph, app, "./button");
+    let button = add_asset(&mut graph, dependency, "/repo/apps/app/button.js");
+
+    let dependency = add_dependency(&mut graph, button, "lodash");
+    add_asset(&mut graph, dependency, "/repo/node_modules/lodash/index.js");
+
+    let dependency = add_dependency(&mut graph, admin, "./table");
+    add_asset(&mut graph, dependency, "/repo/apps/admin/table.js");
+
+    let mut bundle_graph = NativeBundleGraph::from_asset_graph(&graph);
+    for (bundle_id, asset_ids) in [
+      (
+        "app",
+        vec!["/repo/apps/app/index.js", "/repo/apps/app/button.js"],
+      ),
+      ("vendor", vec!["/repo/node_modules/lodash/index.js"]),
+      (
+        "admin",
+        vec!["/repo/apps/admin/index.js", "/repo/apps/admin/table.js"],
+      ),
+    ] {
+      let bundle_node = bundle_graph.add_bundle(make_test_bundle(bundle_id, bundle_id));
+      for asset_id in asset_ids {
+        let asset_node = *bundle_graph.get_node_id_by_content_key(asset_id).unwrap();
+        bundle_graph.add_edge(
+          &bundle_node,
+          &asset_node,
+          NativeBundleGraphEdgeType::Contains,
+        );
+      }
+    }
+
+    let affected = find_affected_entries(
+      &graph,
+      &bundle_graph,
+      &[
+        PathBuf::from("/repo/node_modules/lodash"),
+        PathBuf::from("/repo/README.md"),
+      ],
+    )
+    .unwrap();
+
+    assert_eq!(
+      affected,
+      AffectedEntries {
+        entries: vec![AffectedEntry {
+          entry: String::from("apps/app/index.js"),
+          target: String::from("app"),
+        }],
+        targets: vec![String::from("app")],
+        bundles: vec![AffectedBundle {
+          id: String::from("vendor"),
+          name: Some(String::from("vendor.test")),
+          target: String::new(),
+        }],
+        changed_assets: vec![PathBuf::from("/repo/node_modules/lodash/index.js")],
+        unmatched_files: vec![PathBuf::from("/repo/README.md")],
+      }
+    );
+
+    let affected = find_affected_entries(
+      &graph,
+      &bundle_graph,
+      &[
+        PathBuf::from("/repo/apps/admin/table.js"),
+        PathBuf::from("/repo/apps/app/button.js"),
+      ],
+    )
+    .unwrap();
+
+    assert_eq!(
+      affected.entries,
+      vec![
+        AffectedEntry {
+          entry: String::from("apps/admin/index.js"),
+          target: String::from("admin"),
+        },
+        AffectedEntry {
+          entry: String::from("apps/app/index.js"),
+          target: String::from("app"),
+        },
+      ]
+    );
+    assert_eq!(
+      affected
+        .bundles
+        .iter()
+        .map(|bundle| bundle.id.as_str())
+        .collect::<Vec<_>>(),
+      vec!["admin", "app"]
+    );
+    assert_eq!(affected.unmatched_files, Vec::<PathBuf>::new());
+  }
+}
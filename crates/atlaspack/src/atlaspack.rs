@@ -1,8 +1,11 @@
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+use atlaspack_config::AtlaspackConfig;
 use atlaspack_config::atlaspack_rc_config_loader::{AtlaspackRcConfigLoader, LoadConfigOptions};
+use atlaspack_config::explain::{PluginsExplanation, PrintedConfig};
+use atlaspack_config::map::NamedPattern;
 use atlaspack_core::asset_graph::{AssetGraph, AssetGraphNode, FinalizedSymbolTracker};
 use atlaspack_core::bundle_graph::bundle_graph_from_js::{
   BundleGraphEdgeType, BundleGraphFromJs, BundleGraphNode, types::AssetNode,
@@ -10,17 +13,26 @@ use atlaspack_core::bundle_graph::bundle_graph_from_js::{
 use atlaspack_core::config_loader::ConfigLoader;
 use atlaspack_core::package_result::PackageResult;
 use atlaspack_core::plugin::{PluginContext, PluginLogger, PluginOptions};
-use atlaspack_core::types::{AtlaspackOptions, Environment, SourceField, Targets};
+use atlaspack_core::types::{
+  Asset, AtlaspackOptions, Diagnostic, Environment, SourceField, Targets,
+};
+use atlaspack_filesystem::watcher::{WatchOptions, Watcher};
 use atlaspack_filesystem::{FileSystemRef, os_file_system::OsFileSystem};
-use atlaspack_memoization_cache::{CacheHandler, CacheMode, LmdbCacheReaderWriter, StatsSnapshot};
+use atlaspack_memoization_cache::{
+  CacheGcOptions, CacheHandler, CacheMode, CacheValidationOptions, LmdbCacheReaderWriter,
+  RemoteCacheOptions, RemoteCacheReaderWriter, StatsSnapshot,
+};
 use atlaspack_package_manager::{NodePackageManager, PackageManagerRef};
 use atlaspack_packager_js::JsPackager;
 use atlaspack_plugin_rpc::{RpcFactoryRef, RpcWorkerRef};
+use atlaspack_vcs::{FailureMode, GitFallback, VCSState, get_changed_files, repository_root};
 use lmdb_js_lite::DatabaseHandle;
 use tokio::runtime::Runtime;
 use tokio::sync::RwLock;
 
+use crate::WatchEvent;
 use crate::WatchEvents;
+use crate::affected::{AffectedEntries, find_affected_entries};
 use crate::database::LmdbDatabase;
 use crate::plugins::{PluginsRef, config_plugins::ConfigPlugins};
 use crate::project_root::infer_project_root;
@@ -48,7 +60,10 @@ pub struct Atlaspack {
   pub rpc_worker: RpcWorkerRef,
   pub runtime: Runtime,
   pub config_loader: Arc<ConfigLoader>,
-  pub plugins: PluginsRef,
+  /// The loaded config and the files it was loaded from, which are reloaded when they change
+  config_state: parking_lot::Mutex<ConfigState>,
+  package_manager: PackageManagerRef,
+  plugins: parking_lot::RwLock<PluginsRef>,
   pub request_tracker: Arc<RwLock<RequestTracker>>,
   /// The bundle graph deserialised from JS. Used temporarily until we have a native
   /// bundle graph implementation. Starts empty and is populated via `load_bundle_graph`.
@@ -56,6 +71,8 @@ pub struct Atlaspack {
   /// readers (package) cheaply clone the Arc without locking.
   pub bundle_graph: parking_lot::Mutex<Arc<BundleGraphFromJs>>,
   pub debug_tools: DebugTools,
+  /// Shared with every plugin to collect the warnings they report
+  logger: PluginLogger,
 }
 
 impl Atlaspack {
@@ -111,46 +128,32 @@ impl Atlaspack {
 
     let rpc_worker = rpc.start()?;
 
-    let rc_config_loader = AtlaspackRcConfigLoader::new(Arc::clone(&fs), package_manager.clone());
-
-    let (config, _files) = rc_config_loader.load(
+    let logger = PluginLogger::default();
+    let config_state = ConfigState::load(
+      &fs,
+      &package_manager,
       &project_root,
-      LoadConfigOptions {
-        additional_reporters: vec![], // TODO
-        config: resolved_options.config.as_deref(),
-        fallback_config: resolved_options.fallback_config.as_deref(),
-      },
+      &resolved_options,
+      &logger,
     )?;
 
-    let unstable_alias = config.unstable_alias.clone();
-
     let config_loader = Arc::new(ConfigLoader {
       fs: Arc::clone(&fs),
       project_root: project_root.clone(),
       search_path: project_root.join("index"),
+      files: Default::default(),
     });
 
-    let plugins = Arc::new(ConfigPlugins::new(
+    let plugins = create_plugins(
       rpc_worker.clone(),
-      config,
-      PluginContext {
-        config: Arc::clone(&config_loader),
-        file_system: fs.clone(),
-        options: Arc::new(PluginOptions {
-          core_path: resolved_options.core_path.clone(),
-          env: resolved_options.env.clone(),
-          log_level: resolved_options.log_level.clone(),
-          mode: resolved_options.mode.clone(),
-          project_root: project_root.clone(),
-          feature_flags: resolved_options.feature_flags.clone(),
-          hmr_options: resolved_options.hmr_options.clone(),
-          unstable_alias,
-        }),
-        // TODO Initialise actual logger
-        logger: PluginLogger::default(),
-      },
-      package_manager,
-    )?);
+      config_state.config.clone(),
+      &config_state.plugin_config,
+      &fs,
+      &project_root,
+      &resolved_options,
+      &package_manager,
+      &logger,
+    )?;
 
     let cache_mode = if resolved_options.feature_flags.bool_enabled("v3Caching") {
       // Validate 1 in 1000 cache requests
@@ -159,6 +162,28 @@ impl Atlaspack {
       CacheMode::Off
     };
 
+    let explain_misses =
+      std::env::var("ATLASPACK_EXPLAIN_CACHE_MISSES").is_ok_and(|value| value == "true");
+    let validation_options = CacheValidationOptions::from_env();
+
+    let local_cache =
+      LmdbCacheReaderWriter::with_gc_options(db.clone(), CacheGcOptions::from_env());
+    let cache = match RemoteCacheOptions::from_env() {
+      Some(remote_options) if !matches!(cache_mode, CacheMode::Off) => DynCacheHandler::Remote(
+        CacheHandler::new(
+          RemoteCacheReaderWriter::http(local_cache, &remote_options)?,
+          cache_mode,
+        )
+        .with_miss_explanations(explain_misses)
+        .with_validation_options(validation_options),
+      ),
+      _ => DynCacheHandler::Lmdb(
+        CacheHandler::new(local_cache, cache_mode)
+          .with_miss_explanations(explain_misses)
+          .with_validation_options(validation_options),
+      ),
+    };
+
     let request_tracker = RequestTracker::new(
       Arc::new(LmdbDatabase(db.clone())),
       config_loader.clone(),
@@ -166,10 +191,7 @@ impl Atlaspack {
       Arc::new(resolved_options.clone()),
       plugins.clone(),
       project_root.clone(),
-      Arc::new(DynCacheHandler::Lmdb(CacheHandler::new(
-        LmdbCacheReaderWriter::new(db.clone()),
-        cache_mode,
-      ))),
+      Arc::new(cache),
       None,
     );
 
@@ -184,14 +206,143 @@ impl Atlaspack {
       rpc_worker,
       runtime,
       config_loader,
-      plugins,
+      config_state: parking_lot::Mutex::new(config_state),
+      package_manager,
+      plugins: parking_lot::RwLock::new(plugins),
       request_tracker: Arc::new(RwLock::new(request_tracker)),
       bundle_graph: parking_lot::Mutex::new(Arc::new(BundleGraphFromJs::default())),
       debug_tools,
+      logger,
     })
   }
 }
 
+/// The Atlaspack config, along with every file it was loaded from
+///
+/// The files are the `.parcelrc`, every config it `extends`, and the project `package.json`
+/// that native plugins read their config from when they are created.
+struct ConfigState {
+  config: AtlaspackConfig,
+  files: HashSet<PathBuf>,
+  /// The config loader given to plugins, which tracks the config files they look up
+  plugin_config: Arc<ConfigLoader>,
+}
+
+impl ConfigState {
+  fn load(
+    fs: &FileSystemRef,
+    package_manager: &PackageManagerRef,
+    project_root: &Path,
+    options: &AtlaspackOptions,
+    logger: &PluginLogger,
+  ) -> anyhow::Result<Self> {
+    let rc_config_loader = AtlaspackRcConfigLoader::new(Arc::clone(fs), package_manager.clone());
+
+    let (config, files, warnings) = rc_config_loader.load(
+      project_root,
+      LoadConfigOptions {
+        additional_reporters: vec![], // TODO
+        config: options.config.as_deref(),
+        fallback_config: options.fallback_config.as_deref(),
+      },
+    )?;
+
+    for warning in warnings {
+      logger.warn(warning);
+    }
+
+    let mut files = HashSet::from_iter(files);
+    files.insert(project_root.join("package.json"));
+
+    let plugin_config = Arc::new(ConfigLoader {
+      fs: Arc::clone(fs),
+      project_root: project_root.to_path_buf(),
+      search_path: project_root.join("index"),
+      files: Default::default(),
+    });
+
+    Ok(Self {
+      config,
+      files,
+      plugin_config,
+    })
+  }
+
+  /// Whether the config, or the config that plugins read, was loaded from the file
+  fn depends_on(&self, file_path: &Path) -> bool {
+    self.files.contains(file_path) || self.plugin_config.files.lock().contains(file_path)
+  }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_plugins(
+  rpc_worker: RpcWorkerRef,
+  config: AtlaspackConfig,
+  config_loader: &Arc<ConfigLoader>,
+  fs: &FileSystemRef,
+  project_root: &Path,
+  options: &AtlaspackOptions,
+  package_manager: &PackageManagerRef,
+  logger: &PluginLogger,
+) -> anyhow::Result<PluginsRef> {
+  let unstable_alias = config.unstable_alias.clone();
+
+  Ok(Arc::new(ConfigPlugins::new(
+    rpc_worker,
+    config,
+    PluginContext {
+      config: Arc::clone(config_loader),
+      file_system: fs.clone(),
+      options: Arc::new(PluginOptions {
+        core_path: options.core_path.clone(),
+        env: options.env.clone(),
+        log_level: options.log_level.clone(),
+        mode: options.mode.clone(),
+        project_root: project_root.to_path_buf(),
+        feature_flags: options.feature_flags.clone(),
+        hmr_options: options.hmr_options.clone(),
+        unstable_alias,
+      }),
+      logger: logger.clone(),
+    },
+    package_manager.clone(),
+  )?))
+}
+
+/// Converts an error from reloading the config into a diagnostic, keeping the code frames of
+/// config errors
+fn reload_error_diagnostic(file_path: &Path, error: anyhow::Error) -> Diagnostic {
+  match error.downcast::<Diagnostic>() {
+    Ok(diagnostic) => diagnostic,
+    Err(error) => atlaspack_core::diagnostic!(
+      "Failed to reload config after {} changed: {error}",
+      file_path.display()
+    ),
+  }
+}
+
+/// Whether the transformers that run on an asset differ between two configs
+///
+/// Both the source path and the path with the final asset type are checked, as a change in
+/// type runs the pipeline that matches the new type.
+fn transformers_changed(prev: &AtlaspackConfig, next: &AtlaspackConfig, asset: &Asset) -> bool {
+  let named_pattern = || {
+    asset.pipeline.as_deref().map(|pipeline| NamedPattern {
+      pipeline,
+      use_fallback: false,
+    })
+  };
+
+  [
+    asset.file_path.clone(),
+    asset.file_path.with_extension(asset.file_type.extension()),
+  ]
+  .iter()
+  .any(|path| {
+    prev.transformers.get(path, named_pattern()) != next.transformers.get(path, named_pattern())
+  })
+}
+
 impl Atlaspack {
   pub fn build_asset_graph(
     &self,
@@ -205,7 +356,7 @@ impl Atlaspack {
   ) -> anyhow::Result<(Option<FinalizedSymbolTracker>, Arc<AssetGraph>, bool)> {
     self.runtime.block_on(async move {
       // Notify all resolver plugins that a new build is starting
-      for resolver in self.plugins.resolvers()? {
+      for resolver in self.plugins().resolvers()? {
         resolver.on_new_build();
       }
 
@@ -356,6 +507,11 @@ impl Atlaspack {
     Ok((asset_graph, bundle_delta, had_previous_graph))
   }
 
+  /// Removes and returns the warnings that plugins have reported since the last call
+  pub fn take_warnings(&self) -> Vec<Diagnostic> {
+    self.logger.take_warnings()
+  }
+
   #[tracing::instrument(level = "info", skip_all)]
   pub fn build(&self) -> anyhow::Result<BuildRequestOutput> {
     self.build_with_report_fn(None)
@@ -404,18 +560,162 @@ impl Atlaspack {
     packager.package(&bundle_id)
   }
 
+  pub fn plugins(&self) -> PluginsRef {
+    self.plugins.read().clone()
+  }
+
+  /// Builds the bundle graph and finds the entries, targets and bundles affected by the changes
+  /// since a git revision, including uncommitted changes
+  ///
+  /// Changed yarn.lock resolutions are expanded into the `node_modules` packages that they
+  /// install, so the dependencies must be installed for them to be matched to assets.
+  ///
+  pub fn affected_entries(
+    &self,
+    since: &str,
+    git_fallback: GitFallback,
+  ) -> anyhow::Result<AffectedEntries> {
+    let repo_root = repository_root(&self.project_root)?;
+    let changes = get_changed_files(
+      &repo_root,
+      &VCSState::from_git_hash(since.to_string()),
+      None,
+      FailureMode::IgnoreMissingNodeModules,
+      git_fallback,
+    )?;
+
+    let changed_paths = changes
+      .iter()
+      .map(|change| change.path().to_path_buf())
+      .collect::<Vec<_>>();
+
+    let (asset_graph, bundle_graph, _had_previous_graph) = self.build_bundle_graph()?;
+
+    find_affected_entries(
+      &asset_graph,
+      bundle_graph.bundle_graph.as_ref(),
+      &changed_paths,
+    )
+  }
+
+  /// Resolves the plugins from the loaded config that apply to a file path and named pipeline
+  pub fn explain_plugins(&self, file_path: &Path, pipeline: Option<&str>) -> PluginsExplanation {
+    self.config_state.lock().config.explain(file_path, pipeline)
+  }
+
+  /// Returns the fully merged config, with the location that each plugin was configured at
+  pub fn print_config(&self) -> PrintedConfig {
+    self.config_state.lock().config.print(&self.fs)
+  }
+
+  /// Watches the project for changes, which can be passed to `respond_to_fs_events`
+  ///
+  /// The default dist directory is always ignored, in addition to the given globs, so that writing
+  /// the output does not trigger another build.
+  ///
+  pub fn watch(&self, ignore: Vec<String>) -> anyhow::Result<Box<dyn Watcher>> {
+    let mut options = WatchOptions::default();
+
+    options.ignore.extend(
+      self
+        .options
+        .default_target_options
+        .dist_dir
+        .iter()
+        .map(|dist_dir| dist_dir.to_string_lossy().into_owned()),
+    );
+    options.ignore.extend(ignore);
+
+    Ok(self.fs.watch(&self.project_root, options)?)
+  }
+
   pub fn respond_to_fs_events(&self, events: WatchEvents) -> anyhow::Result<bool> {
+    let changed_config_file = {
+      let config_state = self.config_state.lock();
+
+      events.iter().find_map(|event| match event {
+        WatchEvent::Create(file_path)
+        | WatchEvent::Delete(file_path)
+        | WatchEvent::Update(file_path) => config_state
+          .depends_on(file_path)
+          .then(|| file_path.clone()),
+      })
+    };
+
     self.runtime.block_on(async move {
-      Ok(
-        self
-          .request_tracker
-          .write()
-          .await
-          .respond_to_fs_events(events),
-      )
+      let mut request_tracker = self.request_tracker.write().await;
+
+      // A config that fails to load is reported by the next build, which keeps the previous
+      // config until it is fixed, so that the other changes are still handled
+      let reloaded = match changed_config_file {
+        Some(file_path) => self
+          .reload_config(&mut request_tracker, &file_path)
+          .unwrap_or_else(|error| {
+            self.logger.warn(reload_error_diagnostic(&file_path, error));
+            true
+          }),
+        None => false,
+      };
+
+      Ok(request_tracker.respond_to_fs_events(events) || reloaded)
     })
   }
 
+  /// Reloads the config and recreates every plugin, then invalidates the requests that ran
+  /// with plugins that are no longer configured for them. Returns whether a rebuild is needed.
+  ///
+  /// When a file other than a `.parcelrc` changes, such as the `package.json`, plugins may have
+  /// read different config when they were created, so every asset is transformed again.
+  fn reload_config(
+    &self,
+    request_tracker: &mut RequestTracker,
+    file_path: &PathBuf,
+  ) -> anyhow::Result<bool> {
+    tracing::info!("{} changed, reloading config", file_path.display());
+
+    let next = ConfigState::load(
+      &self.fs,
+      &self.package_manager,
+      &self.project_root,
+      &self.options,
+      &self.logger,
+    )?;
+
+    // Plugins are created from scratch, which also clears the plugin cache
+    let plugins = create_plugins(
+      self.rpc_worker.clone(),
+      next.config.clone(),
+      &next.plugin_config,
+      &self.fs,
+      &self.project_root,
+      &self.options,
+      &self.package_manager,
+      &self.logger,
+    )?;
+
+    let mut config_state = self.config_state.lock();
+    let prev = &config_state.config;
+    let is_package_json = file_path
+      .file_name()
+      .is_some_and(|name| name == "package.json");
+    let config_changed = *prev != next.config;
+    let resolvers_changed = prev.resolvers != next.config.resolvers;
+
+    let invalidated = request_tracker.invalidate_requests(file_path, |result| match result {
+      RequestResult::Asset(output) => {
+        is_package_json || transformers_changed(prev, &next.config, &output.asset)
+      }
+      RequestResult::Path(_) => resolvers_changed,
+      _ => false,
+    });
+
+    request_tracker.set_plugins(plugins.clone());
+    *self.plugins.write() = plugins;
+    *config_state = next;
+
+    Ok(invalidated || config_changed)
+  }
+
   /// Get cache statistics
   pub async fn complete_cache_session(&self) -> Option<StatsSnapshot> {
     match self.request_tracker.read().await.cache.complete_session() {
@@ -462,9 +762,13 @@ impl Atlaspack {
 mod tests {
   use std::env::temp_dir;
 
-  use atlaspack_core::types::{Asset, Code};
+  use atlaspack_config::PluginNode;
+  use atlaspack_config::atlaspack_config_fixtures::default_config;
+  use atlaspack_config::map::NamedPipelinesMap;
+  use atlaspack_core::types::{Code, FileType};
   use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
   use atlaspack_plugin_rpc::{MockRpcFactory, MockRpcWorker};
+  use indexmap::indexmap;
   use lmdb_js_lite::{LMDBOptions, get_database};
 
   use super::*;
@@ -472,7 +776,7 @@ mod tests {
   #[test]
   fn build_asset_graph_commits_assets_to_lmdb() -> Result<(), anyhow::Error> {
     // TODO: Create overlay fs for integration test
-    let db = create_db()?;
+    let db = create_db("asset-graph-tests")?;
     let fs = InMemoryFileSystem::default();
 
     fs.write_file(
@@ -519,8 +823,202 @@ mod tests {
     Ok(())
   }
 
-  fn create_db() -> anyhow::Result<Arc<DatabaseHandle>> {
-    let path = temp_dir().join("atlaspack").join("asset-graph-tests");
+  #[test]
+  fn reloads_config_when_it_changes() -> Result<(), anyhow::Error> {
+    let fs = Arc::new(InMemoryFileSystem::default());
+    let config = |transformers: &str| {
+      format!(
+        r#"
+        {{
+          "bundler": "",
+          "namers": [""],
+          "resolvers": [""],
+          "transformers": {{ {transformers} }}
+        }}
+      "#
+      )
+    };
+
+    fs.write_file(&PathBuf::from("/.parcelrc"), config(r#""*.js": [""]"#));
+
+    let atlaspack = Atlaspack::new(AtlaspackInitOptions {
+      db: create_db("config-reload-tests")?,
+      fs: Some(fs.clone()),
+      options: AtlaspackOptions::default(),
+      package_manager: None,
+      rpc: rpc(),
+    })?;
+
+    assert_eq!(atlaspack.plugins().named_pipelines(), Vec::<String>::new());
+
+    // Changes to unrelated files do not reload the config
+    fs.write_file(
+      &PathBuf::from("/.parcelrc"),
+      config(r#""types:*.ts": [""]"#),
+    );
+    assert!(!atlaspack.respond_to_fs_events(vec![WatchEvent::Update(PathBuf::from("/index.js"))])?);
+    assert_eq!(atlaspack.plugins().named_pipelines(), Vec::<String>::new());
+
+    assert!(atlaspack.respond_to_fs_events(vec![WatchEvent::Update(PathBuf::from("/.parcelrc"))])?);
+    assert_eq!(
+      atlaspack.plugins().named_pipelines(),
+      vec![String::from("types")]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn reports_config_errors_and_handles_the_other_events() -> Result<(), anyhow::Error> {
+    let fs = Arc::new(InMemoryFileSystem::default());
+    let config = r#"{ "bundler": "", "namers": [""], "resolvers": [""] }"#;
+
+    fs.write_file(&PathBuf::from("/.parcelrc"), String::from(config));
+
+    let atlaspack = Atlaspack::new(AtlaspackInitOptions {
+      db: create_db("config-reload-error-tests")?,
+      fs: Some(fs.clone()),
+      options: AtlaspackOptions::default(),
+      package_manager: None,
+      rpc: rpc(),
+    })?;
+
+    fs.write_file(&PathBuf::from("/.parcelrc"), String::from("{"));
+    assert!(atlaspack.respond_to_fs_events(vec![
+      WatchEvent::Update(PathBuf::from("/.parcelrc")),
+      WatchEvent::Update(PathBuf::from("/index.js")),
+    ])?);
+
+    assert_eq!(atlaspack.take_warnings().len(), 1);
+
+    // The previous config is kept, and is reloaded once the file is fixed
+    fs.write_file(&PathBuf::from("/.parcelrc"), String::from(config));
+    atlaspack.respond_to_fs_events(vec![WatchEvent::Update(PathBuf::from("/.parcelrc"))])?;
+    assert!(atlaspack.take_warnings().is_empty());
+
+    Ok(())
+  }
+
+  #[test]
+  fn reloads_config_when_a_file_read_by_plugins_is_created() -> Result<(), anyhow::Error> {
+    let fs = Arc::new(InMemoryFileSystem::default());
+
+    fs.write_file(
+      &PathBuf::from("/.parcelrc"),
+      String::from(r#"{ "bundler": "", "namers": [""], "resolvers": [""] }"#),
+    );
+
+    let atlaspack = Atlaspack::new(AtlaspackInitOptions {
+      db: create_db("config-reload-plugin-config-tests")?,
+      fs: Some(fs.clone()),
+      options: AtlaspackOptions::default(),
+      package_manager: None,
+      rpc: rpc(),
+    })?;
+
+    let plugin_config = Arc::clone(&atlaspack.config_state.lock().plugin_config);
+    assert!(
+      plugin_config
+        .load_json_config::<serde_json::Value>(".postcssrc")
+        .is_err()
+    );
+
+    fs.write_file(&PathBuf::from("/.postcssrc"), String::from("{}"));
+    assert!(
+      atlaspack.respond_to_fs_events(vec![WatchEvent::Create(PathBuf::from("/.postcssrc"))])?
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn explains_plugins_from_the_loaded_config() -> Result<(), anyhow::Error> {
+    let fs = Arc::new(InMemoryFileSystem::default());
+
+    fs.write_file(
+      &PathBuf::from("/.parcelrc"),
+      String::from(
+        r#"
+        {
+          "bundler": "@atlaspack/bundler-default",
+          "namers": ["@atlaspack/namer-default"],
+          "resolvers": ["@atlaspack/resolver-default"],
+          "transformers": {
+            "*.ts": ["@atlaspack/transformer-js"]
+          }
+        }
+      "#,
+      ),
+    );
+
+    let atlaspack = Atlaspack::new(AtlaspackInitOptions {
+      db: create_db("config-explain-tests")?,
+      fs: Some(fs.clone()),
+      options: AtlaspackOptions::default(),
+      package_manager: None,
+      rpc: rpc(),
+    })?;
+
+    let explanation = atlaspack.explain_plugins(Path::new("/index.ts"), None);
+
+    assert_eq!(
+      explanation
+        .transformers
+        .iter()
+        .map(|plugin| (
+          plugin.package_name.as_str(),
+          plugin.config_file.clone(),
+          plugin.pattern.as_deref()
+        ))
+        .collect::<Vec<_>>(),
+      vec![(
+        "@atlaspack/transformer-js",
+        PathBuf::from("/.parcelrc"),
+        Some("*.ts")
+      )]
+    );
+
+    let printed = atlaspack.print_config();
+
+    assert_eq!(
+      printed.transformers[0]
+        .loc
+        .as_ref()
+        .map(|loc| loc.start.line),
+      Some(7)
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn transformers_changed_compares_asset_pipelines() {
+    let config = |pattern: &str, transformer: &str| {
+      let mut config = default_config(Arc::new(PathBuf::default())).atlaspack_config;
+      config.transformers = NamedPipelinesMap::new(indexmap! {
+        String::from(pattern) => vec![PluginNode {
+          package_name: String::from(transformer),
+          resolve_from: Arc::new(PathBuf::default()),
+        }]
+      });
+      config
+    };
+
+    let asset = Asset {
+      file_path: PathBuf::from("index.ts"),
+      file_type: FileType::Js,
+      ..Asset::default()
+    };
+
+    let prev = config("*.ts", "a");
+
+    assert!(!transformers_changed(&prev, &config("*.ts", "a"), &asset));
+    assert!(transformers_changed(&prev, &config("*.ts", "b"), &asset));
+    assert!(transformers_changed(&prev, &config("*.js", "a"), &asset));
+  }
+
+  fn create_db(name: &str) -> anyhow::Result<Arc<DatabaseHandle>> {
+    let path = temp_dir().join("atlaspack").join(name);
     let _ = std::fs::remove_dir_all(&path);
 
     let lmdb = get_database(LMDBOptions {
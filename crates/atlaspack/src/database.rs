@@ -27,4 +27,13 @@ impl Database for LmdbDatabase {
     txn.commit()?;
     Ok(())
   }
+
+  fn put_many(&self, entries: &[(String, Vec<u8>)]) -> anyhow::Result<()> {
+    let mut txn = self.0.database().write_txn()?;
+    for (key, value) in entries {
+      self.0.database().put(&mut txn, key, value)?;
+    }
+    txn.commit()?;
+    Ok(())
+  }
 }
@@ -1,7 +1,10 @@
+pub use affected::*;
 pub use atlaspack::*;
 pub use atlaspack_filesystem as file_system;
 pub use atlaspack_plugin_rpc as rpc;
+pub use atlaspack_vcs::GitFallback;
 pub use error::*;
+pub use project_root::infer_project_root;
 pub use request_tracker::ReportFn;
 pub use watch::*;
 
@@ -10,6 +13,7 @@ pub(crate) mod cache;
 pub mod database;
 pub(crate) mod request_tracker;
 
+mod affected;
 mod error;
 mod plugins;
 mod project_root;
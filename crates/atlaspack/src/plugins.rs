@@ -1,11 +1,19 @@
 use std::fmt::Debug;
 use std::hash::{Hash, Hasher};
+use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use atlaspack_core::plugin::CacheStatus;
+use atlaspack_core::plugin::CompressorPlugin;
+use atlaspack_core::plugin::NamerPlugin;
+use atlaspack_core::plugin::OptimizerPlugin;
+use atlaspack_core::plugin::PackagerPlugin;
+use atlaspack_core::plugin::ReporterPlugin;
 use atlaspack_core::plugin::ResolverPlugin;
+use atlaspack_core::plugin::RuntimePlugin;
 use atlaspack_core::plugin::TransformerPlugin;
+use atlaspack_core::plugin::ValidatorPlugin;
 use atlaspack_core::types::Asset;
 #[cfg(test)]
 use mockall::automock;
@@ -18,9 +26,20 @@ pub mod plugin_cache;
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait Plugins {
+  fn compressors(&self, path: &Path) -> Result<Vec<Arc<dyn CompressorPlugin>>, anyhow::Error>;
   fn named_pipelines(&self) -> Vec<String>;
+  fn namers(&self) -> Result<Vec<Arc<dyn NamerPlugin>>, anyhow::Error>;
+  fn optimizers(
+    &self,
+    path: &Path,
+    pipeline: Option<String>,
+  ) -> Result<Vec<Arc<dyn OptimizerPlugin>>, anyhow::Error>;
+  fn packager(&self, path: &Path) -> Result<Arc<dyn PackagerPlugin>, anyhow::Error>;
+  fn reporters(&self) -> Result<Vec<Arc<dyn ReporterPlugin>>, anyhow::Error>;
   fn resolvers(&self) -> Result<Vec<Arc<dyn ResolverPlugin>>, anyhow::Error>;
+  fn runtimes(&self) -> Result<Vec<Arc<dyn RuntimePlugin>>, anyhow::Error>;
   async fn transformers(&self, asset: &Asset) -> Result<TransformerPipeline, anyhow::Error>;
+  fn validators(&self, path: &Path) -> Result<Vec<Arc<dyn ValidatorPlugin>>, anyhow::Error>;
 }
 
 pub struct TransformerPipeline {
@@ -3,13 +3,23 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use atlaspack_config::AtlaspackConfig;
+use atlaspack_config::PluginNode;
 use atlaspack_config::map::NamedPattern;
 use atlaspack_core::diagnostic_error;
+use atlaspack_core::plugin::CompressorPlugin;
+use atlaspack_core::plugin::NamerPlugin;
+use atlaspack_core::plugin::OptimizerPlugin;
+use atlaspack_core::plugin::PackagerPlugin;
 use atlaspack_core::plugin::PluginContext;
+use atlaspack_core::plugin::ReporterPlugin;
 use atlaspack_core::plugin::ResolverPlugin;
+use atlaspack_core::plugin::RuntimePlugin;
 use atlaspack_core::plugin::TransformerPlugin;
+use atlaspack_core::plugin::ValidatorPlugin;
 use atlaspack_core::types::Asset;
 use atlaspack_package_manager::PackageManagerRef;
+use atlaspack_plugin_compressor_raw::AtlaspackRawCompressorPlugin;
+use atlaspack_plugin_optimizer_html::AtlaspackHtmlOptimizerPlugin;
 use atlaspack_plugin_resolver::AtlaspackResolver;
 use atlaspack_plugin_rpc::RpcWorkerRef;
 use atlaspack_plugin_transformer_css::AtlaspackCssTransformerPlugin;
@@ -19,15 +29,21 @@ use atlaspack_plugin_transformer_inline::AtlaspackInlineTransformerPlugin;
 use atlaspack_plugin_transformer_inline_string::AtlaspackInlineStringTransformerPlugin;
 use atlaspack_plugin_transformer_js::AtlaspackJsTransformerPlugin;
 use atlaspack_plugin_transformer_json::AtlaspackJsonTransformerPlugin;
+use atlaspack_plugin_transformer_postcss::AtlaspackPostcssTransformerPlugin;
 use atlaspack_plugin_transformer_raw::AtlaspackRawTransformerPlugin;
 use atlaspack_plugin_transformer_svg::AtlaspackSvgTransformerPlugin;
 use atlaspack_plugin_transformer_tokens::AtlaspackTokensTransformerPlugin;
+use atlaspack_plugin_transformer_webmanifest::AtlaspackWebManifestTransformerPlugin;
 use atlaspack_plugin_transformer_yaml::AtlaspackYamlTransformerPlugin;
 
 use super::Plugins;
 use super::TransformerPipeline;
 use super::plugin_cache::PluginCache;
 
+/// Optimizers in the default config that run natively as `AtlaspackHtmlOptimizerPlugin`
+const NATIVE_MARKUP_OPTIMIZERS: &[&str] =
+  &["@atlaspack/optimizer-htmlnano", "@atlaspack/optimizer-svgo"];
+
 /// Loads plugins based on the Atlaspack config
 pub struct ConfigPlugins {
   rpc_worker: RpcWorkerRef,
@@ -64,6 +80,39 @@ impl ConfigPlugins {
     diagnostic_error!("No {phase} found for path {}", path.display())
   }
 
+  fn optimizer(&self, optimizer: &PluginNode) -> anyhow::Result<Arc<dyn OptimizerPlugin>> {
+    // The default HTML and SVG optimizers are replaced by the native markup optimizer
+    if !NATIVE_MARKUP_OPTIMIZERS.contains(&optimizer.package_name.as_str()) {
+      return self
+        .plugin_cache
+        .optimizers
+        .get_or_init(&optimizer.package_name, || {
+          self.rpc_worker.create_optimizer(&self.ctx, optimizer)
+        });
+    }
+
+    // Inline scripts are minified by the JS optimizers. These are created before the HTML
+    // optimizer, as the plugin store is locked while a plugin is being created.
+    let js_optimizers = self
+      .config
+      .optimizers
+      .get(Path::new("index.js"), None)
+      .iter()
+      .filter(|js_optimizer| js_optimizer.package_name != optimizer.package_name)
+      .map(|js_optimizer| self.optimizer(js_optimizer))
+      .collect::<anyhow::Result<Vec<_>>>()?;
+
+    self
+      .plugin_cache
+      .optimizers
+      .get_or_init(&optimizer.package_name, || {
+        Ok(Arc::new(AtlaspackHtmlOptimizerPlugin::new(
+          &self.ctx,
+          js_optimizers,
+        )?))
+      })
+  }
+
   fn missing_pipeline_plugin(&self, path: &Path, phase: &str, pipeline: &str) -> anyhow::Error {
     diagnostic_error!(
       "No {phase} found for path {} with pipeline {pipeline}",
@@ -74,10 +123,117 @@ impl ConfigPlugins {
 
 #[async_trait]
 impl Plugins for ConfigPlugins {
+  fn compressors(&self, path: &Path) -> anyhow::Result<Vec<Arc<dyn CompressorPlugin>>> {
+    let compressors = self.config.compressors.get(path);
+
+    if compressors.is_empty() {
+      return Err(self.missing_plugin(path, "compressors"));
+    }
+
+    compressors
+      .iter()
+      .map(|compressor| {
+        self
+          .plugin_cache
+          .compressors
+          .get_or_init(&compressor.package_name, || {
+            if compressor.package_name == "@atlaspack/compressor-raw" {
+              return Ok(Arc::new(AtlaspackRawCompressorPlugin::new(&self.ctx)));
+            }
+
+            self.rpc_worker.create_compressor(&self.ctx, compressor)
+          })
+      })
+      .collect()
+  }
+
   fn named_pipelines(&self) -> Vec<String> {
     self.config.transformers.named_pipelines()
   }
 
+  fn namers(&self) -> anyhow::Result<Vec<Arc<dyn NamerPlugin>>> {
+    self
+      .config
+      .namers
+      .iter()
+      .map(|namer| {
+        self
+          .plugin_cache
+          .namers
+          .get_or_init(&namer.package_name, || {
+            self.rpc_worker.create_namer(&self.ctx, namer)
+          })
+      })
+      .collect()
+  }
+
+  fn optimizers(
+    &self,
+    path: &Path,
+    pipeline: Option<String>,
+  ) -> anyhow::Result<Vec<Arc<dyn OptimizerPlugin>>> {
+    let named_pattern = pipeline.as_deref().map(|pipeline| NamedPattern {
+      pipeline,
+      use_fallback: true,
+    });
+
+    // Bundles do not need to be optimized, so no optimizers is not an error
+    self
+      .config
+      .optimizers
+      .get(path, named_pattern)
+      .iter()
+      .map(|optimizer| self.optimizer(optimizer))
+      .collect()
+  }
+
+  fn packager(&self, path: &Path) -> anyhow::Result<Arc<dyn PackagerPlugin>> {
+    let packager = self
+      .config
+      .packagers
+      .get(path)
+      .ok_or_else(|| self.missing_plugin(path, "packager"))?;
+
+    self
+      .plugin_cache
+      .packagers
+      .get_or_init(&packager.package_name, || {
+        self.rpc_worker.create_packager(&self.ctx, packager)
+      })
+  }
+
+  fn reporters(&self) -> anyhow::Result<Vec<Arc<dyn ReporterPlugin>>> {
+    self
+      .config
+      .reporters
+      .iter()
+      .map(|reporter| {
+        self
+          .plugin_cache
+          .reporters
+          .get_or_init(&reporter.package_name, || {
+            self.rpc_worker.create_reporter(&self.ctx, reporter)
+          })
+      })
+      .collect()
+  }
+
+  fn runtimes(&self) -> anyhow::Result<Vec<Arc<dyn RuntimePlugin>>> {
+    self
+      .config
+      .runtimes
+      .iter()
+      .map(|runtime| {
+        self
+          .plugin_cache
+          .runtimes
+          .get_or_init(&runtime.package_name, || {
+            self.rpc_worker.create_runtime(&self.ctx, runtime)
+          })
+      })
+      .collect()
+  }
+
   fn resolvers(&self) -> anyhow::Result<Vec<Arc<dyn ResolverPlugin>>> {
     self.plugin_cache.get_or_init_resolvers(move || {
       let mut resolvers: Vec<Arc<dyn ResolverPlugin>> = Vec::new();
@@ -119,7 +275,6 @@ impl Plugins for ConfigPlugins {
         // before releasing native asset graph
         "@atlaspack/transformer-react-refresh-wrap" => continue,
         "@atlaspack/transformer-posthtml" => continue,
-        "@atlaspack/transformer-postcss" => continue,
         _ => {}
       }
 
@@ -133,6 +288,10 @@ impl Plugins for ConfigPlugins {
             "@atlaspack/transformer-css" => {
               Arc::new(AtlaspackCssTransformerPlugin::new(&self.ctx)?) as Arc<dyn TransformerPlugin>
             }
+            "@atlaspack/transformer-postcss" => {
+              Arc::new(AtlaspackPostcssTransformerPlugin::new(&self.ctx)?)
+                as Arc<dyn TransformerPlugin>
+            }
             "@atlaspack/transformer-inline-string" => {
               Arc::new(AtlaspackInlineStringTransformerPlugin::new(&self.ctx))
                 as Arc<dyn TransformerPlugin>
@@ -164,6 +323,10 @@ impl Plugins for ConfigPlugins {
               Arc::new(AtlaspackTokensTransformerPlugin::new(&self.ctx)?)
                 as Arc<dyn TransformerPlugin>
             }
+            "@atlaspack/transformer-webmanifest" => {
+              Arc::new(AtlaspackWebManifestTransformerPlugin::new(&self.ctx))
+                as Arc<dyn TransformerPlugin>
+            }
             _ => {
               self
                 .rpc_worker
@@ -190,6 +353,23 @@ impl Plugins for ConfigPlugins {
 
     Ok(TransformerPipeline::new(transformers))
   }
+
+  fn validators(&self, path: &Path) -> anyhow::Result<Vec<Arc<dyn ValidatorPlugin>>> {
+    self
+      .config
+      .validators
+      .get(path)
+      .iter()
+      .map(|validator| {
+        self
+          .plugin_cache
+          .validators
+          .get_or_init(&validator.package_name, || {
+            self.rpc_worker.create_validator(&self.ctx, validator)
+          })
+      })
+      .collect()
+  }
 }
 
 #[cfg(test)]
@@ -207,6 +387,95 @@ mod tests {
     assert_eq!(format!("{:?}", resolvers), "[AtlaspackResolver]")
   }
 
+  #[test]
+  fn returns_packaging_plugins() {
+    let plugins = config_plugins(make_test_plugin_context());
+    let path = Path::new("index.js");
+
+    assert_eq!(
+      format!("{:?}", plugins.compressors(path).unwrap()),
+      "[AtlaspackRawCompressorPlugin]"
+    );
+    assert_eq!(
+      format!("{:?}", plugins.namers().unwrap()),
+      "[RpcNamerPlugin]"
+    );
+    assert_eq!(
+      format!("{:?}", plugins.optimizers(path, None).unwrap()),
+      "[RpcOptimizerPlugin]"
+    );
+    assert_eq!(
+      format!("{:?}", plugins.packager(path).unwrap()),
+      "RpcPackagerPlugin"
+    );
+    assert_eq!(
+      format!("{:?}", plugins.reporters().unwrap()),
+      "[RpcReporterPlugin]"
+    );
+    assert_eq!(
+      format!("{:?}", plugins.runtimes().unwrap()),
+      "[RpcRuntimePlugin]"
+    );
+    assert_eq!(format!("{:?}", plugins.validators(path).unwrap()), "[]");
+  }
+
+  #[test]
+  fn returns_the_native_html_optimizer() {
+    use atlaspack_config::atlaspack_config_fixtures::default_config;
+    use atlaspack_config::map::NamedPipelinesMap;
+    use atlaspack_package_manager::MockPackageManager;
+    use atlaspack_plugin_rpc::RpcFactory;
+    use atlaspack_plugin_rpc::testing::testing::TestingRpcFactory;
+    use indexmap::indexmap;
+    use std::path::PathBuf;
+
+    let resolve_from = Arc::new(PathBuf::default());
+    let plugin_node = |package_name: &str| PluginNode {
+      package_name: String::from(package_name),
+      resolve_from: resolve_from.clone(),
+    };
+
+    let mut config = default_config(resolve_from.clone()).atlaspack_config;
+    config.optimizers = NamedPipelinesMap::new(indexmap! {
+      String::from("*.html") => vec![plugin_node("@atlaspack/optimizer-htmlnano")],
+      String::from("*.svg") => vec![plugin_node("@atlaspack/optimizer-svgo")],
+      String::from("*.js") => vec![plugin_node("@atlaspack/optimizer-swc")],
+    });
+
+    let plugins = ConfigPlugins::new(
+      TestingRpcFactory::default().start().unwrap(),
+      config,
+      make_test_plugin_context(),
+      Arc::new(MockPackageManager::new()),
+    )
+    .unwrap();
+
+    let optimizers = format!(
+      "{:?}",
+      plugins.optimizers(Path::new("index.html"), None).unwrap()
+    );
+
+    assert!(optimizers.starts_with("[AtlaspackHtmlOptimizerPlugin {"));
+    assert!(optimizers.contains("js_optimizers: [RpcOptimizerPlugin]"));
+
+    let optimizers = format!(
+      "{:?}",
+      plugins.optimizers(Path::new("icon.svg"), None).unwrap()
+    );
+
+    assert!(optimizers.starts_with("[AtlaspackHtmlOptimizerPlugin {"));
+  }
+
+  #[test]
+  fn errors_when_no_packager_matches() {
+    let error = config_plugins(make_test_plugin_context())
+      .packager(Path::new("index.css"))
+      .err()
+      .unwrap();
+
+    assert_eq!(error.to_string(), "No packager found for path index.css");
+  }
+
   #[tokio::test]
   async fn returns_transformers() {
     use atlaspack_core::types::{Code, Environment};
@@ -1,6 +1,9 @@
 use std::{collections::HashMap, sync::Arc};
 
-use atlaspack_core::plugin::{ResolverPlugin, TransformerPlugin};
+use atlaspack_core::plugin::{
+  CompressorPlugin, NamerPlugin, OptimizerPlugin, PackagerPlugin, ReporterPlugin, ResolverPlugin,
+  RuntimePlugin, TransformerPlugin, ValidatorPlugin,
+};
 use once_cell::sync::OnceCell;
 use parking_lot::RwLock;
 use std::future::Future;
@@ -12,8 +15,54 @@ type TransformerCell = Arc<AsyncOnceCell<Arc<dyn TransformerPlugin>>>;
 /// A thread safe storage mechanism for plugin instances
 #[derive(Default)]
 pub struct PluginCache {
+  pub compressors: PluginStore<dyn CompressorPlugin>,
+  pub namers: PluginStore<dyn NamerPlugin>,
+  pub optimizers: PluginStore<dyn OptimizerPlugin>,
+  pub packagers: PluginStore<dyn PackagerPlugin>,
+  pub reporters: PluginStore<dyn ReporterPlugin>,
   resolvers_store: OnceCell<Vec<Arc<dyn ResolverPlugin>>>,
+  pub runtimes: PluginStore<dyn RuntimePlugin>,
   transformers_store: RwLock<HashMap<String, TransformerCell>>,
+  pub validators: PluginStore<dyn ValidatorPlugin>,
+}
+
+/// Plugin instances for a single phase, keyed by package name
+///
+/// Plugins for these phases are created synchronously, so they do not need the async cells that
+/// transformers use.
+pub struct PluginStore<P: ?Sized> {
+  plugins: RwLock<HashMap<String, Arc<P>>>,
+}
+
+impl<P: ?Sized> Default for PluginStore<P> {
+  fn default() -> Self {
+    Self {
+      plugins: RwLock::new(HashMap::new()),
+    }
+  }
+}
+
+impl<P: ?Sized> PluginStore<P> {
+  pub fn get_or_init<F>(&self, name: &str, f: F) -> anyhow::Result<Arc<P>>
+  where
+    F: FnOnce() -> anyhow::Result<Arc<P>>,
+  {
+    if let Some(plugin) = self.plugins.read().get(name) {
+      return Ok(plugin.clone());
+    }
+
+    let mut plugins = self.plugins.write();
+
+    // Another thread may have created the plugin while waiting for the lock
+    if let Some(plugin) = plugins.get(name) {
+      return Ok(plugin.clone());
+    }
+
+    let plugin = f()?;
+    plugins.insert(name.to_string(), plugin.clone());
+
+    Ok(plugin)
+  }
 }
 
 impl PluginCache {
@@ -65,7 +114,7 @@ impl PluginCache {
 mod tests {
   use super::*;
   use async_trait::async_trait;
-  use atlaspack_core::plugin::TransformResult;
+  use atlaspack_core::plugin::{CompressedFile, TransformResult};
   use atlaspack_core::types::Asset;
   use std::sync::Arc;
   use std::sync::atomic::{AtomicUsize, Ordering};
@@ -126,4 +175,34 @@ mod tests {
     // Verify the async function was only called once despite 10 concurrent attempts
     assert_eq!(call_count.load(Ordering::SeqCst), 1);
   }
+
+  #[derive(Debug)]
+  struct MockCompressor;
+
+  #[async_trait]
+  impl CompressorPlugin for MockCompressor {
+    async fn compress(&self, _contents: &[u8]) -> anyhow::Result<Option<CompressedFile>> {
+      Ok(None)
+    }
+  }
+
+  #[test]
+  fn test_phase_plugin_only_initialized_once() {
+    let cache = PluginCache::default();
+    let call_count = AtomicUsize::new(0);
+
+    for _ in 0..3 {
+      cache
+        .compressors
+        .get_or_init("test_compressor", || {
+          call_count.fetch_add(1, Ordering::SeqCst);
+
+          let compressor: Arc<dyn CompressorPlugin> = Arc::new(MockCompressor);
+          Ok(compressor)
+        })
+        .unwrap();
+    }
+
+    assert_eq!(call_count.load(Ordering::SeqCst), 1);
+  }
 }
@@ -3,6 +3,7 @@ pub use self::request_graph::*;
 #[allow(unused)]
 pub use self::request_tracker::*;
 
+mod read_hashes;
 mod request;
 mod request_graph;
 
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use atlaspack_filesystem::watcher::{WatchOptions, Watcher};
+use atlaspack_filesystem::{
+  DirEntry, FileSystem, FileSystemRealPathCache, FileSystemRef, Metadata,
+};
+use parking_lot::Mutex;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// The content hashes of the files a request read, keyed by the path it read them from
+pub type ReadHashes = HashMap<PathBuf, u64>;
+
+/// Records the content hash of every file that is read through it, so that the contents a request
+/// depends on are known even when the file changes before the request completes
+///
+/// Hashes use xxh3, matching [`FileSystem::content_hash`]. Everything else is passed through to
+/// the underlying file-system.
+///
+#[derive(Debug)]
+pub struct ReadHashingFileSystem {
+  base: FileSystemRef,
+  hashes: Mutex<ReadHashes>,
+}
+
+impl ReadHashingFileSystem {
+  pub fn new(base: FileSystemRef) -> Self {
+    ReadHashingFileSystem {
+      base,
+      hashes: Default::default(),
+    }
+  }
+
+  /// Returns the hashes recorded so far, leaving none behind
+  pub fn take_hashes(&self) -> ReadHashes {
+    std::mem::take(&mut *self.hashes.lock())
+  }
+
+  fn record(&self, path: &Path, contents: &[u8]) {
+    self
+      .hashes
+      .lock()
+      .insert(path.to_path_buf(), xxh3_64(contents));
+  }
+}
+
+impl FileSystem for ReadHashingFileSystem {
+  fn cwd(&self) -> std::io::Result<PathBuf> {
+    self.base.cwd()
+  }
+
+  fn canonicalize_base(&self, path: &Path) -> std::io::Result<PathBuf> {
+    self.base.canonicalize_base(path)
+  }
+
+  fn canonicalize(&self, path: &Path, cache: &FileSystemRealPathCache) -> std::io::Result<PathBuf> {
+    self.base.canonicalize(path, cache)
+  }
+
+  fn create_directory(&self, path: &Path) -> std::io::Result<()> {
+    self.base.create_directory(path)
+  }
+
+  fn create_dir_all(&self, path: &Path) -> std::io::Result<()> {
+    self.base.create_dir_all(path)
+  }
+
+  fn write(&self, path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    self.base.write(path, contents)
+  }
+
+  fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+    let contents = self.base.read(path)?;
+    self.record(path, &contents);
+    Ok(contents)
+  }
+
+  fn read_dir(&self, path: &Path) -> std::io::Result<Vec<DirEntry>> {
+    self.base.read_dir(path)
+  }
+
+  fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+    let contents = self.base.read_to_string(path)?;
+    self.record(path, contents.as_bytes());
+    Ok(contents)
+  }
+
+  fn is_file(&self, path: &Path) -> bool {
+    self.base.is_file(path)
+  }
+
+  fn is_dir(&self, path: &Path) -> bool {
+    self.base.is_dir(path)
+  }
+
+  fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+    self.base.metadata(path)
+  }
+
+  fn content_hash(&self, path: &Path) -> std::io::Result<u64> {
+    self.base.content_hash(path)
+  }
+
+  fn watch(&self, root: &Path, options: WatchOptions) -> std::io::Result<Box<dyn Watcher>> {
+    self.base.watch(root, options)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn records_the_hash_of_the_contents_that_were_read() {
+    let base = Arc::new(InMemoryFileSystem::default());
+    base.write_file(Path::new("/a.js"), String::from("a"));
+    base.write_file(Path::new("/b.js"), String::from("b"));
+
+    let fs = ReadHashingFileSystem::new(base.clone());
+    fs.read(Path::new("/a.js")).unwrap();
+    base.write_file(Path::new("/a.js"), String::from("changed"));
+    fs.read_to_string(Path::new("/b.js")).unwrap();
+    assert!(fs.read(Path::new("/missing.js")).is_err());
+
+    assert_eq!(
+      fs.take_hashes(),
+      HashMap::from([
+        (PathBuf::from("/a.js"), xxh3_64(b"a")),
+        (PathBuf::from("/b.js"), xxh3_64(b"b")),
+      ])
+    );
+    assert_eq!(fs.take_hashes(), HashMap::new());
+  }
+}
@@ -5,6 +5,7 @@ use atlaspack_memoization_cache::CacheHandler;
 use atlaspack_memoization_cache::CacheHandlerTrait;
 use atlaspack_memoization_cache::InMemoryReaderWriter;
 use atlaspack_memoization_cache::LmdbCacheReaderWriter;
+use atlaspack_memoization_cache::RemoteCacheReaderWriter;
 use std::fmt::Debug;
 use std::future::Future;
 use std::hash::Hash;
@@ -31,6 +32,7 @@ use crate::requests::RequestResult;
 #[allow(clippy::large_enum_variant)]
 pub enum DynCacheHandler {
   Lmdb(CacheHandler<LmdbCacheReaderWriter>),
+  Remote(CacheHandler<RemoteCacheReaderWriter<LmdbCacheReaderWriter>>),
   InMemory(CacheHandler<InMemoryReaderWriter>),
 }
 
@@ -38,6 +40,7 @@ impl DynCacheHandler {
   pub fn complete_session(&self) -> anyhow::Result<atlaspack_memoization_cache::StatsSnapshot> {
     match self {
       DynCacheHandler::Lmdb(cache) => cache.complete_session(),
+      DynCacheHandler::Remote(cache) => cache.complete_session(),
       DynCacheHandler::InMemory(cache) => cache.complete_session(),
     }
   }
@@ -56,6 +59,7 @@ impl DynCacheHandler {
   {
     match self {
       DynCacheHandler::Lmdb(cache) => cache.run(input, run_fn).await,
+      DynCacheHandler::Remote(cache) => cache.run(input, run_fn).await,
       DynCacheHandler::InMemory(cache) => cache.run(input, run_fn).await,
     }
   }
@@ -134,6 +138,7 @@ impl RunRequestContext {
       fs: fs.clone(),
       project_root: PathBuf::default(),
       search_path: PathBuf::default(),
+      files: Default::default(),
     });
 
     Self {
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::mpsc::Sender;
@@ -33,6 +34,7 @@ use super::RequestId;
 use super::RequestNode;
 use super::ResultAndInvalidations;
 use super::RunRequestError;
+use super::read_hashes::{ReadHashes, ReadHashingFileSystem};
 use super::{ReportFn, RunRequestContext, RunRequestMessage};
 
 /// [`RequestTracker`] runs atlaspack work items and constructs a graph of their dependencies.
@@ -56,6 +58,10 @@ pub struct RequestTracker {
   project_root: PathBuf,
   request_index: HashMap<u64, NodeIndex>,
   invalidations: HashMap<PathBuf, NodeIndex>,
+  /// The content hash of each file that requests depend on, recorded from the contents the
+  /// request read and whenever the file changes, so that updates which leave the contents
+  /// unchanged do not invalidate requests. Hashes are also written to the database.
+  content_hashes: HashMap<PathBuf, u64>,
   invalid_nodes: HashSet<NodeIndex>,
   pub cache: CacheRef,
   report_fn: Option<ReportFn>,
@@ -86,6 +92,7 @@ impl RequestTracker {
       project_root,
       request_index: HashMap::new(),
       invalidations: HashMap::new(),
+      content_hashes: HashMap::new(),
       invalid_nodes: HashSet::new(),
       options,
       cache,
@@ -159,10 +166,11 @@ impl RequestTracker {
           }
 
           // Request needs to be executed
+          let file_system = Arc::new(ReadHashingFileSystem::new(self.file_system.clone()));
           let context = RunRequestContext::new(
             self.db.clone(),
             self.config_loader.clone(),
-            self.file_system.clone(),
+            file_system.clone(),
             self.options.clone(),
             Some(request_id),
             self.plugins.clone(),
@@ -188,6 +196,7 @@ impl RequestTracker {
                 request_id,
                 parent_request_id,
                 result,
+                read_hashes: file_system.take_hashes(),
                 response_tx,
               });
             }
@@ -197,10 +206,11 @@ impl RequestTracker {
           request_id,
           parent_request_id,
           result,
+          read_hashes,
           response_tx,
         } => {
           tracing::trace!(?request_id, ?parent_request_id, "Request result");
-          let result = self.store_request(request_id, result);
+          let result = self.store_request(request_id, result, &read_hashes);
           self.link_request_to_parent(request_id, parent_request_id)?;
 
           if let Some(response_tx) = response_tx {
@@ -275,6 +285,7 @@ impl RequestTracker {
     &mut self,
     request_id: u64,
     result: Result<ResultAndInvalidations, RunRequestError>,
+    read_hashes: &ReadHashes,
   ) -> anyhow::Result<Arc<RequestResult>> {
     let node_index = self
       .request_index
@@ -303,6 +314,7 @@ impl RequestTracker {
         // Update node with latest result
         *request_node = RequestNode::Valid(result.clone());
 
+        let mut changed_hashes = Vec::new();
         for invalidation in invalidations.iter() {
           match invalidation {
             Invalidation::FileChange(file_path) => {
@@ -311,6 +323,17 @@ impl RequestTracker {
                 .entry(file_path.clone())
                 .or_insert_with(|| self.graph.add_node(RequestNode::FileInvalidation));
 
+              // The contents the request read are its baseline. The file may have changed since,
+              // so it isn't hashed again. Files that were read some other way keep the hash from
+              // before the request ran, which is never newer than what it read.
+              if let Some(hash) = read_hashes.get(file_path) {
+                changed_hashes.extend(record_content_hash(
+                  &mut self.content_hashes,
+                  file_path,
+                  Some(*hash),
+                ));
+              }
+
               tracing::trace!(
                 "Add {:?} as invalidation for {:?}",
                 file_path
@@ -327,6 +350,8 @@ impl RequestTracker {
           }
         }
 
+        store_content_hashes(&self.db, changed_hashes);
+
         Ok(result)
       }
     }
@@ -420,19 +445,40 @@ impl RequestTracker {
 
   #[tracing::instrument(level = "debug", skip_all, ret, fields(events = watch_events.len()))]
   pub fn respond_to_fs_events(&mut self, watch_events: WatchEvents) -> bool {
-    let nodes_to_invalidate: Vec<(NodeIndex, &PathBuf)> = watch_events
-      .iter()
-      .filter_map(|invalidation| {
-        // We don't currently distinguish between the different file event types
-        match invalidation {
-          WatchEvent::Delete(file_path)
-          | WatchEvent::Update(file_path)
-          | WatchEvent::Create(file_path) => {
-            self.invalidations.get(file_path).map(|n| (*n, file_path))
-          }
-        }
-      })
-      .collect();
+    let mut nodes_to_invalidate: Vec<(NodeIndex, &PathBuf)> = Vec::new();
+    let mut changed_hashes = Vec::new();
+
+    for event in watch_events.iter() {
+      let (WatchEvent::Create(file_path)
+      | WatchEvent::Update(file_path)
+      | WatchEvent::Delete(file_path)) = event;
+
+      let Some(&node_id) = self.invalidations.get(file_path) else {
+        continue;
+      };
+
+      // Only updates are compared, since requests may depend on whether the file exists
+      let hash = match event {
+        WatchEvent::Update(_) => self.file_system.content_hash(file_path).ok(),
+        WatchEvent::Create(_) | WatchEvent::Delete(_) => None,
+      };
+
+      if hash.is_some() && hash == self.baseline_content_hash(file_path) {
+        tracing::debug!("Skipping unchanged file {}", file_path.display());
+        continue;
+      }
+
+      // Requests that run after the hash is recorded will read these contents or newer ones
+      changed_hashes.extend(record_content_hash(
+        &mut self.content_hashes,
+        file_path,
+        hash,
+      ));
+
+      nodes_to_invalidate.push((node_id, file_path));
+    }
+
+    store_content_hashes(&self.db, changed_hashes);
 
     for (node_id, file_path_reason) in nodes_to_invalidate.iter() {
       self.invalidate_node(node_id, file_path_reason);
@@ -453,6 +499,47 @@ impl RequestTracker {
     !self.invalid_nodes.is_empty()
   }
 
+  /// Returns the hash recorded for a file, falling back to the hash written by a previous build
+  fn baseline_content_hash(&self, file_path: &Path) -> Option<u64> {
+    if let Some(hash) = self.content_hashes.get(file_path) {
+      return Some(*hash);
+    }
+
+    let stored = self.db.get(&content_hash_key(file_path)).ok()??;
+    Some(u64::from_le_bytes(stored.try_into().ok()?))
+  }
+
+  /// Replaces the plugins used by requests, e.g. after the config is reloaded in watch mode
+  ///
+  /// Results that depend on the previous plugins are not invalidated, see
+  /// [`RequestTracker::invalidate_requests`].
+  pub fn set_plugins(&mut self, plugins: PluginsRef) {
+    self.plugins = plugins;
+  }
+
+  /// Invalidates every completed request whose result matches the predicate, along with the
+  /// requests that depend on it. Returns whether any request was invalidated.
+  pub fn invalidate_requests(
+    &mut self,
+    reason: &PathBuf,
+    predicate: impl Fn(&RequestResult) -> bool,
+  ) -> bool {
+    let nodes_to_invalidate: Vec<NodeIndex> = self
+      .graph
+      .node_indices()
+      .filter(|node_index| match &self.graph[*node_index] {
+        RequestNode::Valid(result) => predicate(result),
+        _ => false,
+      })
+      .collect();
+
+    for node_index in nodes_to_invalidate.iter() {
+      self.invalidate_node(node_index, reason);
+    }
+
+    !nodes_to_invalidate.is_empty()
+  }
+
   pub fn set_report_fn(&mut self, report_fn: Option<ReportFn>) {
     self.report_fn = report_fn;
   }
@@ -475,6 +562,46 @@ enum RequestQueueMessage {
     request_id: RequestId,
     parent_request_id: Option<RequestId>,
     result: Result<ResultAndInvalidations, RunRequestError>,
+    read_hashes: ReadHashes,
     response_tx: Option<RequestResultSender>,
   },
 }
+
+fn content_hash_key(file_path: &Path) -> String {
+  format!("content-hash:{}", file_path.display())
+}
+
+/// Records the content hash of a file, or that it is unknown, returning the database entry to
+/// write when it changed
+fn record_content_hash(
+  content_hashes: &mut HashMap<PathBuf, u64>,
+  file_path: &Path,
+  hash: Option<u64>,
+) -> Option<(String, Vec<u8>)> {
+  let previous = match hash {
+    Some(hash) => content_hashes.insert(file_path.to_path_buf(), hash),
+    None => content_hashes.remove(file_path),
+  };
+
+  if previous == hash {
+    return None;
+  }
+
+  let value = hash.map(|hash| hash.to_le_bytes().to_vec());
+  Some((content_hash_key(file_path), value.unwrap_or_default()))
+}
+
+/// Writes changed content hashes to the database in a single transaction. Failing to write only
+/// means the next build compares against older hashes.
+fn store_content_hashes(db: &DatabaseRef, entries: Vec<(String, Vec<u8>)>) {
+  if entries.is_empty() {
+    return;
+  }
+
+  if let Err(error) = db.put_many(&entries) {
+    tracing::warn!(
+      "Failed to store the content hashes of {} files: {error}",
+      entries.len()
+    );
+  }
+}
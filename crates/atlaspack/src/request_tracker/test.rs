@@ -1,5 +1,6 @@
 use core::panic;
 use std::collections::HashSet;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
@@ -8,10 +9,16 @@ use std::sync::mpsc::channel;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use atlaspack_filesystem::FileSystem;
+use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
 
 use crate::WatchEvent;
 use crate::requests::RequestResult;
+use crate::test_utils::RequestTrackerTestOptions;
 use crate::test_utils::request_tracker;
+use crate::test_utils::request_tracker_with_db;
+use atlaspack_core::database::DatabaseRef;
+use atlaspack_core::database::InMemoryDatabase;
 use atlaspack_core::types::Invalidation;
 
 use super::*;
@@ -280,6 +287,57 @@ async fn test_invalidation_of_cached_results() {
   assert_eq!(request.run_count(), 2);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_updates_that_leave_the_contents_unchanged_do_not_invalidate() {
+  let db: DatabaseRef = Arc::new(InMemoryDatabase::default());
+  let fs = Arc::new(InMemoryFileSystem::default());
+  let mut rt = request_tracker_with_db(
+    RequestTrackerTestOptions {
+      fs: fs.clone(),
+      ..Default::default()
+    },
+    db.clone(),
+  );
+
+  let request = TestRequestWithInvalidation::new("test", "/test.txt");
+  let update = || vec![WatchEvent::Update(PathBuf::from("/test.txt"))];
+
+  fs.write_file(Path::new("/test.txt"), String::from("before"));
+  rt.run_request(request.clone()).await.unwrap();
+
+  // The file is saved without changes before it has ever changed
+  fs.write_file(Path::new("/test.txt"), String::from("before"));
+  assert!(!rt.respond_to_fs_events(update()));
+  rt.run_request(request.clone()).await.unwrap();
+
+  assert_eq!(request.run_count(), 1);
+  assert_eq!(
+    db.get("content-hash:/test.txt").unwrap(),
+    Some(
+      fs.content_hash(Path::new("/test.txt"))
+        .unwrap()
+        .to_le_bytes()
+        .to_vec()
+    )
+  );
+
+  fs.write_file(Path::new("/test.txt"), String::from("after"));
+  assert!(rt.respond_to_fs_events(update()));
+  rt.run_request(request.clone()).await.unwrap();
+
+  fs.write_file(Path::new("/test.txt"), String::from("after"));
+  assert!(!rt.respond_to_fs_events(update()));
+  rt.run_request(request.clone()).await.unwrap();
+
+  assert_eq!(request.run_count(), 2);
+
+  fs.write_file(Path::new("/test.txt"), String::from("changed"));
+  assert!(rt.respond_to_fs_events(update()));
+  rt.run_request(request.clone()).await.unwrap();
+
+  assert_eq!(request.run_count(), 3);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_selective_invalidation() {
   let mut rt = request_tracker(Default::default());
@@ -332,12 +390,64 @@ async fn test_invalidation_chain() {
   assert_eq!(request_c.run_count(), 2);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_invalidate_requests_by_result() {
+  let mut rt = request_tracker(Default::default());
+
+  let request_a = TestRequestWithInvalidation::new("A", "file_a.txt");
+  let request_b = TestRequestWithInvalidation::new("B", "file_b.txt");
+
+  let _ = rt.run_request(request_a.clone()).await.unwrap();
+  let _ = rt.run_request(request_b.clone()).await.unwrap();
+
+  let invalidated = rt.invalidate_requests(
+    &PathBuf::from(".parcelrc"),
+    |result| matches!(result, RequestResult::TestSub(name) if name == "A"),
+  );
+
+  assert!(invalidated);
+
+  let _ = rt.run_request(request_a.clone()).await.unwrap();
+  let _ = rt.run_request(request_b.clone()).await.unwrap();
+
+  assert_eq!(request_a.run_count(), 2);
+  assert_eq!(request_b.run_count(), 1);
+  assert!(!rt.invalidate_requests(&PathBuf::from(".parcelrc"), |_| false));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_files_edited_while_a_request_runs_invalidate_it() {
+  let fs = Arc::new(InMemoryFileSystem::default());
+  let mut rt = request_tracker(RequestTrackerTestOptions {
+    fs: fs.clone(),
+    ..Default::default()
+  });
+
+  let request = TestRequestWithInvalidation::new("test", "/test.txt").editing_after_read("after");
+  let update = || vec![WatchEvent::Update(PathBuf::from("/test.txt"))];
+
+  fs.write_file(Path::new("/test.txt"), String::from("before"));
+  rt.run_request(request.clone()).await.unwrap();
+
+  // The request read the contents from before the edit, so the event for it must invalidate it
+  assert!(rt.respond_to_fs_events(update()));
+  rt.run_request(request.clone()).await.unwrap();
+
+  assert_eq!(request.run_count(), 2);
+
+  // The second run read the edited contents, which saving again leaves unchanged
+  assert!(!rt.respond_to_fs_events(update()));
+}
+
 // Add a new request type that includes file invalidation
 #[derive(Clone, Debug)]
 struct TestRequestWithInvalidation {
   runs: Arc<AtomicUsize>,
   name: String,
   watched_file: PathBuf,
+  /// Contents written to the watched file once it has been read, as if it was edited while the
+  /// request was running
+  edit_after_read: Option<String>,
 }
 
 impl TestRequestWithInvalidation {
@@ -346,9 +456,15 @@ impl TestRequestWithInvalidation {
       runs: Default::default(),
       name: name.as_ref().to_string(),
       watched_file: PathBuf::from(watched_file),
+      edit_after_read: None,
     }
   }
 
+  fn editing_after_read(mut self, contents: &str) -> Self {
+    self.edit_after_read = Some(contents.to_string());
+    self
+  }
+
   fn run_count(&self) -> usize {
     self.runs.load(Ordering::Relaxed)
   }
@@ -364,10 +480,18 @@ impl std::hash::Hash for TestRequestWithInvalidation {
 impl Request for TestRequestWithInvalidation {
   async fn run(
     &self,
-    _request_context: RunRequestContext,
+    request_context: RunRequestContext,
   ) -> Result<ResultAndInvalidations, RunRequestError> {
     self.runs.fetch_add(1, Ordering::Relaxed);
 
+    // The file may not exist, in which case the request only depends on it being created
+    let fs = request_context.file_system();
+    if fs.read(&self.watched_file).is_ok()
+      && let Some(contents) = &self.edit_after_read
+    {
+      fs.write(&self.watched_file, contents.as_bytes())?;
+    }
+
     Ok(ResultAndInvalidations {
       result: RequestResult::TestSub(self.name.clone()),
       invalidations: vec![Invalidation::FileChange(self.watched_file.clone())],
@@ -1,6 +1,10 @@
+use atlaspack_core::plugin::CacheStatus;
+use atlaspack_core::project_path::to_project_path;
 use atlaspack_core::types::Asset;
 use atlaspack_core::types::AssetWithDependencies;
 use atlaspack_core::types::Dependency;
+use atlaspack_core::version::atlaspack_rust_version;
+use atlaspack_memoization_cache::CacheKeyComponents;
 use atlaspack_memoization_cache::CacheResponse;
 use atlaspack_memoization_cache::Cacheable;
 use serde::Deserialize;
@@ -10,6 +14,7 @@ use serde::ser::{SerializeStruct, Serializer};
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::plugins::PluginsRef;
 use crate::plugins::TransformerPipeline;
@@ -102,15 +107,31 @@ pub async fn run_pipeline(input: RunPipelineInput) -> anyhow::Result<RunPipeline
   })
 }
 
+impl RunPipelineInput {
+  /// The asset with its paths relative to the project root, so that it hashes the same in every
+  /// checkout of the project
+  fn project_relative_asset(&self) -> Asset {
+    let mut env = (*self.asset.env).clone();
+    if let Some(loc) = env.loc.as_mut() {
+      loc.file_path = to_project_path(&self.project_root, &loc.file_path);
+    }
+
+    Asset {
+      env: Arc::new(env),
+      file_path: to_project_path(&self.project_root, &self.asset.file_path),
+      ..self.asset.clone()
+    }
+  }
+}
+
 impl Cacheable for RunPipelineInput {
   fn cache_key(&self) -> Option<(String, String)> {
-    let mut hasher = atlaspack_core::hash::IdentifierHasher::default();
-    self.asset.hash(&mut hasher);
-
     // If the pipeline has no cache key then it is uncachable
-    self.pipeline.cache_key?.hash(&mut hasher);
+    let pipeline_cache_key = self.pipeline.cache_key?;
 
-    self.project_root.hash(&mut hasher);
+    let mut hasher = atlaspack_core::hash::IdentifierHasher::default();
+    self.project_relative_asset().hash(&mut hasher);
+    pipeline_cache_key.hash(&mut hasher);
 
     // Ignore plugins from the cache key
 
@@ -123,10 +144,41 @@ impl Cacheable for RunPipelineInput {
         .unwrap_or(&self.asset.file_path)
         .display()
     );
-    let cache_key = format!("{}|{}", &label, hasher.finish());
+
+    // Cached outputs contain absolute paths, so the project root is part of the key. It is added
+    // as text rather than hashed, so that cache archives can replace it when they are imported
+    // into a checkout at another location.
+    let cache_key = format!(
+      "{}|{}|{}",
+      &label,
+      hasher.finish(),
+      self.project_root.join("").display()
+    );
 
     Some((label, cache_key))
   }
+
+  fn cache_key_components(&self) -> CacheKeyComponents {
+    let mut components = CacheKeyComponents::new();
+    components
+      .add("asset_id", &self.asset.id)
+      .add("code", self.asset.code.bytes())
+      .add("env", &self.asset.env)
+      .add("asset", &self.project_relative_asset())
+      .add("pipeline_id", self.pipeline.id());
+
+    for (index, transformer) in self.pipeline.transformers().iter().enumerate() {
+      if let CacheStatus::Hash(hash) = transformer.cache_key().as_ref() {
+        components.add(format!("transformer[{index}]"), hash);
+      }
+    }
+
+    components
+      .add("project_root", &self.project_root)
+      .add("rust_version", atlaspack_rust_version());
+
+    components
+  }
 }
 
 impl CacheResponse for RunPipelineOutput {
@@ -350,3 +402,88 @@ impl<'de> Deserialize<'de> for RunPipelineOutput {
     deserializer.deserialize_struct("RunPipelineOutput", FIELDS, RunPipelineOutputVisitor)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use atlaspack_core::types::{Environment, SourceLocation};
+  use atlaspack_memoization_cache::{
+    CacheArchiveOptions, CacheReaderWriter, LmdbCacheReaderWriter, export_cache, import_cache,
+  };
+  use lmdb_js_lite::{LMDBOptions, get_database};
+
+  use crate::plugins::MockPlugins;
+
+  use super::*;
+
+  fn input(project_root: &str) -> RunPipelineInput {
+    let project_root = PathBuf::from(project_root);
+
+    RunPipelineInput {
+      asset: Asset {
+        env: Arc::new(Environment {
+          loc: Some(SourceLocation {
+            file_path: project_root.join("package.json"),
+            ..Default::default()
+          }),
+          ..Default::default()
+        }),
+        file_path: project_root.join("src/index.js"),
+        ..Default::default()
+      },
+      pipeline: TransformerPipeline::new(Vec::new()),
+      plugins: Arc::new(MockPlugins::new()),
+      project_root,
+    }
+  }
+
+  fn archive_options(project_root: &str) -> CacheArchiveOptions {
+    CacheArchiveOptions {
+      project_root: PathBuf::from(project_root),
+      rust_version: atlaspack_rust_version(),
+    }
+  }
+
+  #[test]
+  fn cache_keys_differ_between_project_roots() {
+    let (_, old_key) = input("/old/root").cache_key().unwrap();
+    let (_, new_key) = input("/new/root").cache_key().unwrap();
+
+    assert_ne!(old_key, new_key);
+    assert_eq!(old_key.replace("/old/root/", "/new/root/"), new_key);
+  }
+
+  #[test]
+  fn cache_entries_can_be_imported_into_another_project_root() {
+    let dir = std::env::temp_dir()
+      .join("atlaspack")
+      .join("run-pipeline-archive-tests");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let create_db = |name: &str| {
+      get_database(LMDBOptions {
+        path: dir.join(name).to_string_lossy().into_owned(),
+        async_writes: false,
+        map_size: None,
+      })
+      .unwrap()
+    };
+
+    let (_, old_key) = input("/old/root").cache_key().unwrap();
+    let source = create_db("source");
+    let cache = LmdbCacheReaderWriter::new(source.clone());
+    cache.put(&old_key, b"/old/root/src/index.js").unwrap();
+    cache.complete_session().unwrap();
+
+    let archive = dir.join("cache.archive");
+    export_cache(&source, &archive, &archive_options("/old/root")).unwrap();
+
+    let target = create_db("target");
+    import_cache(&target, &archive, &archive_options("/new/root")).unwrap();
+
+    let (_, new_key) = input("/new/root").cache_key().unwrap();
+    assert_eq!(
+      LmdbCacheReaderWriter::new(target).read(&new_key).unwrap(),
+      Some(b"/new/root/src/index.js".to_vec())
+    );
+  }
+}
@@ -112,6 +112,7 @@ impl EntryRequest {
       fs: request_context.file_system().clone(),
       project_root: request_context.project_root.clone(),
       search_path: entry_path.clone(),
+      files: Default::default(),
     };
 
     let package_json_file = config_loader.load_package_json::<PackageJson>()?;
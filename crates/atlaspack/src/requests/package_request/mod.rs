@@ -2,12 +2,15 @@
 #![allow(dead_code)]
 
 use std::collections::HashMap;
+use std::future::Future;
 use std::hash::{Hash, Hasher};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Instant;
 
 use crate::{
+  plugins::PluginsRef,
   request_tracker::{Request, ResultAndInvalidations, RunRequestContext, RunRequestError},
   requests::RequestResult,
 };
@@ -15,12 +18,17 @@ use anyhow::anyhow;
 use async_trait::async_trait;
 use atlaspack_core::{
   bundle_graph::bundle_graph::BundleGraph,
+  database::DatabaseRef,
   debug_tools::DebugTools,
-  package_result::PackageResult,
+  hash::hash_bytes,
+  package_result::{BundleInfo, PackageResult},
+  plugin::{BundleGraphView, OptimizeContext, OptimizeResult, PackageContext, PackagedBundle},
   types::{Bundle, FileType},
 };
+use atlaspack_filesystem::FileSystemRef;
 use atlaspack_packager_css::{CssPackager, CssPackagingContext};
 use atlaspack_packager_js::{JsPackager, PackagingContext};
+use tokio::sync::OnceCell;
 
 /// The prefix used in hash reference placeholders embedded in bundle content.
 /// Matches `HASH_REF_PREFIX` in `packages/core/core/src/constants.ts`.
@@ -57,6 +65,9 @@ pub struct PackageRequest<B: BundleGraph + Send + Sync + 'static> {
   /// performed a topological sort so all dependencies are resolved before
   /// this request runs.
   pub hash_ref_to_name_hash: HashMap<String, String>,
+  /// Bundle graph views built while packaging, keyed by bundle id, so that the packager and
+  /// optimizers of a bundle share one view instead of each collecting their own
+  bundle_graph_views: parking_lot::Mutex<HashMap<String, Arc<OnceCell<Arc<BundleGraphView>>>>>,
   /// Raw bundle content used by the `.test` packager arm. Private so the
   /// varying struct shape is never visible outside this module.
   #[cfg(test)]
@@ -77,6 +88,7 @@ impl<B: BundleGraph + Send + Sync + 'static> PackageRequest<B> {
       bundle,
       bundle_graph,
       hash_ref_to_name_hash,
+      bundle_graph_views: parking_lot::Mutex::default(),
       #[cfg(test)]
       test_content: vec![],
       #[cfg(test)]
@@ -100,6 +112,7 @@ impl<B: BundleGraph + Send + Sync + 'static> PackageRequest<B> {
       bundle,
       bundle_graph,
       hash_ref_to_name_hash,
+      bundle_graph_views: parking_lot::Mutex::default(),
       test_content: content,
       test_map_content: None,
     }
@@ -118,12 +131,269 @@ impl<B: BundleGraph + Send + Sync + 'static> PackageRequest<B> {
       bundle,
       bundle_graph,
       hash_ref_to_name_hash,
+      bundle_graph_views: parking_lot::Mutex::default(),
       test_content: content,
       test_map_content: Some(map_content),
     }
   }
 }
 
+impl<B: BundleGraph + Send + Sync + 'static> PackageRequest<B> {
+  /// Runs the optimizers configured for the bundle, where each receives the output of the previous
+  /// one, and returns the optimized contents and source map
+  async fn optimize(
+    &self,
+    db: &DatabaseRef,
+    plugins: &PluginsRef,
+    project_root: &Path,
+    contents: Vec<u8>,
+    map: Option<Vec<u8>>,
+  ) -> anyhow::Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let name = bundle_file_name(&self.bundle);
+    let optimizers = plugins.optimizers(Path::new(&name), self.bundle.pipeline.clone())?;
+    if optimizers.is_empty() {
+      return Ok((contents, map));
+    }
+
+    let bundle = Arc::new(self.bundle.clone());
+    let bundle_graph = self
+      .bundle_graph_view(&self.bundle, db, plugins, project_root)
+      .await?;
+    let mut result = OptimizeResult {
+      contents,
+      map: map.map(String::from_utf8).transpose()?,
+    };
+
+    for optimizer in optimizers {
+      result = optimizer
+        .optimize(OptimizeContext {
+          bundle: Arc::clone(&bundle),
+          bundle_graph: Arc::clone(&bundle_graph),
+          contents: result.contents,
+          map: result.map,
+        })
+        .await?;
+    }
+
+    Ok((result.contents, result.map.map(String::into_bytes)))
+  }
+
+  /// Packages `bundle` with the native packager for its type, or otherwise with the packager
+  /// plugin configured for its name
+  ///
+  /// This is boxed because packaging a bundle through a plugin packages its inline bundles first.
+  fn package_bundle<'a>(
+    &'a self,
+    bundle: &'a Bundle,
+    db: &'a DatabaseRef,
+    plugins: &'a PluginsRef,
+    project_root: &'a Path,
+  ) -> Pin<Box<dyn Future<Output = anyhow::Result<PackageResult>> + Send + 'a>> {
+    Box::pin(async move {
+      match bundle.bundle_type {
+        FileType::Js => {
+          let packager = JsPackager::new(
+            PackagingContext {
+              db: Arc::clone(db),
+              cache: None,
+              project_root: project_root.to_path_buf(),
+              debug_tools: DebugTools::default(),
+            },
+            Arc::clone(&self.bundle_graph),
+          );
+          packager.package(&bundle.id)
+        }
+        FileType::Css => {
+          let packager = CssPackager::new(
+            CssPackagingContext {
+              db: Arc::clone(db),
+              project_root: project_root.to_path_buf(),
+              output_dir: bundle.target.dist_dir.clone(),
+            },
+            Arc::clone(&self.bundle_graph),
+          );
+          packager.package(&bundle.id)
+        }
+        // To be able to unit test the stuff that happens after the file type packager runs, we implement this
+        // test only file type that just returns the content it's given
+        #[cfg(test)]
+        FileType::Other(ref ext) if ext == ".test" => {
+          let content = self.test_content.clone();
+          let hash = hash_bytes(&content);
+          Ok(PackageResult {
+            bundle_info: BundleInfo {
+              bundle_type: ext.clone(),
+              size: content.len() as u64,
+              total_assets: 0,
+              hash,
+              hash_references: vec![],
+              cache_keys: None,
+              is_large_blob: false,
+              time: None,
+              bundle_contents: Some(content),
+              map_contents: self.test_map_content.clone(),
+            },
+            config_requests: vec![],
+            dev_dep_requests: vec![],
+            invalidations: vec![],
+            warnings: vec![],
+          })
+        }
+        _ => {
+          self
+            .package_with_plugin(bundle, db, plugins, project_root)
+            .await
+        }
+      }
+    })
+  }
+
+  /// Packages a bundle that has no native packager with the packager plugin configured for it
+  async fn package_with_plugin(
+    &self,
+    bundle: &Bundle,
+    db: &DatabaseRef,
+    plugins: &PluginsRef,
+    project_root: &Path,
+  ) -> anyhow::Result<PackageResult> {
+    let packager = plugins.packager(Path::new(&bundle_file_name(bundle)))?;
+    let bundle_graph = self
+      .bundle_graph_view(bundle, db, plugins, project_root)
+      .await?;
+    let total_assets = bundle_graph.assets.len() as u64;
+
+    let packaged = packager
+      .package(PackageContext {
+        bundle: Arc::new(bundle.clone()),
+        bundle_graph,
+      })
+      .await?;
+
+    Ok(PackageResult {
+      bundle_info: BundleInfo {
+        bundle_type: bundle.bundle_type.extension().to_string(),
+        size: packaged.contents.len() as u64,
+        total_assets,
+        hash: hash_bytes(&packaged.contents),
+        hash_references: vec![],
+        cache_keys: None,
+        is_large_blob: false,
+        time: None,
+        bundle_contents: Some(packaged.contents),
+        map_contents: packaged.map.map(String::into_bytes),
+      },
+      config_requests: vec![],
+      dev_dep_requests: vec![],
+      invalidations: vec![],
+      warnings: vec![],
+    })
+  }
+
+  /// Returns the view of the bundle graph that is given to packager and optimizer plugins
+  ///
+  /// The view is collected the first time it is needed for a bundle and reused afterwards.
+  async fn bundle_graph_view(
+    &self,
+    bundle: &Bundle,
+    db: &DatabaseRef,
+    plugins: &PluginsRef,
+    project_root: &Path,
+  ) -> anyhow::Result<Arc<BundleGraphView>> {
+    let cell = self
+      .bundle_graph_views
+      .lock()
+      .entry(bundle.id.clone())
+      .or_default()
+      .clone();
+
+    cell
+      .get_or_try_init(|| async {
+        self
+          .collect_bundle_graph_view(bundle, db, plugins, project_root)
+          .await
+          .map(Arc::new)
+      })
+      .await
+      .cloned()
+  }
+
+  /// Collects the view of the bundle graph for `bundle`
+  ///
+  /// Inline bundles are packaged up front, so that packagers can embed their contents.
+  async fn collect_bundle_graph_view(
+    &self,
+    bundle: &Bundle,
+    db: &DatabaseRef,
+    plugins: &PluginsRef,
+    project_root: &Path,
+  ) -> anyhow::Result<BundleGraphView> {
+    let mut view = BundleGraphView::new(&*self.bundle_graph, bundle, |asset| {
+      // Assets built by the JS side store their code under their content key
+      let key = asset.content_key.as_deref().unwrap_or(asset.id.as_str());
+      db.get(key)?
+        .ok_or_else(|| anyhow!("Unable to read the code of asset {}", asset.id))
+    })?;
+
+    for inline_bundle_id in self.bundle_graph.get_inline_bundle_ids(bundle) {
+      let Some(inline_bundle) = self.bundle_graph.get_bundle_by_id(&inline_bundle_id) else {
+        continue;
+      };
+
+      let bundle_info = self
+        .package_bundle(inline_bundle, db, plugins, project_root)
+        .await?
+        .bundle_info;
+
+      view.inline_bundles.insert(
+        inline_bundle_id,
+        PackagedBundle {
+          contents: bundle_info.bundle_contents.unwrap_or_default(),
+          map: bundle_info
+            .map_contents
+            .map(String::from_utf8)
+            .transpose()?,
+        },
+      );
+    }
+
+    Ok(view)
+  }
+}
+
+/// Returns the name of the bundle that plugins are matched against
+fn bundle_file_name(bundle: &Bundle) -> String {
+  bundle
+    .name
+    .clone()
+    .unwrap_or_else(|| format!("index.{}", bundle.bundle_type.extension()))
+}
+
+/// Writes a file through each compressor configured for its name, like the JS
+/// `WriteBundleRequest`, where a compressor without an extension writes the file itself
+async fn write_compressed(
+  fs: &FileSystemRef,
+  plugins: &PluginsRef,
+  path: &Path,
+  contents: &[u8],
+) -> anyhow::Result<()> {
+  let name = path.file_name().map(Path::new).unwrap_or(path);
+
+  for compressor in plugins.compressors(name)? {
+    let Some(compressed) = compressor.compress(contents).await? else {
+      continue;
+    };
+
+    let mut file_path = path.to_path_buf();
+    if let Some(extension) = compressed.extension {
+      file_path.as_mut_os_string().push(format!(".{extension}"));
+    }
+
+    fs.write(&file_path, &compressed.contents)?;
+  }
+
+  Ok(())
+}
+
 impl<B: BundleGraph + Send + Sync + 'static> std::fmt::Debug for PackageRequest<B> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     f.debug_struct("PackageRequest")
@@ -210,87 +480,13 @@ impl<B: BundleGraph + Send + Sync + 'static> Request for PackageRequest<B> {
   ) -> Result<ResultAndInvalidations, RunRequestError> {
     let start = Instant::now();
 
-    let package_result: Result<PackageResult, RunRequestError> = match self.bundle.bundle_type {
-      FileType::Js => {
-        let packager = JsPackager::new(
-          PackagingContext {
-            db: Arc::clone(&request_context.db),
-            cache: None,
-            project_root: request_context.project_root.clone(),
-            debug_tools: DebugTools::default(),
-          },
-          Arc::clone(&self.bundle_graph),
-        );
-        packager.package(&self.bundle.id)
-      }
-      FileType::Css => {
-        let packager = CssPackager::new(
-          CssPackagingContext {
-            db: Arc::clone(&request_context.db),
-            project_root: request_context.project_root.clone(),
-            output_dir: self.bundle.target.dist_dir.clone(),
-          },
-          Arc::clone(&self.bundle_graph),
-        );
-        packager.package(&self.bundle.id)
-      }
-      // To be able to unit test the stuff that happens after the file type packager runs, we implement this
-      // test only file type that just returns the content it's given
-      #[cfg(test)]
-      FileType::Other(ref ext) if ext == ".test" => {
-        use atlaspack_core::hash::hash_bytes;
-        use atlaspack_core::package_result::BundleInfo;
-        let content = self.test_content.clone();
-        let hash = hash_bytes(&content);
-        Ok(PackageResult {
-          bundle_info: BundleInfo {
-            bundle_type: ext.clone(),
-            size: content.len() as u64,
-            total_assets: 0,
-            hash,
-            hash_references: vec![],
-            cache_keys: None,
-            is_large_blob: false,
-            time: None,
-            bundle_contents: Some(content),
-            map_contents: self.test_map_content.clone(),
-          },
-          config_requests: vec![],
-          dev_dep_requests: vec![],
-          invalidations: vec![],
-          warnings: vec![],
-        })
-      }
-      // Use for testing - delete this soon...
-      // _ => {
-      //   // just write an empty file..
-      //   let content = vec![];
-      //   let hash = atlaspack_core::hash::hash_bytes(&content);
-      //   Ok(PackageResult {
-      //     bundle_info: atlaspack_core::package_result::BundleInfo {
-      //       bundle_type: self.bundle.bundle_type.extension().to_string(),
-      //       size: content.len() as u64,
-      //       total_assets: 0,
-      //       hash,
-      //       hash_references: vec![],
-      //       cache_keys: None,
-      //       is_large_blob: false,
-      //       time: None,
-      //       bundle_contents: Some(content),
-      //       map_contents: None,
-      //     },
-      //     config_requests: vec![],
-      //     dev_dep_requests: vec![],
-      //     invalidations: vec![],
-      //   })
-      // }
-      _ => Err(anyhow!(
-        "Unsupported bundle type: {:?}",
-        self.bundle.bundle_type
-      )),
-    };
+    let db = Arc::clone(&request_context.db);
+    let plugins = Arc::clone(request_context.plugins());
+    let project_root = request_context.project_root.clone();
 
-    let package_result = package_result?;
+    let package_result = self
+      .package_bundle(&self.bundle, &db, &plugins, &project_root)
+      .await?;
     let bundle_info = package_result.bundle_info;
     let content_hash = bundle_info.hash;
 
@@ -299,6 +495,16 @@ impl<B: BundleGraph + Send + Sync + 'static> Request for PackageRequest<B> {
       .bundle_contents
       .ok_or_else(|| anyhow!("Bundle contents are required when packaging"))?;
 
+    let (raw_contents, map_contents) = self
+      .optimize(
+        &db,
+        &plugins,
+        &project_root,
+        raw_contents,
+        bundle_info.map_contents,
+      )
+      .await?;
+
     // Build the substitution map for this bundle. Start with the orchestrator's map (which
     // contains hashes for all bundles that were packaged in earlier topo levels), then add
     // this bundle's own hash reference so that self-references are resolved correctly.
@@ -342,13 +548,15 @@ impl<B: BundleGraph + Send + Sync + 'static> Request for PackageRequest<B> {
       );
       fs.create_dir_all(dist_dir)
         .map_err(|e| anyhow!("Failed to create output directory {:?}: {}", dist_dir, e))?;
-      fs.write(&out_path, &substituted_contents)
+      write_compressed(fs, &plugins, &out_path, &substituted_contents)
+        .await
         .map_err(|e| anyhow!("Failed to write bundle to {:?}: {}", out_path, e))?;
 
-      if let Some(ref map_bytes) = bundle_info.map_contents {
+      if let Some(ref map_bytes) = map_contents {
         let mut map_path = out_path.clone();
         map_path.as_mut_os_string().push(".map");
-        fs.write(&map_path, map_bytes)
+        write_compressed(fs, &plugins, &map_path, map_bytes)
+          .await
           .map_err(|e| anyhow!("Failed to write source map to {map_path:?}: {e}"))?;
       }
     }
@@ -375,6 +583,7 @@ mod tests {
 
   use atlaspack_core::{
     hash::hash_bytes,
+    plugin::{CompressedFile, CompressorPlugin, OptimizerPlugin, PackagerPlugin},
     types::{Environment, Target},
   };
   use atlaspack_filesystem::FileSystem;
@@ -756,4 +965,276 @@ mod tests {
       "no source map should be written when map_contents is None, but found {expected_map_path:?}"
     );
   }
+
+  #[derive(Debug)]
+  struct UppercaseOptimizer;
+
+  #[async_trait]
+  impl OptimizerPlugin for UppercaseOptimizer {
+    async fn optimize(&self, ctx: OptimizeContext) -> anyhow::Result<OptimizeResult> {
+      Ok(OptimizeResult {
+        contents: ctx.contents.to_ascii_uppercase(),
+        map: ctx.map.map(|map| format!("{map} (optimized)")),
+      })
+    }
+  }
+
+  #[tokio::test]
+  async fn test_run_writes_the_output_of_the_optimizers() {
+    use crate::plugins::MockPlugins;
+
+    let dist_dir = PathBuf::from("/dist");
+    let mut bundle = mock_bundle(test_bundle_type());
+    bundle.name = Some("bundle.test".to_string());
+    bundle.pipeline = Some(String::from("minify"));
+    bundle.target = Target {
+      dist_dir: dist_dir.clone(),
+      ..Target::default()
+    };
+
+    let mut plugins = MockPlugins::new();
+    plugins
+      .expect_optimizers()
+      .withf(|path, pipeline| {
+        path == Path::new("bundle.test") && pipeline.as_deref() == Some("minify")
+      })
+      .returning(|_, _| {
+        Ok(vec![
+          Arc::new(UppercaseOptimizer) as Arc<dyn OptimizerPlugin>,
+          Arc::new(UppercaseOptimizer),
+        ])
+      });
+    plugins
+      .expect_compressors()
+      .returning(|_| Ok(vec![Arc::new(RawCompressor) as Arc<dyn CompressorPlugin>]));
+
+    let request = make_test_request_with_map(
+      bundle,
+      b"bundle body",
+      b"source map".to_vec(),
+      HashMap::new(),
+    );
+    let ctx = RunRequestContext::new_for_testing(Arc::new(plugins));
+    let fs = ctx.file_system().clone();
+    let _ = request.run(ctx).await.expect("PackageRequest::run failed");
+
+    assert_eq!(
+      fs.read(&dist_dir.join("bundle.test")).unwrap(),
+      b"BUNDLE BODY".to_vec()
+    );
+    assert_eq!(
+      fs.read(&dist_dir.join("bundle.test.map")).unwrap(),
+      b"source map (optimized) (optimized)".to_vec()
+    );
+  }
+
+  #[derive(Debug)]
+  struct RawCompressor;
+
+  #[async_trait]
+  impl CompressorPlugin for RawCompressor {
+    async fn compress(&self, contents: &[u8]) -> anyhow::Result<Option<CompressedFile>> {
+      Ok(Some(CompressedFile {
+        contents: contents.to_vec(),
+        extension: None,
+      }))
+    }
+  }
+
+  #[derive(Debug)]
+  struct ReversingCompressor;
+
+  #[async_trait]
+  impl CompressorPlugin for ReversingCompressor {
+    async fn compress(&self, contents: &[u8]) -> anyhow::Result<Option<CompressedFile>> {
+      Ok(Some(CompressedFile {
+        contents: contents.iter().rev().copied().collect(),
+        extension: Some(String::from("rev")),
+      }))
+    }
+  }
+
+  /// Embeds the contents of the inline bundles of the bundle between brackets
+  #[derive(Debug)]
+  struct InlineBundlesPackager;
+
+  #[async_trait]
+  impl PackagerPlugin for InlineBundlesPackager {
+    async fn package(&self, ctx: PackageContext) -> anyhow::Result<PackagedBundle> {
+      let mut contents = b"outer".to_vec();
+      for inline_bundle in ctx.bundle_graph.inline_bundles.values() {
+        contents.push(b'[');
+        contents.extend_from_slice(&inline_bundle.contents);
+        contents.push(b']');
+      }
+
+      Ok(PackagedBundle {
+        contents,
+        map: None,
+      })
+    }
+  }
+
+  #[tokio::test]
+  async fn test_run_packages_other_bundle_types_with_the_packager_plugin() {
+    use crate::plugins::MockPlugins;
+
+    let dist_dir = PathBuf::from("/dist");
+    let target = Target {
+      dist_dir: dist_dir.clone(),
+      ..Target::default()
+    };
+
+    let mut bundle = mock_bundle(FileType::Other(String::from("txt")));
+    bundle.id = String::from("outer");
+    bundle.name = Some("bundle.txt".to_string());
+    bundle.target = target.clone();
+
+    let mut inline_bundle = mock_bundle(test_bundle_type());
+    inline_bundle.id = String::from("inline");
+    inline_bundle.target = target;
+
+    let bundle_graph = MockBundleGraph::builder()
+      .bundles(vec![bundle.clone(), inline_bundle])
+      .inline_child("outer", "inline")
+      .build();
+
+    let mut plugins = MockPlugins::new();
+    plugins
+      .expect_packager()
+      .withf(|path| path == Path::new("bundle.txt"))
+      .returning(|_| Ok(Arc::new(InlineBundlesPackager) as Arc<dyn PackagerPlugin>));
+    plugins.expect_optimizers().returning(|_, _| Ok(vec![]));
+    plugins
+      .expect_compressors()
+      .returning(|_| Ok(vec![Arc::new(RawCompressor) as Arc<dyn CompressorPlugin>]));
+
+    let request = PackageRequest::new_for_testing(
+      bundle,
+      Arc::new(bundle_graph),
+      HashMap::new(),
+      b"inline body".to_vec(),
+    );
+    let ctx = RunRequestContext::new_for_testing(Arc::new(plugins));
+    let fs = ctx.file_system().clone();
+    let result = request.run(ctx).await.expect("PackageRequest::run failed");
+
+    let RequestResult::Package(output) = result.result else {
+      panic!("Expected RequestResult::Package");
+    };
+    assert_eq!(output.hash, hash_bytes(b"outer[inline body]"));
+    assert_eq!(
+      fs.read(&dist_dir.join("bundle.txt")).unwrap(),
+      b"outer[inline body]".to_vec()
+    );
+  }
+
+  /// Records the bundle graph view that each plugin it is used as receives
+  #[derive(Debug, Default)]
+  struct ViewRecorder(parking_lot::Mutex<Vec<Arc<BundleGraphView>>>);
+
+  #[async_trait]
+  impl PackagerPlugin for ViewRecorder {
+    async fn package(&self, ctx: PackageContext) -> anyhow::Result<PackagedBundle> {
+      self.0.lock().push(ctx.bundle_graph);
+      Ok(PackagedBundle {
+        contents: b"packaged".to_vec(),
+        map: None,
+      })
+    }
+  }
+
+  #[async_trait]
+  impl OptimizerPlugin for ViewRecorder {
+    async fn optimize(&self, ctx: OptimizeContext) -> anyhow::Result<OptimizeResult> {
+      self.0.lock().push(ctx.bundle_graph);
+      Ok(OptimizeResult {
+        contents: ctx.contents,
+        map: ctx.map,
+      })
+    }
+  }
+
+  #[tokio::test]
+  async fn test_run_shares_one_bundle_graph_view_between_the_packager_and_optimizers() {
+    use crate::plugins::MockPlugins;
+
+    let mut bundle = mock_bundle(FileType::Other(String::from("txt")));
+    bundle.name = Some("bundle.txt".to_string());
+    bundle.target = Target {
+      dist_dir: PathBuf::from("/dist"),
+      ..Target::default()
+    };
+
+    let recorder = Arc::new(ViewRecorder::default());
+
+    let mut plugins = MockPlugins::new();
+    plugins.expect_packager().returning({
+      let recorder = Arc::clone(&recorder);
+      move |_| Ok(Arc::clone(&recorder) as Arc<dyn PackagerPlugin>)
+    });
+    plugins.expect_optimizers().returning({
+      let recorder = Arc::clone(&recorder);
+      move |_, _| {
+        Ok(vec![
+          Arc::clone(&recorder) as Arc<dyn OptimizerPlugin>,
+          Arc::clone(&recorder) as Arc<dyn OptimizerPlugin>,
+        ])
+      }
+    });
+    plugins
+      .expect_compressors()
+      .returning(|_| Ok(vec![Arc::new(RawCompressor) as Arc<dyn CompressorPlugin>]));
+
+    let bundle_graph = MockBundleGraph::builder()
+      .bundles(vec![bundle.clone()])
+      .build();
+    let request = PackageRequest::new(bundle, Arc::new(bundle_graph), HashMap::new());
+    let _ = request
+      .run(RunRequestContext::new_for_testing(Arc::new(plugins)))
+      .await
+      .expect("PackageRequest::run failed");
+
+    let views = recorder.0.lock();
+    assert_eq!(views.len(), 3);
+    assert!(views.iter().all(|view| Arc::ptr_eq(view, &views[0])));
+  }
+
+  #[tokio::test]
+  async fn test_run_writes_the_output_of_each_compressor() {
+    use crate::plugins::MockPlugins;
+
+    let dist_dir = PathBuf::from("/dist");
+    let mut bundle = mock_bundle(test_bundle_type());
+    bundle.name = Some("bundle.test".to_string());
+    bundle.target = Target {
+      dist_dir: dist_dir.clone(),
+      ..Target::default()
+    };
+
+    let mut plugins = MockPlugins::new();
+    plugins.expect_optimizers().returning(|_, _| Ok(vec![]));
+    plugins
+      .expect_compressors()
+      .withf(|path| path == Path::new("bundle.test") || path == Path::new("bundle.test.map"))
+      .returning(|_| {
+        Ok(vec![
+          Arc::new(RawCompressor) as Arc<dyn CompressorPlugin>,
+          Arc::new(ReversingCompressor),
+        ])
+      });
+
+    let request = make_test_request_with_map(bundle, b"body", b"map".to_vec(), HashMap::new());
+    let ctx = RunRequestContext::new_for_testing(Arc::new(plugins));
+    let fs = ctx.file_system().clone();
+    let _ = request.run(ctx).await.expect("PackageRequest::run failed");
+
+    assert_eq!(fs.read(&dist_dir.join("bundle.test")).unwrap(), b"body");
+    assert_eq!(fs.read(&dist_dir.join("bundle.test.rev")).unwrap(), b"ydob");
+    assert_eq!(fs.read(&dist_dir.join("bundle.test.map")).unwrap(), b"map");
+    assert_eq!(
+      fs.read(&dist_dir.join("bundle.test.map.rev")).unwrap(),
+      b"pam"
+    );
+  }
 }
@@ -247,6 +247,7 @@ impl TargetRequest {
       fs: request_context.file_system().clone(),
       project_root: request_context.project_root.clone(),
       search_path: self.entry.package_path.clone(),
+      files: Default::default(),
     };
 
     // TODO Invalidations
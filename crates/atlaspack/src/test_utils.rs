@@ -29,6 +29,7 @@ pub(crate) fn make_test_plugin_context() -> PluginContext {
       fs: fs.clone(),
       project_root: PathBuf::default(),
       search_path: PathBuf::default(),
+      files: Default::default(),
     }),
     file_system: fs.clone(),
     options: Arc::new(PluginOptions::default()),
@@ -94,6 +95,7 @@ pub(crate) fn request_tracker_with_db(
     fs: fs.clone(),
     project_root: project_root.clone(),
     search_path,
+    files: Default::default(),
   });
 
   let plugins = plugins.unwrap_or_else(|| {
@@ -0,0 +1,249 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use atlaspack::file_system::os_file_system::OsFileSystem;
+use atlaspack::rpc::stdio::{StdioRpcFactory, StdioRpcOptions};
+use atlaspack::rpc::wasm::WasmRpcFactory;
+use atlaspack::{Atlaspack, AtlaspackInitOptions};
+use atlaspack_core::types::{AtlaspackOptions, BuildMode, DefaultTargetOptions, FeatureFlags};
+use clap::Args;
+use lmdb_js_lite::{DatabaseHandle, LMDBOptions};
+
+use crate::native_rpc::NativeRpcFactory;
+
+/// Matches the default cache directory of the Node.js CLI
+const DEFAULT_CACHE_DIR: &str = ".parcel-cache";
+
+/// The LMDB map size used by the Node.js cache
+const CACHE_MAP_SIZE: f64 = 1024.0 * 1024.0 * 1024.0 * 15.0;
+
+/// Options shared by all of the commands that run a build
+#[derive(Args, Debug)]
+pub struct AtlaspackArgs {
+  /// Entry files or directories, defaulting to the sources of the targets in package.json
+  pub entries: Vec<String>,
+
+  /// Path to an .atlaspackrc to use instead of the one found in the project
+  #[arg(long)]
+  pub config: Option<String>,
+
+  /// Directory used to cache build results [default: ./.parcel-cache]
+  #[arg(long, env = "ATLASPACK_CACHE_DIR")]
+  pub cache_dir: Option<PathBuf>,
+
+  /// Path to the @atlaspack/core package, used to resolve runtime helpers
+  #[arg(long, env = "ATLASPACK_CORE_PATH")]
+  pub core_path: Option<PathBuf>,
+
+  /// Output directory for targets that do not set a distDir
+  #[arg(long)]
+  pub dist_dir: Option<PathBuf>,
+
+  /// Enables a feature flag, e.g. `--feature-flag v3Caching=true`
+  #[arg(long = "feature-flag", value_name = "NAME=VALUE", value_parser = parse_feature_flag)]
+  pub feature_flags: Vec<(String, serde_json::Value)>,
+
+  /// The build mode, such as development or production [default: depends on the command]
+  #[arg(long)]
+  pub mode: Option<String>,
+
+  /// Disables minification and other optimizations
+  #[arg(long)]
+  pub no_optimize: bool,
+
+  /// The public URL that bundles are served from
+  #[arg(long)]
+  pub public_url: Option<String>,
+
+  /// Builds without the optimizers that require Node.js, with a warning, rather than failing
+  #[arg(long)]
+  pub skip_unsupported_optimizers: bool,
+
+  /// Number of threads used to run the build [default: number of CPUs]
+  #[arg(long)]
+  pub threads: Option<usize>,
+}
+
+impl AtlaspackArgs {
+  pub fn cache_dir(&self) -> anyhow::Result<PathBuf> {
+    match &self.cache_dir {
+      Some(cache_dir) => Ok(std::path::absolute(cache_dir)?),
+      None => Ok(std::env::current_dir()?.join(DEFAULT_CACHE_DIR)),
+    }
+  }
+
+  pub fn atlaspack_options(&self, default_mode: BuildMode) -> anyhow::Result<AtlaspackOptions> {
+    let feature_flags = serde_json::from_value::<FeatureFlags>(serde_json::Value::Object(
+      self.feature_flags.iter().cloned().collect(),
+    ))?;
+
+    let mode = match &self.mode {
+      Some(mode) => serde_json::from_value(serde_json::Value::String(mode.clone()))?,
+      None => default_mode,
+    };
+
+    let mut default_target_options = DefaultTargetOptions {
+      dist_dir: self
+        .dist_dir
+        .as_deref()
+        .map(std::path::absolute)
+        .transpose()?,
+      ..DefaultTargetOptions::default()
+    };
+
+    if self.no_optimize {
+      default_target_options.should_optimize = Some(false);
+    }
+
+    if let Some(public_url) = &self.public_url {
+      default_target_options.public_url = public_url.clone();
+    }
+
+    Ok(AtlaspackOptions {
+      config: self.config.clone(),
+      core_path: self.core_path()?,
+      default_target_options,
+      entries: self.entries.clone(),
+      env: std::env::vars().collect(),
+      feature_flags,
+      mode,
+      threads: self.threads,
+      ..AtlaspackOptions::default()
+    })
+  }
+
+  /// Opens the LMDB database within the cache directory, creating it if needed
+  pub fn open_database(&self) -> anyhow::Result<Arc<DatabaseHandle>> {
+    let cache_dir = self.cache_dir()?;
+    std::fs::create_dir_all(&cache_dir)?;
+
+    let db = lmdb_js_lite::get_database(LMDBOptions {
+      path: cache_dir.to_string_lossy().into_owned(),
+      async_writes: true,
+      map_size: Some(CACHE_MAP_SIZE),
+    })?;
+
+    Ok(db)
+  }
+
+  /// Creates an Atlaspack instance that runs entirely in this process
+  pub fn create_atlaspack(&self, default_mode: BuildMode) -> anyhow::Result<Atlaspack> {
+    Atlaspack::new(AtlaspackInitOptions {
+      db: self.open_database()?,
+      fs: Some(Arc::new(OsFileSystem)),
+      options: self.atlaspack_options(default_mode)?,
+      // Falls back to the native NodePackageManager, rooted at the inferred project root
+      package_manager: None,
+      // Plugins compiled to WebAssembly run in process and `exec:` plugins run as executables, but
+      // any other plugin without a native implementation fails to load
+      rpc: Arc::new(WasmRpcFactory::new(Arc::new(StdioRpcFactory::new(
+        Arc::new(NativeRpcFactory {
+          skip_unsupported_optimizers: self.skip_unsupported_optimizers,
+        }),
+        StdioRpcOptions::default(),
+      )))),
+    })
+  }
+
+  /// Returns the core path from the arguments, or finds @atlaspack/core in node_modules
+  fn core_path(&self) -> anyhow::Result<PathBuf> {
+    if let Some(core_path) = &self.core_path {
+      return Ok(std::path::absolute(core_path)?);
+    }
+
+    let cwd = std::env::current_dir()?;
+
+    cwd
+      .ancestors()
+      .map(|dir| dir.join("node_modules/@atlaspack/core"))
+      .find(|core_path| core_path.is_dir())
+      .ok_or_else(|| {
+        anyhow!(
+          "Unable to find @atlaspack/core from {}, install it or pass --core-path",
+          cwd.display()
+        )
+      })
+  }
+}
+
+/// Parses `name=value` into a feature flag, where `true` and `false` are boolean flags
+fn parse_feature_flag(flag: &str) -> Result<(String, serde_json::Value), String> {
+  let (name, value) = flag
+    .split_once('=')
+    .ok_or_else(|| format!("Expected NAME=VALUE, found {flag}"))?;
+
+  let value = match value {
+    "true" => serde_json::Value::Bool(true),
+    "false" => serde_json::Value::Bool(false),
+    value => serde_json::Value::String(String::from(value)),
+  };
+
+  Ok((String::from(name), value))
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  fn args(argv: &[&str]) -> AtlaspackArgs {
+    #[derive(clap::Parser)]
+    struct TestCommand {
+      #[command(flatten)]
+      args: AtlaspackArgs,
+    }
+
+    let argv = ["atlaspack"].iter().chain(argv);
+    <TestCommand as clap::Parser>::parse_from(argv).args
+  }
+
+  #[test]
+  fn parses_feature_flags() {
+    assert_eq!(
+      parse_feature_flag("v3Caching=true"),
+      Ok((String::from("v3Caching"), json!(true)))
+    );
+
+    assert_eq!(
+      parse_feature_flag("mode=fast"),
+      Ok((String::from("mode"), json!("fast")))
+    );
+
+    assert!(parse_feature_flag("v3Caching").is_err());
+  }
+
+  #[test]
+  fn creates_options_from_args() {
+    let options = args(&[
+      "src/index.html",
+      "--core-path",
+      "/core",
+      "--feature-flag",
+      "v3Caching=true",
+      "--mode",
+      "development",
+      "--no-optimize",
+    ])
+    .atlaspack_options(BuildMode::Production)
+    .unwrap();
+
+    assert_eq!(options.core_path, PathBuf::from("/core"));
+    assert_eq!(options.default_target_options.should_optimize, Some(false));
+    assert_eq!(options.entries, vec![String::from("src/index.html")]);
+    assert!(options.feature_flags.bool_enabled("v3Caching"));
+    assert_eq!(options.mode, BuildMode::Development);
+  }
+
+  #[test]
+  fn uses_the_default_mode() {
+    let options = args(&["--core-path", "/core"])
+      .atlaspack_options(BuildMode::Production)
+      .unwrap();
+
+    assert_eq!(options.mode, BuildMode::Production);
+    assert_eq!(options.default_target_options.should_optimize, None);
+  }
+}
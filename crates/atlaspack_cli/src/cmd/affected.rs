@@ -0,0 +1,121 @@
+use std::path::Path;
+
+use atlaspack::{AffectedEntries, GitFallback};
+use atlaspack_core::types::BuildMode;
+use clap::Parser;
+
+use crate::args::AtlaspackArgs;
+
+#[derive(Debug, Parser)]
+pub struct AffectedCommand {
+  #[command(flatten)]
+  pub args: AtlaspackArgs,
+
+  /// The git revision to compare the working tree with, e.g. the merge base of a pull request
+  #[arg(long)]
+  pub since: String,
+
+  /// Print the affected entries as JSON
+  #[arg(long)]
+  pub json: bool,
+
+  /// Fail when the native git implementation can't answer a query, rather than retrying with the
+  /// `git` CLI
+  #[arg(long)]
+  pub no_git_fallback: bool,
+}
+
+/// Lists the entries, targets and bundles affected by the changes since a git revision
+///
+/// This builds the bundle graph of the current working tree, so CI can skip building and testing
+/// the entries that a change can't affect.
+pub fn main(cmd: AffectedCommand) -> anyhow::Result<()> {
+  let atlaspack = cmd.args.create_atlaspack(BuildMode::Production)?;
+  let git_fallback = match cmd.no_git_fallback {
+    true => GitFallback::Disabled,
+    false => GitFallback::Cli,
+  };
+
+  let affected = atlaspack.affected_entries(&cmd.since, git_fallback)?;
+
+  if cmd.json {
+    println!("{}", serde_json::to_string_pretty(&affected)?);
+  } else {
+    print!("{}", format_affected(&atlaspack.project_root, &affected));
+  }
+
+  Ok(())
+}
+
+fn format_affected(project_root: &Path, affected: &AffectedEntries) -> String {
+  let relative = |path: &Path| {
+    path
+      .strip_prefix(project_root)
+      .unwrap_or(path)
+      .display()
+      .to_string()
+  };
+
+  let mut output = format!("Entries: {}\n", affected.entries.len());
+  for entry in affected.entries.iter() {
+    output += &format!("  {} [{}]\n", entry.entry, entry.target);
+  }
+
+  output += &format!("Bundles: {}\n", affected.bundles.len());
+  for bundle in affected.bundles.iter() {
+    output += &format!("  {}\n", bundle.name.as_deref().unwrap_or(&bundle.id));
+  }
+
+  if !affected.unmatched_files.is_empty() {
+    output += &format!(
+      "Changed files outside of the asset graph: {}\n",
+      affected.unmatched_files.len()
+    );
+
+    for path in affected.unmatched_files.iter() {
+      output += &format!("  {}\n", relative(path));
+    }
+  }
+
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use atlaspack::{AffectedBundle, AffectedEntry};
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn formats_the_affected_entries() {
+    let affected = AffectedEntries {
+      entries: vec![AffectedEntry {
+        entry: String::from("apps/app/index.js"),
+        target: String::from("default"),
+      }],
+      targets: vec![String::from("default")],
+      bundles: vec![AffectedBundle {
+        id: String::from("abc"),
+        name: Some(String::from("app.js")),
+        target: String::from("default"),
+      }],
+      changed_assets: vec![PathBuf::from("/repo/apps/app/index.js")],
+      unmatched_files: vec![PathBuf::from("/repo/yarn.lock")],
+    };
+
+    assert_eq!(
+      format_affected(Path::new("/repo"), &affected),
+      String::from(concat!(
+        "Entries: 1\n",
+        "  apps/app/index.js [default]\n",
+        "Bundles: 1\n",
+        "  app.js\n",
+        "Changed files outside of the asset graph: 1\n",
+        "  yarn.lock\n",
+      ))
+    );
+  }
+}
@@ -0,0 +1,137 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+use atlaspack::Atlaspack;
+use atlaspack_core::types::BuildMode;
+use clap::Parser;
+
+use crate::args::AtlaspackArgs;
+use crate::progress::{ProgressReporter, format_size, format_warning};
+
+#[derive(Debug, Parser)]
+pub struct BuildCommand {
+  #[command(flatten)]
+  pub args: AtlaspackArgs,
+}
+
+/// Builds the project once, in production mode unless another mode is passed
+pub fn main(cmd: BuildCommand) -> anyhow::Result<()> {
+  let atlaspack = cmd.args.create_atlaspack(BuildMode::Production)?;
+
+  build(&atlaspack, &ProgressReporter::new())
+}
+
+pub struct BundleSummary {
+  pub file_path: PathBuf,
+  pub size: u64,
+  pub time: u64,
+}
+
+/// Runs a build, then prints the bundles that were written
+pub fn build(atlaspack: &Atlaspack, reporter: &Arc<ProgressReporter>) -> anyhow::Result<()> {
+  let start = Instant::now();
+  let result = atlaspack.build_with_report_fn(Some(reporter.report_fn()));
+
+  reporter.finish();
+
+  for warning in atlaspack.take_warnings() {
+    eprintln!("{}", format_warning(&atlaspack.project_root, &warning));
+  }
+
+  let output = result?;
+  let mut bundles = output
+    .packaging
+    .bundles
+    .values()
+    .map(|bundle| BundleSummary {
+      file_path: bundle.file_path.clone(),
+      size: bundle.size,
+      time: bundle.time,
+    })
+    .collect::<Vec<BundleSummary>>();
+
+  print!("{}", format_bundles(&atlaspack.project_root, &mut bundles));
+  println!("Built in {:.2}s", start.elapsed().as_secs_f64());
+
+  if let Some(stats) = atlaspack
+    .runtime
+    .block_on(atlaspack.complete_cache_session())
+  {
+    tracing::debug!(?stats, "Completed cache session");
+  }
+
+  Ok(())
+}
+
+/// Formats a table of bundles, from largest to smallest, with paths relative to the project root
+fn format_bundles(project_root: &Path, bundles: &mut [BundleSummary]) -> String {
+  bundles.sort_by(|a, b| {
+    b.size
+      .cmp(&a.size)
+      .then_with(|| a.file_path.cmp(&b.file_path))
+  });
+
+  let rows = bundles
+    .iter()
+    .map(|bundle| {
+      let file_path = bundle
+        .file_path
+        .strip_prefix(project_root)
+        .unwrap_or(&bundle.file_path);
+
+      (
+        file_path.display().to_string(),
+        format_size(bundle.size),
+        format!("{}ms", bundle.time),
+      )
+    })
+    .collect::<Vec<(String, String, String)>>();
+
+  let path_width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0);
+  let size_width = rows.iter().map(|row| row.1.len()).max().unwrap_or(0);
+
+  rows
+    .into_iter()
+    .map(|(file_path, size, time)| {
+      format!("{file_path:<path_width$}  {size:>size_width$}  {time}\n")
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn formats_bundles_from_largest_to_smallest() {
+    let mut bundles = vec![
+      BundleSummary {
+        file_path: PathBuf::from("/project/dist/index.html"),
+        size: 512,
+        time: 3,
+      },
+      BundleSummary {
+        file_path: PathBuf::from("/project/dist/index.1a2b3c.js"),
+        size: 2048,
+        time: 25,
+      },
+      BundleSummary {
+        file_path: PathBuf::from("/elsewhere/index.css"),
+        size: 100,
+        time: 1,
+      },
+    ];
+
+    assert_eq!(
+      format_bundles(Path::new("/project"), &mut bundles),
+      String::from(concat!(
+        "dist/index.1a2b3c.js  2.00 KB  25ms\n",
+        "dist/index.html         512 B  3ms\n",
+        "/elsewhere/index.css    100 B  1ms\n",
+      ))
+    );
+  }
+}
@@ -0,0 +1,253 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use atlaspack::file_system::os_file_system::OsFileSystem;
+use atlaspack::infer_project_root;
+use atlaspack_core::version::atlaspack_rust_version;
+use atlaspack_memoization_cache::{
+  CacheArchiveOptions, CacheArchiveSummary, CacheGcOptions, CacheGcSummary, CacheInspection,
+  CacheUsage, collect_garbage, compact_cache, export_cache, import_cache, inspect_cache,
+};
+use clap::{Args, Parser, Subcommand};
+
+use crate::args::AtlaspackArgs;
+use crate::progress::format_size;
+
+#[derive(Debug, Parser)]
+pub struct CacheCommand {
+  #[command(subcommand)]
+  pub command: CacheCommandType,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCommandType {
+  /// Write the cache entries to a portable archive, e.g. to restore the cache on another machine
+  Export(CacheArchiveArgs),
+  /// Read an archive written by `cache export` into the cache directory
+  Import(CacheArchiveArgs),
+  /// Remove the least recently used entries and blobs until the cache is within the limits
+  Gc(CacheGcArgs),
+  /// Write a compacted copy of the cache, which can replace the cache directory to reclaim space
+  Compact(CacheCompactArgs),
+  /// Print the number of entries and bytes used by each kind of request
+  Inspect(CacheInspectArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CacheArchiveArgs {
+  /// Path to the archive
+  pub archive: PathBuf,
+
+  #[command(flatten)]
+  pub args: AtlaspackArgs,
+}
+
+impl CacheArchiveArgs {
+  fn archive_options(&self) -> anyhow::Result<CacheArchiveOptions> {
+    Ok(CacheArchiveOptions {
+      project_root: infer_project_root(Arc::new(OsFileSystem), self.args.entries.clone())?,
+      rust_version: atlaspack_rust_version(),
+    })
+  }
+}
+
+#[derive(Args, Debug)]
+pub struct CacheGcArgs {
+  /// The maximum total size of the entries and blobs in bytes [env: ATLASPACK_CACHE_MAX_BYTES]
+  #[arg(long)]
+  pub max_bytes: Option<u64>,
+
+  /// Remove entries and blobs that have not been used for this many days
+  /// [env: ATLASPACK_CACHE_MAX_AGE_DAYS]
+  #[arg(long, value_name = "DAYS")]
+  pub max_age: Option<u64>,
+
+  /// The maximum number of entries [default: 300000]
+  #[arg(long)]
+  pub max_entries: Option<usize>,
+
+  #[command(flatten)]
+  pub args: AtlaspackArgs,
+}
+
+impl CacheGcArgs {
+  fn gc_options(&self) -> CacheGcOptions {
+    let defaults = CacheGcOptions::from_env();
+
+    CacheGcOptions {
+      max_entries: self.max_entries.unwrap_or(defaults.max_entries),
+      max_bytes: self.max_bytes.or(defaults.max_bytes),
+      max_age: self
+        .max_age
+        .map(|days| Duration::from_secs(days * 24 * 60 * 60))
+        .or(defaults.max_age),
+    }
+  }
+}
+
+#[derive(Args, Debug)]
+pub struct CacheCompactArgs {
+  /// Directory to write the compacted cache to, which must be outside of the cache directory
+  pub target: PathBuf,
+
+  #[command(flatten)]
+  pub args: AtlaspackArgs,
+}
+
+#[derive(Args, Debug)]
+pub struct CacheInspectArgs {
+  /// Print the usage as JSON
+  #[arg(long)]
+  pub json: bool,
+
+  #[command(flatten)]
+  pub args: AtlaspackArgs,
+}
+
+/// Manages the cache directory without running a build
+///
+/// Unlike copying the cache directory, archives only contain paths relative to the project root,
+/// and entries created by a different version of Atlaspack are skipped when importing.
+pub fn main(cmd: CacheCommand) -> anyhow::Result<()> {
+  match cmd.command {
+    CacheCommandType::Export(cmd) => {
+      let db = cmd.args.open_database()?;
+      let summary = export_cache(&db, &cmd.archive, &cmd.archive_options()?)?;
+      print!("{}", format_archive_summary("Exported", &summary));
+    }
+    CacheCommandType::Import(cmd) => {
+      let db = cmd.args.open_database()?;
+      let summary = import_cache(&db, &cmd.archive, &cmd.archive_options()?)?;
+      print!("{}", format_archive_summary("Imported", &summary));
+    }
+    CacheCommandType::Gc(cmd) => {
+      let db = cmd.args.open_database()?;
+      let summary = collect_garbage(&db, &cmd.gc_options())?;
+      print!("{}", format_gc_summary(&summary));
+    }
+    CacheCommandType::Compact(cmd) => {
+      let db = cmd.args.open_database()?;
+      compact_cache(&db, &cmd.target)?;
+      println!("Compacted the cache into {}", cmd.target.display());
+    }
+    CacheCommandType::Inspect(cmd) => {
+      let db = cmd.args.open_database()?;
+      let inspection = inspect_cache(&db)?;
+
+      if cmd.json {
+        println!("{}", serde_json::to_string_pretty(&inspection)?);
+      } else {
+        print!("{}", format_inspection(&inspection));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn format_archive_summary(action: &str, summary: &CacheArchiveSummary) -> String {
+  let mut output = format!(
+    "{action} {} cache entries and {} blobs\n",
+    summary.entries, summary.blobs
+  );
+
+  if summary.skipped > 0 {
+    output += &format!(
+      "Skipped {} created by another version of Atlaspack\n",
+      summary.skipped
+    );
+  }
+
+  output
+}
+
+fn format_gc_summary(summary: &CacheGcSummary) -> String {
+  format!(
+    "Removed {} cache entries and {} blobs ({})\n",
+    summary.entries,
+    summary.blobs,
+    format_size(summary.bytes)
+  )
+}
+
+fn format_inspection(inspection: &CacheInspection) -> String {
+  let mut output = String::new();
+
+  let mut format_group = |title: &str, usage: &BTreeMap<String, CacheUsage>| {
+    output += &format!("{title}:\n");
+
+    // Largest first, as these are what to look at when the cache is too big
+    let mut usage = usage.iter().collect::<Vec<_>>();
+    usage.sort_by(|(_, a), (_, b)| b.bytes.cmp(&a.bytes));
+
+    for (name, usage) in usage {
+      output += &format!("  {name}: {} ({})\n", usage.count, format_size(usage.bytes));
+    }
+  };
+
+  format_group("Entries", &inspection.entries);
+  format_group("Blobs", &inspection.blobs);
+
+  let total = inspection.total();
+  output += &format!("Total: {} ({})\n", total.count, format_size(total.bytes));
+
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn formats_the_archive_summary() {
+    assert_eq!(
+      format_archive_summary(
+        "Imported",
+        &CacheArchiveSummary {
+          entries: 10,
+          blobs: 2,
+          skipped: 3,
+        }
+      ),
+      String::from(concat!(
+        "Imported 10 cache entries and 2 blobs\n",
+        "Skipped 3 created by another version of Atlaspack\n",
+      ))
+    );
+
+    assert_eq!(
+      format_archive_summary("Exported", &CacheArchiveSummary::default()),
+      String::from("Exported 0 cache entries and 0 blobs\n")
+    );
+  }
+
+  #[test]
+  fn formats_the_inspection() {
+    let usage = |count: usize, bytes: u64| CacheUsage { count, bytes };
+
+    let inspection = CacheInspection {
+      entries: BTreeMap::from([
+        (String::from("resolve"), usage(10, 100)),
+        (String::from("run_pipeline"), usage(2, 4096)),
+      ]),
+      blobs: BTreeMap::from([(String::from("PackagerRunner"), usage(1, 2048))]),
+      oldest_entry: Some(0),
+    };
+
+    assert_eq!(
+      format_inspection(&inspection),
+      String::from(concat!(
+        "Entries:\n",
+        "  run_pipeline: 2 (4.00 KB)\n",
+        "  resolve: 10 (100 B)\n",
+        "Blobs:\n",
+        "  PackagerRunner: 1 (2.00 KB)\n",
+        "Total: 13 (6.10 KB)\n",
+      ))
+    );
+  }
+}
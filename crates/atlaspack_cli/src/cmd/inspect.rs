@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+use atlaspack_core::bundle_graph::BundleGraph;
+use atlaspack_core::types::BuildMode;
+use clap::Parser;
+use serde::Serialize;
+
+use crate::args::AtlaspackArgs;
+use crate::progress::{ProgressReporter, format_size};
+
+#[derive(Debug, Parser)]
+pub struct InspectCommand {
+  #[command(flatten)]
+  pub args: AtlaspackArgs,
+
+  /// Print the summary as JSON
+  #[arg(long)]
+  pub json: bool,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct InspectSummary {
+  asset_graph_time_ms: u128,
+  bundle_graph_time_ms: u128,
+  /// The number of assets and their total size for each file type
+  assets: BTreeMap<String, AssetTypeSummary>,
+  dependencies: usize,
+  bundles: Vec<BundleSummary>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AssetTypeSummary {
+  count: usize,
+  size: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BundleSummary {
+  id: String,
+  name: Option<String>,
+  bundle_type: String,
+  assets: usize,
+}
+
+/// Builds the asset and bundle graphs without packaging, and prints their size and timings
+///
+/// This runs the same requests as a build, so it is useful for profiling the graph building
+/// phases in isolation, e.g. along with `--profile`.
+pub fn main(cmd: InspectCommand) -> anyhow::Result<()> {
+  let atlaspack = cmd.args.create_atlaspack(BuildMode::Production)?;
+  let reporter = ProgressReporter::new();
+
+  let start = Instant::now();
+  let asset_graph_result = atlaspack.build_asset_graph_with_report_fn(Some(reporter.report_fn()));
+  let asset_graph_time = start.elapsed();
+
+  // The asset graph request is cached by the request tracker, so this only adds the time taken
+  // to bundle
+  let start = Instant::now();
+  let bundle_graph_result = asset_graph_result
+    .and_then(|_| atlaspack.build_bundle_graph_with_report_fn(Some(reporter.report_fn())));
+  let bundle_graph_time = start.elapsed();
+
+  reporter.finish();
+
+  let (asset_graph, bundle_graph, _had_previous_graph) = bundle_graph_result?;
+  let bundle_graph = &bundle_graph.bundle_graph;
+
+  let mut summary = InspectSummary {
+    asset_graph_time_ms: asset_graph_time.as_millis(),
+    bundle_graph_time_ms: bundle_graph_time.as_millis(),
+    dependencies: asset_graph.get_dependencies().count(),
+    ..InspectSummary::default()
+  };
+
+  for asset in asset_graph.get_assets() {
+    let asset_type = summary
+      .assets
+      .entry(asset.file_type.extension().to_string())
+      .or_default();
+
+    asset_type.count += 1;
+    asset_type.size += u64::from(asset.code.size());
+  }
+
+  for bundle in bundle_graph.get_bundles() {
+    summary.bundles.push(BundleSummary {
+      id: bundle.id.clone(),
+      name: bundle.name.clone(),
+      bundle_type: bundle.bundle_type.extension().to_string(),
+      assets: bundle_graph.get_bundle_assets(bundle)?.len(),
+    });
+  }
+
+  if cmd.json {
+    println!("{}", serde_json::to_string_pretty(&summary)?);
+  } else {
+    print!("{}", format_summary(&summary));
+  }
+
+  Ok(())
+}
+
+fn format_summary(summary: &InspectSummary) -> String {
+  let mut output = String::new();
+  let asset_count = summary
+    .assets
+    .values()
+    .map(|asset_type| asset_type.count)
+    .sum::<usize>();
+
+  output += &format!(
+    "Asset graph: {asset_count} assets, {} dependencies in {}ms\n",
+    summary.dependencies, summary.asset_graph_time_ms
+  );
+
+  for (file_type, asset_type) in summary.assets.iter() {
+    output += &format!(
+      "  {file_type}: {} ({})\n",
+      asset_type.count,
+      format_size(asset_type.size)
+    );
+  }
+
+  output += &format!(
+    "Bundle graph: {} bundles in {}ms\n",
+    summary.bundles.len(),
+    summary.bundle_graph_time_ms
+  );
+
+  for bundle in summary.bundles.iter() {
+    output += &format!(
+      "  {} [{}] {} assets\n",
+      bundle.name.as_deref().unwrap_or(&bundle.id),
+      bundle.bundle_type,
+      bundle.assets
+    );
+  }
+
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn formats_the_summary() {
+    let summary = InspectSummary {
+      asset_graph_time_ms: 120,
+      bundle_graph_time_ms: 15,
+      assets: BTreeMap::from([
+        (
+          String::from("css"),
+          AssetTypeSummary {
+            count: 1,
+            size: 100,
+          },
+        ),
+        (
+          String::from("js"),
+          AssetTypeSummary {
+            count: 3,
+            size: 2048,
+          },
+        ),
+      ]),
+      dependencies: 5,
+      bundles: vec![
+        BundleSummary {
+          id: String::from("a"),
+          name: Some(String::from("index.js")),
+          bundle_type: String::from("js"),
+          assets: 3,
+        },
+        BundleSummary {
+          id: String::from("b"),
+          name: None,
+          bundle_type: String::from("css"),
+          assets: 1,
+        },
+      ],
+    };
+
+    assert_eq!(
+      format_summary(&summary),
+      String::from(concat!(
+        "Asset graph: 4 assets, 5 dependencies in 120ms\n",
+        "  css: 1 (100 B)\n",
+        "  js: 3 (2.00 KB)\n",
+        "Bundle graph: 2 bundles in 15ms\n",
+        "  index.js [js] 3 assets\n",
+        "  b [css] 1 assets\n",
+      ))
+    );
+  }
+}
@@ -0,0 +1,6 @@
+pub mod affected;
+pub mod build;
+pub mod cache;
+pub mod inspect;
+pub mod print_config;
+pub mod watch;
@@ -0,0 +1,194 @@
+use std::path::PathBuf;
+
+use atlaspack_config::explain::{ConfiguredPlugin, PluginsExplanation, PrintedConfig};
+use atlaspack_core::types::BuildMode;
+use clap::Parser;
+
+use crate::args::AtlaspackArgs;
+
+#[derive(Debug, Parser)]
+pub struct PrintConfigCommand {
+  #[command(flatten)]
+  pub args: AtlaspackArgs,
+
+  /// Print the plugins that apply to this file instead of the whole config
+  #[arg(long, value_name = "FILE")]
+  pub explain: Option<PathBuf>,
+
+  /// The named pipeline to explain the file with, e.g. `types` for `types:*.ts`
+  #[arg(long, requires = "explain")]
+  pub pipeline: Option<String>,
+
+  /// Print the config as JSON
+  #[arg(long)]
+  pub json: bool,
+}
+
+/// Prints the fully merged config, or the plugins that apply to a single file
+///
+/// Each plugin is printed with the config file that declared it, which makes it possible to tell
+/// which plugins come from an extended config.
+pub fn main(cmd: PrintConfigCommand) -> anyhow::Result<()> {
+  let atlaspack = cmd.args.create_atlaspack(BuildMode::Development)?;
+
+  if let Some(file_path) = cmd.explain {
+    let file_path = std::path::absolute(file_path)?;
+    let explanation = atlaspack.explain_plugins(&file_path, cmd.pipeline.as_deref());
+
+    if cmd.json {
+      println!("{}", serde_json::to_string_pretty(&explanation)?);
+    } else {
+      print!("{}", format_explanation(&explanation));
+    }
+
+    return Ok(());
+  }
+
+  let config = atlaspack.print_config();
+
+  if cmd.json {
+    println!("{}", serde_json::to_string_pretty(&config)?);
+  } else {
+    print!("{}", format_config(&config));
+  }
+
+  Ok(())
+}
+
+fn format_config(config: &PrintedConfig) -> String {
+  let mut output = String::new();
+
+  output += &format_phase("bundler", std::slice::from_ref(&config.bundler));
+  output += &format_phase("compressors", &config.compressors);
+  output += &format_phase("namers", &config.namers);
+  output += &format_phase("optimizers", &config.optimizers);
+  output += &format_phase("packagers", &config.packagers);
+  output += &format_phase("reporters", &config.reporters);
+  output += &format_phase("resolvers", &config.resolvers);
+  output += &format_phase("runtimes", &config.runtimes);
+  output += &format_phase("transformers", &config.transformers);
+  output += &format_phase("validators", &config.validators);
+
+  output
+}
+
+fn format_explanation(explanation: &PluginsExplanation) -> String {
+  let mut output = format!("Plugins for {}", explanation.file_path.display());
+
+  if let Some(pipeline) = &explanation.pipeline {
+    output += &format!(" in the {pipeline} pipeline");
+  }
+
+  output += "\n";
+  output += &format_phase("transformers", &explanation.transformers);
+  output += &format_phase("optimizers", &explanation.optimizers);
+  output += &format_phase("packager", explanation.packager.as_slice());
+  output += &format_phase("compressors", &explanation.compressors);
+
+  output
+}
+
+fn format_phase(phase: &str, plugins: &[ConfiguredPlugin]) -> String {
+  let mut output = format!("{phase}:\n");
+
+  if plugins.is_empty() {
+    output += "  (none)\n";
+  }
+
+  for plugin in plugins {
+    let source = match &plugin.loc {
+      Some(loc) => format!(
+        "{}:{}:{}",
+        loc.file_path.display(),
+        loc.start.line,
+        loc.start.column
+      ),
+      None => plugin.config_file.display().to_string(),
+    };
+
+    match &plugin.pattern {
+      Some(pattern) => output += &format!("  {pattern}: {} ({source})\n", plugin.package_name),
+      None => output += &format!("  {} ({source})\n", plugin.package_name),
+    }
+  }
+
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use atlaspack_core::types::{Location, SourceLocation};
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn plugin(package_name: &str, pattern: Option<&str>) -> ConfiguredPlugin {
+    ConfiguredPlugin {
+      package_name: String::from(package_name),
+      config_file: PathBuf::from("/project/.parcelrc"),
+      pattern: pattern.map(String::from),
+      loc: None,
+    }
+  }
+
+  #[test]
+  fn formats_the_explanation() {
+    let explanation = PluginsExplanation {
+      file_path: PathBuf::from("/project/src/index.ts"),
+      pipeline: Some(String::from("types")),
+      transformers: vec![ConfiguredPlugin {
+        loc: Some(SourceLocation {
+          file_path: PathBuf::from("/project/.parcelrc"),
+          start: Location {
+            line: 4,
+            column: 20,
+          },
+          end: Location {
+            line: 4,
+            column: 58,
+          },
+        }),
+        ..plugin(
+          "@atlaspack/transformer-typescript-types",
+          Some("types:*.ts"),
+        )
+      }],
+      optimizers: Vec::new(),
+      packager: Some(plugin("@atlaspack/packager-ts", Some("*.ts"))),
+      compressors: vec![plugin("@atlaspack/compressor-raw", Some("*"))],
+    };
+
+    assert_eq!(
+      format_explanation(&explanation),
+      String::from(concat!(
+        "Plugins for /project/src/index.ts in the types pipeline\n",
+        "transformers:\n",
+        "  types:*.ts: @atlaspack/transformer-typescript-types (/project/.parcelrc:4:20)\n",
+        "optimizers:\n",
+        "  (none)\n",
+        "packager:\n",
+        "  *.ts: @atlaspack/packager-ts (/project/.parcelrc)\n",
+        "compressors:\n",
+        "  *: @atlaspack/compressor-raw (/project/.parcelrc)\n",
+      ))
+    );
+  }
+
+  #[test]
+  fn formats_phases_without_patterns() {
+    assert_eq!(
+      format_phase(
+        "namers",
+        &[
+          plugin("@company/namer", None),
+          plugin("@atlaspack/namer-default", None)
+        ]
+      ),
+      String::from(concat!(
+        "namers:\n",
+        "  @company/namer (/project/.parcelrc)\n",
+        "  @atlaspack/namer-default (/project/.parcelrc)\n",
+      ))
+    );
+  }
+}
@@ -0,0 +1,109 @@
+use std::io::ErrorKind;
+use std::path::Path;
+
+use atlaspack::file_system::watcher::WatchSnapshot;
+use atlaspack_core::types::BuildMode;
+use clap::Parser;
+
+use crate::args::AtlaspackArgs;
+use crate::cmd::build::build;
+use crate::progress::ProgressReporter;
+
+#[derive(Debug, Parser)]
+pub struct WatchCommand {
+  #[command(flatten)]
+  pub args: AtlaspackArgs,
+}
+
+/// The snapshot written when watching stops, relative to the cache directory
+const SNAPSHOT_FILE: &str = "watcher-snapshot.json";
+
+/// Builds the project in development mode, then rebuilds whenever the changed files invalidate
+/// the build
+///
+/// Build errors are printed rather than returned, so that watching continues until they are
+/// fixed. When the watcher drops events, the changes are recovered by comparing the files against
+/// the snapshot taken when watching started, or when events were last recovered.
+///
+/// A snapshot is written to the cache when watching is stopped with Ctrl+C, so that the changes
+/// made while the command was not running are picked up the next time it starts.
+///
+pub fn main(cmd: WatchCommand) -> anyhow::Result<()> {
+  let atlaspack = cmd.args.create_atlaspack(BuildMode::Development)?;
+  let reporter = ProgressReporter::new();
+
+  // Watching starts before the first build, so that changes made during the build are not missed
+  let cache_dir = cmd.args.cache_dir()?;
+  let snapshot_path = cache_dir.join(SNAPSHOT_FILE);
+  let mut watcher = atlaspack.watch(vec![
+    cache_dir.to_string_lossy().into_owned(),
+    String::from("**/node_modules"),
+  ])?;
+  let mut snapshot = watcher.snapshot()?;
+
+  if let Some(previous) = read_snapshot(&snapshot_path) {
+    let events = snapshot.events_since(&previous);
+    tracing::debug!(?events, "Files changed since watching last stopped");
+
+    if let Err(error) = atlaspack.respond_to_fs_events(events) {
+      eprintln!("{error:#}");
+    }
+  }
+
+  if let Err(error) = build(&atlaspack, &reporter) {
+    eprintln!("{error:#}");
+  }
+
+  // The first Ctrl+C lets the current build finish before the snapshot is written, while a second
+  // one exits straight away
+  let stopper = watcher.stopper();
+  atlaspack.runtime.spawn(async move {
+    if tokio::signal::ctrl_c().await.is_ok() {
+      stopper.stop();
+    }
+
+    if tokio::signal::ctrl_c().await.is_ok() {
+      std::process::exit(130);
+    }
+  });
+
+  eprintln!(
+    "Watching for changes in {}",
+    atlaspack.project_root.display()
+  );
+
+  loop {
+    let events = match watcher.next_events() {
+      Ok(events) => events,
+      Err(error) if error.kind() == ErrorKind::NotConnected => {
+        std::fs::write(&snapshot_path, serde_json::to_vec(&watcher.snapshot()?)?)?;
+        return Ok(());
+      }
+      Err(error) => {
+        eprintln!("{error}, comparing the files against the last snapshot");
+        let current = watcher.snapshot()?;
+        let events = current.events_since(&snapshot);
+        snapshot = current;
+        events
+      }
+    };
+
+    tracing::debug!(?events, "Files changed");
+
+    match atlaspack.respond_to_fs_events(events) {
+      Ok(true) => {
+        if let Err(error) = build(&atlaspack, &reporter) {
+          eprintln!("{error:#}");
+        }
+      }
+      Ok(false) => {}
+      Err(error) => eprintln!("{error:#}"),
+    }
+  }
+}
+
+/// Reads the snapshot written when watching last stopped, if there is a valid one
+fn read_snapshot(path: &Path) -> Option<WatchSnapshot> {
+  let contents = std::fs::read(path).ok()?;
+  serde_json::from_slice(&contents).ok()
+}
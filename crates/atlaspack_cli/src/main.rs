@@ -0,0 +1,71 @@
+mod args;
+mod cmd;
+mod native_rpc;
+mod progress;
+
+use clap::Parser;
+use clap::Subcommand;
+
+#[derive(Debug, Subcommand)]
+pub enum AtlaspackCommandType {
+  /// Build the entries once and write the bundles to disk
+  Build(cmd::build::BuildCommand),
+  /// Build the entries, then rebuild whenever a project file changes
+  Watch(cmd::watch::WatchCommand),
+  /// Build the asset and bundle graphs without packaging, and print a summary
+  Inspect(cmd::inspect::InspectCommand),
+  /// Print the merged config, or the plugins that apply to a file, with where each was configured
+  PrintConfig(cmd::print_config::PrintConfigCommand),
+  /// Export, import, garbage collect, compact or inspect the cache
+  Cache(cmd::cache::CacheCommand),
+  /// List the entries, targets and bundles affected by the changes since a git revision
+  Affected(cmd::affected::AffectedCommand),
+}
+
+/// Builds projects with the native Atlaspack pipeline, without Node.js
+///
+/// Only plugins with a native implementation are supported. Builds fail with an error naming the
+/// plugin when the config references a plugin that can only run in Node.js.
+#[derive(Debug, Parser)]
+#[command(name = "atlaspack", version)]
+pub struct AtlaspackCommand {
+  #[clap(subcommand)]
+  pub command: AtlaspackCommandType,
+  /// Write a Chrome trace of the build to the working directory
+  #[arg(long, global = true)]
+  pub profile: bool,
+}
+
+fn main() -> anyhow::Result<()> {
+  let args = AtlaspackCommand::parse();
+
+  let mut monitoring_options = atlaspack_monitoring::MonitoringOptions::from_env()?;
+  if args.profile
+    && !monitoring_options
+      .tracing_options
+      .contains(&atlaspack_monitoring::TracerMode::Chrome)
+  {
+    monitoring_options
+      .tracing_options
+      .push(atlaspack_monitoring::TracerMode::chrome());
+  }
+
+  atlaspack_monitoring::initialize_monitoring(monitoring_options)?;
+
+  let result = match args.command {
+    AtlaspackCommandType::Build(cmd) => cmd::build::main(cmd),
+    AtlaspackCommandType::Watch(cmd) => cmd::watch::main(cmd),
+    AtlaspackCommandType::Inspect(cmd) => cmd::inspect::main(cmd),
+    AtlaspackCommandType::PrintConfig(cmd) => cmd::print_config::main(cmd),
+    AtlaspackCommandType::Cache(cmd) => cmd::cache::main(cmd),
+    AtlaspackCommandType::Affected(cmd) => cmd::affected::main(cmd),
+  };
+
+  atlaspack_monitoring::close_monitoring();
+
+  // Dropping the guard flushes the Chrome trace, which is otherwise only written on exit of a
+  // Node.js process
+  atlaspack_monitoring::MONITORING_GUARD.lock().take();
+
+  result
+}
@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use atlaspack::rpc::{RpcFactory, RpcWorker, RpcWorkerRef};
+use atlaspack_config::PluginNode;
+use atlaspack_core::plugin::*;
+use atlaspack_core::{diagnostic, diagnostic_error};
+use atlaspack_package_manager::PackageManagerRef;
+
+/// Starts workers for a build that runs without Node.js
+///
+/// Atlaspack only creates plugins through RPC when there is no native implementation, so every
+/// plugin requested from these workers fails with an error that names it. Optimizers can be
+/// skipped with a warning instead when `skip_unsupported_optimizers` is set, as bundles do not
+/// need to be optimized.
+#[derive(Default)]
+pub struct NativeRpcFactory {
+  pub skip_unsupported_optimizers: bool,
+}
+
+impl RpcFactory for NativeRpcFactory {
+  fn start(&self) -> anyhow::Result<RpcWorkerRef> {
+    Ok(Arc::new(NativeRpcWorker {
+      skip_unsupported_optimizers: self.skip_unsupported_optimizers,
+    }))
+  }
+}
+
+struct NativeRpcWorker {
+  skip_unsupported_optimizers: bool,
+}
+
+#[async_trait]
+impl RpcWorker for NativeRpcWorker {
+  fn create_resolver(
+    &self,
+    _ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ResolverPlugin>> {
+    Err(unsupported_plugin(plugin, "resolver"))
+  }
+
+  async fn create_transformer(
+    &self,
+    _ctx: &PluginContext,
+    plugin: &PluginNode,
+    _package_manager: PackageManagerRef,
+  ) -> anyhow::Result<Arc<dyn TransformerPlugin>> {
+    Err(unsupported_plugin(plugin, "transformer"))
+  }
+
+  fn create_compressor(
+    &self,
+    _ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn CompressorPlugin>> {
+    Err(unsupported_plugin(plugin, "compressor"))
+  }
+
+  fn create_namer(
+    &self,
+    _ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn NamerPlugin>> {
+    Err(unsupported_plugin(plugin, "namer"))
+  }
+
+  fn create_optimizer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn OptimizerPlugin>> {
+    if !self.skip_unsupported_optimizers {
+      return Err(diagnostic_error!(
+        "The optimizer {} (configured in {}) does not have a native implementation and requires Node.js. Remove it from the config, build with the Node.js CLI, or pass --skip-unsupported-optimizers to build without it.",
+        plugin.package_name,
+        plugin.resolve_from.display(),
+      ));
+    }
+
+    ctx.logger.warn(diagnostic!(
+      "The optimizer {} (configured in {}) does not have a native implementation and was skipped. Build with the Node.js CLI to run it.",
+      plugin.package_name,
+      plugin.resolve_from.display(),
+    ));
+
+    Ok(Arc::new(SkippedOptimizerPlugin))
+  }
+
+  fn create_packager(
+    &self,
+    _ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn PackagerPlugin>> {
+    Err(unsupported_plugin(plugin, "packager"))
+  }
+
+  fn create_reporter(
+    &self,
+    _ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ReporterPlugin>> {
+    Err(unsupported_plugin(plugin, "reporter"))
+  }
+
+  fn create_runtime(
+    &self,
+    _ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn RuntimePlugin>> {
+    Err(unsupported_plugin(plugin, "runtime"))
+  }
+
+  fn create_validator(
+    &self,
+    _ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ValidatorPlugin>> {
+    Err(unsupported_plugin(plugin, "validator"))
+  }
+}
+
+/// Outputs bundles unchanged in place of an optimizer that requires Node.js
+#[derive(Debug)]
+struct SkippedOptimizerPlugin;
+
+#[async_trait]
+impl OptimizerPlugin for SkippedOptimizerPlugin {
+  async fn optimize(&self, ctx: OptimizeContext) -> anyhow::Result<OptimizeResult> {
+    Ok(OptimizeResult {
+      contents: ctx.contents,
+      map: ctx.map,
+    })
+  }
+}
+
+fn unsupported_plugin(plugin: &PluginNode, phase: &str) -> anyhow::Error {
+  diagnostic_error!(
+    "The {phase} {} (configured in {}) does not have a native implementation and requires Node.js. Remove it from the config or build with the Node.js CLI.",
+    plugin.package_name,
+    plugin.resolve_from.display(),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use atlaspack::file_system::os_file_system::OsFileSystem;
+  use atlaspack_core::config_loader::ConfigLoader;
+  use atlaspack_core::plugin::{PluginLogger, PluginOptions};
+  use atlaspack_core::types::Diagnostic;
+
+  use super::*;
+
+  fn plugin_context() -> PluginContext {
+    let fs = Arc::new(OsFileSystem);
+
+    PluginContext {
+      config: Arc::new(ConfigLoader {
+        fs: fs.clone(),
+        project_root: PathBuf::default(),
+        search_path: PathBuf::default(),
+        files: Default::default(),
+      }),
+      file_system: fs,
+      logger: PluginLogger::default(),
+      options: Arc::new(PluginOptions::default()),
+    }
+  }
+
+  #[test]
+  fn names_the_unsupported_plugin() {
+    let worker = NativeRpcFactory::default().start().unwrap();
+    let error = worker
+      .create_resolver(
+        &plugin_context(),
+        &PluginNode {
+          package_name: String::from("@company/resolver-custom"),
+          resolve_from: Arc::new(PathBuf::from("/project/.atlaspackrc")),
+        },
+      )
+      .err()
+      .unwrap();
+
+    let diagnostic = error.downcast_ref::<Diagnostic>().unwrap();
+
+    assert!(
+      diagnostic
+        .message
+        .contains("resolver @company/resolver-custom")
+    );
+    assert!(diagnostic.message.contains("/project/.atlaspackrc"));
+  }
+
+  #[test]
+  fn rejects_optimizers_unless_they_can_be_skipped() {
+    let ctx = plugin_context();
+    let worker = NativeRpcFactory::default().start().unwrap();
+
+    let error = worker
+      .create_optimizer(
+        &ctx,
+        &PluginNode {
+          package_name: String::from("@atlaspack/optimizer-swc"),
+          resolve_from: Arc::new(PathBuf::from("/project/.atlaspackrc")),
+        },
+      )
+      .err()
+      .unwrap();
+
+    let diagnostic = error.downcast_ref::<Diagnostic>().unwrap();
+
+    assert!(
+      diagnostic
+        .message
+        .contains("optimizer @atlaspack/optimizer-swc")
+    );
+    assert!(diagnostic.message.contains("--skip-unsupported-optimizers"));
+    assert!(ctx.logger.take_warnings().is_empty());
+  }
+
+  #[test]
+  fn skips_optimizers_with_a_warning_when_enabled() {
+    let ctx = plugin_context();
+    let worker = NativeRpcFactory {
+      skip_unsupported_optimizers: true,
+    }
+    .start()
+    .unwrap();
+
+    let optimizer = worker.create_optimizer(
+      &ctx,
+      &PluginNode {
+        package_name: String::from("@atlaspack/optimizer-swc"),
+        resolve_from: Arc::new(PathBuf::from("/project/.atlaspackrc")),
+      },
+    );
+
+    assert_eq!(
+      format!("{:?}", optimizer.unwrap()),
+      String::from("SkippedOptimizerPlugin")
+    );
+
+    let warnings = ctx.logger.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert!(
+      warnings[0]
+        .message
+        .contains("optimizer @atlaspack/optimizer-swc")
+    );
+  }
+}
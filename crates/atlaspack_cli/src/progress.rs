@@ -0,0 +1,170 @@
+use std::io::IsTerminal;
+use std::mem::Discriminant;
+use std::path::Path;
+use std::sync::Arc;
+
+use atlaspack::ReportFn;
+use atlaspack_core::build_progress::BuildProgressEvent;
+use atlaspack_core::types::Diagnostic;
+use parking_lot::Mutex;
+
+/// Prints build progress to stderr
+///
+/// In a terminal the progress is redrawn on a single line. Otherwise, such as in CI logs, a line
+/// is only printed when the build moves on to a new phase.
+pub struct ProgressReporter {
+  is_terminal: bool,
+  last_phase: Mutex<Option<Discriminant<BuildProgressEvent>>>,
+}
+
+impl ProgressReporter {
+  pub fn new() -> Arc<Self> {
+    Arc::new(ProgressReporter {
+      is_terminal: std::io::stderr().is_terminal(),
+      last_phase: Mutex::new(None),
+    })
+  }
+
+  pub fn report_fn(self: &Arc<Self>) -> ReportFn {
+    let reporter = Arc::clone(self);
+    Arc::new(move |event| reporter.report(&event))
+  }
+
+  pub fn report(&self, event: &BuildProgressEvent) {
+    let phase = std::mem::discriminant(event);
+    let is_new_phase = self.last_phase.lock().replace(phase) != Some(phase);
+
+    if self.is_terminal {
+      eprint!("\r\x1b[2K{}", progress_message(event));
+    } else if is_new_phase {
+      eprintln!("{}", progress_message(event));
+    }
+  }
+
+  /// Clears the progress line so that the build output can be printed
+  pub fn finish(&self) {
+    if self.is_terminal && self.last_phase.lock().take().is_some() {
+      eprint!("\r\x1b[2K");
+    }
+  }
+}
+
+pub fn progress_message(event: &BuildProgressEvent) -> String {
+  match event {
+    BuildProgressEvent::Building {
+      complete_assets,
+      total_assets,
+    } => format!("Building... {complete_assets}/{total_assets} assets"),
+    BuildProgressEvent::Bundling => String::from("Bundling..."),
+    BuildProgressEvent::PackagingAndOptimizing {
+      complete_bundles,
+      total_bundles,
+    } => format!("Packaging & optimizing... {complete_bundles}/{total_bundles} bundles"),
+  }
+}
+
+/// Formats a warning reported during the build, with the location of each of its code frames
+pub fn format_warning(project_root: &Path, warning: &Diagnostic) -> String {
+  let mut output = format!("Warning: {}", warning.message);
+
+  for code_frame in &warning.code_frames {
+    let Some(file_path) = &code_frame.file_path else {
+      continue;
+    };
+
+    let file_path = file_path.strip_prefix(project_root).unwrap_or(file_path);
+    match code_frame.code_highlights.first() {
+      Some(highlight) => output.push_str(&format!(
+        "\n  at {}:{}:{}",
+        file_path.display(),
+        highlight.start.line,
+        highlight.start.column
+      )),
+      None => output.push_str(&format!("\n  at {}", file_path.display())),
+    }
+  }
+
+  for hint in &warning.hints {
+    output.push_str(&format!("\n  hint: {hint}"));
+  }
+
+  output
+}
+
+/// Formats a number of bytes for display, e.g. `1.5 KB`
+pub fn format_size(bytes: u64) -> String {
+  const UNITS: &[&str] = &["KB", "MB", "GB"];
+
+  if bytes < 1024 {
+    return format!("{bytes} B");
+  }
+
+  let mut size = bytes as f64 / 1024.0;
+  let mut unit = UNITS[0];
+
+  for next_unit in &UNITS[1..] {
+    if size < 1024.0 {
+      break;
+    }
+
+    size /= 1024.0;
+    unit = next_unit;
+  }
+
+  format!("{size:.2} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn formats_progress_messages() {
+    assert_eq!(
+      progress_message(&BuildProgressEvent::Building {
+        complete_assets: 5,
+        total_assets: 10,
+      }),
+      String::from("Building... 5/10 assets")
+    );
+
+    assert_eq!(
+      progress_message(&BuildProgressEvent::PackagingAndOptimizing {
+        complete_bundles: 1,
+        total_bundles: 2,
+      }),
+      String::from("Packaging & optimizing... 1/2 bundles")
+    );
+  }
+
+  #[test]
+  fn formats_warnings_relative_to_the_project_root() {
+    use atlaspack_core::types::{CodeFrame, CodeHighlight, DiagnosticBuilder};
+
+    let warning = DiagnosticBuilder::default()
+      .code_frames(vec![CodeFrame {
+        code: None,
+        code_highlights: vec![CodeHighlight::from([3, 7])],
+        language: None,
+        file_path: Some("/project/src/styles.css".into()),
+      }])
+      .hints(vec![String::from("Fix the selector")])
+      .message("Invalid selector")
+      .build()
+      .unwrap();
+
+    assert_eq!(
+      format_warning(Path::new("/project"), &warning),
+      String::from("Warning: Invalid selector\n  at src/styles.css:3:7\n  hint: Fix the selector")
+    );
+  }
+
+  #[test]
+  fn formats_sizes() {
+    assert_eq!(format_size(512), String::from("512 B"));
+    assert_eq!(format_size(1536), String::from("1.50 KB"));
+    assert_eq!(format_size(5 * 1024 * 1024), String::from("5.00 MB"));
+  }
+}
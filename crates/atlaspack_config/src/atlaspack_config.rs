@@ -20,7 +20,7 @@ pub struct PluginNode {
 }
 
 /// Represents a fully merged and validated .atlaspack_rc config
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct AtlaspackConfig {
   pub bundler: PluginNode,
   pub compressors: PipelinesMap,
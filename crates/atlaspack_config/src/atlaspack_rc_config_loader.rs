@@ -2,11 +2,14 @@ use std::collections::HashSet;
 use std::path::Path;
 use std::path::PathBuf;
 
+use anyhow::anyhow;
 use atlaspack_core::diagnostic_error;
 use atlaspack_core::types::CodeFrame;
 use atlaspack_core::types::CodeHighlight;
+use atlaspack_core::types::Diagnostic;
 use atlaspack_core::types::DiagnosticBuilder;
 use atlaspack_core::types::DiagnosticError;
+use atlaspack_core::types::Diagnostics;
 use atlaspack_core::types::File;
 use atlaspack_filesystem::FileSystemRef;
 use atlaspack_filesystem::search::find_ancestor_file;
@@ -18,6 +21,9 @@ use super::atlaspack_config::AtlaspackConfig;
 use super::atlaspack_config::PluginNode;
 use super::atlaspack_rc::AtlaspackRcFile;
 use super::atlaspack_rc::Extends;
+use super::atlaspack_rc_validation::RcValue;
+use super::atlaspack_rc_validation::find_replaced_transformers;
+use super::atlaspack_rc_validation::validate_atlaspack_rc;
 use super::partial_atlaspack_config::PartialAtlaspackConfig;
 
 #[derive(Default)]
@@ -70,6 +76,7 @@ impl AtlaspackRcConfigLoader {
   fn load_config(
     &self,
     path: PathBuf,
+    warnings: &mut Vec<Diagnostic>,
   ) -> Result<(PartialAtlaspackConfig, Vec<PathBuf>), DiagnosticError> {
     let raw = self.fs.read_to_string(&path).map_err(|source| {
       diagnostic_error!(
@@ -79,21 +86,30 @@ impl AtlaspackRcConfigLoader {
       )
     })?;
 
-    let contents = serde_json5::from_str(&raw).map_err(|error| {
-      serde_to_diagnostic_error(
-        error,
-        File {
-          contents: raw.clone(),
-          path: path.clone(),
-        },
-      )
-    })?;
+    let file = File {
+      contents: raw.clone(),
+      path: path.clone(),
+    };
 
-    self.process_config(AtlaspackRcFile {
-      contents,
-      path,
-      raw,
-    })
+    let value = serde_json5::from_str::<RcValue>(&raw)
+      .map_err(|error| serde_to_diagnostic_error(error, file.clone()))?;
+
+    let diagnostics = validate_atlaspack_rc(&file, &value);
+    if !diagnostics.is_empty() {
+      return Err(anyhow!(Diagnostics::from(diagnostics)));
+    }
+
+    let contents = serde_json5::from_str(&raw)
+      .map_err(|error| serde_to_diagnostic_error(error, file.clone()))?;
+
+    self.process_config(
+      AtlaspackRcFile {
+        contents,
+        path,
+        raw,
+      },
+      warnings,
+    )
   }
 
   fn resolve_extends(
@@ -141,11 +157,13 @@ impl AtlaspackRcConfigLoader {
   ///
   /// Configuration merging will be applied to all "extends" configurations, before being merged
   /// into the base config for a more natural merging order. It will replace any "..." seen in
-  /// plugin pipelines with the corresponding plugins from "extends" if present.
+  /// plugin pipelines with the corresponding plugins from "extends" if present. Pipelines that
+  /// replace an extended pipeline without "..." are added to `warnings`.
   ///
   fn process_config(
     &self,
     atlaspack_rc_file: AtlaspackRcFile,
+    warnings: &mut Vec<Diagnostic>,
   ) -> Result<(PartialAtlaspackConfig, Vec<PathBuf>), DiagnosticError> {
     let mut files = vec![atlaspack_rc_file.path.clone()];
     let extends = atlaspack_rc_file.contents.extends.as_ref();
@@ -164,7 +182,8 @@ impl AtlaspackRcConfigLoader {
     let mut merged_config: Option<PartialAtlaspackConfig> = None;
     for extend in extends {
       let extended_file_path = self.resolve_extends(&atlaspack_rc_file, &extend)?;
-      let (extended_config, mut extended_file_paths) = self.load_config(extended_file_path)?;
+      let (extended_config, mut extended_file_paths) =
+        self.load_config(extended_file_path, warnings)?;
 
       merged_config = match merged_config {
         None => Some(extended_config),
@@ -174,9 +193,16 @@ impl AtlaspackRcConfigLoader {
       files.append(&mut extended_file_paths);
     }
 
+    let merged_config = merged_config.unwrap();
+
+    warnings.extend(find_replaced_transformers(
+      &atlaspack_rc_file,
+      &merged_config,
+    ));
+
     let config = PartialAtlaspackConfig::merge(
       PartialAtlaspackConfig::try_from(atlaspack_rc_file)?,
-      merged_config.unwrap(),
+      merged_config,
     );
 
     Ok((config, files))
@@ -189,11 +215,13 @@ impl AtlaspackRcConfigLoader {
   /// current working directory does not live within the project root, the default config will be
   /// loaded from the project root.
   ///
+  /// Returns the config, the files it was loaded from, and warnings about the config.
+  ///
   pub fn load(
     &self,
     project_root: &Path,
     options: LoadConfigOptions,
-  ) -> Result<(AtlaspackConfig, Vec<PathBuf>), DiagnosticError> {
+  ) -> Result<(AtlaspackConfig, Vec<PathBuf>, Vec<Diagnostic>), DiagnosticError> {
     let resolve_from = self.resolve_from(project_root);
     let mut config_path = match options.config {
       Some(config) => self
@@ -225,7 +253,8 @@ impl AtlaspackRcConfigLoader {
     }
 
     let config_path = config_path?;
-    let (mut atlaspack_config, files) = self.load_config(config_path)?;
+    let mut warnings = Vec::new();
+    let (mut atlaspack_config, files) = self.load_config(config_path, &mut warnings)?;
 
     if !options.additional_reporters.is_empty() {
       atlaspack_config
@@ -245,7 +274,7 @@ impl AtlaspackRcConfigLoader {
 
     let atlaspack_config = AtlaspackConfig::try_from(atlaspack_config)?;
 
-    Ok((atlaspack_config, files))
+    Ok((atlaspack_config, files, warnings))
   }
 }
 
@@ -412,6 +441,74 @@ mod tests {
       );
     }
 
+    #[test]
+    fn errors_with_every_schema_problem_in_parcelrc() {
+      let project_root = PathBuf::from("/test");
+      let fs = test_fixture! {
+        project_root.clone(),
+        ".parcelrc" => {r#"
+          {
+            "bundler": "@atlaspack/bundler-default",
+            "namers": ["@atlaspack/namer-default"],
+            "resolvers": ["@atlaspack/resolver-default", 1],
+            "transfomers": {
+              "*.js": ["@atlaspack/transformer-js"]
+            },
+            "packagers": {
+              "*.{js": "@atlaspack/packager-js"
+            }
+          }
+        "#}
+      };
+
+      let package_manager = Arc::new(TestPackageManager {
+        fs: Arc::clone(&fs),
+      });
+
+      let diagnostics = AtlaspackRcConfigLoader::new(Arc::clone(&fs), package_manager)
+        .load(&project_root, LoadConfigOptions::default())
+        .unwrap_err()
+        .downcast::<Diagnostics>()
+        .expect("Expected diagnostics");
+
+      let config_path = project_root.join(".parcelrc");
+
+      assert_eq!(
+        diagnostics
+          .diagnostics
+          .iter()
+          .map(|diagnostic| (
+            diagnostic.message.clone(),
+            diagnostic.code_frames[0].file_path.clone(),
+            diagnostic.code_frames[0].code_highlights[0].start.line,
+          ))
+          .collect::<Vec<_>>(),
+        vec![
+          (
+            format!(
+              "Plugin names in \"resolvers\" must be strings, but found a number in {}",
+              config_path.display()
+            ),
+            Some(config_path.clone()),
+            4
+          ),
+          (
+            format!("Unknown field \"transfomers\" in {}", config_path.display()),
+            Some(config_path.clone()),
+            5
+          ),
+          (
+            format!(
+              "Invalid glob \"*.{{js\" in \"packagers\": \"{{\" is not closed in {}",
+              config_path.display()
+            ),
+            Some(config_path.clone()),
+            9
+          ),
+        ]
+      );
+    }
+
     #[test]
     fn returns_default_atlaspack_config() {
       let project_root = PathBuf::from("/test");
@@ -499,7 +596,10 @@ mod tests {
 
       let expected_files = vec![project_root.join(".parcelrc")];
 
-      assert_eq!(atlaspack_config, Ok((expected_config, expected_files)));
+      assert_eq!(
+        atlaspack_config,
+        Ok((expected_config, expected_files, Vec::new()))
+      );
     }
 
     #[test]
@@ -519,7 +619,7 @@ mod tests {
 
       assert_eq!(
         atlaspack_config,
-        Ok((default_config.atlaspack_config, files))
+        Ok((default_config.atlaspack_config, files, Vec::new()))
       );
     }
 
@@ -541,7 +641,7 @@ mod tests {
 
       assert_eq!(
         atlaspack_config,
-        Ok((default_config.atlaspack_config, files))
+        Ok((default_config.atlaspack_config, files, Vec::new()))
       );
     }
 
@@ -606,7 +706,7 @@ mod tests {
       ];
 
       assert!(atlaspack_config.is_ok());
-      let (config, files) = atlaspack_config.unwrap();
+      let (config, files, _) = atlaspack_config.unwrap();
 
       // Verify files were loaded correctly
       assert_eq!(files, expected_files);
@@ -664,7 +764,7 @@ mod tests {
         panic!("Config loading failed: {}", e);
       }
       assert!(atlaspack_config.is_ok());
-      let (config, _files) = atlaspack_config.unwrap();
+      let (config, _files, warnings) = atlaspack_config.unwrap();
 
       // The user's transformer should take precedence, not the base config
       let svg_transformers = config.transformers.get(&PathBuf::from("icon.svg"), None);
@@ -680,6 +780,21 @@ mod tests {
           .iter()
           .any(|t| t.package_name == "@atlaspack/transformer-svg")
       );
+
+      // Replacing the pipeline without "..." is reported as a warning located in the user config
+      assert_eq!(
+        warnings
+          .iter()
+          .map(|warning| (
+            warning.message.as_str(),
+            warning.code_frames[0].file_path.clone()
+          ))
+          .collect::<Vec<_>>(),
+        vec![(
+          "The transformers for \"*.svg\" replace @atlaspack/transformer-svg from the extended config in /test/.parcelrc",
+          Some(project_root.join(".parcelrc"))
+        )]
+      );
     }
 
     #[test]
@@ -718,7 +833,7 @@ mod tests {
         .map_err(|e| e.to_string());
 
       assert!(atlaspack_config.is_ok());
-      let (config, _files) = atlaspack_config.unwrap();
+      let (config, _files, _) = atlaspack_config.unwrap();
 
       // Should have merged transformers: pre + base + post
       let js_transformers = config.transformers.get(&PathBuf::from("app.js"), None);
@@ -768,7 +883,7 @@ mod tests {
         .map_err(|e| e.to_string());
 
       assert!(atlaspack_config.is_ok());
-      let (config, _files) = atlaspack_config.unwrap();
+      let (config, _files, _) = atlaspack_config.unwrap();
 
       // SVG is actually matching *.{svg,png} pattern first and getting flattened
       // This pattern has "..." so it returns user + base transformer
@@ -830,7 +945,7 @@ mod tests {
         .map_err(|e| e.to_string());
 
       assert!(atlaspack_config.is_ok());
-      let (config, _files) = atlaspack_config.unwrap();
+      let (config, _files, _) = atlaspack_config.unwrap();
 
       // TS: base transformer first, then post-processor
       let ts_transformers = config.transformers.get(&PathBuf::from("app.ts"), None);
@@ -885,7 +1000,7 @@ mod tests {
       let pm = Arc::new(TestPackageManager {
         fs: Arc::clone(&fs),
       });
-      let (config, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
+      let (config, _, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
         .load(&project_root, LoadConfigOptions::default())
         .expect("config should load");
 
@@ -932,7 +1047,7 @@ mod tests {
       let pm = Arc::new(TestPackageManager {
         fs: Arc::clone(&fs),
       });
-      let (config, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
+      let (config, _, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
         .load(&project_root, LoadConfigOptions::default())
         .expect("config should load");
 
@@ -978,7 +1093,7 @@ mod tests {
       let pm = Arc::new(TestPackageManager {
         fs: Arc::clone(&fs),
       });
-      let (config, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
+      let (config, _, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
         .load(&project_root, LoadConfigOptions::default())
         .expect("config should load");
 
@@ -1019,7 +1134,7 @@ mod tests {
       let pm = Arc::new(TestPackageManager {
         fs: Arc::clone(&fs),
       });
-      let (config, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
+      let (config, _, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
         .load(&project_root, LoadConfigOptions::default())
         .expect("config should load");
 
@@ -1066,7 +1181,7 @@ mod tests {
       let pm = Arc::new(TestPackageManager {
         fs: Arc::clone(&fs),
       });
-      let (config, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
+      let (config, _, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
         .load(&project_root, LoadConfigOptions::default())
         .expect("config should load");
 
@@ -1113,7 +1228,7 @@ mod tests {
       let pm = Arc::new(TestPackageManager {
         fs: Arc::clone(&fs),
       });
-      let (config, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
+      let (config, _, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
         .load(&project_root, LoadConfigOptions::default())
         .expect("config should load");
 
@@ -1160,7 +1275,7 @@ mod tests {
       let pm = Arc::new(TestPackageManager {
         fs: Arc::clone(&fs),
       });
-      let (config, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
+      let (config, _, _) = AtlaspackRcConfigLoader::new(Arc::clone(&fs), pm)
         .load(&project_root, LoadConfigOptions::default())
         .expect("config should load");
 
@@ -1210,7 +1325,7 @@ mod tests {
         .map_err(|e| e.to_string());
 
       assert!(atlaspack_config.is_ok());
-      let (config, _files) = atlaspack_config.unwrap();
+      let (config, _files, _) = atlaspack_config.unwrap();
 
       // JS files should match the first pattern in user config (*.{js,ts,tsx})
       let js_transformers = config.transformers.get(&PathBuf::from("app.js"), None);
@@ -1375,7 +1490,7 @@ mod tests {
 
       assert_eq!(
         atlaspack_config,
-        Ok((specified_config.atlaspack_config, files))
+        Ok((specified_config.atlaspack_config, files, Vec::new()))
       );
     }
   }
@@ -1527,7 +1642,8 @@ mod tests {
         atlaspack_config,
         Ok((
           project_root_config.atlaspack_config,
-          vec!(project_root_config.path)
+          vec!(project_root_config.path),
+          Vec::new()
         ))
       );
     }
@@ -1558,7 +1674,10 @@ mod tests {
         )
         .map_err(|e| e.to_string());
 
-      assert_eq!(atlaspack_config, Ok((fallback.atlaspack_config, files)));
+      assert_eq!(
+        atlaspack_config,
+        Ok((fallback.atlaspack_config, files, Vec::new()))
+      );
     }
   }
 
@@ -1597,7 +1716,10 @@ mod tests {
         )
         .map_err(|e| e.to_string());
 
-      assert_eq!(atlaspack_config, Ok((config.atlaspack_config, files)));
+      assert_eq!(
+        atlaspack_config,
+        Ok((config.atlaspack_config, files, Vec::new()))
+      );
     }
 
     #[test]
@@ -1628,7 +1750,10 @@ mod tests {
         )
         .map_err(|e| e.to_string());
 
-      assert_eq!(atlaspack_config, Ok((fallback.atlaspack_config, files)));
+      assert_eq!(
+        atlaspack_config,
+        Ok((fallback.atlaspack_config, files, Vec::new()))
+      );
     }
   }
 
@@ -1669,7 +1794,7 @@ mod tests {
 
       let config_loader = AtlaspackRcConfigLoader::new(Arc::clone(&fs), package_manager);
 
-      let (atlaspack_config, _files) = config_loader
+      let (atlaspack_config, _files, _) = config_loader
         .load(
           &project_root,
           LoadConfigOptions {
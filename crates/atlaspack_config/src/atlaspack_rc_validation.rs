@@ -0,0 +1,640 @@
+use atlaspack_core::diagnostic;
+use atlaspack_core::types::CodeFrame;
+use atlaspack_core::types::CodeHighlight;
+use atlaspack_core::types::Diagnostic;
+use atlaspack_core::types::DiagnosticBuilder;
+use atlaspack_core::types::File;
+use indexmap::IndexMap;
+use serde::Deserialize;
+
+use super::atlaspack_rc::AtlaspackRcFile;
+use super::partial_atlaspack_config::PartialAtlaspackConfig;
+use crate::locate::find_key;
+
+/// A loosely typed .parcelrc, used to find every problem with a config before it is deserialized
+/// into an `AtlaspackRc`
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum RcValue {
+  Null,
+  // Only the type of booleans and numbers is needed, but untagged variants need a value to match
+  #[allow(dead_code)]
+  Bool(bool),
+  #[allow(dead_code)]
+  Number(f64),
+  String(String),
+  Array(Vec<RcValue>),
+  Object(IndexMap<String, RcValue>),
+}
+
+impl RcValue {
+  fn description(&self) -> &'static str {
+    match self {
+      RcValue::Null => "null",
+      RcValue::Bool(_) => "a boolean",
+      RcValue::Number(_) => "a number",
+      RcValue::String(_) => "a string",
+      RcValue::Array(_) => "an array",
+      RcValue::Object(_) => "an object",
+    }
+  }
+}
+
+/// The shape of the value for each top-level key
+#[derive(Clone, Copy)]
+enum Field {
+  Alias,
+  Extends,
+  /// A single plugin, such as the bundler
+  Plugin,
+  /// A map of globs to a single plugin, such as packagers
+  Pipeline,
+  /// A map of globs to a list of plugins, such as transformers
+  Pipelines,
+  /// A list of plugins, such as resolvers
+  Plugins,
+  Schema,
+}
+
+const FIELDS: [(&str, Field); 13] = [
+  ("$schema", Field::Schema),
+  ("extends", Field::Extends),
+  ("bundler", Field::Plugin),
+  ("compressors", Field::Pipelines),
+  ("namers", Field::Plugins),
+  ("optimizers", Field::Pipelines),
+  ("packagers", Field::Pipeline),
+  ("reporters", Field::Plugins),
+  ("resolvers", Field::Plugins),
+  ("runtimes", Field::Plugins),
+  ("transformers", Field::Pipelines),
+  ("validators", Field::Pipelines),
+  ("unstable_alias", Field::Alias),
+];
+
+/// Checks a .parcelrc against the config schema, returning a diagnostic for every problem found
+///
+/// Each diagnostic has a code frame that highlights the key containing the problem.
+///
+pub fn validate_atlaspack_rc(file: &File, value: &RcValue) -> Vec<Diagnostic> {
+  let mut validator = Validator {
+    diagnostics: Vec::new(),
+    file,
+  };
+
+  let RcValue::Object(config) = value else {
+    validator.error(
+      &[],
+      format!("Expected an object, but found {}", value.description()),
+      None,
+    );
+
+    return validator.diagnostics;
+  };
+
+  for (key, value) in config.iter() {
+    let Some((_, field)) = FIELDS.iter().find(|(name, _)| *name == key.as_str()) else {
+      let hint = FIELDS
+        .iter()
+        .map(|(name, _)| *name)
+        .filter(|name| edit_distance(name, key) <= 2)
+        .min_by_key(|name| edit_distance(name, key))
+        .map(|name| format!("Did you mean \"{name}\"?"));
+
+      validator.error(&[key.as_str()], format!("Unknown field \"{key}\""), hint);
+      continue;
+    };
+
+    validator.field(key, *field, value);
+  }
+
+  validator.diagnostics
+}
+
+/// Finds transformer pipelines that replace a pipeline with the same glob from an extended config
+///
+/// Replacing a pipeline is allowed, but is easy to do by accident when the intent was to add a
+/// transformer, so these are reported as warnings rather than errors.
+///
+pub fn find_replaced_transformers(
+  file: &AtlaspackRcFile,
+  extended_config: &PartialAtlaspackConfig,
+) -> Vec<Diagnostic> {
+  let validator = Validator {
+    diagnostics: Vec::new(),
+    file: &File::from(file),
+  };
+
+  let Some(transformers) = file.contents.transformers.as_ref() else {
+    return Vec::new();
+  };
+
+  let mut warnings = Vec::new();
+
+  for (pattern, plugins) in transformers.iter() {
+    if plugins.iter().any(|plugin| plugin == "...") {
+      continue;
+    }
+
+    let Some(extended_plugins) = extended_config.transformers.get(pattern) else {
+      continue;
+    };
+
+    let replaced = extended_plugins
+      .iter()
+      .map(|plugin| plugin.package_name.as_str())
+      .filter(|package_name| *package_name != "..." && !plugins.iter().any(|p| p == *package_name))
+      .collect::<Vec<&str>>();
+
+    if replaced.is_empty() {
+      continue;
+    }
+
+    warnings.push(validator.diagnostic(
+      &["transformers", pattern.as_str()],
+      format!(
+        "The transformers for \"{pattern}\" replace {} from the extended config",
+        replaced.join(", ")
+      ),
+      Some(String::from(
+        "Add \"...\" to the pipeline to run the extended transformers as well",
+      )),
+    ));
+  }
+
+  warnings
+}
+
+struct Validator<'a> {
+  diagnostics: Vec<Diagnostic>,
+  file: &'a File,
+}
+
+impl Validator<'_> {
+  fn error(&mut self, keys: &[&str], message: String, hint: Option<String>) {
+    let diagnostic = self.diagnostic(keys, message, hint);
+    self.diagnostics.push(diagnostic);
+  }
+
+  /// Creates a diagnostic that highlights the value at `keys` in the file
+  fn diagnostic(&self, keys: &[&str], message: String, hint: Option<String>) -> Diagnostic {
+    let code_highlights = find_key(&self.file.contents, keys)
+      .map(|(start, end)| CodeHighlight {
+        message: None,
+        start,
+        end,
+      })
+      .into_iter()
+      .collect();
+
+    diagnostic!(
+      DiagnosticBuilder::default()
+        .message(format!("{message} in {}", self.file.path.display()))
+        .code_frames(vec![CodeFrame {
+          code_highlights,
+          ..CodeFrame::from(self.file.clone())
+        }])
+        .hints(hint.into_iter().collect())
+    )
+  }
+
+  fn field(&mut self, key: &str, field: Field, value: &RcValue) {
+    match field {
+      Field::Schema => self.string(&[key], value),
+      Field::Extends => match value {
+        RcValue::Array(extends) => {
+          for extend in extends {
+            self.string(&[key], extend);
+          }
+        }
+        value => self.string(&[key], value),
+      },
+      Field::Plugin => self.plugin(&[key], value, false),
+      Field::Plugins => self.plugins(&[key], value),
+      Field::Pipeline => self.object(&[key], value, |validator, pattern, value| {
+        validator.glob(key, pattern);
+        validator.plugin(&[key, pattern], value, false);
+      }),
+      Field::Pipelines => self.object(&[key], value, |validator, pattern, value| {
+        validator.glob(key, pattern);
+        validator.plugins(&[key, pattern], value);
+      }),
+      Field::Alias => self.object(&[key], value, |validator, alias, value| {
+        validator.string(&[key, alias], value);
+      }),
+    }
+  }
+
+  fn object(
+    &mut self,
+    keys: &[&str],
+    value: &RcValue,
+    mut validate: impl FnMut(&mut Self, &str, &RcValue),
+  ) {
+    let RcValue::Object(map) = value else {
+      self.expected(keys, "an object", value);
+      return;
+    };
+
+    for (key, value) in map.iter() {
+      validate(self, key, value);
+    }
+  }
+
+  fn string(&mut self, keys: &[&str], value: &RcValue) {
+    if !matches!(value, RcValue::String(_)) {
+      self.expected(keys, "a string", value);
+    }
+  }
+
+  fn plugin(&mut self, keys: &[&str], value: &RcValue, allow_spread: bool) {
+    match value {
+      RcValue::String(name) if name == "..." && !allow_spread => self.error(
+        keys,
+        format!("\"...\" cannot be used in \"{}\"", keys.join(" > ")),
+        Some(String::from(
+          "\"...\" can only be used in a list of plugins to include the plugins from extended configs",
+        )),
+      ),
+      RcValue::String(_) => {}
+      value => self.error(
+        keys,
+        format!(
+          "Plugin names in \"{}\" must be strings, but found {}",
+          keys.join(" > "),
+          value.description()
+        ),
+        None,
+      ),
+    }
+  }
+
+  fn plugins(&mut self, keys: &[&str], value: &RcValue) {
+    let RcValue::Array(plugins) = value else {
+      self.expected(keys, "an array of plugin names", value);
+      return;
+    };
+
+    for plugin in plugins {
+      self.plugin(keys, plugin, true);
+    }
+
+    let spreads = plugins
+      .iter()
+      .filter(|plugin| matches!(plugin, RcValue::String(name) if name == "..."))
+      .count();
+
+    if spreads > 1 {
+      self.error(
+        keys,
+        format!("\"...\" can only be used once in \"{}\"", keys.join(" > ")),
+        None,
+      );
+    }
+  }
+
+  fn glob(&mut self, key: &str, pattern: &str) {
+    if let Some(problem) = glob_problem(pattern) {
+      self.error(
+        &[key, pattern],
+        format!("Invalid glob \"{pattern}\" in \"{key}\": {problem}"),
+        None,
+      );
+    }
+  }
+
+  fn expected(&mut self, keys: &[&str], expected: &str, value: &RcValue) {
+    self.error(
+      keys,
+      format!(
+        "Expected \"{}\" to be {expected}, but found {}",
+        keys.join(" > "),
+        value.description()
+      ),
+      None,
+    );
+  }
+}
+
+/// Describes why a glob, which may be prefixed by a named pipeline, cannot be matched
+fn glob_problem(pattern: &str) -> Option<&'static str> {
+  let glob = match pattern.split_once(':') {
+    Some(("", _)) => return Some("named pipelines must have a name before the \":\""),
+    Some((_, glob)) => glob,
+    None => pattern,
+  };
+
+  if glob.is_empty() {
+    return Some("globs cannot be empty");
+  }
+
+  let mut braces = 0;
+  let mut in_brackets = false;
+  let mut chars = glob.chars();
+
+  while let Some(c) = chars.next() {
+    match c {
+      '\\' => {
+        chars.next();
+      }
+      '[' if !in_brackets => in_brackets = true,
+      ']' if in_brackets => in_brackets = false,
+      '{' if !in_brackets => braces += 1,
+      '}' if !in_brackets => {
+        if braces == 0 {
+          return Some("\"}\" does not close a \"{\"");
+        }
+
+        braces -= 1;
+      }
+      _ => {}
+    }
+  }
+
+  if in_brackets {
+    Some("\"[\" is not closed")
+  } else if braces > 0 {
+    Some("\"{\" is not closed")
+  } else {
+    None
+  }
+}
+
+/// The number of single character edits to turn one string into another
+fn edit_distance(a: &str, b: &str) -> usize {
+  let b = b.chars().collect::<Vec<char>>();
+  let mut previous = (0..=b.len()).collect::<Vec<usize>>();
+
+  for (i, a) in a.chars().enumerate() {
+    let mut current = vec![i + 1];
+
+    for (j, b) in b.iter().enumerate() {
+      let substitution = previous[j] + usize::from(a != *b);
+      current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+    }
+
+    previous = current;
+  }
+
+  previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+  use std::sync::Arc;
+
+  use atlaspack_core::types::Location;
+  use indexmap::indexmap;
+
+  use crate::PluginNode;
+
+  use super::*;
+
+  fn validate(contents: &str) -> Vec<(String, Option<Location>, Vec<String>)> {
+    let file = File {
+      contents: String::from(contents),
+      path: PathBuf::from("/project/.parcelrc"),
+    };
+
+    let value = serde_json5::from_str(contents).unwrap();
+
+    validate_atlaspack_rc(&file, &value)
+      .into_iter()
+      .map(|diagnostic| {
+        (
+          diagnostic.message,
+          diagnostic.code_frames[0]
+            .code_highlights
+            .first()
+            .map(|highlight| highlight.start.clone()),
+          diagnostic.hints,
+        )
+      })
+      .collect()
+  }
+
+  fn at(line: usize, column: usize) -> Option<Location> {
+    Some(Location { line, column })
+  }
+
+  #[test]
+  fn accepts_a_valid_config() {
+    assert_eq!(
+      validate(
+        r#"{
+          "$schema": "https://example.com/schema.json",
+          "extends": ["@atlaspack/config-default"],
+          "bundler": "@atlaspack/bundler-default",
+          "namers": ["...", "@company/namer"],
+          "packagers": { "*.js": "@atlaspack/packager-js" },
+          "transformers": {
+            "types:*.{ts,tsx}": ["@atlaspack/transformer-typescript-types"],
+            "*.[jt]s": ["...", "@company/transformer"]
+          },
+          unstable_alias: { "react": "preact" }
+        }"#
+      ),
+      Vec::new()
+    );
+  }
+
+  #[test]
+  fn reports_unknown_fields_with_suggestions() {
+    assert_eq!(
+      validate(
+        r#"{
+          "transfomers": {},
+          "plugins": []
+        }"#
+      ),
+      vec![
+        (
+          String::from("Unknown field \"transfomers\" in /project/.parcelrc"),
+          at(2, 12),
+          vec![String::from("Did you mean \"transformers\"?")]
+        ),
+        (
+          String::from("Unknown field \"plugins\" in /project/.parcelrc"),
+          at(3, 12),
+          Vec::new()
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_every_invalid_plugin() {
+    assert_eq!(
+      validate(
+        r#"{
+          "bundler": 1,
+          "resolvers": "@atlaspack/resolver-default",
+          "transformers": {
+            "*.js": ["@atlaspack/transformer-js", true, "...", "..."]
+          },
+          "packagers": {
+            "*.js": "..."
+          }
+        }"#
+      ),
+      vec![
+        (
+          String::from(
+            "Plugin names in \"bundler\" must be strings, but found a number in /project/.parcelrc"
+          ),
+          at(2, 12),
+          Vec::new()
+        ),
+        (
+          String::from(
+            "Expected \"resolvers\" to be an array of plugin names, but found a string in /project/.parcelrc"
+          ),
+          at(3, 12),
+          Vec::new()
+        ),
+        (
+          String::from(
+            "Plugin names in \"transformers > *.js\" must be strings, but found a boolean in /project/.parcelrc"
+          ),
+          at(5, 14),
+          Vec::new()
+        ),
+        (
+          String::from(
+            "\"...\" can only be used once in \"transformers > *.js\" in /project/.parcelrc"
+          ),
+          at(5, 14),
+          Vec::new()
+        ),
+        (
+          String::from("\"...\" cannot be used in \"packagers > *.js\" in /project/.parcelrc"),
+          at(8, 14),
+          vec![String::from(
+            "\"...\" can only be used in a list of plugins to include the plugins from extended configs"
+          )]
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_invalid_globs() {
+    assert_eq!(
+      validate(
+        r#"{
+          "transformers": {
+            "*.{js,ts": [],
+            ":*.js": [],
+            "*.[jt": [],
+            "types:": []
+          }
+        }"#
+      )
+      .into_iter()
+      .map(|(message, location, _)| (message, location))
+      .collect::<Vec<_>>(),
+      vec![
+        (
+          String::from(
+            "Invalid glob \"*.{js,ts\" in \"transformers\": \"{\" is not closed in /project/.parcelrc"
+          ),
+          at(3, 14)
+        ),
+        (
+          String::from(
+            "Invalid glob \":*.js\" in \"transformers\": named pipelines must have a name before the \":\" in /project/.parcelrc"
+          ),
+          at(4, 14)
+        ),
+        (
+          String::from(
+            "Invalid glob \"*.[jt\" in \"transformers\": \"[\" is not closed in /project/.parcelrc"
+          ),
+          at(5, 14)
+        ),
+        (
+          String::from(
+            "Invalid glob \"types:\" in \"transformers\": globs cannot be empty in /project/.parcelrc"
+          ),
+          at(6, 14)
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_a_config_that_is_not_an_object() {
+    assert_eq!(
+      validate("[]"),
+      vec![(
+        String::from("Expected an object, but found an array in /project/.parcelrc"),
+        None,
+        Vec::new()
+      )]
+    );
+  }
+
+  #[test]
+  fn finds_transformers_that_replace_extended_pipelines() {
+    let raw = String::from(
+      r#"{
+        "extends": "@atlaspack/config-default",
+        "transformers": {
+          "*.svg": ["@company/transformer-svg"],
+          "*.js": ["@company/transformer-js", "..."],
+          "*.css": ["@atlaspack/transformer-css"]
+        }
+      }"#,
+    );
+
+    let file = AtlaspackRcFile {
+      contents: serde_json5::from_str(&raw).unwrap(),
+      path: PathBuf::from("/project/.parcelrc"),
+      raw,
+    };
+
+    let extended_plugins = |package_name: &str| {
+      vec![PluginNode {
+        package_name: String::from(package_name),
+        resolve_from: Arc::new(PathBuf::from("/project/node_modules/config/index.json")),
+      }]
+    };
+
+    let extended_config = PartialAtlaspackConfig {
+      transformers: indexmap! {
+        String::from("*.svg") => extended_plugins("@atlaspack/transformer-svg"),
+        String::from("*.js") => extended_plugins("@atlaspack/transformer-js"),
+        String::from("*.css") => extended_plugins("@atlaspack/transformer-css"),
+      },
+      ..PartialAtlaspackConfig::default()
+    };
+
+    let diagnostics = find_replaced_transformers(&file, &extended_config);
+
+    assert_eq!(
+      diagnostics
+        .iter()
+        .map(|diagnostic| (
+          diagnostic.message.as_str(),
+          diagnostic.code_frames[0].code_highlights[0].start.clone()
+        ))
+        .collect::<Vec<_>>(),
+      vec![(
+        "The transformers for \"*.svg\" replace @atlaspack/transformer-svg from the extended config in /project/.parcelrc",
+        Location {
+          line: 4,
+          column: 12
+        }
+      )]
+    );
+  }
+
+  #[test]
+  fn computes_edit_distance() {
+    assert_eq!(edit_distance("transformers", "transformers"), 0);
+    assert_eq!(edit_distance("transformers", "transfomers"), 1);
+    assert_eq!(edit_distance("namers", "nammer"), 2);
+    assert_eq!(edit_distance("", "abc"), 3);
+  }
+}
@@ -0,0 +1,396 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use atlaspack_core::types::SourceLocation;
+use atlaspack_filesystem::FileSystemRef;
+use serde::Serialize;
+
+use crate::AtlaspackConfig;
+use crate::PluginNode;
+use crate::locate::find_value;
+use crate::map::NamedPattern;
+
+/// A plugin from the merged config, along with where it was configured
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfiguredPlugin {
+  pub package_name: String,
+
+  /// The config file that declared the plugin, which may be a config that was extended
+  pub config_file: PathBuf,
+
+  /// The glob that contributed the plugin, for phases that are keyed by file path
+  pub pattern: Option<String>,
+
+  /// The position of the plugin name in the config file
+  ///
+  /// This is only populated by `AtlaspackConfig::print`, since it requires reading the config
+  /// files again.
+  ///
+  pub loc: Option<SourceLocation>,
+}
+
+impl ConfiguredPlugin {
+  fn new(pattern: Option<&str>, plugin: &PluginNode) -> Self {
+    ConfiguredPlugin {
+      package_name: plugin.package_name.clone(),
+      config_file: plugin.resolve_from.to_path_buf(),
+      pattern: pattern.map(String::from),
+      loc: None,
+    }
+  }
+}
+
+/// The plugins that apply to a file path, in the order that they run
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PluginsExplanation {
+  pub file_path: PathBuf,
+  pub pipeline: Option<String>,
+  pub transformers: Vec<ConfiguredPlugin>,
+  pub optimizers: Vec<ConfiguredPlugin>,
+  pub packager: Option<ConfiguredPlugin>,
+  pub compressors: Vec<ConfiguredPlugin>,
+}
+
+/// The fully merged config, where every plugin is annotated with where it was configured
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrintedConfig {
+  pub bundler: ConfiguredPlugin,
+  pub compressors: Vec<ConfiguredPlugin>,
+  pub namers: Vec<ConfiguredPlugin>,
+  pub optimizers: Vec<ConfiguredPlugin>,
+  pub packagers: Vec<ConfiguredPlugin>,
+  pub reporters: Vec<ConfiguredPlugin>,
+  pub resolvers: Vec<ConfiguredPlugin>,
+  pub runtimes: Vec<ConfiguredPlugin>,
+  pub transformers: Vec<ConfiguredPlugin>,
+  pub validators: Vec<ConfiguredPlugin>,
+}
+
+impl AtlaspackConfig {
+  /// Resolves the plugins that will be used for the given file path and named pipeline
+  ///
+  /// Transformers only fall back to unnamed patterns when there is no pipeline, whereas
+  /// optimizers always do, which matches how the plugins are loaded during a build.
+  ///
+  pub fn explain(&self, file_path: &Path, pipeline: Option<&str>) -> PluginsExplanation {
+    let named_pattern = |use_fallback| {
+      pipeline.map(|pipeline| NamedPattern {
+        pipeline,
+        use_fallback,
+      })
+    };
+
+    let configured = |plugins: Vec<(&str, &PluginNode)>| {
+      plugins
+        .into_iter()
+        .map(|(pattern, plugin)| ConfiguredPlugin::new(Some(pattern), plugin))
+        .collect()
+    };
+
+    PluginsExplanation {
+      file_path: file_path.to_path_buf(),
+      pipeline: pipeline.map(String::from),
+      transformers: configured(
+        self
+          .transformers
+          .get_with_patterns(file_path, named_pattern(false)),
+      ),
+      optimizers: configured(
+        self
+          .optimizers
+          .get_with_patterns(file_path, named_pattern(true)),
+      ),
+      packager: self
+        .packagers
+        .get_with_pattern(file_path)
+        .map(|(pattern, plugin)| ConfiguredPlugin::new(Some(pattern), plugin)),
+      compressors: configured(self.compressors.get_with_patterns(file_path)),
+    }
+  }
+
+  /// Lists every plugin in the merged config, with the location that each was declared at
+  ///
+  /// Spreads ("...") are omitted, since the plugins they refer to are listed under the config that
+  /// was extended.
+  ///
+  pub fn print(&self, fs: &FileSystemRef) -> PrintedConfig {
+    let mut locator = PluginLocator {
+      fs,
+      sources: HashMap::new(),
+    };
+
+    let mut list = |phase: &str, plugins: &[PluginNode]| {
+      plugins
+        .iter()
+        .map(|plugin| locator.locate(phase, None, plugin))
+        .collect::<Vec<ConfiguredPlugin>>()
+    };
+
+    let namers = list("namers", &self.namers);
+    let reporters = list("reporters", &self.reporters);
+    let resolvers = list("resolvers", &self.resolvers);
+    let runtimes = list("runtimes", &self.runtimes);
+
+    let mut pipelines = |phase: &str, entries: Vec<(&String, &Vec<PluginNode>)>| {
+      entries
+        .into_iter()
+        .flat_map(|(pattern, plugins)| plugins.iter().map(move |plugin| (pattern, plugin)))
+        .filter(|(_, plugin)| plugin.package_name != "...")
+        .map(|(pattern, plugin)| locator.locate(phase, Some(pattern.as_str()), plugin))
+        .collect::<Vec<ConfiguredPlugin>>()
+    };
+
+    let compressors = pipelines("compressors", self.compressors.iter().collect());
+    let optimizers = pipelines("optimizers", self.optimizers.iter().collect());
+    let transformers = pipelines("transformers", self.transformers.iter().collect());
+    let validators = pipelines("validators", self.validators.iter().collect());
+
+    let packagers = self
+      .packagers
+      .iter()
+      .map(|(pattern, plugin)| locator.locate("packagers", Some(pattern.as_str()), plugin))
+      .collect();
+
+    PrintedConfig {
+      bundler: locator.locate("bundler", None, &self.bundler),
+      compressors,
+      namers,
+      optimizers,
+      packagers,
+      reporters,
+      resolvers,
+      runtimes,
+      transformers,
+      validators,
+    }
+  }
+}
+
+/// Finds where plugins were declared by searching the raw config files that declared them
+struct PluginLocator<'a> {
+  fs: &'a FileSystemRef,
+  sources: HashMap<PathBuf, Option<String>>,
+}
+
+impl PluginLocator<'_> {
+  fn locate(
+    &mut self,
+    phase: &str,
+    pattern: Option<&str>,
+    plugin: &PluginNode,
+  ) -> ConfiguredPlugin {
+    let config_file = &*plugin.resolve_from;
+    let source = self
+      .sources
+      .entry(config_file.clone())
+      .or_insert_with(|| self.fs.read_to_string(config_file).ok());
+
+    let loc = source.as_deref().and_then(|source| {
+      let mut keys = vec![phase];
+      keys.extend(pattern);
+
+      find_value(source, &keys, &plugin.package_name).map(|(start, end)| SourceLocation {
+        file_path: config_file.clone(),
+        start,
+        end,
+      })
+    });
+
+    ConfiguredPlugin {
+      loc,
+      ..ConfiguredPlugin::new(pattern, plugin)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+  use std::sync::Arc;
+
+  use atlaspack_core::types::Location;
+  use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
+  use indexmap::indexmap;
+
+  use super::*;
+  use crate::atlaspack_config_fixtures::default_config;
+  use crate::atlaspack_config_fixtures::default_extended_config;
+  use crate::map::NamedPipelinesMap;
+
+  fn configured(package_name: &str, config_file: &Path, pattern: &str) -> ConfiguredPlugin {
+    ConfiguredPlugin {
+      package_name: String::from(package_name),
+      config_file: config_file.to_path_buf(),
+      pattern: Some(String::from(pattern)),
+      loc: None,
+    }
+  }
+
+  #[test]
+  fn explains_the_plugins_for_a_file_path() {
+    let project_root = PathBuf::from("/project");
+    let fixture = default_extended_config(&project_root);
+    let extended = &fixture.extended_config.path;
+
+    assert_eq!(
+      fixture
+        .atlaspack_config
+        .explain(Path::new("/project/src/index.ts"), None),
+      PluginsExplanation {
+        file_path: PathBuf::from("/project/src/index.ts"),
+        pipeline: None,
+        transformers: vec![configured(
+          "@atlaspack/transformer-js",
+          extended,
+          "*.{js,mjs,jsm,jsx,es6,cjs,ts,tsx}"
+        )],
+        optimizers: Vec::new(),
+        packager: None,
+        compressors: vec![configured("@atlaspack/compressor-raw", extended, "*")],
+      }
+    );
+
+    assert_eq!(
+      fixture
+        .atlaspack_config
+        .explain(Path::new("/project/dist/index.js"), None),
+      PluginsExplanation {
+        file_path: PathBuf::from("/project/dist/index.js"),
+        pipeline: None,
+        transformers: vec![configured(
+          "@atlaspack/transformer-js",
+          extended,
+          "*.{js,mjs,jsm,jsx,es6,cjs,ts,tsx}"
+        )],
+        optimizers: vec![configured(
+          "@atlaspack/optimizer-swc",
+          extended,
+          "*.{js,mjs,cjs}"
+        )],
+        packager: Some(configured(
+          "@atlaspack/packager-js",
+          extended,
+          "*.{js,mjs,cjs}"
+        )),
+        compressors: vec![configured("@atlaspack/compressor-raw", extended, "*")],
+      }
+    );
+  }
+
+  #[test]
+  fn explains_the_plugins_for_a_named_pipeline() {
+    let config_file = Arc::new(PathBuf::from("/project/.parcelrc"));
+    let mut config = default_config(config_file.clone()).atlaspack_config;
+
+    config.transformers = NamedPipelinesMap::new(indexmap! {
+      String::from("types:*.ts") => vec![PluginNode {
+        package_name: String::from("@atlaspack/transformer-typescript-types"),
+        resolve_from: config_file.clone(),
+      }],
+    });
+
+    let explanation = config.explain(Path::new("index.ts"), Some("types"));
+
+    assert_eq!(
+      explanation.transformers,
+      vec![configured(
+        "@atlaspack/transformer-typescript-types",
+        &config_file,
+        "types:*.ts"
+      )]
+    );
+
+    assert_eq!(
+      config
+        .explain(Path::new("index.ts"), Some("unknown"))
+        .transformers,
+      Vec::new()
+    );
+  }
+
+  #[test]
+  fn prints_the_config_with_source_locations() {
+    let project_root = PathBuf::from("/project");
+    let fixture = default_extended_config(&project_root);
+    let fs = Arc::new(InMemoryFileSystem::default());
+
+    fs.write_file(
+      &fixture.base_config.path,
+      fixture.base_config.atlaspack_rc.clone(),
+    );
+    fs.write_file(
+      &fixture.extended_config.path,
+      fixture.extended_config.atlaspack_rc.clone(),
+    );
+
+    let printed = fixture.atlaspack_config.print(&(fs as FileSystemRef));
+
+    assert_eq!(
+      printed.bundler.loc,
+      Some(SourceLocation {
+        file_path: fixture.extended_config.path.clone(),
+        start: Location {
+          line: 3,
+          column: 23,
+        },
+        end: Location {
+          line: 3,
+          column: 48,
+        },
+      })
+    );
+
+    assert_eq!(
+      printed
+        .transformers
+        .iter()
+        .map(|plugin| (
+          plugin.package_name.as_str(),
+          plugin.config_file.clone(),
+          plugin.loc.as_ref().map(|loc| loc.start.clone())
+        ))
+        .collect::<Vec<_>>(),
+      vec![
+        (
+          "@atlaspack/transformer-js",
+          fixture.extended_config.path.clone(),
+          Some(Location {
+            line: 19,
+            column: 16,
+          })
+        ),
+        (
+          "@scope/atlaspack-transformer-ts",
+          fixture.base_config.path.clone(),
+          Some(Location {
+            line: 7,
+            column: 18,
+          })
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn prints_the_config_without_locations_when_the_source_is_missing() {
+    let config_file = Arc::new(PathBuf::from("/project/.parcelrc"));
+    let fixture = default_config(config_file.clone());
+    let fs: FileSystemRef = Arc::new(InMemoryFileSystem::default());
+
+    let printed = fixture.atlaspack_config.print(&fs);
+
+    assert_eq!(
+      printed.namers,
+      vec![ConfiguredPlugin {
+        package_name: String::from("@atlaspack/namer-default"),
+        config_file: config_file.to_path_buf(),
+        pattern: None,
+        loc: None,
+      }]
+    );
+  }
+}
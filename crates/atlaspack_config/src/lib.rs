@@ -2,6 +2,9 @@ pub mod atlaspack_config;
 pub mod atlaspack_config_fixtures;
 pub mod atlaspack_rc;
 pub mod atlaspack_rc_config_loader;
+mod atlaspack_rc_validation;
+pub mod explain;
+mod locate;
 pub mod map;
 mod partial_atlaspack_config;
 
@@ -0,0 +1,132 @@
+//! Finds keys and values in the raw text of .parcelrc files
+//!
+//! These are textual searches rather than a parse, which is sufficient for .parcelrc files since
+//! phase names, globs and plugin names rarely appear anywhere other than where they are declared.
+
+use atlaspack_core::types::Location;
+
+/// Finds the key at the end of the given sequence of nested keys
+///
+/// Returns the first and last character of the key, excluding any quotes.
+///
+pub(crate) fn find_key(source: &str, keys: &[&str]) -> Option<(Location, Location)> {
+  let (start, key) = find_keys(source, keys)?;
+
+  Some(span(source, start, key))
+}
+
+/// Finds a string value after the given sequence of nested keys
+///
+/// Returns the first and last character of the value, excluding the quotes.
+///
+pub(crate) fn find_value(source: &str, keys: &[&str], value: &str) -> Option<(Location, Location)> {
+  let offset = match keys {
+    [] => 0,
+    keys => {
+      let (start, key) = find_keys(source, keys)?;
+      start + key.len()
+    }
+  };
+
+  let start = find_string(source, offset, value)?;
+
+  Some(span(source, start, value))
+}
+
+fn find_keys<'a>(source: &str, keys: &[&'a str]) -> Option<(usize, &'a str)> {
+  let mut offset = 0;
+  let mut found = None;
+
+  for key in keys {
+    let start = find_string(source, offset, key)
+      .into_iter()
+      .chain(find_identifier(source, offset, key))
+      .min()?;
+
+    offset = start + key.len();
+    found = Some((start, *key));
+  }
+
+  found
+}
+
+/// Returns the byte offset of the contents of the next quoted string that matches `value`
+fn find_string(source: &str, offset: usize, value: &str) -> Option<usize> {
+  ['"', '\'']
+    .into_iter()
+    .filter_map(|quote| {
+      source[offset..]
+        .find(&format!("{quote}{value}{quote}"))
+        .map(|index| offset + index + 1)
+    })
+    .min()
+}
+
+/// Returns the byte offset of the next unquoted JSON5 key that matches `key`
+fn find_identifier(source: &str, offset: usize, key: &str) -> Option<usize> {
+  let is_identifier = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+  source[offset..]
+    .match_indices(key)
+    .map(|(index, _)| offset + index)
+    .find(|start| {
+      let before = source[..*start].chars().next_back();
+      let after = source[start + key.len()..].trim_start().chars().next();
+
+      !before.is_some_and(|c| is_identifier(c) || c == '"' || c == '\'') && after == Some(':')
+    })
+}
+
+fn span(source: &str, start: usize, value: &str) -> (Location, Location) {
+  let end = start + value.len().saturating_sub(1);
+
+  (location(source, start), location(source, end))
+}
+
+/// Converts a byte offset into a 1-based line and column
+fn location(source: &str, offset: usize) -> Location {
+  let before = &source[..offset];
+  let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+
+  Location {
+    line: before.matches('\n').count() + 1,
+    column: before[line_start..].chars().count() + 1,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn loc(line: usize, column: usize) -> Location {
+    Location { line, column }
+  }
+
+  #[test]
+  fn finds_quoted_and_unquoted_keys() {
+    let source = "{\n  \"transformers\": {\n    '*.js': []\n  },\n  namers: []\n}";
+
+    assert_eq!(
+      find_key(source, &["transformers"]),
+      Some((loc(2, 4), loc(2, 15)))
+    );
+    assert_eq!(
+      find_key(source, &["transformers", "*.js"]),
+      Some((loc(3, 6), loc(3, 9)))
+    );
+    assert_eq!(find_key(source, &["namers"]), Some((loc(5, 3), loc(5, 8))));
+    assert_eq!(find_key(source, &["runtimes"]), None);
+  }
+
+  #[test]
+  fn finds_values_after_keys() {
+    let source = r#"{"namers": ["a"], "resolvers": ["a"]}"#;
+
+    assert_eq!(
+      find_value(source, &["resolvers"], "a"),
+      Some((loc(1, 34), loc(1, 34)))
+    );
+    assert_eq!(find_value(source, &[], "a"), Some((loc(1, 14), loc(1, 14))));
+    assert_eq!(find_value(source, &["namers"], "b"), None);
+  }
+}
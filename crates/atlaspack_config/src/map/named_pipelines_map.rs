@@ -42,7 +42,7 @@ pub struct NamedPattern<'a> {
 /// });
 /// ```
 ///
-#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 #[serde(transparent)]
 pub struct NamedPipelinesMap {
   /// Maps patterns and named patterns to a series of plugins, called pipelines
@@ -65,6 +65,11 @@ impl NamedPipelinesMap {
     Self { inner: map }
   }
 
+  /// Iterates over the patterns and their plugins in config order
+  pub fn iter(&self) -> impl Iterator<Item = (&String, &Vec<PluginNode>)> {
+    self.inner.iter()
+  }
+
   /// Finds pipelines contained by a pattern that match the given file path and named pipeline
   ///
   /// This function will return an empty vector when a pipeline is provided and there are no exact
@@ -117,10 +122,32 @@ impl NamedPipelinesMap {
   /// - Named pipelines: exact named match first, then unnamed matches, with same flatten rules
   /// - Named fallback: if no exact match and use_fallback=false, return empty; if true, use unnamed behavior
   pub fn get(&self, path: &Path, named_pattern: Option<NamedPattern>) -> Vec<PluginNode> {
+    self
+      .get_with_patterns(path, named_pattern)
+      .into_iter()
+      .map(|(_pattern, plugin)| plugin.clone())
+      .collect()
+  }
+
+  /// Finds the same plugins as `get`, along with the pattern that contributed each plugin
+  pub fn get_with_patterns(
+    &self,
+    path: &Path,
+    named_pattern: Option<NamedPattern>,
+  ) -> Vec<(&str, &PluginNode)> {
     let is_match = named_pattern_matcher(path);
 
     // Always collect all matching pipelines in order (user before base due to merge precedence)
-    let mut all_matches: Vec<Vec<PluginNode>> = Vec::new();
+    let mut all_matches: Vec<Vec<(&str, &PluginNode)>> = Vec::new();
+
+    fn with_pattern<'a>(
+      (pattern, pipelines): (&'a String, &'a Vec<PluginNode>),
+    ) -> Vec<(&'a str, &'a PluginNode)> {
+      pipelines
+        .iter()
+        .map(|plugin| (pattern.as_str(), plugin))
+        .collect()
+    }
 
     // For named pipelines, exact named match goes first
     if let Some(named_pattern) = named_pattern {
@@ -129,8 +156,8 @@ impl NamedPipelinesMap {
         .iter()
         .find(|(pattern, _)| is_match(pattern, named_pattern.pipeline));
 
-      if let Some((_, pipelines)) = exact_match {
-        all_matches.push(pipelines.clone());
+      if let Some(exact_match) = exact_match {
+        all_matches.push(with_pattern(exact_match));
       } else if !named_pattern.use_fallback {
         return Vec::new();
       }
@@ -139,7 +166,7 @@ impl NamedPipelinesMap {
     // Collect all unnamed matches in order
     for (pattern, pipelines) in self.inner.iter() {
       if is_match(pattern, "") {
-        all_matches.push(pipelines.clone());
+        all_matches.push(with_pattern((pattern, pipelines)));
       }
     }
 
@@ -148,11 +175,13 @@ impl NamedPipelinesMap {
     }
 
     // Flatten matches using '...' semantics similar to JS implementation
-    fn has_spread(p: &[PluginNode]) -> Option<usize> {
-      p.iter().position(|n| n.package_name == "...")
+    fn has_spread(p: &[(&str, &PluginNode)]) -> Option<usize> {
+      p.iter().position(|(_, n)| n.package_name == "...")
     }
 
-    fn flatten(mut groups: Vec<Vec<PluginNode>>) -> Vec<PluginNode> {
+    fn flatten<'a>(
+      mut groups: Vec<Vec<(&'a str, &'a PluginNode)>>,
+    ) -> Vec<(&'a str, &'a PluginNode)> {
       let first = match groups.first().cloned() {
         Some(p) => p,
         None => return Vec::new(),
@@ -177,7 +206,7 @@ impl NamedPipelinesMap {
       // Remove any residual spreads to be safe
       result
         .into_iter()
-        .filter(|n| n.package_name != "...")
+        .filter(|(_, n)| n.package_name != "...")
         .collect()
     }
 
@@ -27,7 +27,7 @@ use crate::PluginNode;
 /// });
 /// ```
 ///
-#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct PipelineMap(
   /// Maps patterns to a single pipeline plugin
   IndexMap<String, PluginNode>,
@@ -38,6 +38,11 @@ impl PipelineMap {
     Self(map)
   }
 
+  /// Iterates over the patterns and their plugins in config order
+  pub fn iter(&self) -> impl Iterator<Item = (&String, &PluginNode)> {
+    self.0.iter()
+  }
+
   /// Finds the plugin that matches the given file path
   ///
   /// # Examples
@@ -62,15 +67,18 @@ impl PipelineMap {
   /// pipeline_map.get(Path::new("Cargo.toml"));
   /// ```
   pub fn get(&self, path: &Path) -> Option<&PluginNode> {
-    let is_match = pattern_matcher(path);
+    self.get_with_pattern(path).map(|(_pattern, plugin)| plugin)
+  }
 
-    for (pattern, plugin) in self.0.iter() {
-      if is_match(pattern) {
-        return Some(plugin);
-      }
-    }
+  /// Finds the same plugin as `get`, along with the pattern that matched the path
+  pub fn get_with_pattern(&self, path: &Path) -> Option<(&str, &PluginNode)> {
+    let is_match = pattern_matcher(path);
 
-    None
+    self
+      .0
+      .iter()
+      .find(|(pattern, _)| is_match(pattern))
+      .map(|(pattern, plugin)| (pattern.as_str(), plugin))
   }
 }
 
@@ -27,7 +27,7 @@ use crate::PluginNode;
 /// });
 /// ```
 ///
-#[derive(Debug, Default, Deserialize, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
 pub struct PipelinesMap(
   /// Maps patterns to a series of plugins, called pipelines
   IndexMap<String, Vec<PluginNode>>,
@@ -38,6 +38,11 @@ impl PipelinesMap {
     Self(map)
   }
 
+  /// Iterates over the patterns and their plugins in config order
+  pub fn iter(&self) -> impl Iterator<Item = (&String, &Vec<PluginNode>)> {
+    self.0.iter()
+  }
+
   /// Finds pipelines that match the given file path
   ///
   /// # Examples
@@ -62,12 +67,21 @@ impl PipelinesMap {
   /// pipelines_map.get(Path::new("Cargo.toml"));
   /// ```
   pub fn get(&self, path: &Path) -> Vec<PluginNode> {
+    self
+      .get_with_patterns(path)
+      .into_iter()
+      .map(|(_pattern, plugin)| plugin.clone())
+      .collect()
+  }
+
+  /// Finds the same plugins as `get`, along with the pattern that contributed each plugin
+  pub fn get_with_patterns(&self, path: &Path) -> Vec<(&str, &PluginNode)> {
     let is_match = pattern_matcher(path);
-    let mut matches: Vec<PluginNode> = Vec::new();
+    let mut matches = Vec::new();
 
     for (pattern, pipelines) in self.0.iter() {
       if is_match(pattern) {
-        matches.extend(pipelines.iter().cloned());
+        matches.extend(pipelines.iter().map(|plugin| (pattern.as_str(), plugin)));
       }
     }
 
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -18,6 +19,8 @@ pub struct ConfigLoader {
   pub fs: FileSystemRef,
   pub project_root: PathBuf,
   pub search_path: PathBuf,
+  /// Every config file that has been looked up, including the candidates that did not exist
+  pub files: parking_lot::Mutex<HashSet<PathBuf>>,
 }
 
 #[derive(Debug, PartialEq)]
@@ -41,17 +44,19 @@ impl ConfigLoader {
     filename: &str,
     search_path: &Path,
   ) -> Result<ConfigFile<Config>, DiagnosticError> {
-    let path = find_ancestor_file(&*self.fs, &[filename], search_path, &self.project_root)
-      .ok_or_else(|| {
-        diagnostic_error!(
-          DiagnosticBuilder::default()
-            .kind(ErrorKind::NotFound)
-            .message(format!(
-              "Unable to locate {filename} config file from {}",
-              self.search_path.display()
-            ))
-        )
-      })?;
+    let path = find_ancestor_file(&*self.fs, &[filename], search_path, &self.project_root);
+    self.track_lookup(filename, search_path, path.as_deref());
+
+    let path = path.ok_or_else(|| {
+      diagnostic_error!(
+        DiagnosticBuilder::default()
+          .kind(ErrorKind::NotFound)
+          .message(format!(
+            "Unable to locate {filename} config file from {}",
+            self.search_path.display()
+          ))
+      )
+    })?;
 
     let code = self.fs.read_to_string(&path)?;
 
@@ -77,6 +82,27 @@ impl ConfigLoader {
     })
   }
 
+  /// Records the files that were checked when looking up `filename`, as creating, changing or
+  /// deleting any of them changes the config that is loaded
+  fn track_lookup(&self, filename: &str, search_path: &Path, found: Option<&Path>) {
+    let mut files = self.files.lock();
+
+    for dir in search_path.ancestors() {
+      let candidate = dir.join(filename);
+      let is_found = found == Some(candidate.as_path());
+      files.insert(candidate);
+
+      if is_found || dir == self.project_root {
+        break;
+      }
+    }
+  }
+
+  /// Returns the config files that have been looked up through this loader
+  pub fn files(&self) -> HashSet<PathBuf> {
+    self.files.lock().clone()
+  }
+
   /// WARNING: This is a dangerous API and should be avoided as it breaks
   /// caching
   pub fn load_local_package_json<Config: DeserializeOwned>(
@@ -118,6 +144,7 @@ mod tests {
         fs: Arc::new(InMemoryFileSystem::default()),
         project_root,
         search_path: search_path.clone(),
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -146,6 +173,7 @@ mod tests {
         fs,
         project_root: PathBuf::default(),
         search_path: search_path.clone(),
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -171,6 +199,7 @@ mod tests {
         fs,
         project_root,
         search_path: search_path.clone(),
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -197,6 +226,7 @@ mod tests {
         fs,
         project_root,
         search_path,
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -224,6 +254,7 @@ mod tests {
         fs,
         project_root,
         search_path,
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -237,6 +268,35 @@ mod tests {
         })
       )
     }
+
+    #[test]
+    fn tracks_the_files_that_were_looked_up() {
+      let fs = Arc::new(InMemoryFileSystem::default());
+      let project_root = PathBuf::from("/project-root");
+      let search_path = project_root.join("index");
+
+      fs.write_file(&project_root.join("found.json"), String::from("{}"));
+
+      let config = ConfigLoader {
+        fs,
+        project_root: project_root.clone(),
+        search_path: search_path.clone(),
+        files: Default::default(),
+      };
+
+      let _ = config.load_json_config::<JsonConfig>("found.json");
+      let _ = config.load_json_config::<JsonConfig>("missing.json");
+
+      assert_eq!(
+        config.files(),
+        HashSet::from([
+          search_path.join("found.json"),
+          project_root.join("found.json"),
+          search_path.join("missing.json"),
+          project_root.join("missing.json"),
+        ])
+      );
+    }
   }
 
   mod load_package_json_config {
@@ -283,6 +343,7 @@ mod tests {
         fs: Arc::new(InMemoryFileSystem::default()),
         project_root,
         search_path: search_path.clone(),
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -310,6 +371,7 @@ mod tests {
         fs,
         project_root,
         search_path,
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -336,6 +398,7 @@ mod tests {
         fs,
         project_root,
         search_path,
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -362,6 +425,7 @@ mod tests {
         fs,
         project_root,
         search_path,
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -389,6 +453,7 @@ mod tests {
         fs,
         project_root,
         search_path,
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -417,6 +482,7 @@ mod tests {
         fs,
         project_root,
         search_path,
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -444,6 +510,7 @@ mod tests {
         fs,
         project_root,
         search_path,
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -471,6 +538,7 @@ mod tests {
         fs,
         project_root,
         search_path,
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -498,6 +566,7 @@ mod tests {
         fs,
         project_root,
         search_path,
+        files: Default::default(),
       };
 
       assert_eq!(
@@ -12,6 +12,15 @@ pub type DatabaseRef = Arc<dyn Database + Send + Sync>;
 pub trait Database: std::fmt::Debug {
   fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
   fn put(&self, key: &str, value: &[u8]) -> anyhow::Result<()>;
+
+  /// Writes several entries, which implementations should do in a single transaction
+  fn put_many(&self, entries: &[(String, Vec<u8>)]) -> anyhow::Result<()> {
+    for (key, value) in entries {
+      self.put(key, value)?;
+    }
+
+    Ok(())
+  }
 }
 
 /// An in-memory [`Database`] suitable for use in tests.
@@ -3,15 +3,32 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use atlaspack_filesystem::FileSystemRef;
+pub use bundle_graph_view::*;
+pub use compressor_plugin::*;
+pub use namer_plugin::*;
+pub use optimizer_plugin::*;
+pub use packager_plugin::*;
+use parking_lot::Mutex;
+pub use reporter_plugin::*;
 pub use resolver_plugin::*;
+pub use runtime_plugin::*;
 use serde::{Deserialize, Serialize};
 pub use transformer_plugin::*;
+pub use validator_plugin::*;
 
 use crate::config_loader::ConfigLoaderRef;
-use crate::types::{AliasMap, BuildMode, FeatureFlags, LogLevel};
+use crate::types::{AliasMap, BuildMode, Diagnostic, FeatureFlags, LogLevel};
 
+mod bundle_graph_view;
+mod compressor_plugin;
+mod namer_plugin;
+mod optimizer_plugin;
+mod packager_plugin;
+mod reporter_plugin;
 mod resolver_plugin;
+mod runtime_plugin;
 mod transformer_plugin;
+mod validator_plugin;
 
 pub struct PluginContext {
   pub config: ConfigLoaderRef,
@@ -26,8 +43,37 @@ pub struct HmrOptions {
   pub host: Option<String>,
 }
 
-#[derive(Default)]
-pub struct PluginLogger {}
+/// Collects the non-fatal diagnostics, such as warnings, that plugins report
+///
+/// Clones share the same buffer, so the diagnostics logged by every plugin can be taken in one
+/// place once a build completes.
+#[derive(Clone, Default)]
+pub struct PluginLogger {
+  warnings: Arc<Mutex<Vec<Diagnostic>>>,
+}
+
+impl PluginLogger {
+  pub fn warn(&self, diagnostic: Diagnostic) {
+    tracing::warn!(hints = ?diagnostic.hints, "{}", diagnostic.message);
+    self.warnings.lock().push(diagnostic);
+  }
+
+  /// Removes and returns the warnings logged so far
+  pub fn take_warnings(&self) -> Vec<Diagnostic> {
+    std::mem::take(&mut *self.warnings.lock())
+  }
+}
+
+impl std::fmt::Debug for PluginLogger {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("PluginLogger").finish_non_exhaustive()
+  }
+}
+
+/// The logger has no effect on plugin output, so it does not contribute to plugin cache keys
+impl std::hash::Hash for PluginLogger {
+  fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
 
 #[derive(Debug, Default)]
 pub struct PluginOptions {
@@ -0,0 +1,80 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::bundle_graph::BundleGraph;
+use crate::types::{Asset, Bundle, Dependency};
+
+use super::PackagedBundle;
+
+/// A read-only view of the bundle graph, as seen from the bundle being packaged or optimized
+///
+/// Packagers and optimizers may run outside of Rust, where the graph cannot be queried, so the
+/// parts of it that they can access are collected before they run.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BundleGraphView {
+  /// The assets of the bundle, in source order
+  pub assets: Vec<Asset>,
+  /// The code of each asset of the bundle, keyed by asset id
+  pub asset_code: HashMap<String, Vec<u8>>,
+  /// Every bundle in the graph
+  pub bundles: Vec<Bundle>,
+  /// The dependencies of each asset of the bundle, keyed by asset id
+  pub dependencies: HashMap<String, Vec<Dependency>>,
+  /// The packaged contents of each inline bundle of the bundle, keyed by bundle id
+  pub inline_bundles: HashMap<String, PackagedBundle>,
+  /// The public id of each asset of the bundle, keyed by asset id
+  pub public_ids: HashMap<String, String>,
+  /// The ids of the bundles whose hash reference is embedded in the bundle
+  pub referenced_bundle_ids: Vec<String>,
+  /// The id of the asset that each dependency resolves to, keyed by dependency id
+  pub resolved_assets: HashMap<String, String>,
+  /// The ids of dependencies that were excluded because none of their symbols are used
+  pub skipped_dependencies: HashSet<String>,
+}
+
+impl BundleGraphView {
+  /// Collects the view of `bundle`, reading the code of each of its assets with `read_code`
+  ///
+  /// Inline bundles are not packaged here, so `inline_bundles` is left for the caller to fill.
+  pub fn new<B: BundleGraph + ?Sized>(
+    graph: &B,
+    bundle: &Bundle,
+    read_code: impl Fn(&Asset) -> anyhow::Result<Vec<u8>>,
+  ) -> anyhow::Result<Self> {
+    let mut view = BundleGraphView {
+      bundles: graph.get_bundles().into_iter().cloned().collect(),
+      referenced_bundle_ids: graph.get_referenced_bundle_ids(bundle),
+      ..BundleGraphView::default()
+    };
+
+    for asset in graph.get_bundle_assets_in_source_order(bundle)? {
+      let dependencies = graph.get_dependencies(asset)?;
+
+      for dependency in &dependencies {
+        if graph.is_dependency_skipped(dependency) {
+          view.skipped_dependencies.insert(dependency.id.clone());
+        }
+
+        if let Some(resolved) = graph.get_resolved_asset(dependency, bundle)? {
+          view
+            .resolved_assets
+            .insert(dependency.id.clone(), resolved.id.clone());
+        }
+      }
+
+      if let Some(public_id) = graph.get_public_asset_id(&asset.id) {
+        view
+          .public_ids
+          .insert(asset.id.clone(), public_id.to_string());
+      }
+
+      view.asset_code.insert(asset.id.clone(), read_code(asset)?);
+      view.dependencies.insert(
+        asset.id.clone(),
+        dependencies.into_iter().cloned().collect(),
+      );
+      view.assets.push(asset.clone());
+    }
+
+    Ok(view)
+  }
+}
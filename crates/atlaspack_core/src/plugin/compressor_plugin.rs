@@ -0,0 +1,53 @@
+use std::any::Any;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+use async_trait::async_trait;
+
+use crate::hash::IdentifierHasher;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompressedFile {
+  pub contents: Vec<u8>,
+  /// The extension appended to the file name, such as `gz`, or None to replace the original file
+  pub extension: Option<String>,
+}
+
+/// Compresses the contents of an output file
+///
+/// All compressors matching the file name run, and each writes its own output file.
+///
+#[async_trait]
+pub trait CompressorPlugin: Any + Debug + Send + Sync {
+  /// Unique ID for this compressor
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.type_id().hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// Compresses the contents, or returns None to skip writing an output file
+  async fn compress(&self, contents: &[u8]) -> anyhow::Result<Option<CompressedFile>>;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Debug)]
+  struct TestCompressorPlugin {}
+
+  #[async_trait]
+  impl CompressorPlugin for TestCompressorPlugin {
+    async fn compress(&self, _contents: &[u8]) -> anyhow::Result<Option<CompressedFile>> {
+      todo!()
+    }
+  }
+
+  #[test]
+  fn can_be_defined_in_dyn_vec() {
+    let compressors: Vec<Box<dyn CompressorPlugin>> = vec![Box::new(TestCompressorPlugin {})];
+
+    assert_eq!(compressors.len(), 1);
+  }
+}
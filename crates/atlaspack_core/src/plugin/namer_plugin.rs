@@ -0,0 +1,52 @@
+use std::any::Any;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::hash::IdentifierHasher;
+use crate::types::Bundle;
+
+pub struct NameContext {
+  pub bundle: Arc<Bundle>,
+}
+
+/// Determines the output file name of a bundle
+///
+/// Namers run in a pipeline until one of them returns a name.
+///
+#[async_trait]
+pub trait NamerPlugin: Any + Debug + Send + Sync {
+  /// Unique ID for this namer
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.type_id().hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// Names the bundle, or returns None to defer to the next namer
+  async fn name(&self, ctx: NameContext) -> anyhow::Result<Option<String>>;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Debug)]
+  struct TestNamerPlugin {}
+
+  #[async_trait]
+  impl NamerPlugin for TestNamerPlugin {
+    async fn name(&self, _ctx: NameContext) -> anyhow::Result<Option<String>> {
+      todo!()
+    }
+  }
+
+  #[test]
+  fn can_be_defined_in_dyn_vec() {
+    let namers: Vec<Box<dyn NamerPlugin>> = vec![Box::new(TestNamerPlugin {})];
+
+    assert_eq!(namers.len(), 1);
+  }
+}
@@ -0,0 +1,65 @@
+use std::any::Any;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::hash::IdentifierHasher;
+use crate::types::Bundle;
+
+use super::BundleGraphView;
+
+pub struct OptimizeContext {
+  pub bundle: Arc<Bundle>,
+  pub bundle_graph: Arc<BundleGraphView>,
+  /// The packaged contents of the bundle, or the output of the previous optimizer
+  pub contents: Vec<u8>,
+  /// The source map of the contents, as JSON
+  pub map: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OptimizeResult {
+  pub contents: Vec<u8>,
+  pub map: Option<String>,
+}
+
+/// Applies modifications to a packaged bundle, such as minification
+///
+/// Optimizers run in a pipeline, where each optimizer receives the output of the previous one.
+///
+#[async_trait]
+pub trait OptimizerPlugin: Any + Debug + Send + Sync {
+  /// Unique ID for this optimizer
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.type_id().hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// Optimizes the contents of the bundle
+  async fn optimize(&self, ctx: OptimizeContext) -> anyhow::Result<OptimizeResult>;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Debug)]
+  struct TestOptimizerPlugin {}
+
+  #[async_trait]
+  impl OptimizerPlugin for TestOptimizerPlugin {
+    async fn optimize(&self, _ctx: OptimizeContext) -> anyhow::Result<OptimizeResult> {
+      todo!()
+    }
+  }
+
+  #[test]
+  fn can_be_defined_in_dyn_vec() {
+    let optimizers: Vec<Box<dyn OptimizerPlugin>> = vec![Box::new(TestOptimizerPlugin {})];
+
+    assert_eq!(optimizers.len(), 1);
+  }
+}
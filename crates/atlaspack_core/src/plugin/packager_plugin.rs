@@ -0,0 +1,62 @@
+use std::any::Any;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::hash::IdentifierHasher;
+use crate::types::Bundle;
+
+use super::BundleGraphView;
+
+pub struct PackageContext {
+  pub bundle: Arc<Bundle>,
+  pub bundle_graph: Arc<BundleGraphView>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PackagedBundle {
+  pub contents: Vec<u8>,
+  /// The source map of the bundle, as JSON
+  pub map: Option<String>,
+}
+
+/// Combines the assets of a bundle into a single output file
+///
+/// A single packager is used for each bundle, based on the bundle file name.
+///
+#[async_trait]
+pub trait PackagerPlugin: Any + Debug + Send + Sync {
+  /// Unique ID for this packager
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.type_id().hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// Packages the assets of the bundle
+  async fn package(&self, ctx: PackageContext) -> anyhow::Result<PackagedBundle>;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Debug)]
+  struct TestPackagerPlugin {}
+
+  #[async_trait]
+  impl PackagerPlugin for TestPackagerPlugin {
+    async fn package(&self, _ctx: PackageContext) -> anyhow::Result<PackagedBundle> {
+      todo!()
+    }
+  }
+
+  #[test]
+  fn can_be_defined_in_dyn_vec() {
+    let packagers: Vec<Box<dyn PackagerPlugin>> = vec![Box::new(TestPackagerPlugin {})];
+
+    assert_eq!(packagers.len(), 1);
+  }
+}
@@ -0,0 +1,88 @@
+use std::any::Any;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use crate::build_progress::BuildProgressEvent;
+use crate::hash::IdentifierHasher;
+
+/// Events emitted to reporters during a build
+///
+/// These serialize to the same shape as the `type` tagged events received by JS reporters.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ReporterEvent {
+  BuildStart,
+  BuildProgress(BuildProgressEvent),
+  #[serde(rename_all = "camelCase")]
+  BuildSuccess {
+    /// The files written by the build
+    bundles: Vec<PathBuf>,
+    build_time: u64,
+  },
+  BuildFailure {
+    diagnostics: Vec<String>,
+  },
+}
+
+/// Receives events from Atlaspack as they occur throughout the build process
+///
+/// All reporters receive every event.
+///
+#[async_trait]
+pub trait ReporterPlugin: Any + Debug + Send + Sync {
+  /// Unique ID for this reporter
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.type_id().hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// Processes the event
+  async fn report(&self, event: &ReporterEvent) -> anyhow::Result<()>;
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[derive(Debug)]
+  struct TestReporterPlugin {}
+
+  #[async_trait]
+  impl ReporterPlugin for TestReporterPlugin {
+    async fn report(&self, _event: &ReporterEvent) -> anyhow::Result<()> {
+      todo!()
+    }
+  }
+
+  #[test]
+  fn can_be_defined_in_dyn_vec() {
+    let reporters: Vec<Box<dyn ReporterPlugin>> = vec![Box::new(TestReporterPlugin {})];
+
+    assert_eq!(reporters.len(), 1);
+  }
+
+  #[test]
+  fn serializes_events_like_js_reporter_events() {
+    assert_eq!(
+      serde_json::to_value(ReporterEvent::BuildProgress(BuildProgressEvent::Bundling)).unwrap(),
+      json!({ "type": "buildProgress", "phase": "bundling" })
+    );
+
+    assert_eq!(
+      serde_json::to_value(ReporterEvent::BuildSuccess {
+        bundles: vec![PathBuf::from("dist/index.js")],
+        build_time: 10,
+      })
+      .unwrap(),
+      json!({ "type": "buildSuccess", "bundles": ["dist/index.js"], "buildTime": 10 })
+    );
+  }
+}
@@ -0,0 +1,66 @@
+use std::any::Any;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::hash::IdentifierHasher;
+use crate::types::Bundle;
+
+pub struct RuntimeContext {
+  pub bundle: Arc<Bundle>,
+}
+
+/// An asset that is added to a bundle by a runtime
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeAsset {
+  /// The path of the asset, which determines how the code is transformed
+  pub file_path: PathBuf,
+  pub code: String,
+  /// Whether the asset runs before the entries of the bundle
+  #[serde(default)]
+  pub is_entry: bool,
+}
+
+/// Programmatically inserts assets into bundles
+///
+/// Runtimes are used to load other bundles at runtime, e.g. for dynamic imports and HMR.
+///
+#[async_trait]
+pub trait RuntimePlugin: Any + Debug + Send + Sync {
+  /// Unique ID for this runtime
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.type_id().hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// Returns the assets to add to the bundle
+  async fn apply(&self, ctx: RuntimeContext) -> anyhow::Result<Vec<RuntimeAsset>>;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Debug)]
+  struct TestRuntimePlugin {}
+
+  #[async_trait]
+  impl RuntimePlugin for TestRuntimePlugin {
+    async fn apply(&self, _ctx: RuntimeContext) -> anyhow::Result<Vec<RuntimeAsset>> {
+      todo!()
+    }
+  }
+
+  #[test]
+  fn can_be_defined_in_dyn_vec() {
+    let runtimes: Vec<Box<dyn RuntimePlugin>> = vec![Box::new(TestRuntimePlugin {})];
+
+    assert_eq!(runtimes.len(), 1);
+  }
+}
@@ -0,0 +1,62 @@
+use std::any::Any;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::hash::IdentifierHasher;
+use crate::types::Asset;
+
+pub struct ValidateContext {
+  pub asset: Arc<Asset>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+pub struct ValidateResult {
+  /// Errors fail the build
+  #[serde(default)]
+  pub errors: Vec<String>,
+  #[serde(default)]
+  pub warnings: Vec<String>,
+}
+
+/// Analyzes assets to ensure they are in a valid state, such as type checking
+///
+/// All validators matching the asset file name run, and validation does not change the asset.
+///
+#[async_trait]
+pub trait ValidatorPlugin: Any + Debug + Send + Sync {
+  /// Unique ID for this validator
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.type_id().hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// Validates the asset
+  async fn validate(&self, ctx: ValidateContext) -> anyhow::Result<ValidateResult>;
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[derive(Debug)]
+  struct TestValidatorPlugin {}
+
+  #[async_trait]
+  impl ValidatorPlugin for TestValidatorPlugin {
+    async fn validate(&self, _ctx: ValidateContext) -> anyhow::Result<ValidateResult> {
+      todo!()
+    }
+  }
+
+  #[test]
+  fn can_be_defined_in_dyn_vec() {
+    let validators: Vec<Box<dyn ValidatorPlugin>> = vec![Box::new(TestValidatorPlugin {})];
+
+    assert_eq!(validators.len(), 1);
+  }
+}
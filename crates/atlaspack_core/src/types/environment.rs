@@ -219,7 +219,7 @@ pub enum SourceType {
 }
 
 /// Source map options for the target output
-#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TargetSourceMapOptions {
   /// Inlines the source map as a data URL into the bundle, rather than link to it as a separate output file
@@ -240,6 +240,35 @@ pub struct TargetSourceMapOptions {
   ///
   #[serde(skip_serializing_if = "Option::is_none")]
   source_root: Option<String>,
+
+  /// Globs of the sources that debuggers should hide from stack traces, relative to the project root
+  ///
+  /// Sources outside the project root are always ignored. This defaults to `**/node_modules/**`.
+  ///
+  #[serde(skip_serializing_if = "Option::is_none")]
+  ignore_list: Option<Vec<String>>,
+}
+
+impl TargetSourceMapOptions {
+  pub fn ignore_list(&self) -> Vec<String> {
+    self
+      .ignore_list
+      .clone()
+      .unwrap_or_else(|| vec![String::from("**/node_modules/**")])
+  }
+}
+
+impl Hash for TargetSourceMapOptions {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.inline.hash(state);
+    self.inline_sources.hash(state);
+    self.source_root.hash(state);
+
+    // Only hash the ignore list when it's set to maintain backward compatibility
+    if let Some(ignore_list) = &self.ignore_list {
+      ignore_list.hash(state);
+    }
+  }
 }
 
 #[cfg(test)]
@@ -3,16 +3,36 @@ use std::collections::HashMap;
 use std::path::Component;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::SystemTime;
 
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::DirEntry;
 use crate::FileSystem;
+use crate::FileType;
+use crate::Metadata;
 
 /// In memory implementation of a file-system entry
 #[derive(Debug)]
 enum InMemoryFileSystemEntry {
-  File { contents: String },
+  File {
+    contents: String,
+    hash: u64,
+    modified: SystemTime,
+  },
   Directory,
 }
 
+impl InMemoryFileSystemEntry {
+  fn file(contents: String) -> Self {
+    InMemoryFileSystemEntry::File {
+      hash: xxh3_64(contents.as_bytes()),
+      contents,
+      modified: SystemTime::now(),
+    }
+  }
+}
+
 /// In memory implementation of the `FileSystem` trait, for testing purpouses.
 #[derive(Debug)]
 pub struct InMemoryFileSystem {
@@ -32,7 +52,7 @@ impl InMemoryFileSystem {
   pub fn write_file(&self, path: &Path, contents: String) {
     let path = self.canonicalize_impl(path);
     let mut files = self.files.write();
-    files.insert(path.clone(), InMemoryFileSystemEntry::File { contents });
+    files.insert(path.clone(), InMemoryFileSystemEntry::file(contents));
 
     let mut dir = path.parent();
     while let Some(path) = dir {
@@ -135,12 +155,7 @@ impl FileSystem for InMemoryFileSystem {
       dir = p.parent();
     }
 
-    files.insert(
-      path,
-      InMemoryFileSystemEntry::File {
-        contents: contents_str,
-      },
-    );
+    files.insert(path, InMemoryFileSystemEntry::file(contents_str));
     Ok(())
   }
 
@@ -149,8 +164,39 @@ impl FileSystem for InMemoryFileSystem {
     Ok(str.as_bytes().to_vec())
   }
 
-  fn read_dir(&self, _path: &Path) -> std::io::Result<std::fs::ReadDir> {
-    todo!("InMemoryFileSystem::read_dir")
+  fn read_dir(&self, path: &Path) -> std::io::Result<Vec<DirEntry>> {
+    let path = self.canonicalize_impl(path);
+    let files = self.files.read();
+
+    match files.get(&path) {
+      Some(InMemoryFileSystemEntry::Directory) => {}
+      Some(InMemoryFileSystemEntry::File { .. }) => {
+        return Err(std::io::Error::new(
+          std::io::ErrorKind::NotADirectory,
+          "Path is a file",
+        ));
+      }
+      None => {
+        return Err(std::io::Error::new(
+          std::io::ErrorKind::NotFound,
+          "Directory not found",
+        ));
+      }
+    }
+
+    let entries = files
+      .iter()
+      .filter(|(entry_path, _)| entry_path.parent() == Some(path.as_path()))
+      .map(|(entry_path, entry)| DirEntry {
+        path: entry_path.clone(),
+        file_type: match entry {
+          InMemoryFileSystemEntry::File { .. } => FileType::File,
+          InMemoryFileSystemEntry::Directory => FileType::Directory,
+        },
+      })
+      .collect();
+
+    Ok(entries)
   }
 
   fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
@@ -164,7 +210,7 @@ impl FileSystem for InMemoryFileSystem {
         ))
       },
       |entry| match entry {
-        InMemoryFileSystemEntry::File { contents } => Ok(contents.clone()),
+        InMemoryFileSystemEntry::File { contents, .. } => Ok(contents.clone()),
         InMemoryFileSystemEntry::Directory => Err(std::io::Error::new(
           std::io::ErrorKind::InvalidInput,
           "Path is a directory",
@@ -186,6 +232,49 @@ impl FileSystem for InMemoryFileSystem {
     let file = files.get(&path);
     matches!(file, Some(InMemoryFileSystemEntry::Directory))
   }
+
+  fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+    let path = self.canonicalize_impl(path);
+    let files = self.files.read();
+
+    match files.get(&path) {
+      Some(InMemoryFileSystemEntry::File {
+        contents, modified, ..
+      }) => Ok(Metadata {
+        file_type: FileType::File,
+        len: contents.len() as u64,
+        modified: *modified,
+        is_symlink: false,
+      }),
+      Some(InMemoryFileSystemEntry::Directory) => Ok(Metadata {
+        file_type: FileType::Directory,
+        len: 0,
+        modified: SystemTime::UNIX_EPOCH,
+        is_symlink: false,
+      }),
+      None => Err(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "File not found",
+      )),
+    }
+  }
+
+  fn content_hash(&self, path: &Path) -> std::io::Result<u64> {
+    let path = self.canonicalize_impl(path);
+    let files = self.files.read();
+
+    match files.get(&path) {
+      Some(InMemoryFileSystemEntry::File { hash, .. }) => Ok(*hash),
+      Some(InMemoryFileSystemEntry::Directory) => Err(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        "Path is a directory",
+      )),
+      None => Err(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "File not found",
+      )),
+    }
+  }
 }
 
 #[cfg(test)]
@@ -271,6 +360,56 @@ mod tests {
     assert!(!fs.is_dir(Path::new("/foo/bar")));
   }
 
+  #[test]
+  fn test_read_dir() {
+    let fs = InMemoryFileSystem::default();
+
+    fs.write_file(&PathBuf::from("/foo/bar.js"), String::default());
+    fs.write_file(&PathBuf::from("/foo/baz/qux.js"), String::default());
+
+    let mut entries = fs.read_dir(Path::new("/foo")).unwrap();
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    assert_eq!(
+      entries,
+      vec![
+        DirEntry {
+          path: root_dir().join("foo/bar.js"),
+          file_type: FileType::File,
+        },
+        DirEntry {
+          path: root_dir().join("foo/baz"),
+          file_type: FileType::Directory,
+        },
+      ]
+    );
+
+    assert!(fs.read_dir(Path::new("/foo/bar.js")).is_err());
+    assert!(fs.read_dir(Path::new("/missing")).is_err());
+  }
+
+  #[test]
+  fn test_metadata_and_content_hash() {
+    let fs = InMemoryFileSystem::default();
+
+    fs.write_file(&PathBuf::from("/foo/bar.js"), String::from("contents"));
+
+    let metadata = fs.metadata(Path::new("/foo/bar.js")).unwrap();
+    assert_eq!(metadata.file_type, FileType::File);
+    assert_eq!(metadata.len, 8);
+    assert_eq!(
+      fs.metadata(Path::new("/foo")).unwrap().file_type,
+      FileType::Directory
+    );
+    assert!(fs.metadata(Path::new("/missing")).is_err());
+
+    assert_eq!(
+      fs.content_hash(Path::new("/foo/bar.js")).unwrap(),
+      xxh3_64(b"contents")
+    );
+    assert!(fs.content_hash(Path::new("/foo")).is_err());
+  }
+
   #[test]
   fn test_changing_the_cwd_will_correctly_resolve_files() {
     let cwd = PathBuf::from("/foo");
@@ -1,9 +1,13 @@
+use std::ffi::OsStr;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 use atlaspack_shared_map::SharedHashMap;
 
+use crate::watcher::{WatchOptions, Watcher};
+
 /// In-memory file-system for testing
 pub mod in_memory_file_system;
 
@@ -12,6 +16,12 @@ pub mod search;
 /// File-system implementation using std::fs and a canonicalize cache
 pub mod os_file_system;
 
+/// File-system that layers in-memory contents over another file-system
+pub mod overlay_file_system;
+
+/// Watching directories for changes and comparing snapshots of their files
+pub mod watcher;
+
 /// FileSystem abstraction instance
 ///
 /// This should be `OsFileSystem` for non-testing environments and `InMemoryFileSystem` for testing.
@@ -19,6 +29,50 @@ pub type FileSystemRef = Arc<dyn FileSystem + Send + Sync>;
 
 pub type FileSystemRealPathCache = SharedHashMap<PathBuf, Option<PathBuf>>;
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileType {
+  File,
+  Directory,
+  Symlink,
+}
+
+impl From<std::fs::FileType> for FileType {
+  fn from(file_type: std::fs::FileType) -> Self {
+    if file_type.is_symlink() {
+      FileType::Symlink
+    } else if file_type.is_dir() {
+      FileType::Directory
+    } else {
+      FileType::File
+    }
+  }
+}
+
+/// The size, modification time and type of a file or directory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Metadata {
+  /// Whether the path is a file or directory, after following symlinks
+  pub file_type: FileType,
+  pub len: u64,
+  pub modified: SystemTime,
+  /// Whether the path itself is a symlink
+  pub is_symlink: bool,
+}
+
+/// An entry within a directory, which unlike `std::fs::DirEntry` can be created by any
+/// `FileSystem` implementation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DirEntry {
+  pub path: PathBuf,
+  pub file_type: FileType,
+}
+
+impl DirEntry {
+  pub fn file_name(&self) -> &OsStr {
+    self.path.file_name().unwrap_or(self.path.as_os_str())
+  }
+}
+
 /// Trait abstracting file-system operations
 /// .
 ///
@@ -65,8 +119,30 @@ pub trait FileSystem: std::fmt::Debug {
   }
 
   fn read(&self, path: &Path) -> std::io::Result<Vec<u8>>;
-  fn read_dir(&self, path: &Path) -> std::io::Result<std::fs::ReadDir>;
+  /// List the entries within a directory, in no particular order
+  fn read_dir(&self, path: &Path) -> std::io::Result<Vec<DirEntry>>;
   fn read_to_string(&self, path: &Path) -> std::io::Result<String>;
   fn is_file(&self, path: &Path) -> bool;
   fn is_dir(&self, path: &Path) -> bool;
+
+  /// Query the metadata of a file or directory, following symlinks
+  fn metadata(&self, _path: &Path) -> std::io::Result<Metadata> {
+    Err(std::io::Error::other(
+      "Not implemented: FileSystem::metadata",
+    ))
+  }
+
+  /// Hash the contents of a file with xxh3
+  ///
+  /// Implementations may cache hashes, in which case a cached hash is only used while the size and
+  /// modification time of the file are unchanged.
+  ///
+  fn content_hash(&self, path: &Path) -> std::io::Result<u64> {
+    Ok(xxhash_rust::xxh3::xxh3_64(&self.read(path)?))
+  }
+
+  /// Watch a directory and everything within it for changes
+  fn watch(&self, _root: &Path, _options: WatchOptions) -> std::io::Result<Box<dyn Watcher>> {
+    Err(std::io::Error::other("Not implemented: FileSystem::watch"))
+  }
 }
@@ -2,10 +2,16 @@ use std::path::Path;
 use std::path::PathBuf;
 
 use canonicalize::canonicalize;
+use content_hash::content_hash;
 
-use crate::{FileSystem, FileSystemRealPathCache};
+use crate::watcher::{WatchOptions, Watcher};
+use crate::{DirEntry, FileSystem, FileSystemRealPathCache, FileType, Metadata};
 
 mod canonicalize;
+mod content_hash;
+mod native_watcher;
+
+pub use native_watcher::NativeWatcher;
 
 #[derive(Default, Debug)]
 pub struct OsFileSystem;
@@ -39,8 +45,17 @@ impl FileSystem for OsFileSystem {
     std::fs::read(path)
   }
 
-  fn read_dir(&self, path: &Path) -> std::io::Result<std::fs::ReadDir> {
-    std::fs::read_dir(path)
+  fn read_dir(&self, path: &Path) -> std::io::Result<Vec<DirEntry>> {
+    std::fs::read_dir(path)?
+      .map(|entry| {
+        let entry = entry?;
+
+        Ok(DirEntry {
+          path: entry.path(),
+          file_type: FileType::from(entry.file_type()?),
+        })
+      })
+      .collect()
   }
 
   fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
@@ -56,4 +71,24 @@ impl FileSystem for OsFileSystem {
     let path: &Path = path;
     path.is_dir()
   }
+
+  fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+    let is_symlink = std::fs::symlink_metadata(path)?.is_symlink();
+    let metadata = std::fs::metadata(path)?;
+
+    Ok(Metadata {
+      file_type: FileType::from(metadata.file_type()),
+      len: metadata.len(),
+      modified: metadata.modified()?,
+      is_symlink,
+    })
+  }
+
+  fn content_hash(&self, path: &Path) -> std::io::Result<u64> {
+    content_hash(path)
+  }
+
+  fn watch(&self, root: &Path, options: WatchOptions) -> std::io::Result<Box<dyn Watcher>> {
+    Ok(Box::new(NativeWatcher::new(root, options)?))
+  }
 }
@@ -0,0 +1,97 @@
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use atlaspack_shared_map::SharedHashMap;
+use xxhash_rust::xxh3::xxh3_64;
+
+/// The size and modification time of a file when it was hashed
+type FileStamp = (u64, SystemTime);
+
+/// Hashes are shared between every `OsFileSystem`, since they are only used while the file is
+/// unchanged on disk
+static CONTENT_HASHES: LazyLock<SharedHashMap<PathBuf, (FileStamp, u64)>> =
+  LazyLock::new(SharedHashMap::new);
+
+/// Files modified more recently than this are not cached, as a write within the resolution of the
+/// modification time that keeps the same size would otherwise go unnoticed
+const MIN_CACHE_AGE: Duration = Duration::from_secs(2);
+
+/// Hashes the contents of a file with xxh3, reusing the previous hash if the file is unchanged
+pub fn content_hash(path: &Path) -> std::io::Result<u64> {
+  let metadata = std::fs::metadata(path)?;
+  let stamp = (metadata.len(), metadata.modified()?);
+
+  if let Some((cached_stamp, hash)) = CONTENT_HASHES.get(path)
+    && cached_stamp == stamp
+  {
+    return Ok(hash);
+  }
+
+  // The stamp is read before the contents, so that a write during the read is picked up next time
+  let hash = xxh3_64(&std::fs::read(path)?);
+
+  let is_settled = SystemTime::now()
+    .duration_since(stamp.1)
+    .is_ok_and(|age| age >= MIN_CACHE_AGE);
+
+  if is_settled {
+    CONTENT_HASHES.insert(path.to_path_buf(), (stamp, hash));
+  }
+
+  Ok(hash)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs::File;
+
+  use assert_fs::TempDir;
+
+  use super::*;
+
+  #[test]
+  fn hashes_the_contents_of_a_file() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("index.js");
+
+    std::fs::write(&path, "contents").unwrap();
+
+    assert_eq!(content_hash(&path).unwrap(), xxh3_64(b"contents"));
+    assert!(content_hash(&dir.path().join("missing.js")).is_err());
+  }
+
+  #[test]
+  fn reuses_the_hash_while_the_file_is_unchanged() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("index.js");
+    let modified = SystemTime::now() - Duration::from_secs(60);
+
+    std::fs::write(&path, "before").unwrap();
+    File::options()
+      .write(true)
+      .open(&path)
+      .unwrap()
+      .set_modified(modified)
+      .unwrap();
+
+    assert_eq!(content_hash(&path).unwrap(), xxh3_64(b"before"));
+
+    // Writes that keep the size and modification time can't be detected
+    std::fs::write(&path, "stale!").unwrap();
+    File::options()
+      .write(true)
+      .open(&path)
+      .unwrap()
+      .set_modified(modified)
+      .unwrap();
+
+    assert_eq!(content_hash(&path).unwrap(), xxh3_64(b"before"));
+
+    std::fs::write(&path, "after").unwrap();
+
+    assert_eq!(content_hash(&path).unwrap(), xxh3_64(b"after"));
+  }
+}
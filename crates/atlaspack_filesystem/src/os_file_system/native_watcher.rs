@@ -0,0 +1,575 @@
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender, channel};
+use std::time::{Duration, Instant};
+
+use notify::event::{EventKind, ModifyKind, RenameMode};
+use notify::{RecommendedWatcher, RecursiveMode};
+
+use crate::watcher::{
+  EventCoalescer, FileState, IgnoreGlobs, WatchEvent, WatchEvents, WatchOptions, WatchSnapshot,
+  WatchStopper, Watcher,
+};
+
+/// Whether the platform backend watches a whole tree natively
+///
+/// Other backends, such as inotify, register a watch for every directory, so each directory is
+/// watched separately in order to skip ignored directories like `node_modules`. Watching those
+/// recursively can exhaust the limit on the number of watches.
+///
+const WATCHES_TREES_NATIVELY: bool = cfg!(any(target_os = "macos", target_os = "windows"));
+
+enum Message {
+  Event(notify::Result<notify::Event>),
+  Stop,
+}
+
+/// Watches a directory recursively using the native events of the platform, such as inotify on
+/// Linux, FSEvents on macOS and ReadDirectoryChangesW on Windows
+pub struct NativeWatcher {
+  root: PathBuf,
+  debounce: Duration,
+  max_wait: Duration,
+  ignore: IgnoreGlobs,
+  sender: Sender<Message>,
+  receiver: Receiver<Message>,
+  coalescer: EventCoalescer,
+  watch_per_directory: bool,
+  // Events stop being delivered once the watcher is dropped
+  watcher: Option<RecommendedWatcher>,
+}
+
+impl NativeWatcher {
+  pub fn new(root: &Path, options: WatchOptions) -> std::io::Result<Self> {
+    let (sender, receiver) = channel();
+
+    let watcher = notify::recommended_watcher({
+      let sender = sender.clone();
+      move |event| {
+        let _ = sender.send(Message::Event(event));
+      }
+    })
+    .map_err(std::io::Error::other)?;
+
+    let mut native_watcher = Self::with_channel(root, options, sender, receiver, Some(watcher));
+
+    if WATCHES_TREES_NATIVELY {
+      native_watcher.watch_per_directory = false;
+      if let Some(watcher) = &mut native_watcher.watcher {
+        notify::Watcher::watch(watcher, root, RecursiveMode::Recursive)
+          .map_err(std::io::Error::other)?;
+      }
+    } else {
+      native_watcher.watch_dir(root)?;
+    }
+
+    Ok(native_watcher)
+  }
+
+  /// Creates a watcher that reports the messages sent to `receiver`
+  fn with_channel(
+    root: &Path,
+    options: WatchOptions,
+    sender: Sender<Message>,
+    receiver: Receiver<Message>,
+    watcher: Option<RecommendedWatcher>,
+  ) -> Self {
+    NativeWatcher {
+      root: root.to_path_buf(),
+      debounce: options.debounce,
+      max_wait: options.max_wait,
+      ignore: IgnoreGlobs::new(root, &options.ignore),
+      sender,
+      receiver,
+      coalescer: EventCoalescer::default(),
+      watch_per_directory: true,
+      watcher,
+    }
+  }
+
+  /// Watches a directory and the directories within it that are not ignored, returning the files
+  /// that they contain
+  fn watch_dir(&mut self, dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut watched = Vec::new();
+    let mut files = Vec::new();
+    watch_tree(
+      &mut self.watcher,
+      dir,
+      &self.ignore,
+      &mut watched,
+      &mut files,
+    )?;
+
+    Ok(files)
+  }
+
+  fn push(&mut self, message: Message) -> std::io::Result<()> {
+    let event = match message {
+      Message::Event(event) => event.map_err(std::io::Error::other)?,
+      Message::Stop => {
+        return Err(std::io::Error::new(
+          std::io::ErrorKind::NotConnected,
+          "The file watcher was stopped",
+        ));
+      }
+    };
+
+    if event.need_rescan() {
+      return Err(std::io::Error::other(format!(
+        "Events were dropped while watching {}",
+        self.root.display()
+      )));
+    }
+
+    for event in to_watch_events(event) {
+      if self.ignore.is_ignored(event.path()) {
+        continue;
+      }
+
+      // Directories created after watching started need watches of their own, and files may have
+      // been written to them before those watches were registered
+      let created_files = match &event {
+        WatchEvent::Create(path) if self.watch_per_directory && path.is_dir() => {
+          self.watch_dir(path)?
+        }
+        _ => Vec::new(),
+      };
+
+      self.coalescer.push(event);
+      for file in created_files {
+        self.coalescer.push(WatchEvent::Create(file));
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl Watcher for NativeWatcher {
+  fn next_events(&mut self) -> std::io::Result<WatchEvents> {
+    let disconnected = || {
+      std::io::Error::new(
+        std::io::ErrorKind::NotConnected,
+        "The file watcher stopped unexpectedly",
+      )
+    };
+
+    loop {
+      let message = self.receiver.recv().map_err(|_| disconnected())?;
+      self.push(message)?;
+
+      let deadline = Instant::now() + self.max_wait;
+      loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+          break;
+        }
+
+        match self.receiver.recv_timeout(self.debounce.min(remaining)) {
+          Ok(message) => self.push(message)?,
+          Err(RecvTimeoutError::Timeout) => break,
+          Err(RecvTimeoutError::Disconnected) => return Err(disconnected()),
+        }
+      }
+
+      // Every event may have been ignored or cancelled out, in which case keep waiting
+      if !self.coalescer.is_empty() {
+        return Ok(self.coalescer.drain());
+      }
+    }
+  }
+
+  fn snapshot(&self) -> std::io::Result<WatchSnapshot> {
+    let mut snapshot = WatchSnapshot::default();
+    scan_dir(&self.root, &self.ignore, &mut snapshot)?;
+    Ok(snapshot)
+  }
+
+  fn stopper(&self) -> WatchStopper {
+    let sender = self.sender.clone();
+    WatchStopper::new(move || {
+      let _ = sender.send(Message::Stop);
+    })
+  }
+}
+
+fn to_watch_events(event: notify::Event) -> WatchEvents {
+  let notify::Event { kind, paths, .. } = event;
+
+  match kind {
+    EventKind::Access(_) => Vec::new(),
+    EventKind::Create(_) => paths.into_iter().map(WatchEvent::Create).collect(),
+    EventKind::Remove(_) => paths.into_iter().map(WatchEvent::Delete).collect(),
+    EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+      paths.into_iter().map(WatchEvent::Delete).collect()
+    }
+    EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+      paths.into_iter().map(WatchEvent::Create).collect()
+    }
+    EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+      let mut paths = paths.into_iter();
+      let mut events = Vec::new();
+      events.extend(paths.next().map(WatchEvent::Delete));
+      events.extend(paths.next().map(WatchEvent::Create));
+      events
+    }
+    // Backends that can't tell whether a path was renamed to or from are checked on disk
+    EventKind::Modify(ModifyKind::Name(_)) => paths
+      .into_iter()
+      .map(|path| match path.exists() {
+        true => WatchEvent::Create(path),
+        false => WatchEvent::Delete(path),
+      })
+      .collect(),
+    EventKind::Modify(_) | EventKind::Any | EventKind::Other => paths
+      .into_iter()
+      .map(|path| match path.exists() {
+        true => WatchEvent::Update(path),
+        false => WatchEvent::Delete(path),
+      })
+      .collect(),
+  }
+}
+
+fn scan_dir(dir: &Path, ignore: &IgnoreGlobs, snapshot: &mut WatchSnapshot) -> std::io::Result<()> {
+  for entry in std::fs::read_dir(dir)? {
+    // Files can be removed while scanning, in which case they are left out of the snapshot
+    let Ok(entry) = entry else {
+      continue;
+    };
+
+    let path = entry.path();
+    if ignore.is_ignored(&path) {
+      continue;
+    }
+
+    let Ok(metadata) = entry.metadata() else {
+      continue;
+    };
+
+    if metadata.is_dir() {
+      match scan_dir(&path, ignore, snapshot) {
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+        result => result?,
+      }
+    } else if let Ok(modified) = metadata.modified() {
+      snapshot.files.insert(
+        path,
+        FileState {
+          modified,
+          size: metadata.len(),
+        },
+      );
+    }
+  }
+
+  Ok(())
+}
+
+/// Registers a watch for each directory that is not ignored, recording the directories that were
+/// watched and the files found within them
+///
+/// Each directory is watched before it is read, so that files created while reading are reported.
+///
+fn watch_tree(
+  watcher: &mut Option<RecommendedWatcher>,
+  dir: &Path,
+  ignore: &IgnoreGlobs,
+  watched: &mut Vec<PathBuf>,
+  files: &mut Vec<PathBuf>,
+) -> std::io::Result<()> {
+  if let Some(watcher) = watcher {
+    notify::Watcher::watch(watcher, dir, RecursiveMode::NonRecursive).map_err(
+      |error| match error.kind {
+        notify::ErrorKind::PathNotFound => std::io::Error::from(std::io::ErrorKind::NotFound),
+        notify::ErrorKind::Io(error) => error,
+        _ => std::io::Error::other(error),
+      },
+    )?;
+  }
+
+  watched.push(dir.to_path_buf());
+
+  for entry in std::fs::read_dir(dir)? {
+    let Ok(entry) = entry else {
+      continue;
+    };
+
+    let path = entry.path();
+    if ignore.is_ignored(&path) {
+      continue;
+    }
+
+    let Ok(file_type) = entry.file_type() else {
+      continue;
+    };
+
+    if file_type.is_dir() {
+      // Directories can be removed before they are watched
+      match watch_tree(watcher, &path, ignore, watched, files) {
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+        result => result?,
+      }
+    } else {
+      files.push(path);
+    }
+  }
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+  use assert_fs::TempDir;
+  use notify::event::{CreateKind, DataChange, Flag, RemoveKind};
+
+  use super::*;
+
+  fn options(ignore: &[&str]) -> WatchOptions {
+    WatchOptions {
+      ignore: ignore.iter().map(|glob| glob.to_string()).collect(),
+      debounce: Duration::from_millis(100),
+      max_wait: Duration::from_secs(10),
+    }
+  }
+
+  fn test_watcher(root: &Path, options: WatchOptions) -> (NativeWatcher, Sender<Message>) {
+    let (sender, receiver) = channel();
+    let watcher = NativeWatcher::with_channel(root, options, sender.clone(), receiver, None);
+    (watcher, sender)
+  }
+
+  #[test]
+  fn snapshots_the_files_that_are_not_ignored() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    std::fs::create_dir_all(root.join("dist")).unwrap();
+    std::fs::create_dir_all(root.join(".git")).unwrap();
+    std::fs::write(root.join("src/index.js"), "index").unwrap();
+    std::fs::write(root.join("src/removed.js"), "removed").unwrap();
+    std::fs::write(root.join("dist/index.js"), "dist").unwrap();
+    std::fs::write(root.join(".git/HEAD"), "head").unwrap();
+
+    let ignore = root.join("dist").to_string_lossy().into_owned();
+    let watcher = NativeWatcher::new(&root, options(&[".git", &ignore])).unwrap();
+    let snapshot = watcher.snapshot().unwrap();
+
+    assert_eq!(
+      snapshot.files.keys().cloned().collect::<Vec<_>>(),
+      vec![root.join("src/index.js"), root.join("src/removed.js")]
+    );
+
+    std::fs::write(root.join("src/index.js"), "updated").unwrap();
+    std::fs::write(root.join("src/created.js"), "created").unwrap();
+    std::fs::remove_file(root.join("src/removed.js")).unwrap();
+    std::fs::write(root.join("dist/index.js"), "ignored").unwrap();
+
+    assert_eq!(
+      watcher.get_events_since(&snapshot).unwrap(),
+      vec![
+        WatchEvent::Create(root.join("src/created.js")),
+        WatchEvent::Update(root.join("src/index.js")),
+        WatchEvent::Delete(root.join("src/removed.js")),
+      ]
+    );
+  }
+
+  fn send(sender: &Sender<Message>, kind: EventKind, path: PathBuf) {
+    sender
+      .send(Message::Event(Ok(notify::Event::new(kind).add_path(path))))
+      .unwrap();
+  }
+
+  #[test]
+  fn reports_coalesced_changes() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().to_path_buf();
+    std::fs::write(root.join("index.js"), "index").unwrap();
+
+    let (mut watcher, sender) = test_watcher(&root, options(&["dist"]));
+
+    send(
+      &sender,
+      EventKind::Create(CreateKind::File),
+      root.join("dist/index.js"),
+    );
+    send(
+      &sender,
+      EventKind::Create(CreateKind::File),
+      root.join("index.js"),
+    );
+    send(
+      &sender,
+      EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+      root.join("index.js"),
+    );
+    send(
+      &sender,
+      EventKind::Remove(RemoveKind::File),
+      root.join("removed.js"),
+    );
+
+    let started = Instant::now();
+    let events = watcher.next_events().unwrap();
+
+    assert_eq!(
+      events,
+      vec![
+        WatchEvent::Create(root.join("index.js")),
+        WatchEvent::Delete(root.join("removed.js")),
+      ]
+    );
+    assert!(started.elapsed() >= Duration::from_millis(100));
+  }
+
+  #[test]
+  fn returns_an_error_when_events_were_dropped() {
+    let root = PathBuf::from("/project");
+    let (mut watcher, sender) = test_watcher(&root, options(&[]));
+
+    sender
+      .send(Message::Event(Ok(
+        notify::Event::new(EventKind::Other).set_flag(Flag::Rescan),
+      )))
+      .unwrap();
+
+    let error = watcher.next_events().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::Other);
+
+    // Watching continues after the events are recovered from a snapshot
+    send(
+      &sender,
+      EventKind::Create(CreateKind::File),
+      root.join("index.js"),
+    );
+    assert_eq!(
+      watcher.next_events().unwrap(),
+      vec![WatchEvent::Create(root.join("index.js"))]
+    );
+
+    watcher.stopper().stop();
+    let error = watcher.next_events().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::NotConnected);
+  }
+
+  #[test]
+  fn stops_while_waiting_for_changes() {
+    let (mut watcher, _sender) = test_watcher(Path::new("/project"), options(&[]));
+    let stopper = watcher.stopper();
+
+    let stopping = std::thread::spawn(move || {
+      std::thread::sleep(Duration::from_millis(50));
+      stopper.stop();
+    });
+
+    let error = watcher.next_events().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::NotConnected);
+    stopping.join().unwrap();
+  }
+
+  #[test]
+  fn reports_changes_once_the_maximum_wait_passes() {
+    let root = PathBuf::from("/project");
+    let (mut watcher, sender) = test_watcher(
+      &root,
+      WatchOptions {
+        max_wait: Duration::from_millis(300),
+        ..options(&[])
+      },
+    );
+
+    // A file that is written more often than the debounce would otherwise never be reported
+    let writing = std::thread::spawn({
+      let path = root.join("log.txt");
+      move || {
+        for _ in 0..100 {
+          send(
+            &sender,
+            EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+            path.clone(),
+          );
+          std::thread::sleep(Duration::from_millis(20));
+        }
+      }
+    });
+
+    let started = Instant::now();
+    let events = watcher.next_events().unwrap();
+
+    assert_eq!(events, vec![WatchEvent::Delete(root.join("log.txt"))]);
+    assert!(started.elapsed() >= Duration::from_millis(300));
+    assert!(started.elapsed() < Duration::from_millis(1000));
+    writing.join().unwrap();
+  }
+
+  #[test]
+  fn watches_the_directories_that_are_not_ignored() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+
+    std::fs::create_dir_all(root.join("src/components")).unwrap();
+    std::fs::create_dir_all(root.join("node_modules/react")).unwrap();
+    std::fs::write(root.join("src/index.js"), "index").unwrap();
+    std::fs::write(root.join("src/components/app.js"), "app").unwrap();
+    std::fs::write(root.join("node_modules/react/index.js"), "react").unwrap();
+
+    let mut watched = Vec::new();
+    let mut files = Vec::new();
+    watch_tree(
+      &mut None,
+      &root,
+      &IgnoreGlobs::new(&root, &[String::from("node_modules")]),
+      &mut watched,
+      &mut files,
+    )
+    .unwrap();
+
+    watched.sort();
+    files.sort();
+
+    assert_eq!(
+      watched,
+      vec![root.clone(), root.join("src"), root.join("src/components")]
+    );
+    assert_eq!(
+      files,
+      vec![
+        root.join("src/components/app.js"),
+        root.join("src/index.js")
+      ]
+    );
+  }
+
+  #[test]
+  fn reports_the_files_within_created_directories() {
+    let dir = TempDir::new().unwrap();
+    let root = dir.path().canonicalize().unwrap();
+    let (mut watcher, sender) = test_watcher(&root, options(&["node_modules"]));
+
+    std::fs::create_dir_all(root.join("src/generated")).unwrap();
+    std::fs::create_dir_all(root.join("node_modules/react")).unwrap();
+    std::fs::write(root.join("src/generated/types.js"), "types").unwrap();
+    std::fs::write(root.join("node_modules/react/index.js"), "react").unwrap();
+
+    send(
+      &sender,
+      EventKind::Create(CreateKind::Folder),
+      root.join("src/generated"),
+    );
+    send(
+      &sender,
+      EventKind::Create(CreateKind::Folder),
+      root.join("node_modules/react"),
+    );
+
+    assert_eq!(
+      watcher.next_events().unwrap(),
+      vec![
+        WatchEvent::Create(root.join("src/generated")),
+        WatchEvent::Create(root.join("src/generated/types.js")),
+      ]
+    );
+  }
+}
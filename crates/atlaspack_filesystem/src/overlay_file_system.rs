@@ -0,0 +1,564 @@
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use parking_lot::RwLock;
+use xxhash_rust::xxh3::xxh3_64;
+
+use crate::watcher::{WatchEvent, WatchEvents, WatchOptions, WatchSnapshot, WatchStopper, Watcher};
+use crate::{DirEntry, FileSystem, FileSystemRealPathCache, FileSystemRef, FileType, Metadata};
+
+#[derive(Debug)]
+struct Override {
+  contents: Vec<u8>,
+  hash: u64,
+  modified: SystemTime,
+}
+
+type Overrides = Arc<RwLock<BTreeMap<PathBuf, Override>>>;
+
+/// Layers in-memory file contents over another file-system, such as the unsaved buffers of an
+/// editor or generated files that are never written to disk
+///
+/// Overrides are keyed by absolute paths without `.` or `..` components, and relative paths are
+/// resolved against the current working directory of the underlying file-system. Directories
+/// that only contain overrides exist as far as `is_dir`, `read_dir` and `canonicalize` are
+/// concerned. Writes always go to the underlying file-system.
+///
+#[derive(Debug)]
+pub struct OverlayFileSystem {
+  base: FileSystemRef,
+  overrides: Overrides,
+}
+
+impl OverlayFileSystem {
+  pub fn new(base: FileSystemRef) -> Self {
+    OverlayFileSystem {
+      base,
+      overrides: Default::default(),
+    }
+  }
+
+  /// Overrides the contents of a file, returning the event that describes the change
+  pub fn set_file(&self, path: &Path, contents: impl Into<Vec<u8>>) -> WatchEvent {
+    let path = self.normalize(path);
+    let mut overrides = self.overrides.write();
+
+    let event = if overrides.contains_key(&path) || self.base.is_file(&path) {
+      WatchEvent::Update(path.clone())
+    } else {
+      WatchEvent::Create(path.clone())
+    };
+
+    let contents = contents.into();
+    overrides.insert(
+      path,
+      Override {
+        hash: xxh3_64(&contents),
+        contents,
+        modified: SystemTime::now(),
+      },
+    );
+
+    event
+  }
+
+  /// Removes the override for a file, returning the event that describes the change when there
+  /// was one
+  ///
+  /// The file is updated when it also exists in the underlying file-system, and deleted otherwise.
+  ///
+  pub fn remove_file(&self, path: &Path) -> Option<WatchEvent> {
+    let path = self.normalize(path);
+
+    self.overrides.write().remove(&path)?;
+    Some(self.removed_event(path))
+  }
+
+  /// Removes every override, returning the events that describe the changes
+  pub fn clear(&self) -> WatchEvents {
+    let overrides = std::mem::take(&mut *self.overrides.write());
+
+    overrides
+      .into_keys()
+      .map(|path| self.removed_event(path))
+      .collect()
+  }
+
+  fn removed_event(&self, path: PathBuf) -> WatchEvent {
+    if self.base.is_file(&path) {
+      WatchEvent::Update(path)
+    } else {
+      WatchEvent::Delete(path)
+    }
+  }
+
+  fn normalize(&self, path: &Path) -> PathBuf {
+    let mut result = if path.is_absolute() {
+      PathBuf::new()
+    } else {
+      self.base.cwd().unwrap_or_default()
+    };
+
+    for component in path.components() {
+      match component {
+        Component::CurDir => {}
+        Component::ParentDir => {
+          result.pop();
+        }
+        component => result.push(component),
+      }
+    }
+
+    result
+  }
+
+  /// Resolves symlinks in the part of an overridden path that exists in the underlying
+  /// file-system, which can't canonicalize the rest of it
+  fn canonicalize_with(
+    &self,
+    path: &Path,
+    canonicalize: impl Fn(&Path) -> std::io::Result<PathBuf>,
+  ) -> std::io::Result<PathBuf> {
+    let path = self.normalize(path);
+
+    {
+      let overrides = self.overrides.read();
+      if !overrides.contains_key(&path) && !is_virtual_dir(&overrides, &path) {
+        return canonicalize(&path);
+      }
+    }
+
+    for ancestor in path.ancestors().skip(1) {
+      if let Ok(real_path) = canonicalize(ancestor)
+        && let Ok(rest) = path.strip_prefix(ancestor)
+      {
+        return Ok(real_path.join(rest));
+      }
+    }
+
+    Ok(path)
+  }
+}
+
+/// Whether there are overrides within the directory
+fn is_virtual_dir(overrides: &BTreeMap<PathBuf, Override>, path: &Path) -> bool {
+  overrides_within(overrides, path).next().is_some()
+}
+
+fn overrides_within<'a>(
+  overrides: &'a BTreeMap<PathBuf, Override>,
+  path: &'a Path,
+) -> impl Iterator<Item = (&'a PathBuf, &'a Override)> {
+  overrides
+    .range::<Path, _>((Bound::Excluded(path), Bound::Unbounded))
+    .take_while(move |(override_path, _)| override_path.starts_with(path))
+}
+
+impl FileSystem for OverlayFileSystem {
+  fn cwd(&self) -> std::io::Result<PathBuf> {
+    self.base.cwd()
+  }
+
+  fn canonicalize_base(&self, path: &Path) -> std::io::Result<PathBuf> {
+    self.canonicalize_with(path, |path| self.base.canonicalize_base(path))
+  }
+
+  fn canonicalize(&self, path: &Path, cache: &FileSystemRealPathCache) -> std::io::Result<PathBuf> {
+    self.canonicalize_with(path, |path| self.base.canonicalize(path, cache))
+  }
+
+  fn create_directory(&self, path: &Path) -> std::io::Result<()> {
+    self.base.create_directory(path)
+  }
+
+  fn create_dir_all(&self, path: &Path) -> std::io::Result<()> {
+    self.base.create_dir_all(path)
+  }
+
+  fn write(&self, path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    self.base.write(path, contents)
+  }
+
+  fn read(&self, path: &Path) -> std::io::Result<Vec<u8>> {
+    let path = self.normalize(path);
+
+    match self.overrides.read().get(&path) {
+      Some(file) => Ok(file.contents.clone()),
+      None => self.base.read(&path),
+    }
+  }
+
+  fn read_dir(&self, path: &Path) -> std::io::Result<Vec<DirEntry>> {
+    let path = self.normalize(path);
+    let overrides = self.overrides.read();
+
+    let mut entries = match self.base.read_dir(&path) {
+      Ok(entries) => entries,
+      Err(error)
+        if error.kind() == std::io::ErrorKind::NotFound && is_virtual_dir(&overrides, &path) =>
+      {
+        Vec::new()
+      }
+      Err(error) => return Err(error),
+    };
+
+    for (override_path, _) in overrides_within(&overrides, &path) {
+      let Some(name) = override_path
+        .strip_prefix(&path)
+        .ok()
+        .and_then(|rest| rest.components().next())
+      else {
+        continue;
+      };
+
+      let entry_path = path.join(name);
+      let file_type = if entry_path == *override_path {
+        FileType::File
+      } else {
+        FileType::Directory
+      };
+
+      match entries.iter_mut().find(|entry| entry.path == entry_path) {
+        Some(entry) if file_type == FileType::File => entry.file_type = file_type,
+        Some(_) => {}
+        None => entries.push(DirEntry {
+          path: entry_path,
+          file_type,
+        }),
+      }
+    }
+
+    Ok(entries)
+  }
+
+  fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+    let path = self.normalize(path);
+
+    match self.overrides.read().get(&path) {
+      Some(file) => String::from_utf8(file.contents.clone())
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid UTF-8")),
+      None => self.base.read_to_string(&path),
+    }
+  }
+
+  fn is_file(&self, path: &Path) -> bool {
+    let path = self.normalize(path);
+
+    self.overrides.read().contains_key(&path) || self.base.is_file(&path)
+  }
+
+  fn is_dir(&self, path: &Path) -> bool {
+    let path = self.normalize(path);
+
+    is_virtual_dir(&self.overrides.read(), &path) || self.base.is_dir(&path)
+  }
+
+  fn metadata(&self, path: &Path) -> std::io::Result<Metadata> {
+    let path = self.normalize(path);
+    let overrides = self.overrides.read();
+
+    if let Some(file) = overrides.get(&path) {
+      return Ok(Metadata {
+        file_type: FileType::File,
+        len: file.contents.len() as u64,
+        modified: file.modified,
+        is_symlink: false,
+      });
+    }
+
+    match self.base.metadata(&path) {
+      Err(error)
+        if error.kind() == std::io::ErrorKind::NotFound && is_virtual_dir(&overrides, &path) =>
+      {
+        let modified = overrides_within(&overrides, &path)
+          .map(|(_, file)| file.modified)
+          .max()
+          .unwrap_or(SystemTime::UNIX_EPOCH);
+
+        Ok(Metadata {
+          file_type: FileType::Directory,
+          len: 0,
+          modified,
+          is_symlink: false,
+        })
+      }
+      result => result,
+    }
+  }
+
+  fn content_hash(&self, path: &Path) -> std::io::Result<u64> {
+    let path = self.normalize(path);
+
+    match self.overrides.read().get(&path) {
+      Some(file) => Ok(file.hash),
+      None => self.base.content_hash(&path),
+    }
+  }
+
+  fn watch(&self, root: &Path, options: WatchOptions) -> std::io::Result<Box<dyn Watcher>> {
+    Ok(Box::new(OverlayWatcher {
+      inner: self.base.watch(root, options)?,
+      overrides: self.overrides.clone(),
+    }))
+  }
+}
+
+/// Watches the underlying file-system, ignoring changes to overridden files since they are not
+/// visible through the overlay
+struct OverlayWatcher {
+  inner: Box<dyn Watcher>,
+  overrides: Overrides,
+}
+
+impl Watcher for OverlayWatcher {
+  fn next_events(&mut self) -> std::io::Result<WatchEvents> {
+    loop {
+      let mut events = self.inner.next_events()?;
+
+      {
+        let overrides = self.overrides.read();
+        events.retain(|event| !overrides.contains_key(event.path()));
+      }
+
+      if !events.is_empty() {
+        return Ok(events);
+      }
+    }
+  }
+
+  fn snapshot(&self) -> std::io::Result<WatchSnapshot> {
+    self.inner.snapshot()
+  }
+
+  fn stopper(&self) -> WatchStopper {
+    self.inner.stopper()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::MockFileSystem;
+  use crate::in_memory_file_system::InMemoryFileSystem;
+
+  use super::*;
+
+  fn overlay() -> OverlayFileSystem {
+    let base = InMemoryFileSystem::default();
+
+    base.write_file(Path::new("/project/src/index.js"), String::from("disk"));
+    base.write_file(Path::new("/project/src/other.js"), String::from("other"));
+
+    OverlayFileSystem::new(Arc::new(base))
+  }
+
+  fn sorted(mut entries: Vec<DirEntry>) -> Vec<DirEntry> {
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+    entries
+  }
+
+  #[test]
+  fn reads_overrides_before_the_underlying_file_system() {
+    let fs = overlay();
+
+    assert_eq!(
+      fs.set_file(Path::new("/project/src/index.js"), "unsaved"),
+      WatchEvent::Update(PathBuf::from("/project/src/index.js"))
+    );
+    assert_eq!(
+      fs.set_file(Path::new("/project/src/./new.js"), "new"),
+      WatchEvent::Create(PathBuf::from("/project/src/new.js"))
+    );
+
+    assert_eq!(
+      fs.read_to_string(Path::new("/project/src/index.js"))
+        .unwrap(),
+      "unsaved"
+    );
+    assert_eq!(
+      fs.read(Path::new("/project/src/new.js")).unwrap(),
+      b"new".to_vec()
+    );
+    assert_eq!(
+      fs.read_to_string(Path::new("/project/src/other.js"))
+        .unwrap(),
+      "other"
+    );
+
+    assert_eq!(
+      fs.remove_file(Path::new("/project/src/index.js")),
+      Some(WatchEvent::Update(PathBuf::from("/project/src/index.js")))
+    );
+    assert_eq!(
+      fs.clear(),
+      vec![WatchEvent::Delete(PathBuf::from("/project/src/new.js"))]
+    );
+    assert_eq!(fs.remove_file(Path::new("/project/src/new.js")), None);
+
+    assert_eq!(
+      fs.read_to_string(Path::new("/project/src/index.js"))
+        .unwrap(),
+      "disk"
+    );
+    assert!(!fs.is_file(Path::new("/project/src/new.js")));
+  }
+
+  #[test]
+  fn composes_directories_with_the_underlying_file_system() {
+    let fs = overlay();
+
+    fs.set_file(Path::new("/project/src/index.js"), "unsaved");
+    fs.set_file(Path::new("/project/src/generated/a.js"), "a");
+    fs.set_file(Path::new("/project/src/generated/nested/b.js"), "b");
+
+    assert!(fs.is_file(Path::new("/project/src/generated/a.js")));
+    assert!(fs.is_dir(Path::new("/project/src/generated")));
+    assert!(fs.is_dir(Path::new("/project/src/generated/nested")));
+    assert!(!fs.is_dir(Path::new("/project/src/generated/a.js")));
+    assert!(!fs.is_dir(Path::new("/project/src/gen")));
+
+    assert_eq!(
+      sorted(fs.read_dir(Path::new("/project/src")).unwrap()),
+      vec![
+        DirEntry {
+          path: PathBuf::from("/project/src/generated"),
+          file_type: FileType::Directory,
+        },
+        DirEntry {
+          path: PathBuf::from("/project/src/index.js"),
+          file_type: FileType::File,
+        },
+        DirEntry {
+          path: PathBuf::from("/project/src/other.js"),
+          file_type: FileType::File,
+        },
+      ]
+    );
+
+    assert_eq!(
+      sorted(fs.read_dir(Path::new("/project/src/generated")).unwrap()),
+      vec![
+        DirEntry {
+          path: PathBuf::from("/project/src/generated/a.js"),
+          file_type: FileType::File,
+        },
+        DirEntry {
+          path: PathBuf::from("/project/src/generated/nested"),
+          file_type: FileType::Directory,
+        },
+      ]
+    );
+
+    assert!(fs.read_dir(Path::new("/project/missing")).is_err());
+  }
+
+  #[test]
+  fn reports_the_metadata_and_content_hash_of_overrides() {
+    let fs = overlay();
+
+    fs.set_file(Path::new("/project/src/index.js"), "unsaved");
+    fs.set_file(Path::new("/project/src/generated/a.js"), "a");
+
+    let metadata = fs.metadata(Path::new("/project/src/index.js")).unwrap();
+    assert_eq!(metadata.file_type, FileType::File);
+    assert_eq!(metadata.len, 7);
+
+    assert_eq!(
+      fs.metadata(Path::new("/project/src/generated"))
+        .unwrap()
+        .file_type,
+      FileType::Directory
+    );
+    assert_eq!(
+      fs.metadata(Path::new("/project/src/other.js")).unwrap().len,
+      5
+    );
+
+    assert_eq!(
+      fs.content_hash(Path::new("/project/src/index.js")).unwrap(),
+      xxh3_64(b"unsaved")
+    );
+    assert_eq!(
+      fs.content_hash(Path::new("/project/src/other.js")).unwrap(),
+      xxh3_64(b"other")
+    );
+  }
+
+  #[test]
+  fn canonicalizes_overrides_within_symlinked_directories() {
+    let mut base = MockFileSystem::new();
+
+    base
+      .expect_canonicalize_base()
+      .returning(|path| match path.to_str() {
+        Some("/link") => Ok(PathBuf::from("/real")),
+        Some("/real/file.js") => Ok(path.to_path_buf()),
+        _ => Err(std::io::Error::from(std::io::ErrorKind::NotFound)),
+      });
+    base.expect_is_file().returning(|_| false);
+
+    let fs = OverlayFileSystem::new(Arc::new(base));
+    fs.set_file(Path::new("/link/generated/index.js"), "generated");
+
+    assert_eq!(
+      fs.canonicalize_base(Path::new("/link/generated/index.js"))
+        .unwrap(),
+      PathBuf::from("/real/generated/index.js")
+    );
+    assert_eq!(
+      fs.canonicalize_base(Path::new("/link/generated")).unwrap(),
+      PathBuf::from("/real/generated")
+    );
+    assert_eq!(
+      fs.canonicalize_base(Path::new("/real/file.js")).unwrap(),
+      PathBuf::from("/real/file.js")
+    );
+    assert!(fs.canonicalize_base(Path::new("/link/missing.js")).is_err());
+  }
+
+  struct FakeWatcher(Vec<WatchEvents>);
+
+  impl Watcher for FakeWatcher {
+    fn next_events(&mut self) -> std::io::Result<WatchEvents> {
+      Ok(self.0.remove(0))
+    }
+
+    fn snapshot(&self) -> std::io::Result<WatchSnapshot> {
+      Ok(WatchSnapshot::default())
+    }
+
+    fn stopper(&self) -> WatchStopper {
+      WatchStopper::new(|| {})
+    }
+  }
+
+  #[test]
+  fn ignores_changes_on_disk_to_overridden_files() {
+    let mut base = MockFileSystem::new();
+
+    base.expect_is_file().returning(|_| true);
+    base.expect_watch().returning(|_, _| {
+      Ok(Box::new(FakeWatcher(vec![
+        vec![WatchEvent::Update(PathBuf::from("/project/index.js"))],
+        vec![
+          WatchEvent::Update(PathBuf::from("/project/index.js")),
+          WatchEvent::Update(PathBuf::from("/project/other.js")),
+        ],
+      ])))
+    });
+
+    let fs = OverlayFileSystem::new(Arc::new(base));
+    fs.set_file(Path::new("/project/index.js"), "unsaved");
+
+    let mut watcher = fs
+      .watch(Path::new("/project"), WatchOptions::default())
+      .unwrap();
+
+    assert_eq!(
+      watcher.next_events().unwrap(),
+      vec![WatchEvent::Update(PathBuf::from("/project/other.js"))]
+    );
+  }
+}
@@ -0,0 +1,326 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+use serde::Serialize;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "path", rename_all = "camelCase")]
+pub enum WatchEvent {
+  Create(PathBuf),
+  Update(PathBuf),
+  Delete(PathBuf),
+}
+
+impl WatchEvent {
+  pub fn path(&self) -> &Path {
+    match self {
+      WatchEvent::Create(path) | WatchEvent::Update(path) | WatchEvent::Delete(path) => path,
+    }
+  }
+}
+
+pub type WatchEvents = Vec<WatchEvent>;
+
+/// Directories that never contain build inputs, which are ignored by every watcher
+pub const DEFAULT_IGNORE: &[&str] = &[".git", ".hg"];
+
+#[derive(Clone, Debug)]
+pub struct WatchOptions {
+  /// Globs for the paths that should not be reported, relative to the watched directory
+  ///
+  /// Absolute paths within the watched directory, such as the cache and dist directories, are
+  /// also accepted. A path is ignored when it or any of its parent directories match, so a glob
+  /// for a directory ignores everything inside of it.
+  ///
+  pub ignore: Vec<String>,
+
+  /// How long to wait for further changes before reporting a batch of events
+  pub debounce: Duration,
+
+  /// The longest a batch of events is held back for, so that files that are written
+  /// continuously do not stop changes from being reported
+  pub max_wait: Duration,
+}
+
+impl Default for WatchOptions {
+  fn default() -> Self {
+    WatchOptions {
+      ignore: DEFAULT_IGNORE.iter().map(|glob| glob.to_string()).collect(),
+      debounce: Duration::from_millis(50),
+      max_wait: Duration::from_millis(500),
+    }
+  }
+}
+
+/// Stops a watcher from another thread, such as when the process is shutting down
+#[derive(Clone)]
+pub struct WatchStopper(Arc<dyn Fn() + Send + Sync>);
+
+impl WatchStopper {
+  pub fn new(stop: impl Fn() + Send + Sync + 'static) -> Self {
+    WatchStopper(Arc::new(stop))
+  }
+
+  /// Makes the watcher return a `NotConnected` error from `next_events`, including when it is
+  /// currently waiting for changes
+  pub fn stop(&self) {
+    (self.0)()
+  }
+}
+
+impl std::fmt::Debug for WatchStopper {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("WatchStopper").finish_non_exhaustive()
+  }
+}
+
+/// Reports changes to the files within a directory
+pub trait Watcher: Send {
+  /// Blocks until files have changed, then returns the changes once no further changes arrive
+  /// within the debounce window, or once the maximum wait has passed
+  ///
+  /// Returns an error when the underlying watcher dropped events, in which case the changes can
+  /// be recovered by comparing against a snapshot with `get_events_since`. An error with the
+  /// `NotConnected` kind means the watcher stopped, and no further events will be reported.
+  ///
+  fn next_events(&mut self) -> std::io::Result<WatchEvents>;
+
+  /// Records the current state of the watched files
+  fn snapshot(&self) -> std::io::Result<WatchSnapshot>;
+
+  /// Returns a handle that stops this watcher from another thread
+  fn stopper(&self) -> WatchStopper;
+
+  /// Returns the changes since the snapshot was taken, which may be from a previous process
+  fn get_events_since(&self, snapshot: &WatchSnapshot) -> std::io::Result<WatchEvents> {
+    Ok(self.snapshot()?.events_since(snapshot))
+  }
+}
+
+/// The size is compared as well as the modification time, as the time may have a coarse resolution
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FileState {
+  pub modified: SystemTime,
+  pub size: u64,
+}
+
+/// The state of every file within a watched directory at a point in time
+///
+/// Snapshots can be serialised and written to the cache, so that the changes made while the
+/// process was not running can be found when it starts again.
+///
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WatchSnapshot {
+  pub files: BTreeMap<PathBuf, FileState>,
+}
+
+impl WatchSnapshot {
+  /// Compares this snapshot with an earlier one
+  pub fn events_since(&self, previous: &WatchSnapshot) -> WatchEvents {
+    let mut events = Vec::new();
+
+    for (path, state) in self.files.iter() {
+      match previous.files.get(path) {
+        None => events.push(WatchEvent::Create(path.clone())),
+        Some(previous) if previous != state => events.push(WatchEvent::Update(path.clone())),
+        Some(_) => {}
+      }
+    }
+
+    for path in previous.files.keys() {
+      if !self.files.contains_key(path) {
+        events.push(WatchEvent::Delete(path.clone()));
+      }
+    }
+
+    events
+  }
+}
+
+/// Matches paths against the ignore globs of a watcher
+#[derive(Clone, Debug)]
+pub(crate) struct IgnoreGlobs {
+  root: PathBuf,
+  globs: Vec<String>,
+}
+
+impl IgnoreGlobs {
+  pub(crate) fn new(root: &Path, ignore: &[String]) -> Self {
+    let globs = ignore
+      .iter()
+      .filter_map(|glob| {
+        let path = Path::new(glob);
+        if !path.is_absolute() {
+          return Some(glob.clone());
+        }
+
+        // Absolute paths outside of the watched directory can never match
+        path.strip_prefix(root).ok().map(to_slash)
+      })
+      .collect();
+
+    IgnoreGlobs {
+      root: root.to_path_buf(),
+      globs,
+    }
+  }
+
+  pub(crate) fn is_ignored(&self, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(&self.root) else {
+      return false;
+    };
+
+    relative
+      .ancestors()
+      .filter(|ancestor| !ancestor.as_os_str().is_empty())
+      .any(|ancestor| {
+        let ancestor = to_slash(ancestor);
+        self
+          .globs
+          .iter()
+          .any(|glob| glob_match::glob_match(glob, &ancestor))
+      })
+  }
+}
+
+fn to_slash(path: &Path) -> String {
+  path.to_string_lossy().replace('\\', "/")
+}
+
+/// Merges the events for each path, so that a batch contains at most one event per path
+///
+/// A file that is created then deleted within a batch is not reported at all, and a file that is
+/// deleted then created again is reported as updated.
+///
+#[derive(Debug, Default)]
+pub(crate) struct EventCoalescer {
+  events: BTreeMap<PathBuf, WatchEvent>,
+}
+
+impl EventCoalescer {
+  pub(crate) fn push(&mut self, event: WatchEvent) {
+    let path = event.path().to_path_buf();
+
+    let coalesced = match (self.events.remove(&path), event) {
+      (None, event) => Some(event),
+      (Some(WatchEvent::Create(_)), WatchEvent::Delete(_)) => None,
+      (Some(WatchEvent::Create(path)), _) => Some(WatchEvent::Create(path)),
+      (Some(WatchEvent::Delete(_)), WatchEvent::Create(path) | WatchEvent::Update(path)) => {
+        Some(WatchEvent::Update(path))
+      }
+      (Some(WatchEvent::Update(_) | WatchEvent::Delete(_)), WatchEvent::Delete(path)) => {
+        Some(WatchEvent::Delete(path))
+      }
+      (Some(WatchEvent::Update(_)), WatchEvent::Create(path) | WatchEvent::Update(path)) => {
+        Some(WatchEvent::Update(path))
+      }
+    };
+
+    if let Some(event) = coalesced {
+      self.events.insert(path, event);
+    }
+  }
+
+  pub(crate) fn is_empty(&self) -> bool {
+    self.events.is_empty()
+  }
+
+  pub(crate) fn drain(&mut self) -> WatchEvents {
+    std::mem::take(&mut self.events).into_values().collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn ignores_paths_that_match_or_are_within_a_glob() {
+    let root = Path::new("/project");
+    let ignore = IgnoreGlobs::new(
+      root,
+      &[
+        String::from(".git"),
+        String::from("**/node_modules"),
+        String::from("/project/dist"),
+        String::from("/elsewhere/cache"),
+      ],
+    );
+
+    assert!(ignore.is_ignored(Path::new("/project/.git")));
+    assert!(ignore.is_ignored(Path::new("/project/.git/HEAD")));
+    assert!(ignore.is_ignored(Path::new("/project/packages/a/node_modules/b/index.js")));
+    assert!(ignore.is_ignored(Path::new("/project/dist/index.js")));
+    assert!(!ignore.is_ignored(Path::new("/project/src/index.js")));
+    assert!(!ignore.is_ignored(Path::new("/project/src/.gitignore")));
+    assert!(!ignore.is_ignored(Path::new("/elsewhere/cache/data.mdb")));
+  }
+
+  #[test]
+  fn coalesces_events_for_the_same_path() {
+    let mut coalescer = EventCoalescer::default();
+    let path = |name: &str| PathBuf::from(format!("/project/{name}"));
+
+    coalescer.push(WatchEvent::Create(path("created.js")));
+    coalescer.push(WatchEvent::Update(path("created.js")));
+    coalescer.push(WatchEvent::Create(path("temporary.js")));
+    coalescer.push(WatchEvent::Delete(path("temporary.js")));
+    coalescer.push(WatchEvent::Delete(path("replaced.js")));
+    coalescer.push(WatchEvent::Create(path("replaced.js")));
+    coalescer.push(WatchEvent::Update(path("removed.js")));
+    coalescer.push(WatchEvent::Delete(path("removed.js")));
+    coalescer.push(WatchEvent::Update(path("updated.js")));
+    coalescer.push(WatchEvent::Update(path("updated.js")));
+
+    assert_eq!(
+      coalescer.drain(),
+      vec![
+        WatchEvent::Create(path("created.js")),
+        WatchEvent::Delete(path("removed.js")),
+        WatchEvent::Update(path("replaced.js")),
+        WatchEvent::Update(path("updated.js")),
+      ]
+    );
+
+    assert!(coalescer.is_empty());
+  }
+
+  #[test]
+  fn compares_snapshots() {
+    let state = |seconds: u64, size: u64| FileState {
+      modified: SystemTime::UNIX_EPOCH + Duration::from_secs(seconds),
+      size,
+    };
+
+    let previous = WatchSnapshot {
+      files: BTreeMap::from([
+        (PathBuf::from("/project/index.js"), state(1, 10)),
+        (PathBuf::from("/project/removed.js"), state(1, 10)),
+        (PathBuf::from("/project/resized.js"), state(1, 10)),
+        (PathBuf::from("/project/unchanged.js"), state(1, 10)),
+      ]),
+    };
+
+    let current = WatchSnapshot {
+      files: BTreeMap::from([
+        (PathBuf::from("/project/created.js"), state(2, 10)),
+        (PathBuf::from("/project/index.js"), state(2, 10)),
+        (PathBuf::from("/project/resized.js"), state(1, 20)),
+        (PathBuf::from("/project/unchanged.js"), state(1, 10)),
+      ]),
+    };
+
+    assert_eq!(
+      current.events_since(&previous),
+      vec![
+        WatchEvent::Create(PathBuf::from("/project/created.js")),
+        WatchEvent::Update(PathBuf::from("/project/index.js")),
+        WatchEvent::Update(PathBuf::from("/project/resized.js")),
+        WatchEvent::Delete(PathBuf::from("/project/removed.js")),
+      ]
+    );
+  }
+}
@@ -0,0 +1,145 @@
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Files that belong to LMDB rather than the blob cache
+pub(crate) const LMDB_FILES: &[&str] = &["data.mdb", "lock.mdb"];
+
+/// A large blob, which is stored as a file within the cache directory rather than in LMDB
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Blob {
+  /// The cache key, which is the path relative to the cache directory
+  pub(crate) key: String,
+  pub(crate) path: PathBuf,
+  pub(crate) size: u64,
+  /// Seconds since the epoch that the blob was last read or written
+  pub(crate) last_used: u64,
+}
+
+impl Blob {
+  /// The version embedded as the second segment of keys written by the native packagers, such as
+  /// `PackagerRunner/<version>/<hash>/content`
+  ///
+  /// Blobs without a version may belong to the JavaScript cache.
+  ///
+  pub(crate) fn version(&self) -> Option<u64> {
+    blob_version(&self.key)
+  }
+
+  /// The key of the large blob that this file is a chunk of, when it was written by the
+  /// JavaScript `FSCache`, which stores large blobs at the root of the cache directory as
+  /// `<key>-<index>`
+  pub(crate) fn large_blob_key(&self) -> Option<&str> {
+    if self.key.contains('/') {
+      return None;
+    }
+
+    let (key, index) = self.key.rsplit_once('-')?;
+    let is_index = !index.is_empty() && index.bytes().all(|byte| byte.is_ascii_digit());
+
+    is_index.then_some(key)
+  }
+}
+
+pub(crate) fn blob_version(key: &str) -> Option<u64> {
+  key.split('/').nth(1)?.parse().ok()
+}
+
+/// Lists the blobs within a cache directory, sorted by key
+pub(crate) fn list_blobs(cache_dir: &Path) -> std::io::Result<Vec<Blob>> {
+  let mut blobs = Vec::new();
+  let mut dirs = vec![cache_dir.to_path_buf()];
+
+  while let Some(dir) = dirs.pop() {
+    for entry in std::fs::read_dir(dir)? {
+      let entry = entry?;
+      let file_type = entry.file_type()?;
+      let path = entry.path();
+
+      if file_type.is_dir() {
+        dirs.push(path);
+        continue;
+      }
+
+      let Ok(relative) = path.strip_prefix(cache_dir) else {
+        continue;
+      };
+
+      let key = to_key(relative);
+      if !file_type.is_file() || LMDB_FILES.contains(&key.as_str()) {
+        continue;
+      }
+
+      let metadata = entry.metadata()?;
+
+      // Access times are often disabled or coarse, so the modification time is used as a floor
+      let last_used = [metadata.accessed(), metadata.modified()]
+        .into_iter()
+        .filter_map(|time| time.ok())
+        .max()
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+
+      blobs.push(Blob {
+        key,
+        path,
+        size: metadata.len(),
+        last_used: last_used
+          .duration_since(UNIX_EPOCH)
+          .map_or(0, |duration| duration.as_secs()),
+      });
+    }
+  }
+
+  blobs.sort_by(|a, b| a.key.cmp(&b.key));
+  Ok(blobs)
+}
+
+fn to_key(relative: &Path) -> String {
+  relative
+    .components()
+    .map(|component| component.as_os_str().to_string_lossy())
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use tempfile::tempdir;
+
+  use super::*;
+
+  #[test]
+  fn lists_the_blobs_within_the_cache_directory() {
+    let dir = tempdir().unwrap();
+
+    std::fs::create_dir_all(dir.path().join("PackagerRunner/7/abc")).unwrap();
+    std::fs::write(dir.path().join("PackagerRunner/7/abc/content"), "bundle").unwrap();
+    std::fs::write(dir.path().join("requestGraph-abc-0"), "graph").unwrap();
+    std::fs::write(dir.path().join("requestGraph-abc-1"), "chunk").unwrap();
+    std::fs::write(dir.path().join("snapshot-abc.txt"), "snapshot").unwrap();
+    std::fs::write(dir.path().join("data.mdb"), "").unwrap();
+    std::fs::write(dir.path().join("lock.mdb"), "").unwrap();
+
+    let blobs = list_blobs(dir.path()).unwrap();
+
+    assert_eq!(
+      blobs
+        .iter()
+        .map(|blob| (
+          blob.key.as_str(),
+          blob.size,
+          blob.version(),
+          blob.large_blob_key()
+        ))
+        .collect::<Vec<_>>(),
+      vec![
+        ("PackagerRunner/7/abc/content", 6, Some(7), None),
+        ("requestGraph-abc-0", 5, None, Some("requestGraph-abc")),
+        ("requestGraph-abc-1", 5, None, Some("requestGraph-abc")),
+        ("snapshot-abc.txt", 8, None, None),
+      ]
+    );
+
+    assert!(blobs.iter().all(|blob| blob.last_used > 0));
+  }
+}
@@ -0,0 +1,429 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use lmdb_js_lite::DatabaseHandle;
+use serde::{Deserialize, Serialize};
+
+use crate::blob_cache::{LMDB_FILES, blob_version, list_blobs};
+use crate::lmdb_cache_reader_writer::{CacheTracker, METADATA_KEY};
+
+const MAGIC: &[u8; 8] = b"ATLCACHE";
+const FORMAT_VERSION: u32 = 1;
+
+/// Stands in for the project root within archived keys and values. JSON escapes control
+/// characters, so this can't appear within a serialized value.
+const PROJECT_ROOT_PLACEHOLDER: &str = "\0project_root\0";
+
+/// The number of entries written per LMDB transaction when importing
+const IMPORT_BATCH_SIZE: usize = 1000;
+
+#[derive(Clone, Debug)]
+pub struct CacheArchiveOptions {
+  /// Paths within the project root are archived relative to it, so that an archive can be
+  /// imported into a checkout at a different location
+  pub project_root: PathBuf,
+
+  /// The `atlaspack_rust_version()` of the current build, which entries must have been created by
+  /// to be imported
+  pub rust_version: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CacheArchiveSummary {
+  /// Memoization cache entries that were exported or imported
+  pub entries: usize,
+  /// Large blobs that were exported or imported
+  pub blobs: usize,
+  /// Entries and blobs that were left out, as they were created by another version
+  pub skipped: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ArchiveHeader {
+  rust_version: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+enum ArchiveRecord {
+  Entry {
+    key: String,
+    value: Vec<u8>,
+    timestamp: u64,
+  },
+  Blob {
+    key: String,
+    contents: Vec<u8>,
+  },
+  End,
+}
+
+/// Writes the memoization cache entries and large blobs of a cache to a compressed archive
+///
+/// Entries are the keys recorded by the `CacheTracker`, along with when they were last used.
+/// Blobs are every other file within the cache directory, which includes the large blobs written
+/// by the JavaScript cache as `<key>-<index>` and those written by the native packagers as
+/// `PackagerRunner/<version>/<hash>/content`.
+///
+pub fn export_cache(
+  db_handle: &DatabaseHandle,
+  output: &Path,
+  options: &CacheArchiveOptions,
+) -> anyhow::Result<CacheArchiveSummary> {
+  let output = std::path::absolute(output)?;
+  let cache_dir = db_handle.database().path().to_path_buf();
+  let project_root = project_root_prefix(&options.project_root);
+  let mut summary = CacheArchiveSummary::default();
+
+  let mut writer = BufWriter::new(File::create(&output)?);
+  writer.write_all(MAGIC)?;
+  writer.write_all(&FORMAT_VERSION.to_le_bytes())?;
+
+  let mut encoder = lz4_flex::frame::FrameEncoder::new(writer);
+  bincode::serialize_into(
+    &mut encoder,
+    &ArchiveHeader {
+      rust_version: options.rust_version,
+    },
+  )?;
+
+  let tracker = CacheTracker::load(db_handle)?;
+  let db = db_handle.database();
+  let transaction = db.read_txn()?;
+
+  for entry in tracker.entries.iter() {
+    // Entries can be tracked after they have been removed
+    let Some(value) = db.get(&transaction, &entry.key)? else {
+      continue;
+    };
+
+    let record = ArchiveRecord::Entry {
+      key: entry.key.replace(&project_root, PROJECT_ROOT_PLACEHOLDER),
+      value: replace_bytes(&value, &project_root, PROJECT_ROOT_PLACEHOLDER),
+      timestamp: entry.timestamp,
+    };
+
+    bincode::serialize_into(&mut encoder, &record)?;
+    summary.entries += 1;
+  }
+
+  drop(transaction);
+
+  for blob in list_blobs(&cache_dir)? {
+    if blob.path == output {
+      continue;
+    }
+
+    let record = ArchiveRecord::Blob {
+      contents: std::fs::read(&blob.path)?,
+      key: blob.key,
+    };
+
+    bincode::serialize_into(&mut encoder, &record)?;
+    summary.blobs += 1;
+  }
+
+  bincode::serialize_into(&mut encoder, &ArchiveRecord::End)?;
+  encoder.finish()?.flush()?;
+
+  Ok(summary)
+}
+
+/// Reads an archive created by `export_cache` into a cache
+///
+/// Entries, and blobs whose keys include a version, are skipped when they were created by a
+/// different version, as they would never be read. Existing entries and blobs with the same keys
+/// are replaced.
+///
+pub fn import_cache(
+  db_handle: &DatabaseHandle,
+  input: &Path,
+  options: &CacheArchiveOptions,
+) -> anyhow::Result<CacheArchiveSummary> {
+  let cache_dir = db_handle.database().path().to_path_buf();
+  let project_root = project_root_prefix(&options.project_root);
+  let mut summary = CacheArchiveSummary::default();
+
+  let mut reader = BufReader::new(File::open(input)?);
+
+  let mut magic = [0; MAGIC.len()];
+  reader.read_exact(&mut magic)?;
+  if &magic != MAGIC {
+    return Err(anyhow::anyhow!(
+      "{} is not an Atlaspack cache archive",
+      input.display()
+    ));
+  }
+
+  let mut format_version = [0; 4];
+  reader.read_exact(&mut format_version)?;
+  let format_version = u32::from_le_bytes(format_version);
+  if format_version != FORMAT_VERSION {
+    return Err(anyhow::anyhow!(
+      "Unsupported cache archive format {format_version}, expected {FORMAT_VERSION}"
+    ));
+  }
+
+  let mut decoder = lz4_flex::frame::FrameDecoder::new(reader);
+  let header: ArchiveHeader = bincode::deserialize_from(&mut decoder)?;
+
+  // The version is hashed into memoization cache keys rather than embedded, so entries can only
+  // be checked against the version that exported them
+  let entries_are_compatible = header.rust_version == options.rust_version;
+
+  let mut tracker = CacheTracker::load(db_handle)?;
+  let db = db_handle.database();
+  let mut transaction = db.write_txn()?;
+  let mut batch_size = 0;
+
+  loop {
+    match bincode::deserialize_from(&mut decoder)? {
+      ArchiveRecord::Entry {
+        key,
+        value,
+        timestamp,
+      } => {
+        if !entries_are_compatible || key == METADATA_KEY {
+          summary.skipped += 1;
+          continue;
+        }
+
+        let key = key.replace(PROJECT_ROOT_PLACEHOLDER, &project_root);
+        let value = replace_bytes(&value, PROJECT_ROOT_PLACEHOLDER, &project_root);
+
+        db.put(&mut transaction, &key, &value)?;
+        tracker.update_entry(key, timestamp);
+        summary.entries += 1;
+
+        batch_size += 1;
+        if batch_size == IMPORT_BATCH_SIZE {
+          transaction.commit()?;
+          transaction = db.write_txn()?;
+          batch_size = 0;
+        }
+      }
+      ArchiveRecord::Blob { key, contents } => {
+        if blob_version(&key).is_some_and(|version| version != options.rust_version) {
+          summary.skipped += 1;
+          continue;
+        }
+
+        let path = cache_dir.join(blob_path(&key)?);
+        if let Some(parent) = path.parent() {
+          std::fs::create_dir_all(parent)?;
+        }
+
+        std::fs::write(&path, contents)?;
+        summary.blobs += 1;
+      }
+      ArchiveRecord::End => break,
+    }
+  }
+
+  transaction.commit()?;
+  tracker.save(db_handle)?;
+
+  Ok(summary)
+}
+
+/// Archives are untrusted input, so blob keys can't be allowed to escape the cache directory
+fn blob_path(key: &str) -> anyhow::Result<PathBuf> {
+  let path = PathBuf::from(key);
+  let is_relative = path
+    .components()
+    .all(|component| matches!(component, Component::Normal(_)));
+
+  match is_relative && !LMDB_FILES.contains(&key) {
+    true => Ok(path),
+    false => Err(anyhow::anyhow!("Invalid blob key in cache archive: {key}")),
+  }
+}
+
+/// The project root is only replaced when followed by a separator, so that a sibling directory
+/// sharing its name as a prefix is left alone
+fn project_root_prefix(project_root: &Path) -> String {
+  let mut prefix = project_root.to_string_lossy().into_owned();
+  if !prefix.ends_with(std::path::MAIN_SEPARATOR) {
+    prefix.push(std::path::MAIN_SEPARATOR);
+  }
+
+  prefix
+}
+
+fn replace_bytes(input: &[u8], from: &str, to: &str) -> Vec<u8> {
+  let (from, to) = (from.as_bytes(), to.as_bytes());
+  let mut output = Vec::with_capacity(input.len());
+  let mut rest = input;
+
+  while let Some(index) = rest.windows(from.len()).position(|window| window == from) {
+    output.extend_from_slice(&rest[..index]);
+    output.extend_from_slice(to);
+    rest = &rest[index + from.len()..];
+  }
+
+  output.extend_from_slice(rest);
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use lmdb_js_lite::{LMDBOptions, get_database};
+  use pretty_assertions::assert_eq;
+  use tempfile::{TempDir, tempdir};
+
+  use super::*;
+  use crate::{CacheReaderWriter, LmdbCacheReaderWriter};
+
+  fn create_db(dir: &TempDir) -> Arc<DatabaseHandle> {
+    get_database(LMDBOptions {
+      path: dir.path().join("cache").to_string_lossy().into_owned(),
+      async_writes: false,
+      map_size: None,
+    })
+    .unwrap()
+  }
+
+  fn options(project_root: &str, rust_version: u64) -> CacheArchiveOptions {
+    CacheArchiveOptions {
+      project_root: PathBuf::from(project_root),
+      rust_version,
+    }
+  }
+
+  fn write_blob(db: &DatabaseHandle, key: &str, contents: &str) {
+    let path = db.database().path().join(key);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+  }
+
+  #[test]
+  fn round_trips_entries_and_blobs_between_caches() {
+    let dir = tempdir().unwrap();
+    let source = create_db(&dir);
+
+    let cache = LmdbCacheReaderWriter::new(source.clone());
+    cache
+      .put(
+        "run_pipeline|src/a.js|1",
+        br#"{"filePath":"/old/root/src/a.js"}"#,
+      )
+      .unwrap();
+    cache.put("run_pipeline|src/b.js|2", b"b").unwrap();
+    cache.complete_session().unwrap();
+
+    write_blob(&source, "PackagerRunner/7/abc/content", "bundle");
+    write_blob(&source, "PackagerRunner/6/def/content", "stale bundle");
+    write_blob(&source, "requestGraph-abc-0", "graph");
+    write_blob(&source, "requestGraph-abc-1", "chunk");
+
+    let archive = dir.path().join("cache.archive");
+    let exported = export_cache(&source, &archive, &options("/old/root", 7)).unwrap();
+
+    assert_eq!(
+      exported,
+      CacheArchiveSummary {
+        entries: 2,
+        blobs: 4,
+        skipped: 0
+      }
+    );
+
+    let target_dir = tempdir().unwrap();
+    let target = create_db(&target_dir);
+    let imported = import_cache(&target, &archive, &options("/new/root", 7)).unwrap();
+
+    assert_eq!(
+      imported,
+      CacheArchiveSummary {
+        entries: 2,
+        blobs: 3,
+        skipped: 1
+      }
+    );
+
+    let cache = LmdbCacheReaderWriter::new(target.clone());
+    assert_eq!(
+      cache.read("run_pipeline|src/a.js|1").unwrap(),
+      Some(br#"{"filePath":"/new/root/src/a.js"}"#.to_vec())
+    );
+    assert_eq!(
+      cache.read("run_pipeline|src/b.js|2").unwrap(),
+      Some(b"b".to_vec())
+    );
+
+    let tracker = CacheTracker::load(&target).unwrap();
+    assert_eq!(tracker.entries.len(), 2);
+
+    let blobs = target.database().path();
+    assert_eq!(
+      std::fs::read_to_string(blobs.join("PackagerRunner/7/abc/content")).unwrap(),
+      "bundle"
+    );
+    assert!(!blobs.join("PackagerRunner/6/def/content").exists());
+    assert_eq!(
+      std::fs::read_to_string(blobs.join("requestGraph-abc-0")).unwrap(),
+      "graph"
+    );
+    assert_eq!(
+      std::fs::read_to_string(blobs.join("requestGraph-abc-1")).unwrap(),
+      "chunk"
+    );
+  }
+
+  #[test]
+  fn skips_entries_from_another_version() {
+    let dir = tempdir().unwrap();
+    let source = create_db(&dir);
+
+    let cache = LmdbCacheReaderWriter::new(source.clone());
+    cache.put("key", b"value").unwrap();
+    cache.complete_session().unwrap();
+
+    let archive = dir.path().join("cache.archive");
+    export_cache(&source, &archive, &options("/root", 1)).unwrap();
+
+    let target_dir = tempdir().unwrap();
+    let target = create_db(&target_dir);
+    let imported = import_cache(&target, &archive, &options("/root", 2)).unwrap();
+
+    assert_eq!(
+      imported,
+      CacheArchiveSummary {
+        entries: 0,
+        blobs: 0,
+        skipped: 1
+      }
+    );
+    assert_eq!(
+      LmdbCacheReaderWriter::new(target).read("key").unwrap(),
+      None
+    );
+  }
+
+  #[test]
+  fn rejects_invalid_archives() {
+    let dir = tempdir().unwrap();
+    let db = create_db(&dir);
+    let archive = dir.path().join("cache.archive");
+
+    std::fs::write(&archive, "not an archive").unwrap();
+    assert!(import_cache(&db, &archive, &options("/root", 1)).is_err());
+
+    assert!(blob_path("PackagerRunner/1/abc/content").is_ok());
+    assert!(blob_path("PackagerRunner/1/../../../etc/passwd").is_err());
+    assert!(blob_path("/etc/passwd").is_err());
+    assert!(blob_path("data.mdb").is_err());
+  }
+
+  #[test]
+  fn replaces_the_project_root() {
+    let root = project_root_prefix(Path::new("/root"));
+
+    assert_eq!(
+      replace_bytes(b"/root/a.js /root-other/b.js /root/c.js", &root, "<>"),
+      b"<>a.js /root-other/b.js <>c.js".to_vec()
+    );
+  }
+}
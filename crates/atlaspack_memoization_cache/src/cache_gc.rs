@@ -0,0 +1,454 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use lmdb_js_lite::DatabaseHandle;
+use serde::Serialize;
+
+use crate::blob_cache::list_blobs;
+use crate::lmdb_cache_reader_writer::CacheTracker;
+
+#[derive(Clone, Debug)]
+pub struct CacheGcOptions {
+  /// The maximum number of memoization cache entries, beyond which the oldest are removed
+  pub max_entries: usize,
+
+  /// The maximum total size of the memoization cache entries and large blobs
+  ///
+  /// Entries are measured before compression, and count as empty until they are next written if
+  /// they were written by a version that didn't track sizes.
+  ///
+  pub max_bytes: Option<u64>,
+
+  /// Entries and blobs that haven't been used for longer than this are removed
+  pub max_age: Option<Duration>,
+}
+
+impl Default for CacheGcOptions {
+  fn default() -> Self {
+    CacheGcOptions {
+      max_entries: 300000,
+      max_bytes: None,
+      max_age: None,
+    }
+  }
+}
+
+impl CacheGcOptions {
+  /// Reads the limits from `ATLASPACK_CACHE_MAX_BYTES` and `ATLASPACK_CACHE_MAX_AGE_DAYS`
+  pub fn from_env() -> Self {
+    let var = |name: &str| std::env::var(name).ok()?.parse::<u64>().ok();
+
+    CacheGcOptions {
+      max_bytes: var("ATLASPACK_CACHE_MAX_BYTES"),
+      max_age: var("ATLASPACK_CACHE_MAX_AGE_DAYS")
+        .map(|days| Duration::from_secs(days * 24 * 60 * 60)),
+      ..CacheGcOptions::default()
+    }
+  }
+}
+
+/// What was removed by a garbage collection
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CacheGcSummary {
+  pub entries: usize,
+  pub blobs: usize,
+  pub bytes: u64,
+}
+
+enum Garbage {
+  Entry(String),
+  Blob(Vec<PathBuf>),
+}
+
+struct Candidate {
+  last_used: u64,
+  size: u64,
+  garbage: Garbage,
+}
+
+/// Removes memoization cache entries and large blobs according to the limits
+pub fn collect_garbage(
+  db_handle: &DatabaseHandle,
+  options: &CacheGcOptions,
+) -> anyhow::Result<CacheGcSummary> {
+  let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+  let mut tracker = CacheTracker::load(db_handle)?;
+
+  let summary = collect_garbage_with_tracker(db_handle, &mut tracker, options, now)?;
+  tracker.save(db_handle)?;
+
+  Ok(summary)
+}
+
+/// Removes the garbage from the cache and the tracker, which the caller is responsible for saving
+pub(crate) fn collect_garbage_with_tracker(
+  db_handle: &DatabaseHandle,
+  tracker: &mut CacheTracker,
+  options: &CacheGcOptions,
+  now: u64,
+) -> anyhow::Result<CacheGcSummary> {
+  let mut summary = CacheGcSummary::default();
+  let mut removed_keys = Vec::new();
+  let mut removed_blobs = Vec::new();
+
+  let excess_entries = tracker.entries.len().saturating_sub(options.max_entries);
+  if excess_entries > 0 {
+    tracing::info!(
+      "Cache exceeded max entries ({}). Removing {} oldest entries.",
+      options.max_entries,
+      excess_entries
+    );
+
+    summary.bytes += tracker
+      .entries
+      .iter()
+      .take(excess_entries)
+      .map(|entry| tracker.size(&entry.key))
+      .sum::<u64>();
+
+    removed_keys.extend(tracker.remove_oldest_entries(excess_entries));
+  }
+
+  // Listing the blobs requires a walk of the cache directory, so it is only done when needed
+  if options.max_bytes.is_some() || options.max_age.is_some() {
+    let mut candidates = tracker
+      .entries
+      .iter()
+      .map(|entry| Candidate {
+        last_used: entry.timestamp,
+        size: tracker.size(&entry.key),
+        garbage: Garbage::Entry(entry.key.clone()),
+      })
+      .collect::<Vec<_>>();
+
+    // The chunks of a large blob written by the JavaScript cache are only readable together, so
+    // they are removed together. Other files, such as watcher snapshots, are left alone.
+    let mut large_blobs = HashMap::<String, Candidate>::new();
+    for blob in list_blobs(db_handle.database().path())? {
+      if let Some(key) = blob.large_blob_key() {
+        let candidate = large_blobs
+          .entry(key.to_string())
+          .or_insert_with(|| Candidate {
+            last_used: 0,
+            size: 0,
+            garbage: Garbage::Blob(Vec::new()),
+          });
+
+        candidate.last_used = candidate.last_used.max(blob.last_used);
+        candidate.size += blob.size;
+        if let Garbage::Blob(paths) = &mut candidate.garbage {
+          paths.push(blob.path);
+        }
+      } else if blob.version().is_some() {
+        candidates.push(Candidate {
+          last_used: blob.last_used,
+          size: blob.size,
+          garbage: Garbage::Blob(vec![blob.path]),
+        });
+      }
+    }
+
+    candidates.extend(large_blobs.into_values());
+
+    candidates.sort_by_key(|candidate| candidate.last_used);
+
+    let cutoff = options
+      .max_age
+      .map(|max_age| now.saturating_sub(max_age.as_secs()));
+    let mut total_bytes = candidates
+      .iter()
+      .map(|candidate| candidate.size)
+      .sum::<u64>();
+
+    for candidate in candidates {
+      let is_expired = cutoff.is_some_and(|cutoff| candidate.last_used < cutoff);
+      let is_over_size = options
+        .max_bytes
+        .is_some_and(|max_bytes| total_bytes > max_bytes);
+
+      if !is_expired && !is_over_size {
+        break;
+      }
+
+      total_bytes -= candidate.size;
+      summary.bytes += candidate.size;
+
+      match candidate.garbage {
+        Garbage::Entry(key) => {
+          tracker.remove_key(&key);
+          removed_keys.push(key);
+        }
+        Garbage::Blob(paths) => removed_blobs.extend(paths),
+      }
+    }
+  }
+
+  if !removed_keys.is_empty() {
+    let db = db_handle.database();
+    let mut transaction = db.write_txn()?;
+
+    for key in removed_keys.iter() {
+      // Entries may already have been removed, e.g. by the JavaScript cache
+      let _ = db.delete(&mut transaction, key);
+    }
+
+    transaction.commit()?;
+  }
+
+  for path in removed_blobs.iter() {
+    if let Err(error) = std::fs::remove_file(path)
+      && error.kind() != std::io::ErrorKind::NotFound
+    {
+      tracing::warn!("Failed to remove cached blob {}: {}", path.display(), error);
+    }
+  }
+
+  summary.entries = removed_keys.len();
+  summary.blobs = removed_blobs.len();
+
+  if summary.entries > 0 || summary.blobs > 0 {
+    tracing::info!("Cache garbage collection removed {:?}", summary);
+  }
+
+  Ok(summary)
+}
+
+/// Writes a compacted copy of the cache to an empty directory, including the large blobs
+///
+/// LMDB never shrinks its data file, so the space of removed entries is only reclaimed by
+/// replacing the cache directory with the compacted copy once no process is using it.
+///
+pub fn compact_cache(db_handle: &DatabaseHandle, target_dir: &Path) -> anyhow::Result<()> {
+  let cache_dir = db_handle.database().path();
+  let target_dir = std::path::absolute(target_dir)?;
+
+  if target_dir.starts_with(cache_dir) {
+    return Err(anyhow::anyhow!(
+      "Unable to compact the cache into {}, as it is within the cache directory",
+      target_dir.display()
+    ));
+  }
+
+  std::fs::create_dir_all(&target_dir)?;
+
+  for blob in list_blobs(cache_dir)? {
+    let target = target_dir.join(&blob.key);
+    if let Some(parent) = target.parent() {
+      std::fs::create_dir_all(parent)?;
+    }
+
+    std::fs::copy(&blob.path, target)?;
+  }
+
+  db_handle.database().compact(&target_dir.join("data.mdb"))?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use std::fs::File;
+  use std::sync::Arc;
+
+  use lmdb_js_lite::{LMDBOptions, get_database};
+  use pretty_assertions::assert_eq;
+  use tempfile::{TempDir, tempdir};
+
+  use super::*;
+  use crate::{CacheReaderWriter, LmdbCacheReaderWriter};
+
+  fn create_db(dir: &TempDir) -> Arc<DatabaseHandle> {
+    get_database(LMDBOptions {
+      path: dir.path().join("cache").to_string_lossy().into_owned(),
+      async_writes: false,
+      map_size: None,
+    })
+    .unwrap()
+  }
+
+  /// Writes a blob that was last used the given number of seconds ago
+  fn write_blob(db: &DatabaseHandle, key: &str, contents: &str, age: u64) {
+    let path = db.database().path().join(key);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, contents).unwrap();
+
+    let time = SystemTime::now() - Duration::from_secs(age);
+    let file = File::options().write(true).open(&path).unwrap();
+    file
+      .set_times(
+        std::fs::FileTimes::new()
+          .set_accessed(time)
+          .set_modified(time),
+      )
+      .unwrap();
+  }
+
+  fn now() -> u64 {
+    SystemTime::now()
+      .duration_since(UNIX_EPOCH)
+      .unwrap()
+      .as_secs()
+  }
+
+  fn keys(db: &Arc<DatabaseHandle>) -> Vec<String> {
+    let tracker = CacheTracker::load(db).unwrap();
+    let mut keys = tracker
+      .entries
+      .iter()
+      .map(|entry| entry.key.clone())
+      .collect::<Vec<_>>();
+
+    keys.sort();
+    keys
+  }
+
+  #[test]
+  fn removes_entries_and_blobs_that_have_not_been_used_recently() {
+    let dir = tempdir().unwrap();
+    let db = create_db(&dir);
+
+    let mut tracker = CacheTracker::default();
+    for (key, age) in [("old", 3600), ("recent", 60)] {
+      LmdbCacheReaderWriter::new(db.clone())
+        .put(key, b"value")
+        .unwrap();
+      tracker.update_entry(key.to_string(), now() - age);
+    }
+    tracker.save(&db).unwrap();
+
+    write_blob(&db, "PackagerRunner/1/old/content", "old", 3600);
+    write_blob(&db, "PackagerRunner/1/recent/content", "recent", 60);
+    write_blob(&db, "requestGraph-old", "unversioned", 3600);
+
+    let summary = collect_garbage(
+      &db,
+      &CacheGcOptions {
+        max_age: Some(Duration::from_secs(1800)),
+        ..CacheGcOptions::default()
+      },
+    )
+    .unwrap();
+
+    assert_eq!(
+      summary,
+      CacheGcSummary {
+        entries: 1,
+        blobs: 1,
+        bytes: 3,
+      }
+    );
+
+    assert_eq!(keys(&db), vec![String::from("recent")]);
+
+    let cache = LmdbCacheReaderWriter::new(db.clone());
+    assert_eq!(cache.read("old").unwrap(), None);
+    assert_eq!(cache.read("recent").unwrap(), Some(b"value".to_vec()));
+
+    let cache_dir = db.database().path();
+    assert!(!cache_dir.join("PackagerRunner/1/old/content").exists());
+    assert!(cache_dir.join("PackagerRunner/1/recent/content").exists());
+    assert!(cache_dir.join("requestGraph-old").exists());
+  }
+
+  #[test]
+  fn removes_all_chunks_of_large_blobs_written_by_the_javascript_cache() {
+    let dir = tempdir().unwrap();
+    let db = create_db(&dir);
+
+    // FSCache stores large blobs at the root of the cache directory as `<key>-<index>`
+    write_blob(&db, "requestGraph-7f3a9c-0", "graph", 3600);
+    write_blob(&db, "requestGraph-7f3a9c-1", "graph", 3600);
+    write_blob(&db, "requestGraph-nodes-0-7f3a9c-0", "nodes", 3600);
+    write_blob(&db, "requestGraph-1b2c3d-0", "graph", 3600);
+    write_blob(&db, "requestGraph-1b2c3d-1", "graph", 60);
+    write_blob(&db, "snapshot-7f3a9c.txt", "snapshot", 3600);
+
+    let summary = collect_garbage(
+      &db,
+      &CacheGcOptions {
+        max_age: Some(Duration::from_secs(1800)),
+        ..CacheGcOptions::default()
+      },
+    )
+    .unwrap();
+
+    assert_eq!(
+      summary,
+      CacheGcSummary {
+        entries: 0,
+        blobs: 3,
+        bytes: 15,
+      }
+    );
+
+    let cache_dir = db.database().path();
+    assert!(!cache_dir.join("requestGraph-7f3a9c-0").exists());
+    assert!(!cache_dir.join("requestGraph-7f3a9c-1").exists());
+    assert!(!cache_dir.join("requestGraph-nodes-0-7f3a9c-0").exists());
+
+    // A chunk that was used recently keeps the other chunks of its blob
+    assert!(cache_dir.join("requestGraph-1b2c3d-0").exists());
+    assert!(cache_dir.join("requestGraph-1b2c3d-1").exists());
+    assert!(cache_dir.join("snapshot-7f3a9c.txt").exists());
+  }
+
+  #[test]
+  fn removes_the_least_recently_used_entries_and_blobs_over_the_size_limit() {
+    let dir = tempdir().unwrap();
+    let db = create_db(&dir);
+    let cache = LmdbCacheReaderWriter::with_gc_options(
+      db.clone(),
+      CacheGcOptions {
+        max_bytes: Some(25),
+        ..CacheGcOptions::default()
+      },
+    );
+
+    write_blob(&db, "PackagerRunner/1/old/content", "0123456789", 3600);
+    write_blob(&db, "PackagerRunner/1/new/content", "0123456789", 0);
+
+    cache.put("a", b"0123456789").unwrap();
+    cache.complete_session().unwrap();
+
+    // The blobs and entries add up to 30 bytes, so the oldest blob is removed
+    let cache_dir = db.database().path();
+    assert!(!cache_dir.join("PackagerRunner/1/old/content").exists());
+    assert!(cache_dir.join("PackagerRunner/1/new/content").exists());
+    assert_eq!(keys(&db), vec![String::from("a")]);
+  }
+
+  #[test]
+  fn compacts_the_cache_into_another_directory() {
+    let dir = tempdir().unwrap();
+    let db = create_db(&dir);
+
+    let cache = LmdbCacheReaderWriter::new(db.clone());
+    cache.put("key", b"value").unwrap();
+    cache.complete_session().unwrap();
+    write_blob(&db, "PackagerRunner/1/abc/content", "bundle", 0);
+
+    let target = dir.path().join("compacted");
+    compact_cache(&db, &target).unwrap();
+
+    assert!(target.join("data.mdb").exists());
+    assert_eq!(
+      std::fs::read_to_string(target.join("PackagerRunner/1/abc/content")).unwrap(),
+      "bundle"
+    );
+
+    let compacted = get_database(LMDBOptions {
+      path: target.to_string_lossy().into_owned(),
+      async_writes: false,
+      map_size: None,
+    })
+    .unwrap();
+
+    assert_eq!(
+      LmdbCacheReaderWriter::new(compacted).read("key").unwrap(),
+      Some(b"value".to_vec())
+    );
+
+    assert!(compact_cache(&db, &db.database().path().join("nested")).is_err());
+  }
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeMap;
+
+use lmdb_js_lite::DatabaseHandle;
+use serde::Serialize;
+
+use crate::blob_cache::list_blobs;
+use crate::lmdb_cache_reader_writer::CacheTracker;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CacheUsage {
+  pub count: usize,
+  pub bytes: u64,
+}
+
+impl CacheUsage {
+  fn add(&mut self, bytes: u64) {
+    self.count += 1;
+    self.bytes += bytes;
+  }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct CacheInspection {
+  /// Memoization cache entries grouped by request type, which is the label prefix before the
+  /// first `|` of their key, such as `run_pipeline`
+  pub entries: BTreeMap<String, CacheUsage>,
+
+  /// Large blobs grouped by the first segment of their key, such as `PackagerRunner`
+  pub blobs: BTreeMap<String, CacheUsage>,
+
+  /// Seconds since the epoch that the least recently used entry was last used
+  pub oldest_entry: Option<u64>,
+}
+
+impl CacheInspection {
+  pub fn total(&self) -> CacheUsage {
+    let mut total = CacheUsage::default();
+
+    for usage in self.entries.values().chain(self.blobs.values()) {
+      total.count += usage.count;
+      total.bytes += usage.bytes;
+    }
+
+    total
+  }
+}
+
+/// Reports how many entries and bytes each kind of request is using in the cache
+///
+/// Entries are measured before compression, so this reads every tracked entry.
+pub fn inspect_cache(db_handle: &DatabaseHandle) -> anyhow::Result<CacheInspection> {
+  let tracker = CacheTracker::load(db_handle)?;
+  let db = db_handle.database();
+  let transaction = db.read_txn()?;
+
+  let mut inspection = CacheInspection {
+    oldest_entry: tracker.entries.first().map(|entry| entry.timestamp),
+    ..CacheInspection::default()
+  };
+
+  for entry in tracker.entries.iter() {
+    let Some(value) = db.get(&transaction, &entry.key)? else {
+      continue;
+    };
+
+    let request_type = entry.key.split('|').next().unwrap_or_default();
+
+    inspection
+      .entries
+      .entry(request_type.to_string())
+      .or_default()
+      .add(value.len() as u64);
+  }
+
+  for blob in list_blobs(db.path())? {
+    let prefix = blob
+      .key
+      .split_once('/')
+      .map_or("other", |(prefix, _)| prefix);
+
+    inspection
+      .blobs
+      .entry(prefix.to_string())
+      .or_default()
+      .add(blob.size);
+  }
+
+  Ok(inspection)
+}
+
+#[cfg(test)]
+mod tests {
+  use lmdb_js_lite::{LMDBOptions, get_database};
+  use pretty_assertions::assert_eq;
+  use tempfile::tempdir;
+
+  use super::*;
+  use crate::{CacheReaderWriter, LmdbCacheReaderWriter};
+
+  #[test]
+  fn groups_entries_by_request_type_and_blobs_by_prefix() {
+    let dir = tempdir().unwrap();
+    let db = get_database(LMDBOptions {
+      path: dir.path().to_string_lossy().into_owned(),
+      async_writes: false,
+      map_size: None,
+    })
+    .unwrap();
+
+    let cache = LmdbCacheReaderWriter::new(db.clone());
+    cache.put("run_pipeline|src/a.js|1", b"aaaa").unwrap();
+    cache.put("run_pipeline|src/b.js|2", b"bb").unwrap();
+    cache.put("resolve|src/a.js|3", b"c").unwrap();
+    cache.complete_session().unwrap();
+
+    std::fs::create_dir_all(dir.path().join("PackagerRunner/1/abc")).unwrap();
+    std::fs::write(dir.path().join("PackagerRunner/1/abc/content"), "bundle").unwrap();
+    std::fs::write(dir.path().join("requestGraph-abc"), "graph").unwrap();
+
+    let inspection = inspect_cache(&db).unwrap();
+
+    assert_eq!(
+      inspection.entries,
+      BTreeMap::from([
+        (String::from("resolve"), CacheUsage { count: 1, bytes: 1 }),
+        (
+          String::from("run_pipeline"),
+          CacheUsage { count: 2, bytes: 6 }
+        ),
+      ])
+    );
+
+    assert_eq!(
+      inspection.blobs,
+      BTreeMap::from([
+        (
+          String::from("PackagerRunner"),
+          CacheUsage { count: 1, bytes: 6 }
+        ),
+        (String::from("other"), CacheUsage { count: 1, bytes: 5 }),
+      ])
+    );
+
+    assert_eq!(
+      inspection.total(),
+      CacheUsage {
+        count: 5,
+        bytes: 18
+      }
+    );
+    assert!(inspection.oldest_entry.is_some());
+  }
+}
@@ -0,0 +1,230 @@
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+use xxhash_rust::xxh3::Xxh3Default;
+
+use crate::CacheReaderWriter;
+
+/// Prefix of the keys that the components of the latest entry for each label are stored under
+const COMPONENTS_KEY_PREFIX: &str = "cache_key_components";
+
+/// The named inputs that make up a cache key, such as the asset, the transformer pipeline and the
+/// environment
+///
+/// These are only computed when explaining cache misses, and are stored as hashes so that large
+/// inputs don't bloat the cache.
+///
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct CacheKeyComponents(Vec<(String, String)>);
+
+impl CacheKeyComponents {
+  pub fn new() -> Self {
+    Self::default()
+  }
+
+  pub fn add(&mut self, name: impl Into<String>, value: impl Hash) -> &mut Self {
+    let mut hasher = Xxh3Default::new();
+    value.hash(&mut hasher);
+
+    self
+      .0
+      .push((name.into(), format!("{:016x}", hasher.finish())));
+
+    self
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  fn get(&self, name: &str) -> Option<&String> {
+    self
+      .0
+      .iter()
+      .find_map(|(component, value)| (component == name).then_some(value))
+  }
+
+  /// Lists the components that differ from a previous entry, in the order they were added
+  fn changes_since(&self, previous: &CacheKeyComponents) -> Vec<CacheKeyComponentChange> {
+    let added_or_changed = self.0.iter().filter_map(|(name, value)| {
+      let previous = previous.get(name);
+
+      (previous != Some(value)).then(|| CacheKeyComponentChange {
+        name: name.clone(),
+        previous: previous.cloned(),
+        current: Some(value.clone()),
+      })
+    });
+
+    let removed = previous
+      .0
+      .iter()
+      .filter(|(name, _)| self.get(name).is_none())
+      .map(|(name, value)| CacheKeyComponentChange {
+        name: name.clone(),
+        previous: Some(value.clone()),
+        current: None,
+      });
+
+    added_or_changed.chain(removed).collect()
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CacheKeyComponentChange {
+  pub name: String,
+  /// The hash of the component in the previous entry, if it had this component
+  pub previous: Option<String>,
+  /// The hash of the component now, if it still has this component
+  pub current: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub enum CacheMissReason {
+  /// No previous entry has been recorded for the label, e.g. because the asset is new
+  NewLabel,
+  /// The components match the previous entry, so it was removed from the cache, e.g. by garbage
+  /// collection
+  Evicted,
+  /// The components that differ from the previous entry for the label
+  Changed(Vec<CacheKeyComponentChange>),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct CacheMissExplanation {
+  pub label: String,
+  pub reason: CacheMissReason,
+}
+
+impl Display for CacheMissExplanation {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match &self.reason {
+      CacheMissReason::NewLabel => write!(f, "{} has no previous entry", self.label),
+      CacheMissReason::Evicted => write!(
+        f,
+        "{} has not changed, so its entry was removed from the cache",
+        self.label
+      ),
+      CacheMissReason::Changed(changes) => {
+        let names = changes
+          .iter()
+          .map(|change| change.name.as_str())
+          .collect::<Vec<_>>();
+
+        write!(f, "{} changed {}", self.label, names.join(", "))
+      }
+    }
+  }
+}
+
+fn components_key(label: &str) -> String {
+  format!("{COMPONENTS_KEY_PREFIX}|{label}")
+}
+
+/// Compares the components of a missed cache key with the latest entry recorded for the label
+///
+/// Components are only kept in the local cache, as they describe the inputs on this machine and
+/// looking them up remotely would add a request to every miss.
+///
+pub(crate) fn explain_miss(
+  reader_writer: &impl CacheReaderWriter,
+  label: &str,
+  components: &CacheKeyComponents,
+) -> anyhow::Result<CacheMissExplanation> {
+  let reason = match reader_writer.read_local_only(&components_key(label))? {
+    None => CacheMissReason::NewLabel,
+    Some(previous) => {
+      let previous = serde_json::from_slice::<CacheKeyComponents>(&previous)?;
+      let changes = components.changes_since(&previous);
+
+      if changes.is_empty() {
+        CacheMissReason::Evicted
+      } else {
+        CacheMissReason::Changed(changes)
+      }
+    }
+  };
+
+  Ok(CacheMissExplanation {
+    label: label.to_string(),
+    reason,
+  })
+}
+
+/// Records the components of the entry that was written for the label, for the next miss to
+/// compare against
+pub(crate) fn record_components(
+  reader_writer: &impl CacheReaderWriter,
+  label: &str,
+  components: &CacheKeyComponents,
+) -> anyhow::Result<()> {
+  reader_writer.put_local_only(&components_key(label), &serde_json::to_vec(components)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::InMemoryReaderWriter;
+
+  fn components(values: &[(&str, u64)]) -> CacheKeyComponents {
+    let mut components = CacheKeyComponents::new();
+    for (name, value) in values {
+      components.add(*name, value);
+    }
+
+    components
+  }
+
+  #[test]
+  fn explains_which_components_changed() {
+    let cache = InMemoryReaderWriter::default();
+    let label = "run_pipeline|src/index.js";
+
+    assert_eq!(
+      explain_miss(&cache, label, &components(&[("asset", 1)])).unwrap(),
+      CacheMissExplanation {
+        label: label.to_string(),
+        reason: CacheMissReason::NewLabel,
+      }
+    );
+
+    let previous = components(&[("asset", 1), ("env", 2), ("transformer[0]", 3)]);
+    record_components(&cache, label, &previous).unwrap();
+
+    assert_eq!(
+      explain_miss(&cache, label, &previous).unwrap().reason,
+      CacheMissReason::Evicted
+    );
+
+    let current = components(&[("asset", 1), ("env", 4), ("pipeline", 5)]);
+    let explanation = explain_miss(&cache, label, &current).unwrap();
+
+    assert_eq!(
+      explanation.to_string(),
+      format!("{label} changed env, pipeline, transformer[0]")
+    );
+
+    let CacheMissReason::Changed(changes) = explanation.reason else {
+      panic!("Expected the components to have changed");
+    };
+
+    assert_eq!(
+      changes
+        .iter()
+        .map(|change| (
+          change.name.as_str(),
+          change.previous.is_some(),
+          change.current.is_some()
+        ))
+        .collect::<Vec<_>>(),
+      vec![
+        ("env", true, true),
+        ("pipeline", false, true),
+        ("transformer[0]", true, false),
+      ]
+    );
+  }
+}
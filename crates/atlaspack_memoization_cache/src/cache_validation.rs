@@ -0,0 +1,197 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+use xxhash_rust::xxh3::xxh3_64;
+
+#[derive(Clone, Debug, Default)]
+pub struct CacheValidationOptions {
+  /// Where to write the mismatches found during each session as JSON
+  pub report_path: Option<PathBuf>,
+
+  /// Replace mismatched entries with the computed result, so that later builds don't keep reading
+  /// the incorrect value
+  pub evict_mismatches: bool,
+
+  /// Validation rates that override the cache mode rate for labels starting with a prefix, such
+  /// as `run_pipeline`, so that suspect request types can be validated more often
+  ///
+  /// The first matching prefix is used.
+  ///
+  pub label_rates: Vec<(String, f32)>,
+}
+
+impl CacheValidationOptions {
+  /// Reads the options from `ATLASPACK_CACHE_VALIDATION_REPORT`,
+  /// `ATLASPACK_CACHE_EVICT_MISMATCHES=true` and `ATLASPACK_CACHE_VALIDATION_RATES`, which is a
+  /// comma separated list of rates such as `run_pipeline=1,resolve=0.1`
+  pub fn from_env() -> Self {
+    CacheValidationOptions {
+      report_path: std::env::var("ATLASPACK_CACHE_VALIDATION_REPORT")
+        .ok()
+        .map(PathBuf::from),
+      evict_mismatches: std::env::var("ATLASPACK_CACHE_EVICT_MISMATCHES")
+        .is_ok_and(|value| value == "true"),
+      label_rates: std::env::var("ATLASPACK_CACHE_VALIDATION_RATES")
+        .map(|rates| parse_label_rates(&rates))
+        .unwrap_or_default(),
+    }
+  }
+
+  pub(crate) fn label_rate(&self, label: &str) -> Option<f32> {
+    self
+      .label_rates
+      .iter()
+      .find_map(|(prefix, rate)| label.starts_with(prefix.as_str()).then_some(*rate))
+  }
+}
+
+fn parse_label_rates(rates: &str) -> Vec<(String, f32)> {
+  rates
+    .split(',')
+    .filter_map(|rate| {
+      let (prefix, rate) = rate.split_once('=')?;
+      let Ok(rate) = rate.trim().parse::<f32>() else {
+        tracing::warn!("Ignoring invalid cache validation rate {:?}", rate);
+        return None;
+      };
+
+      Some((prefix.trim().to_string(), rate.clamp(0.0, 1.0)))
+    })
+    .collect()
+}
+
+/// A cached value that did not match the result computed for the same cache key
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationMismatch {
+  pub label: String,
+  pub cache_key: String,
+  /// Path to the first value that differs within the serialized result, such as
+  /// `$.pipeline_result.Complete[0].code`
+  pub field_path: Option<String>,
+  pub cached_hash: String,
+  pub computed_hash: String,
+}
+
+impl ValidationMismatch {
+  pub(crate) fn new(label: &str, cache_key: &str, cached: &[u8], computed: &[u8]) -> Self {
+    let field_path = match (
+      serde_json::from_slice::<Value>(cached),
+      serde_json::from_slice::<Value>(computed),
+    ) {
+      (Ok(cached), Ok(computed)) => first_difference(&cached, &computed, String::from("$")),
+      _ => None,
+    };
+
+    ValidationMismatch {
+      label: label.to_string(),
+      cache_key: cache_key.to_string(),
+      field_path,
+      cached_hash: format!("{:016x}", xxh3_64(cached)),
+      computed_hash: format!("{:016x}", xxh3_64(computed)),
+    }
+  }
+}
+
+fn first_difference(cached: &Value, computed: &Value, path: String) -> Option<String> {
+  match (cached, computed) {
+    (Value::Object(cached), Value::Object(computed)) => {
+      for (key, cached_value) in cached {
+        let field_path = format!("{path}.{key}");
+        let Some(computed_value) = computed.get(key) else {
+          return Some(field_path);
+        };
+
+        if let Some(field_path) = first_difference(cached_value, computed_value, field_path) {
+          return Some(field_path);
+        }
+      }
+
+      computed
+        .keys()
+        .find(|key| !cached.contains_key(*key))
+        .map(|key| format!("{path}.{key}"))
+    }
+    (Value::Array(cached), Value::Array(computed)) => {
+      for index in 0..cached.len().max(computed.len()) {
+        let field_path = format!("{path}[{index}]");
+        let (Some(cached_value), Some(computed_value)) = (cached.get(index), computed.get(index))
+        else {
+          return Some(field_path);
+        };
+
+        if let Some(field_path) = first_difference(cached_value, computed_value, field_path) {
+          return Some(field_path);
+        }
+      }
+
+      None
+    }
+    (cached, computed) => (cached != computed).then_some(path),
+  }
+}
+
+pub(crate) fn write_report(path: &Path, mismatches: &[ValidationMismatch]) -> anyhow::Result<()> {
+  if let Some(parent) = path.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+
+  std::fs::write(path, serde_json::to_vec_pretty(mismatches)?)?;
+
+  Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use serde_json::json;
+
+  use super::*;
+
+  #[test]
+  fn finds_the_first_differing_field() {
+    let cached = json!({ "asset": { "code": "a", "deps": [1, 2] }, "invalidations": [] });
+
+    let path = |computed: Value| first_difference(&cached, &computed, String::from("$"));
+
+    assert_eq!(path(cached.clone()), None);
+    assert_eq!(
+      path(json!({ "asset": { "code": "b", "deps": [1, 2] }, "invalidations": [] })),
+      Some(String::from("$.asset.code"))
+    );
+    assert_eq!(
+      path(json!({ "asset": { "code": "a", "deps": [1, 3] }, "invalidations": [] })),
+      Some(String::from("$.asset.deps[1]"))
+    );
+    assert_eq!(
+      path(json!({ "asset": { "code": "a", "deps": [1, 2] }, "invalidations": ["a.js"] })),
+      Some(String::from("$.invalidations[0]"))
+    );
+    assert_eq!(
+      path(json!({ "asset": { "code": "a", "deps": [1, 2] }, "invalidations": [], "extra": 1 })),
+      Some(String::from("$.extra"))
+    );
+    assert_eq!(path(json!("other")), Some(String::from("$")));
+  }
+
+  #[test]
+  fn parses_label_rates() {
+    let options = CacheValidationOptions {
+      label_rates: parse_label_rates("run_pipeline=1, resolve = 0.5,invalid=abc,missing,big=2"),
+      ..CacheValidationOptions::default()
+    };
+
+    assert_eq!(
+      options.label_rates,
+      vec![
+        (String::from("run_pipeline"), 1.0),
+        (String::from("resolve"), 0.5),
+        (String::from("big"), 1.0),
+      ]
+    );
+
+    assert_eq!(options.label_rate("run_pipeline|src/a.js"), Some(1.0));
+    assert_eq!(options.label_rate("package|bundle"), None);
+  }
+}
@@ -0,0 +1,110 @@
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+/// Stops requests to a remote store after consecutive failures, so that an unreachable store
+/// doesn't add a timeout to every cache read
+///
+/// Once the cooldown has elapsed a single request is let through, which closes the breaker again
+/// if it succeeds.
+///
+#[derive(Debug)]
+pub(crate) struct CircuitBreaker {
+  failure_threshold: u32,
+  cooldown: Duration,
+  state: Mutex<CircuitState>,
+}
+
+#[derive(Debug, Default)]
+struct CircuitState {
+  consecutive_failures: u32,
+  open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+  pub(crate) fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+    CircuitBreaker {
+      failure_threshold: failure_threshold.max(1),
+      cooldown,
+      state: Mutex::new(CircuitState::default()),
+    }
+  }
+
+  /// Whether a request should be made to the remote store
+  pub(crate) fn allow(&self) -> bool {
+    let mut state = self.state.lock();
+
+    match state.open_until {
+      None => true,
+      Some(open_until) if Instant::now() >= open_until => {
+        // Re-open immediately, so that only one request probes the store at a time
+        state.open_until = Some(Instant::now() + self.cooldown);
+        true
+      }
+      Some(_) => false,
+    }
+  }
+
+  pub(crate) fn is_open(&self) -> bool {
+    self.state.lock().open_until.is_some()
+  }
+
+  pub(crate) fn record_success(&self) {
+    let mut state = self.state.lock();
+    state.consecutive_failures = 0;
+    state.open_until = None;
+  }
+
+  pub(crate) fn record_failure(&self) {
+    let mut state = self.state.lock();
+    state.consecutive_failures += 1;
+
+    if state.consecutive_failures >= self.failure_threshold {
+      if state.open_until.is_none() {
+        tracing::warn!(
+          "Remote cache failed {} times in a row, falling back to the local cache for {:?}",
+          state.consecutive_failures,
+          self.cooldown
+        );
+      }
+
+      state.open_until = Some(Instant::now() + self.cooldown);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn opens_after_consecutive_failures() {
+    let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+
+    breaker.record_failure();
+    breaker.record_success();
+    breaker.record_failure();
+    assert!(breaker.allow());
+
+    breaker.record_failure();
+    assert!(breaker.is_open());
+    assert!(!breaker.allow());
+  }
+
+  #[test]
+  fn lets_a_single_request_through_after_the_cooldown() {
+    let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+
+    breaker.record_failure();
+    assert!(!breaker.allow());
+
+    std::thread::sleep(Duration::from_millis(30));
+
+    assert!(breaker.allow());
+    assert!(!breaker.allow());
+
+    breaker.record_success();
+    assert!(!breaker.is_open());
+    assert!(breaker.allow());
+  }
+}
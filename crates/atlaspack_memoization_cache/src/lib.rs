@@ -6,16 +6,63 @@ use std::future::Future;
 use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicU64, Ordering};
 
+use cache_miss_explanation::{explain_miss, record_components};
+use cache_validation::write_report;
+
+mod blob_cache;
+mod cache_archive;
+mod cache_gc;
+mod cache_inspector;
+mod cache_miss_explanation;
+mod cache_validation;
+mod circuit_breaker;
 mod lmdb_cache_reader_writer;
-
+mod remote_cache_reader_writer;
+mod remote_store;
+
+pub use cache_archive::{CacheArchiveOptions, CacheArchiveSummary, export_cache, import_cache};
+pub use cache_gc::{CacheGcOptions, CacheGcSummary, collect_garbage, compact_cache};
+pub use cache_inspector::{CacheInspection, CacheUsage, inspect_cache};
+pub use cache_miss_explanation::{
+  CacheKeyComponentChange, CacheKeyComponents, CacheMissExplanation, CacheMissReason,
+};
+pub use cache_validation::{CacheValidationOptions, ValidationMismatch};
 pub use lmdb_cache_reader_writer::LmdbCacheReaderWriter;
+pub use remote_cache_reader_writer::{RemoteCacheOptions, RemoteCacheReaderWriter};
+pub use remote_store::{HttpRemoteStore, RemoteStore};
 
+#[async_trait]
 pub trait CacheReaderWriter: Send + Sync {
   fn read(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+  /// Reads an entry from within the async runtime, which implementations that wait on the
+  /// network override so that they don't block the runtime's workers
+  async fn read_async(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+    self.read(key)
+  }
+
   fn put(&self, key: &str, value: &[u8]) -> anyhow::Result<()>;
+
+  /// Reads an entry that is only kept on this machine, such as the components recorded to explain
+  /// cache misses, which implementations backed by a shared store don't look up remotely
+  fn read_local_only(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+    self.read(key)
+  }
+
+  /// Writes an entry that is only kept on this machine, which implementations backed by a shared
+  /// store don't upload
+  fn put_local_only(&self, key: &str, value: &[u8]) -> anyhow::Result<()> {
+    self.put(key, value)
+  }
+
   fn complete_session(&self) -> anyhow::Result<()> {
     Ok(())
   }
+
+  /// Returns and resets the stats of each tier, for implementations made up of several caches
+  fn take_tier_stats(&self) -> Vec<TierStatsSnapshot> {
+    Vec::new()
+  }
 }
 
 /// In-memory cache implementation for testing
@@ -46,6 +93,7 @@ struct Stats {
   bailouts: AtomicU64,
   errors: AtomicU64,
   validations: AtomicU64,
+  mismatches: AtomicU64,
 }
 
 impl Default for Stats {
@@ -57,6 +105,7 @@ impl Default for Stats {
       bailouts: AtomicU64::new(0),
       errors: AtomicU64::new(0),
       validations: AtomicU64::new(0),
+      mismatches: AtomicU64::new(0),
     }
   }
 }
@@ -69,6 +118,7 @@ impl Stats {
     self.bailouts.store(0, Ordering::Relaxed);
     self.errors.store(0, Ordering::Relaxed);
     self.validations.store(0, Ordering::Relaxed);
+    self.mismatches.store(0, Ordering::Relaxed);
   }
 
   fn increment_hits(&self) {
@@ -95,6 +145,10 @@ impl Stats {
     self.validations.fetch_add(1, Ordering::Relaxed);
   }
 
+  fn increment_mismatches(&self) {
+    self.mismatches.fetch_add(1, Ordering::Relaxed);
+  }
+
   fn get_snapshot(&self) -> StatsSnapshot {
     StatsSnapshot {
       hits: self.hits.load(Ordering::Relaxed),
@@ -103,6 +157,9 @@ impl Stats {
       bailouts: self.bailouts.load(Ordering::Relaxed),
       errors: self.errors.load(Ordering::Relaxed),
       validations: self.validations.load(Ordering::Relaxed),
+      mismatches: self.mismatches.load(Ordering::Relaxed),
+      tiers: Vec::new(),
+      miss_explanations: Vec::new(),
     }
   }
 }
@@ -115,6 +172,23 @@ pub struct StatsSnapshot {
   pub bailouts: u64,
   pub errors: u64,
   pub validations: u64,
+  /// Validations where the cached value did not match the computed result
+  pub mismatches: u64,
+  pub tiers: Vec<TierStatsSnapshot>,
+  /// Why each cache miss happened, when miss explanations are enabled
+  #[serde(rename = "missExplanations")]
+  pub miss_explanations: Vec<CacheMissExplanation>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TierStatsSnapshot {
+  pub name: String,
+  pub hits: u64,
+  pub misses: u64,
+  pub errors: u64,
+  pub writes: u64,
+  /// Requests that were not made because the tier was unavailable
+  pub skipped: u64,
 }
 
 pub enum CacheMode {
@@ -129,6 +203,10 @@ pub struct CacheHandler<T: CacheReaderWriter> {
   reader_writer: T,
   stats: Stats,
   mode: CacheMode,
+  explain_misses: bool,
+  miss_explanations: parking_lot::Mutex<Vec<CacheMissExplanation>>,
+  validation_options: CacheValidationOptions,
+  mismatches: parking_lot::Mutex<Vec<ValidationMismatch>>,
 }
 
 #[async_trait]
@@ -154,9 +232,28 @@ impl<T: CacheReaderWriter> CacheHandler<T> {
       reader_writer,
       stats: Stats::default(),
       mode,
+      explain_misses: false,
+      miss_explanations: Default::default(),
+      validation_options: CacheValidationOptions::default(),
+      mismatches: Default::default(),
     }
   }
 
+  pub fn with_validation_options(mut self, validation_options: CacheValidationOptions) -> Self {
+    self.validation_options = validation_options;
+    self
+  }
+
+  /// Records the components of each cache key, so that misses can be explained by comparing them
+  /// with the previous entry for the same label
+  ///
+  /// This costs an extra read and write per miss, so it is intended for debugging.
+  ///
+  pub fn with_miss_explanations(mut self, explain_misses: bool) -> Self {
+    self.explain_misses = explain_misses;
+    self
+  }
+
   async fn run_with_cache<Input, RunFn, Res, FutureResult, Error>(
     &self,
     label: String,
@@ -172,7 +269,7 @@ impl<T: CacheReaderWriter> CacheHandler<T> {
     RunFn: FnOnce(Input) -> FutureResult,
   {
     // Then check the cache for an existing value
-    let cache_result = match self.reader_writer.read(&cache_key) {
+    let cache_result = match self.reader_writer.read_async(&cache_key).await {
       Ok(value) => value,
       Err(err) => {
         // We don't want to blow up the build for a cache read error, so we log it and
@@ -183,6 +280,14 @@ impl<T: CacheReaderWriter> CacheHandler<T> {
       }
     };
 
+    let components = if self.explain_misses && cache_result.is_none() {
+      let components = input.cache_key_components();
+      self.record_miss_explanation(&label, &components);
+      Some(components)
+    } else {
+      None
+    };
+
     if !should_validate && let Some(value) = cache_result.as_ref() {
       match deserialize(value) {
         Ok(value) => {
@@ -216,8 +321,8 @@ impl<T: CacheReaderWriter> CacheHandler<T> {
     // Now we have a result, try to serialize and cache it
     match serialize(&computed_result) {
       Ok(serialized_result) => {
-        if let Some(cached_result) = cache_result
-          && let Ok(cached_result) = deserialize::<Res>(&cached_result)
+        if let Some(cached_value) = cache_result
+          && let Ok(cached_result) = deserialize::<Res>(&cached_value)
         {
           self.stats.increment_validations();
 
@@ -229,6 +334,8 @@ impl<T: CacheReaderWriter> CacheHandler<T> {
               label,
               comparison
             );
+
+            self.record_mismatch(&label, &cache_key, &cached_value, &serialized_result);
           }
         } else {
           self.stats.increment_misses();
@@ -236,6 +343,14 @@ impl<T: CacheReaderWriter> CacheHandler<T> {
           if let Err(error) = self.reader_writer.put(&cache_key, &serialized_result) {
             tracing::error!("Failed to write to cache for {}: {}", label, error);
             self.stats.increment_errors();
+          } else if let Some(components) = components.filter(|c| !c.is_empty())
+            && let Err(error) = record_components(&self.reader_writer, &label, &components)
+          {
+            tracing::error!(
+              "Failed to record cache key components for {}: {}",
+              label,
+              error
+            );
           }
         }
       }
@@ -249,8 +364,46 @@ impl<T: CacheReaderWriter> CacheHandler<T> {
     Ok(computed_result)
   }
 
+  fn record_miss_explanation(&self, label: &str, components: &CacheKeyComponents) {
+    if components.is_empty() {
+      return;
+    }
+
+    match explain_miss(&self.reader_writer, label, components) {
+      Ok(explanation) => {
+        tracing::info!("Cache miss: {}", explanation);
+        self.miss_explanations.lock().push(explanation);
+      }
+      Err(error) => tracing::error!("Failed to explain cache miss for {}: {}", label, error),
+    }
+  }
+
+  fn record_mismatch(&self, label: &str, cache_key: &str, cached: &[u8], computed: &[u8]) {
+    self.stats.increment_mismatches();
+
+    let mismatch = ValidationMismatch::new(label, cache_key, cached, computed);
+    tracing::debug!("Cache validation mismatch {:?}", mismatch);
+    self.mismatches.lock().push(mismatch);
+
+    if self.validation_options.evict_mismatches
+      && let Err(error) = self.reader_writer.put(cache_key, computed)
+    {
+      tracing::error!(
+        "Failed to evict mismatched cache entry for {}: {}",
+        label,
+        error
+      );
+      self.stats.increment_errors();
+    }
+  }
+
   /// Determines if we should validate this cache hit based on the configured sampling rate
   fn should_validate(&self, label: &str, validation_rate: f32) -> bool {
+    let validation_rate = self
+      .validation_options
+      .label_rate(label)
+      .unwrap_or(validation_rate);
+
     // Use label as seed to get consistent results per key
     let mut hasher = DefaultHasher::new();
     label.hash(&mut hasher);
@@ -266,7 +419,20 @@ impl<T: CacheReaderWriter> CacheHandler<T> {
 #[async_trait]
 impl<T: CacheReaderWriter> CacheHandlerTrait for CacheHandler<T> {
   fn complete_session(&self) -> anyhow::Result<StatsSnapshot> {
-    let snapshot = self.stats.get_snapshot();
+    let mut snapshot = self.stats.get_snapshot();
+    snapshot.tiers = self.reader_writer.take_tier_stats();
+    snapshot.miss_explanations = std::mem::take(&mut *self.miss_explanations.lock());
+
+    let mismatches = std::mem::take(&mut *self.mismatches.lock());
+    if let Some(report_path) = self.validation_options.report_path.as_ref()
+      && let Err(error) = write_report(report_path, &mismatches)
+    {
+      tracing::error!(
+        "Failed to write cache validation report to {}: {}",
+        report_path.display(),
+        error
+      );
+    }
     tracing::info!("Cache stats {:#?}", snapshot);
 
     self.stats.clear();
@@ -336,6 +502,11 @@ fn serialize<Input: Serialize>(value: Input) -> Result<Vec<u8>, serde_json::Erro
 
 pub trait Cacheable {
   fn cache_key(&self) -> Option<(String, String)>;
+
+  /// The named inputs that were hashed into the cache key, used to explain cache misses
+  fn cache_key_components(&self) -> CacheKeyComponents {
+    CacheKeyComponents::default()
+  }
 }
 
 pub trait CacheResponse: DeserializeOwned + Serialize + PartialEq + std::fmt::Debug {
@@ -391,6 +562,12 @@ mod test {
     fn cache_key(&self) -> Option<(String, String)> {
       Some((self.label.clone(), format!("{}:{}", self.label, self.value)))
     }
+
+    fn cache_key_components(&self) -> CacheKeyComponents {
+      let mut components = CacheKeyComponents::new();
+      components.add("value", &self.value);
+      components
+    }
   }
 
   #[tokio::test]
@@ -541,6 +718,92 @@ mod test {
     assert_eq!(result, Ok(TestResponse::new("CACHED")));
   }
 
+  #[tokio::test]
+  async fn explains_cache_misses() {
+    let handler = CacheHandler::new(InMemoryReaderWriter::default(), CacheMode::On(0.0))
+      .with_miss_explanations(true);
+
+    for value in ["Hello", "Hello", "World"] {
+      let result = handler
+        .run(
+          CacheableString::new("test_label", value),
+          |input| async move { Ok::<_, ()>(TestResponse::new(&input.value.to_ascii_uppercase())) },
+        )
+        .await;
+
+      assert_eq!(result, Ok(TestResponse::new(&value.to_ascii_uppercase())));
+    }
+
+    let snapshot = handler.complete_session().unwrap();
+
+    assert_eq!(snapshot.misses, 2);
+    assert_eq!(
+      snapshot
+        .miss_explanations
+        .iter()
+        .map(|explanation| explanation.to_string())
+        .collect::<Vec<_>>(),
+      vec![
+        String::from("test_label has no previous entry"),
+        String::from("test_label changed value"),
+      ]
+    );
+
+    let snapshot = handler.complete_session().unwrap();
+    assert_eq!(snapshot.miss_explanations, Vec::new());
+  }
+
+  #[tokio::test]
+  async fn reports_and_evicts_validation_mismatches() {
+    let dir = tempfile::tempdir().unwrap();
+    let report_path = dir.path().join("report.json");
+
+    let reader_writer = InMemoryReaderWriter::default();
+    reader_writer
+      .put(
+        "test_label:Hello",
+        &serde_json::to_vec(&TestResponse::new("WRONG")).unwrap(),
+      )
+      .unwrap();
+
+    let handler = CacheHandler::new(reader_writer, CacheMode::On(0.0)).with_validation_options(
+      CacheValidationOptions {
+        report_path: Some(report_path.clone()),
+        evict_mismatches: true,
+        label_rates: vec![(String::from("test_label"), 1.0)],
+      },
+    );
+
+    for _ in 0..2 {
+      let result = handler
+        .run(
+          CacheableString::new("test_label", "Hello"),
+          |input| async move { Ok::<_, ()>(TestResponse::new(&input.value.to_ascii_uppercase())) },
+        )
+        .await;
+
+      assert_eq!(result, Ok(TestResponse::new("HELLO")));
+    }
+
+    let snapshot = handler.complete_session().unwrap();
+    assert_eq!(snapshot.validations, 2);
+    assert_eq!(snapshot.mismatches, 1);
+
+    let report: serde_json::Value =
+      serde_json::from_slice(&std::fs::read(&report_path).unwrap()).unwrap();
+
+    assert_eq!(report.as_array().map(|report| report.len()), Some(1));
+    assert_eq!(report[0]["label"], "test_label");
+    assert_eq!(report[0]["cacheKey"], "test_label:Hello");
+    assert_eq!(report[0]["fieldPath"], "$.value");
+    assert_ne!(report[0]["cachedHash"], report[0]["computedHash"]);
+
+    assert_eq!(
+      handler.reader_writer.read("test_label:Hello").unwrap(),
+      Some(serde_json::to_vec(&TestResponse::new("HELLO")).unwrap())
+    );
+  }
+
   // Simple mock for testing cache miss scenarios
   struct SimpleMockReaderWriter {
     cached_value: Option<Vec<u8>>,
@@ -6,45 +6,97 @@ use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::CacheReaderWriter;
+use crate::cache_gc::{CacheGcOptions, collect_garbage_with_tracker};
+
+/// The key of the `CacheTracker`, which records when each entry was last used
+pub(crate) const METADATA_KEY: &str = "memo_cache_metadata";
 
 pub struct LmdbCacheReaderWriter {
   db_handle: Arc<DatabaseHandle>,
-  entries: SegQueue<String>,
-  max_entries: usize,
+  /// The keys used this session, along with the size of the values written to them
+  entries: SegQueue<(String, Option<u64>)>,
+  gc_options: CacheGcOptions,
 }
 
 impl LmdbCacheReaderWriter {
   pub fn new(db_handle: Arc<DatabaseHandle>) -> Self {
-    Self {
-      db_handle,
-      entries: SegQueue::new(),
-      max_entries: 300000, // Default max entries
-    }
+    Self::with_gc_options(db_handle, CacheGcOptions::default())
   }
 
   pub fn with_max_entries(db_handle: Arc<DatabaseHandle>, max_entries: usize) -> Self {
+    Self::with_gc_options(
+      db_handle,
+      CacheGcOptions {
+        max_entries,
+        ..CacheGcOptions::default()
+      },
+    )
+  }
+
+  pub fn with_gc_options(db_handle: Arc<DatabaseHandle>, gc_options: CacheGcOptions) -> Self {
     Self {
       db_handle,
       entries: SegQueue::new(),
-      max_entries,
+      gc_options,
     }
   }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
-struct CacheEntry {
-  timestamp: u64,
-  key: String,
+pub(crate) struct CacheEntry {
+  pub(crate) timestamp: u64,
+  pub(crate) key: String,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
-struct CacheTracker {
+pub(crate) struct CacheTracker {
+  pub(crate) entries: BTreeSet<CacheEntry>,
+  key_to_timestamp: HashMap<String, u64>,
+  /// The uncompressed size of each entry, which is unknown for entries that haven't been written
+  /// since sizes were tracked
+  sizes: HashMap<String, u64>,
+}
+
+/// The tracker as it was written before sizes were tracked
+#[derive(Deserialize)]
+struct LegacyCacheTracker {
   entries: BTreeSet<CacheEntry>,
   key_to_timestamp: HashMap<String, u64>,
 }
 
 impl CacheTracker {
-  fn update_entry(&mut self, key: String, timestamp: u64) {
+  pub(crate) fn from_bytes(raw_metadata: &[u8]) -> anyhow::Result<Self> {
+    if let Ok(tracker) = bincode::deserialize::<CacheTracker>(raw_metadata) {
+      return Ok(tracker);
+    }
+
+    let legacy = bincode::deserialize::<LegacyCacheTracker>(raw_metadata)?;
+
+    Ok(CacheTracker {
+      entries: legacy.entries,
+      key_to_timestamp: legacy.key_to_timestamp,
+      sizes: HashMap::new(),
+    })
+  }
+
+  pub(crate) fn load(db_handle: &DatabaseHandle) -> anyhow::Result<Self> {
+    let db = db_handle.database();
+    let transaction = db.read_txn()?;
+
+    match db.get(&transaction, METADATA_KEY)? {
+      Some(raw_metadata) => CacheTracker::from_bytes(&raw_metadata),
+      None => Ok(CacheTracker::default()),
+    }
+  }
+
+  pub(crate) fn save(&self, db_handle: &DatabaseHandle) -> anyhow::Result<()> {
+    let db = db_handle.database();
+    let mut transaction = db.write_txn()?;
+    db.put(&mut transaction, METADATA_KEY, &bincode::serialize(self)?)?;
+    Ok(transaction.commit()?)
+  }
+
+  pub(crate) fn update_entry(&mut self, key: String, timestamp: u64) {
     // Remove old entry if exists
     if let Some(old_timestamp) = self.key_to_timestamp.get(&key) {
       let old_entry = CacheEntry {
@@ -63,7 +115,16 @@ impl CacheTracker {
     self.key_to_timestamp.insert(key, timestamp);
   }
 
-  fn remove_oldest_entries(&mut self, count: usize) -> Vec<String> {
+  pub(crate) fn set_size(&mut self, key: &str, size: u64) {
+    self.sizes.insert(key.to_string(), size);
+  }
+
+  /// Returns the size of an entry, or zero when it is unknown
+  pub(crate) fn size(&self, key: &str) -> u64 {
+    self.sizes.get(key).copied().unwrap_or_default()
+  }
+
+  pub(crate) fn remove_oldest_entries(&mut self, count: usize) -> Vec<String> {
     let keys_to_remove: Vec<String> = self
       .entries
       .iter()
@@ -78,7 +139,9 @@ impl CacheTracker {
     keys_to_remove
   }
 
-  fn remove_key(&mut self, key: &str) {
+  pub(crate) fn remove_key(&mut self, key: &str) {
+    self.sizes.remove(key);
+
     if let Some(timestamp) = self.key_to_timestamp.remove(key) {
       let entry = CacheEntry {
         timestamp,
@@ -96,8 +159,8 @@ impl CacheReaderWriter for LmdbCacheReaderWriter {
     let transaction = db_writer.read_txn()?;
 
     // Don't track metadata reads as session keys
-    if key != "memo_cache_metadata" {
-      self.entries.push(key.to_string());
+    if key != METADATA_KEY {
+      self.entries.push((key.to_string(), None));
     }
 
     db_writer
@@ -111,8 +174,10 @@ impl CacheReaderWriter for LmdbCacheReaderWriter {
     let mut transaction = db_writer.write_txn()?;
 
     // Don't track metadata writes as session keys
-    if key != "memo_cache_metadata" {
-      self.entries.push(key.to_string());
+    if key != METADATA_KEY {
+      self
+        .entries
+        .push((key.to_string(), Some(value.len() as u64)));
     }
     db_writer.put(&mut transaction, key, value)?;
 
@@ -126,39 +191,26 @@ impl CacheReaderWriter for LmdbCacheReaderWriter {
     let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
     // Load existing metadata
-    let mut cache_tracker = if let Some(raw_metadata) = self.read("memo_cache_metadata")? {
-      bincode::deserialize::<CacheTracker>(&raw_metadata)?
+    let mut cache_tracker = if let Some(raw_metadata) = self.read(METADATA_KEY)? {
+      CacheTracker::from_bytes(&raw_metadata)?
     } else {
       CacheTracker::default()
     };
 
     // Update entries for all keys from this session
-    while let Some(key) = self.entries.pop() {
+    while let Some((key, size)) = self.entries.pop() {
+      if let Some(size) = size {
+        cache_tracker.set_size(&key, size);
+      }
+
       cache_tracker.update_entry(key, now);
     }
 
-    // Enforce max entries limit
-    let entries_to_remove = cache_tracker.entries.len().saturating_sub(self.max_entries);
-    if entries_to_remove > 0 {
-      tracing::info!(
-        "Cache exceeded max entries ({}). Removing {} oldest entries.",
-        self.max_entries,
-        entries_to_remove
-      );
-      let removed_keys = cache_tracker.remove_oldest_entries(entries_to_remove);
-
-      // Optionally remove the actual cache entries from LMDB
-      for key in removed_keys {
-        let db_writer = self.db_handle.database();
-        let mut transaction = db_writer.write_txn()?;
-        let _ = db_writer.delete(&mut transaction, &key);
-        transaction.commit().ok(); // Ignore errors on cleanup
-      }
-    }
+    collect_garbage_with_tracker(&self.db_handle, &mut cache_tracker, &self.gc_options, now)?;
 
     // Save updated metadata back to database
     let serialized_metadata = bincode::serialize(&cache_tracker)?;
-    self.put("memo_cache_metadata", &serialized_metadata)
+    self.put(METADATA_KEY, &serialized_metadata)
   }
 }
 
@@ -268,6 +320,33 @@ mod tests {
     assert_eq!(tracker.key_to_timestamp, deserialized.key_to_timestamp);
   }
 
+  #[test]
+  fn test_cache_tracker_reads_metadata_without_sizes() {
+    #[derive(Serialize)]
+    struct LegacyMetadata {
+      entries: BTreeSet<CacheEntry>,
+      key_to_timestamp: HashMap<String, u64>,
+    }
+
+    let mut tracker = CacheTracker::default();
+    tracker.update_entry("key1".to_string(), 1000);
+
+    let legacy = bincode::serialize(&LegacyMetadata {
+      entries: tracker.entries.clone(),
+      key_to_timestamp: tracker.key_to_timestamp.clone(),
+    })
+    .unwrap();
+
+    let mut deserialized = CacheTracker::from_bytes(&legacy).unwrap();
+    assert_eq!(deserialized.entries, tracker.entries);
+    assert_eq!(deserialized.size("key1"), 0);
+
+    deserialized.set_size("key1", 10);
+    let deserialized =
+      CacheTracker::from_bytes(&bincode::serialize(&deserialized).unwrap()).unwrap();
+    assert_eq!(deserialized.size("key1"), 10);
+  }
+
   #[test]
   fn test_put_and_read() {
     let db = create_test_db();
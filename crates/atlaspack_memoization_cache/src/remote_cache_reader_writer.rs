@@ -0,0 +1,657 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use crossbeam::channel::{Sender, unbounded};
+use parking_lot::{Condvar, Mutex};
+use xxhash_rust::xxh3::xxh3_128;
+
+use crate::circuit_breaker::CircuitBreaker;
+use crate::remote_store::{HttpRemoteStore, RemoteStore};
+use crate::{CacheReaderWriter, TierStatsSnapshot};
+
+const RAW: u8 = 0;
+const LZ4: u8 = 1;
+
+#[derive(Clone, Debug)]
+pub struct RemoteCacheOptions {
+  /// The base URL of the store, which entries are read from and written to at `<url>/<address>`
+  pub url: String,
+  /// Sent as a bearer token with every request
+  pub token: Option<String>,
+  /// How long to wait for a request before treating it as a failure
+  pub timeout: Duration,
+  /// The number of consecutive failures before the remote cache is skipped
+  pub failure_threshold: u32,
+  /// How long the remote cache is skipped for after it has failed
+  pub cooldown: Duration,
+  /// Whether entries are compressed with lz4 before they are uploaded
+  pub compress: bool,
+  /// The number of threads that upload entries in the background
+  pub upload_threads: usize,
+  /// How long to wait for pending uploads at the end of a session
+  pub flush_timeout: Duration,
+}
+
+impl RemoteCacheOptions {
+  pub fn new(url: &str) -> Self {
+    RemoteCacheOptions {
+      url: url.to_string(),
+      token: None,
+      timeout: Duration::from_secs(2),
+      failure_threshold: 5,
+      cooldown: Duration::from_secs(60),
+      compress: true,
+      upload_threads: 4,
+      flush_timeout: Duration::from_secs(30),
+    }
+  }
+
+  /// Reads the options from the environment, returning `None` when no URL is set
+  ///
+  /// * `ATLASPACK_REMOTE_CACHE_URL` and `ATLASPACK_REMOTE_CACHE_TOKEN`
+  /// * `ATLASPACK_REMOTE_CACHE_TIMEOUT_MS`, `ATLASPACK_REMOTE_CACHE_COOLDOWN_MS` and
+  ///   `ATLASPACK_REMOTE_CACHE_FLUSH_TIMEOUT_MS`
+  /// * `ATLASPACK_REMOTE_CACHE_FAILURE_THRESHOLD`
+  /// * `ATLASPACK_REMOTE_CACHE_COMPRESS`, which disables compression when `false` or `0`
+  ///
+  pub fn from_env() -> Option<Self> {
+    Self::from_vars(|name| std::env::var(name).ok())
+  }
+
+  fn from_vars(var: impl Fn(&str) -> Option<String>) -> Option<Self> {
+    let url = var("ATLASPACK_REMOTE_CACHE_URL").filter(|url| !url.is_empty())?;
+    let number = |name: &str| var(name)?.parse::<u64>().ok();
+    let millis = |name: &str| number(name).map(Duration::from_millis);
+
+    let mut options = RemoteCacheOptions::new(&url);
+    options.token = var("ATLASPACK_REMOTE_CACHE_TOKEN");
+
+    if let Some(timeout) = millis("ATLASPACK_REMOTE_CACHE_TIMEOUT_MS") {
+      options.timeout = timeout;
+    }
+    if let Some(threshold) = number("ATLASPACK_REMOTE_CACHE_FAILURE_THRESHOLD") {
+      options.failure_threshold = u32::try_from(threshold).unwrap_or(u32::MAX);
+    }
+    if let Some(cooldown) = millis("ATLASPACK_REMOTE_CACHE_COOLDOWN_MS") {
+      options.cooldown = cooldown;
+    }
+    if let Some(compress) = var("ATLASPACK_REMOTE_CACHE_COMPRESS") {
+      options.compress = !matches!(compress.as_str(), "false" | "0");
+    }
+    if let Some(flush_timeout) = millis("ATLASPACK_REMOTE_CACHE_FLUSH_TIMEOUT_MS") {
+      options.flush_timeout = flush_timeout;
+    }
+
+    Some(options)
+  }
+}
+
+#[derive(Debug, Default)]
+struct TierStats {
+  hits: AtomicU64,
+  misses: AtomicU64,
+  errors: AtomicU64,
+  writes: AtomicU64,
+  skipped: AtomicU64,
+}
+
+impl TierStats {
+  fn increment(counter: &AtomicU64) {
+    counter.fetch_add(1, Ordering::Relaxed);
+  }
+
+  fn take_snapshot(&self, name: &str) -> TierStatsSnapshot {
+    TierStatsSnapshot {
+      name: name.to_string(),
+      hits: self.hits.swap(0, Ordering::Relaxed),
+      misses: self.misses.swap(0, Ordering::Relaxed),
+      errors: self.errors.swap(0, Ordering::Relaxed),
+      writes: self.writes.swap(0, Ordering::Relaxed),
+      skipped: self.skipped.swap(0, Ordering::Relaxed),
+    }
+  }
+}
+
+/// The number of uploads that have been queued but not yet finished
+#[derive(Default)]
+struct PendingUploads {
+  count: Mutex<usize>,
+  finished: Condvar,
+}
+
+impl PendingUploads {
+  fn start(&self) {
+    *self.count.lock() += 1;
+  }
+
+  fn finish(&self) {
+    let mut count = self.count.lock();
+    *count -= 1;
+
+    if *count == 0 {
+      self.finished.notify_all();
+    }
+  }
+
+  /// Waits for the pending uploads, returning how many are still pending after the timeout
+  fn wait(&self, timeout: Duration) -> usize {
+    let deadline = Instant::now() + timeout;
+    let mut count = self.count.lock();
+
+    while *count > 0 {
+      if self.finished.wait_until(&mut count, deadline).timed_out() {
+        break;
+      }
+    }
+
+    *count
+  }
+}
+
+struct Upload {
+  address: String,
+  payload: Vec<u8>,
+}
+
+/// State shared with the upload threads
+struct Remote {
+  store: Arc<dyn RemoteStore>,
+  breaker: CircuitBreaker,
+  stats: TierStats,
+  pending: PendingUploads,
+}
+
+impl Remote {
+  fn upload(&self, upload: Upload) {
+    if !self.breaker.allow() {
+      TierStats::increment(&self.stats.skipped);
+      return;
+    }
+
+    match self.store.put(&upload.address, &upload.payload) {
+      Ok(()) => {
+        TierStats::increment(&self.stats.writes);
+        self.breaker.record_success();
+      }
+      Err(error) => {
+        tracing::warn!("Failed to write to the remote cache: {}", error);
+        TierStats::increment(&self.stats.errors);
+        self.breaker.record_failure();
+      }
+    }
+  }
+
+  fn read(&self, key: &str) -> Option<Vec<u8>> {
+    if !self.breaker.allow() {
+      TierStats::increment(&self.stats.skipped);
+      return None;
+    }
+
+    match self.store.get(&address(key)) {
+      Ok(None) => {
+        self.breaker.record_success();
+        TierStats::increment(&self.stats.misses);
+        None
+      }
+      Ok(Some(payload)) => {
+        self.breaker.record_success();
+
+        match decode(&payload) {
+          Ok(value) => {
+            TierStats::increment(&self.stats.hits);
+            Some(value)
+          }
+          Err(error) => {
+            tracing::warn!("Invalid remote cache entry for {}: {}", key, error);
+            TierStats::increment(&self.stats.errors);
+            None
+          }
+        }
+      }
+      Err(error) => {
+        tracing::warn!("Failed to read from the remote cache: {}", error);
+        TierStats::increment(&self.stats.errors);
+        self.breaker.record_failure();
+        None
+      }
+    }
+  }
+}
+
+/// Places a shared remote store behind a local cache
+///
+/// Reads go to the local cache first, and entries found remotely are written locally so that
+/// later reads don't need a request. Writes are made locally and then uploaded in the background,
+/// so that builds never wait on the remote store. Any remote failure is treated as a miss, and the
+/// remote store is skipped entirely while it keeps failing.
+///
+pub struct RemoteCacheReaderWriter<L: CacheReaderWriter> {
+  local: L,
+  local_stats: TierStats,
+  remote: Arc<Remote>,
+  uploads: Sender<Upload>,
+  compress: bool,
+  flush_timeout: Duration,
+}
+
+impl<L: CacheReaderWriter> RemoteCacheReaderWriter<L> {
+  pub fn new(local: L, store: Arc<dyn RemoteStore>, options: &RemoteCacheOptions) -> Self {
+    let remote = Arc::new(Remote {
+      store,
+      breaker: CircuitBreaker::new(options.failure_threshold, options.cooldown),
+      stats: TierStats::default(),
+      pending: PendingUploads::default(),
+    });
+
+    let (uploads, receiver) = unbounded::<Upload>();
+
+    // The threads exit once the sender is dropped along with the cache
+    for _ in 0..options.upload_threads.max(1) {
+      let receiver = receiver.clone();
+      let remote = remote.clone();
+
+      std::thread::spawn(move || {
+        for upload in receiver.iter() {
+          remote.upload(upload);
+          remote.pending.finish();
+        }
+      });
+    }
+
+    RemoteCacheReaderWriter {
+      local,
+      local_stats: TierStats::default(),
+      remote,
+      uploads,
+      compress: options.compress,
+      flush_timeout: options.flush_timeout,
+    }
+  }
+
+  /// Uses an HTTP store at the configured URL
+  pub fn http(local: L, options: &RemoteCacheOptions) -> anyhow::Result<Self> {
+    let store = HttpRemoteStore::new(&options.url, options.token.clone(), options.timeout)?;
+    Ok(Self::new(local, Arc::new(store), options))
+  }
+
+  fn read_local(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+    let value = self.local.read(key)?;
+
+    match value {
+      Some(_) => TierStats::increment(&self.local_stats.hits),
+      None => TierStats::increment(&self.local_stats.misses),
+    }
+
+    Ok(value)
+  }
+
+  /// Keeps an entry that was found remotely, so that later reads don't need a request
+  fn write_local(&self, key: &str, value: &[u8]) {
+    if let Err(error) = self.local.put(key, value) {
+      tracing::warn!("Failed to write remote cache entry locally: {}", error);
+      TierStats::increment(&self.local_stats.errors);
+    } else {
+      TierStats::increment(&self.local_stats.writes);
+    }
+  }
+}
+
+#[async_trait]
+impl<L: CacheReaderWriter> CacheReaderWriter for RemoteCacheReaderWriter<L> {
+  fn read(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+    if let Some(value) = self.read_local(key)? {
+      return Ok(Some(value));
+    }
+
+    let Some(value) = self.remote.read(key) else {
+      return Ok(None);
+    };
+
+    self.write_local(key, &value);
+    Ok(Some(value))
+  }
+
+  async fn read_async(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+    if let Some(value) = self.read_local(key)? {
+      return Ok(Some(value));
+    }
+
+    // The remote store blocks until it responds, so it is read on a blocking thread
+    let remote = self.remote.clone();
+    let remote_key = key.to_string();
+    let Some(value) = tokio::task::spawn_blocking(move || remote.read(&remote_key)).await? else {
+      return Ok(None);
+    };
+
+    self.write_local(key, &value);
+    Ok(Some(value))
+  }
+
+  fn put(&self, key: &str, value: &[u8]) -> anyhow::Result<()> {
+    self.local.put(key, value)?;
+    TierStats::increment(&self.local_stats.writes);
+
+    if self.remote.breaker.is_open() {
+      TierStats::increment(&self.remote.stats.skipped);
+      return Ok(());
+    }
+
+    self.remote.pending.start();
+
+    let upload = Upload {
+      address: address(key),
+      payload: encode(value, self.compress),
+    };
+
+    if self.uploads.send(upload).is_err() {
+      self.remote.pending.finish();
+    }
+
+    Ok(())
+  }
+
+  fn read_local_only(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+    self.local.read_local_only(key)
+  }
+
+  fn put_local_only(&self, key: &str, value: &[u8]) -> anyhow::Result<()> {
+    self.local.put_local_only(key, value)
+  }
+
+  fn complete_session(&self) -> anyhow::Result<()> {
+    let pending = self.remote.pending.wait(self.flush_timeout);
+    if pending > 0 {
+      tracing::warn!("{} remote cache uploads did not finish in time", pending);
+    }
+
+    self.local.complete_session()
+  }
+
+  fn take_tier_stats(&self) -> Vec<TierStatsSnapshot> {
+    vec![
+      self.local_stats.take_snapshot("local"),
+      self.remote.stats.take_snapshot("remote"),
+    ]
+  }
+}
+
+/// Entries are stored by a hash of their key, which keeps paths within keys out of the store
+fn address(key: &str) -> String {
+  format!("{:032x}", xxh3_128(key.as_bytes()))
+}
+
+/// Prefixes the value with a byte describing its encoding, so that compression can be toggled
+/// without invalidating existing entries
+fn encode(value: &[u8], compress: bool) -> Vec<u8> {
+  let mut payload = Vec::with_capacity(value.len() + 1);
+
+  if compress {
+    payload.push(LZ4);
+    payload.extend(lz4_flex::compress_prepend_size(value));
+  } else {
+    payload.push(RAW);
+    payload.extend_from_slice(value);
+  }
+
+  payload
+}
+
+fn decode(payload: &[u8]) -> anyhow::Result<Vec<u8>> {
+  match payload.split_first() {
+    Some((&RAW, value)) => Ok(value.to_vec()),
+    Some((&LZ4, value)) => Ok(lz4_flex::decompress_size_prepended(value)?),
+    Some((encoding, _)) => Err(anyhow::anyhow!("Unknown encoding {encoding}")),
+    None => Err(anyhow::anyhow!("Empty payload")),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+  use std::sync::atomic::AtomicBool;
+
+  use super::*;
+  use crate::InMemoryReaderWriter;
+  use crate::remote_store::test_server::TestServer;
+
+  #[derive(Default)]
+  struct FakeStore {
+    entries: Mutex<HashMap<String, Vec<u8>>>,
+    requests: AtomicU64,
+    failing: AtomicBool,
+  }
+
+  impl RemoteStore for FakeStore {
+    fn get(&self, address: &str) -> anyhow::Result<Option<Vec<u8>>> {
+      self.requests.fetch_add(1, Ordering::Relaxed);
+      match self.failing.load(Ordering::Relaxed) {
+        true => Err(anyhow::anyhow!("Unavailable")),
+        false => Ok(self.entries.lock().get(address).cloned()),
+      }
+    }
+
+    fn put(&self, address: &str, value: &[u8]) -> anyhow::Result<()> {
+      self.requests.fetch_add(1, Ordering::Relaxed);
+      match self.failing.load(Ordering::Relaxed) {
+        true => Err(anyhow::anyhow!("Unavailable")),
+        false => {
+          self
+            .entries
+            .lock()
+            .insert(address.to_string(), value.to_vec());
+          Ok(())
+        }
+      }
+    }
+  }
+
+  fn cache(
+    store: Arc<FakeStore>,
+    options: &RemoteCacheOptions,
+  ) -> RemoteCacheReaderWriter<InMemoryReaderWriter> {
+    RemoteCacheReaderWriter::new(InMemoryReaderWriter::default(), store, options)
+  }
+
+  fn stats(name: &str, [hits, misses, errors, writes, skipped]: [u64; 5]) -> TierStatsSnapshot {
+    TierStatsSnapshot {
+      name: name.to_string(),
+      hits,
+      misses,
+      errors,
+      writes,
+      skipped,
+    }
+  }
+
+  #[test]
+  fn encodes_payloads() {
+    let value = b"value value value value value value".to_vec();
+
+    assert_eq!(encode(&value, false)[0], RAW);
+    assert_eq!(encode(&value, true)[0], LZ4);
+    assert_eq!(decode(&encode(&value, false)).unwrap(), value);
+    assert_eq!(decode(&encode(&value, true)).unwrap(), value);
+    assert!(decode(&[]).is_err());
+    assert!(decode(&[7, 1, 2]).is_err());
+  }
+
+  #[test]
+  fn reads_through_to_the_remote_store() {
+    let store = Arc::new(FakeStore::default());
+    store
+      .entries
+      .lock()
+      .insert(address("key"), encode(b"remote", true));
+
+    let cache = cache(store.clone(), &RemoteCacheOptions::new("http://unused"));
+
+    assert_eq!(cache.read("key").unwrap(), Some(b"remote".to_vec()));
+    assert_eq!(cache.read("key").unwrap(), Some(b"remote".to_vec()));
+    assert_eq!(cache.read("missing").unwrap(), None);
+
+    // The second read is served from the local cache
+    assert_eq!(store.requests.load(Ordering::Relaxed), 2);
+    assert_eq!(
+      cache.take_tier_stats(),
+      vec![
+        stats("local", [1, 2, 0, 1, 0]),
+        stats("remote", [1, 1, 0, 0, 0])
+      ]
+    );
+  }
+
+  #[tokio::test(flavor = "current_thread")]
+  async fn reads_the_remote_store_off_the_async_runtime() {
+    let store = Arc::new(FakeStore::default());
+    store
+      .entries
+      .lock()
+      .insert(address("key"), encode(b"remote", true));
+
+    let cache = cache(store.clone(), &RemoteCacheOptions::new("http://unused"));
+
+    assert_eq!(
+      cache.read_async("key").await.unwrap(),
+      Some(b"remote".to_vec())
+    );
+    assert_eq!(
+      cache.read_async("key").await.unwrap(),
+      Some(b"remote".to_vec())
+    );
+    assert_eq!(cache.read_async("missing").await.unwrap(), None);
+
+    assert_eq!(store.requests.load(Ordering::Relaxed), 2);
+    assert_eq!(
+      cache.take_tier_stats(),
+      vec![
+        stats("local", [1, 2, 0, 1, 0]),
+        stats("remote", [1, 1, 0, 0, 0])
+      ]
+    );
+  }
+
+  #[test]
+  fn uploads_writes_in_the_background() {
+    let store = Arc::new(FakeStore::default());
+    let cache = cache(store.clone(), &RemoteCacheOptions::new("http://unused"));
+
+    cache.put("key", b"value").unwrap();
+    cache.complete_session().unwrap();
+
+    let payload = store.entries.lock().get(&address("key")).cloned().unwrap();
+    assert_eq!(decode(&payload).unwrap(), b"value".to_vec());
+    assert_eq!(
+      cache.take_tier_stats(),
+      vec![
+        stats("local", [0, 0, 0, 1, 0]),
+        stats("remote", [0, 0, 0, 1, 0])
+      ]
+    );
+  }
+
+  #[test]
+  fn keeps_local_only_entries_out_of_the_remote_store() {
+    let store = Arc::new(FakeStore::default());
+    store
+      .entries
+      .lock()
+      .insert(address("remote"), encode(b"remote", true));
+
+    let cache = cache(store.clone(), &RemoteCacheOptions::new("http://unused"));
+
+    cache.put_local_only("key", b"value").unwrap();
+    cache.complete_session().unwrap();
+
+    assert_eq!(
+      cache.read_local_only("key").unwrap(),
+      Some(b"value".to_vec())
+    );
+    assert_eq!(cache.read_local_only("remote").unwrap(), None);
+    assert_eq!(store.requests.load(Ordering::Relaxed), 0);
+    assert_eq!(store.entries.lock().len(), 1);
+  }
+
+  #[test]
+  fn falls_back_to_the_local_cache_while_the_remote_store_fails() {
+    let store = Arc::new(FakeStore::default());
+    store.failing.store(true, Ordering::Relaxed);
+
+    let mut options = RemoteCacheOptions::new("http://unused");
+    options.failure_threshold = 2;
+
+    let cache = cache(store.clone(), &options);
+
+    assert_eq!(cache.read("a").unwrap(), None);
+    assert_eq!(cache.read("b").unwrap(), None);
+    assert_eq!(cache.read("c").unwrap(), None);
+
+    cache.put("d", b"value").unwrap();
+    cache.complete_session().unwrap();
+
+    assert_eq!(cache.read("d").unwrap(), Some(b"value".to_vec()));
+    assert_eq!(store.requests.load(Ordering::Relaxed), 2);
+    assert_eq!(
+      cache.take_tier_stats(),
+      vec![
+        stats("local", [1, 3, 0, 1, 0]),
+        stats("remote", [0, 0, 2, 0, 2])
+      ]
+    );
+  }
+
+  #[test]
+  fn shares_entries_through_an_http_store() {
+    let server = TestServer::start();
+
+    let mut options = RemoteCacheOptions::new(&server.url);
+    options.token = Some(String::from("secret"));
+
+    let writer = RemoteCacheReaderWriter::http(InMemoryReaderWriter::default(), &options).unwrap();
+    writer.put("key", b"value").unwrap();
+    writer.complete_session().unwrap();
+
+    let reader = RemoteCacheReaderWriter::http(InMemoryReaderWriter::default(), &options).unwrap();
+    assert_eq!(reader.read("key").unwrap(), Some(b"value".to_vec()));
+    assert_eq!(reader.read("missing").unwrap(), None);
+  }
+
+  #[test]
+  fn reads_the_options_from_the_environment() {
+    let options = |vars: &[(&str, &str)]| {
+      RemoteCacheOptions::from_vars(|name| {
+        vars
+          .iter()
+          .find(|(key, _)| *key == name)
+          .map(|(_, value)| value.to_string())
+      })
+    };
+
+    assert!(options(&[]).is_none());
+    assert!(options(&[("ATLASPACK_REMOTE_CACHE_URL", "")]).is_none());
+
+    let defaults = options(&[("ATLASPACK_REMOTE_CACHE_URL", "http://cache")]).unwrap();
+    assert_eq!(defaults.failure_threshold, 5);
+    assert!(defaults.compress);
+
+    let configured = options(&[
+      ("ATLASPACK_REMOTE_CACHE_URL", "http://cache"),
+      ("ATLASPACK_REMOTE_CACHE_TOKEN", "secret"),
+      ("ATLASPACK_REMOTE_CACHE_TIMEOUT_MS", "500"),
+      ("ATLASPACK_REMOTE_CACHE_FAILURE_THRESHOLD", "3"),
+      ("ATLASPACK_REMOTE_CACHE_COOLDOWN_MS", "1000"),
+      ("ATLASPACK_REMOTE_CACHE_COMPRESS", "false"),
+      ("ATLASPACK_REMOTE_CACHE_FLUSH_TIMEOUT_MS", "2000"),
+    ])
+    .unwrap();
+
+    assert_eq!(configured.url, "http://cache");
+    assert_eq!(configured.token.as_deref(), Some("secret"));
+    assert_eq!(configured.timeout, Duration::from_millis(500));
+    assert_eq!(configured.failure_threshold, 3);
+    assert_eq!(configured.cooldown, Duration::from_secs(1));
+    assert!(!configured.compress);
+    assert_eq!(configured.flush_timeout, Duration::from_secs(2));
+  }
+}
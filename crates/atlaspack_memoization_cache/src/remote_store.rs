@@ -0,0 +1,262 @@
+use std::future::Future;
+use std::time::Duration;
+
+use reqwest::{Method, RequestBuilder, StatusCode};
+use tokio::runtime::Runtime;
+
+/// A content-addressed store that is shared between machines
+///
+/// Entries are immutable, so an address always refers to the same contents.
+pub trait RemoteStore: Send + Sync {
+  fn get(&self, address: &str) -> anyhow::Result<Option<Vec<u8>>>;
+  fn put(&self, address: &str, value: &[u8]) -> anyhow::Result<()>;
+}
+
+/// Reads and writes entries with `GET` and `PUT` requests to `<url>/<address>`
+///
+/// Requests run on a runtime owned by the store, since the cache is read from within the async
+/// runtime that runs requests, where a blocking HTTP client can't be used.
+///
+pub struct HttpRemoteStore {
+  client: reqwest::Client,
+  url: String,
+  token: Option<String>,
+  runtime: Option<Runtime>,
+}
+
+impl HttpRemoteStore {
+  pub fn new(url: &str, token: Option<String>, timeout: Duration) -> anyhow::Result<Self> {
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+      .worker_threads(2)
+      .thread_name("atlaspack-remote-cache")
+      .enable_all()
+      .build()?;
+
+    let client = reqwest::Client::builder()
+      .connect_timeout(timeout)
+      .timeout(timeout)
+      .build()?;
+
+    Ok(HttpRemoteStore {
+      client,
+      url: url.trim_end_matches('/').to_string(),
+      token,
+      runtime: Some(runtime),
+    })
+  }
+
+  fn request(&self, method: Method, address: &str) -> RequestBuilder {
+    let request = self
+      .client
+      .request(method, format!("{}/{}", self.url, address));
+
+    match &self.token {
+      Some(token) => request.bearer_auth(token),
+      None => request,
+    }
+  }
+
+  fn block_on<T: Send + 'static>(
+    &self,
+    future: impl Future<Output = anyhow::Result<T>> + Send + 'static,
+  ) -> anyhow::Result<T> {
+    let runtime = self
+      .runtime
+      .as_ref()
+      .ok_or_else(|| anyhow::anyhow!("The remote cache has been shut down"))?;
+
+    let (sender, receiver) = std::sync::mpsc::channel();
+    runtime.spawn(async move {
+      let _ = sender.send(future.await);
+    });
+
+    receiver.recv()?
+  }
+}
+
+impl RemoteStore for HttpRemoteStore {
+  fn get(&self, address: &str) -> anyhow::Result<Option<Vec<u8>>> {
+    let request = self.request(Method::GET, address);
+
+    self.block_on(async move {
+      let response = request.send().await?;
+
+      match response.status() {
+        StatusCode::NOT_FOUND => Ok(None),
+        status if status.is_success() => Ok(Some(response.bytes().await?.to_vec())),
+        status => Err(anyhow::anyhow!("Remote cache responded with {status}")),
+      }
+    })
+  }
+
+  fn put(&self, address: &str, value: &[u8]) -> anyhow::Result<()> {
+    let request = self.request(Method::PUT, address).body(value.to_vec());
+
+    self.block_on(async move {
+      let status = request.send().await?.status();
+
+      match status.is_success() {
+        true => Ok(()),
+        false => Err(anyhow::anyhow!("Remote cache responded with {status}")),
+      }
+    })
+  }
+}
+
+impl Drop for HttpRemoteStore {
+  fn drop(&mut self) {
+    // Dropping a runtime blocks, which panics if the store is dropped within another runtime
+    if let Some(runtime) = self.runtime.take() {
+      runtime.shutdown_background();
+    }
+  }
+}
+
+#[cfg(test)]
+pub(crate) mod test_server {
+  use std::collections::HashMap;
+  use std::io::{BufRead, BufReader, Read, Write};
+  use std::net::{TcpListener, TcpStream};
+  use std::sync::Arc;
+
+  use parking_lot::Mutex;
+
+  /// A stand-in for a content-addressed store, which keeps entries in memory and requires the
+  /// `secret` bearer token
+  pub(crate) struct TestServer {
+    pub url: String,
+    pub entries: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+  }
+
+  impl TestServer {
+    pub(crate) fn start() -> Self {
+      let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+      let url = format!("http://{}", listener.local_addr().unwrap());
+      let entries = Arc::new(Mutex::new(HashMap::new()));
+
+      std::thread::spawn({
+        let entries = entries.clone();
+        move || {
+          for stream in listener.incoming() {
+            let entries = entries.clone();
+            std::thread::spawn(move || handle(stream.unwrap(), &entries));
+          }
+        }
+      });
+
+      TestServer { url, entries }
+    }
+  }
+
+  fn handle(mut stream: TcpStream, entries: &Mutex<HashMap<String, Vec<u8>>>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let address = parts
+      .next()
+      .unwrap_or_default()
+      .trim_start_matches('/')
+      .to_string();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+
+    loop {
+      let mut line = String::new();
+      reader.read_line(&mut line).unwrap();
+
+      let Some((name, value)) = line.trim_end().split_once(':') else {
+        break;
+      };
+
+      match name.to_ascii_lowercase().as_str() {
+        "content-length" => content_length = value.trim().parse().unwrap(),
+        "authorization" => authorization = Some(value.trim().to_string()),
+        _ => {}
+      }
+    }
+
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).unwrap();
+
+    let (status, body) = match (authorization.as_deref(), method.as_str()) {
+      (Some("Bearer secret"), "GET") => match entries.lock().get(&address) {
+        Some(value) => ("200 OK", value.clone()),
+        None => ("404 Not Found", Vec::new()),
+      },
+      (Some("Bearer secret"), "PUT") => {
+        entries.lock().insert(address, body);
+        ("201 Created", Vec::new())
+      }
+      (Some("Bearer secret"), _) => ("405 Method Not Allowed", Vec::new()),
+      _ => ("401 Unauthorized", Vec::new()),
+    };
+
+    write!(
+      stream,
+      "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+      body.len()
+    )
+    .unwrap();
+    stream.write_all(&body).unwrap();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::net::TcpListener;
+
+  use super::test_server::TestServer;
+  use super::*;
+
+  fn store(url: &str, token: &str) -> HttpRemoteStore {
+    HttpRemoteStore::new(url, Some(String::from(token)), Duration::from_secs(5)).unwrap()
+  }
+
+  #[test]
+  fn reads_and_writes_entries() {
+    let server = TestServer::start();
+    let store = store(&format!("{}/", server.url), "secret");
+
+    assert_eq!(store.get("abc").unwrap(), None);
+
+    store.put("abc", b"value").unwrap();
+
+    assert_eq!(store.get("abc").unwrap(), Some(b"value".to_vec()));
+    assert_eq!(
+      server.entries.lock().get("abc").cloned(),
+      Some(b"value".to_vec())
+    );
+  }
+
+  #[test]
+  fn errors_when_the_request_fails() {
+    let server = TestServer::start();
+
+    assert!(store(&server.url, "wrong").get("abc").is_err());
+    assert!(store(&server.url, "wrong").put("abc", b"value").is_err());
+
+    // Nothing is listening once the listener is dropped
+    let url = {
+      let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+      format!("http://{}", listener.local_addr().unwrap())
+    };
+
+    assert!(store(&url, "secret").get("abc").is_err());
+  }
+
+  #[tokio::test]
+  async fn can_be_used_and_dropped_within_a_runtime() {
+    let server = TestServer::start();
+    let store = store(&server.url, "secret");
+
+    store.put("abc", b"value").unwrap();
+    assert_eq!(store.get("abc").unwrap(), Some(b"value".to_vec()));
+
+    drop(store);
+  }
+}
@@ -6,6 +6,7 @@ use anyhow::Result;
 use atlaspack_core::bundle_graph::bundle_graph::BundleGraph;
 use atlaspack_core::package_result::{BundleInfo, PackageResult};
 use atlaspack_core::types::Priority;
+use atlaspack_sourcemap::{SourceMap, generate_debug_id};
 use lightningcss::bundler::{Bundler, SourceProvider};
 use lightningcss::printer::PrinterOptions;
 use lightningcss::stylesheet::ParserOptions;
@@ -464,11 +465,22 @@ impl<B: BundleGraph + Send + Sync> CssPackager<B> {
     let map_bytes: Option<Vec<u8>> = if let Some(ref mut sm) = lc_source_map_opt {
       let bundle_name = bundle.name.as_deref().unwrap_or("output.css");
       let map_name = format!("{}.map", bundle_name);
+      let debug_id = generate_debug_id(css.as_bytes());
+      css.push_str(&format!("\n/*# debugId={} */", debug_id));
       css.push_str(&format!("\n/*# sourceMappingURL={} */", map_name));
       let map_json = sm
         .to_json(None)
         .map_err(|e| anyhow::anyhow!("source map serialisation failed: {:?}", e))?;
-      Some(map_json.into_bytes())
+
+      // Lightning CSS only writes the classic source map fields, so the debug ID and ignore list
+      // are added to a copy of the map.
+      let mut source_map = SourceMap::from_json(&self.context.project_root, &map_json)?;
+      source_map.set_debug_id(Some(debug_id));
+      if let Some(source_map_options) = &bundle.env.source_map {
+        source_map.populate_ignore_list(&source_map_options.ignore_list())?;
+      }
+
+      Some(source_map.to_json(None)?.into_bytes())
     } else {
       None
     };
@@ -1931,6 +1943,53 @@ mod tests {
     );
   }
 
+  #[test]
+  fn source_map_debug_id_matches_bundle_comment() {
+    let db = make_db();
+    db.put("asset_debug_id", b".foo { color: red; }").unwrap();
+
+    let asset = Asset {
+      id: "asset_debug_id".to_string(),
+      file_type: FileType::Css,
+      env: Arc::new(make_env_with_source_map()),
+      ..Asset::default()
+    };
+
+    let mut bundle = make_bundle_with_name("bundle_debug_id", vec!["asset_debug_id"], "output.css");
+    bundle.env = make_env_with_source_map();
+
+    let mut graph = TestBundleGraph::new();
+    graph.bundles.push(bundle);
+    graph
+      .assets_by_bundle
+      .insert("bundle_debug_id".to_string(), vec![asset]);
+
+    let packager = CssPackager::new(
+      CssPackagingContext {
+        db,
+        project_root: PathBuf::from("/tmp"),
+        output_dir: PathBuf::from("/tmp/dist"),
+      },
+      Arc::new(graph),
+    );
+
+    let result = packager
+      .package("bundle_debug_id")
+      .expect("package() must succeed");
+
+    let map_json: Value = from_slice(result.bundle_info.map_contents.as_ref().unwrap())
+      .expect("map_contents must be valid JSON");
+    let debug_id = map_json["debugId"]
+      .as_str()
+      .expect("source map must have a 'debugId'");
+
+    let css = output_string(&result);
+    assert!(
+      css.contains(&format!("/*# debugId={debug_id} */")),
+      "CSS must contain a debugId comment matching the source map; got: {css:?}"
+    );
+  }
+
   #[test]
   fn source_map_line_offset_correct_for_hoisted_imports() {
     let db = make_db();
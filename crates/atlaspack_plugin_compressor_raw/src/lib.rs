@@ -0,0 +1,3 @@
+pub use raw_compressor::AtlaspackRawCompressorPlugin;
+
+mod raw_compressor;
@@ -0,0 +1,60 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use atlaspack_core::plugin::{CompressedFile, CompressorPlugin, PluginContext};
+
+/// Writes output files without compressing them
+#[derive(Debug, Hash)]
+pub struct AtlaspackRawCompressorPlugin {}
+
+impl AtlaspackRawCompressorPlugin {
+  pub fn new(_ctx: &PluginContext) -> Self {
+    AtlaspackRawCompressorPlugin {}
+  }
+}
+
+#[async_trait]
+impl CompressorPlugin for AtlaspackRawCompressorPlugin {
+  async fn compress(&self, contents: &[u8]) -> Result<Option<CompressedFile>, Error> {
+    Ok(Some(CompressedFile {
+      contents: contents.to_vec(),
+      extension: None,
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{path::PathBuf, sync::Arc};
+
+  use atlaspack_core::{
+    config_loader::ConfigLoader,
+    plugin::{PluginLogger, PluginOptions},
+  };
+  use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
+
+  use super::*;
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn returns_the_contents_unchanged() {
+    let file_system = Arc::new(InMemoryFileSystem::default());
+    let plugin = AtlaspackRawCompressorPlugin::new(&PluginContext {
+      config: Arc::new(ConfigLoader {
+        fs: file_system.clone(),
+        project_root: PathBuf::default(),
+        search_path: PathBuf::default(),
+        files: Default::default(),
+      }),
+      file_system,
+      logger: PluginLogger::default(),
+      options: Arc::new(PluginOptions::default()),
+    });
+
+    assert_eq!(
+      plugin.compress(b"contents").await.map_err(|e| e.to_string()),
+      Ok(Some(CompressedFile {
+        contents: b"contents".to_vec(),
+        extension: None,
+      }))
+    );
+  }
+}
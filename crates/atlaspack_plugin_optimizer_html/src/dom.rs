@@ -0,0 +1,132 @@
+use std::cell::RefCell;
+use std::io::BufReader;
+use std::sync::LazyLock;
+
+use anyhow::{Error, anyhow};
+use html5ever::serialize::{SerializeOpts, TraversalScope};
+use html5ever::tendril::TendrilSink;
+use html5ever::{ParseOpts, serialize};
+use markup5ever::{Attribute, QualName, local_name, namespace_url, ns};
+use markup5ever_rcdom::{Handle, Node, NodeData, RcDom, SerializableHandle};
+use regex::Regex;
+
+/// Matches the doctype or the tags that only appear in complete documents
+static DOCUMENT_RE: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"(?i)<(?:!doctype|html|head|body)[\s/>]").unwrap());
+
+pub fn parse_document(bytes: &[u8]) -> Result<RcDom, Error> {
+  let mut bytes = BufReader::new(bytes);
+  let dom = html5ever::parse_document(RcDom::default(), ParseOpts::default())
+    .from_utf8()
+    .read_from(&mut bytes)?;
+
+  Ok(dom)
+}
+
+/// Parses HTML, returning the DOM and the node whose children make up the code
+///
+/// Code without a doctype or `<html>`, `<head>` and `<body>` tags, such as a template, is parsed
+/// as the contents of a `<body>`, so that those elements are not added to it.
+pub fn parse_html(code: &str) -> Result<(RcDom, Handle), Error> {
+  if DOCUMENT_RE.is_match(code) {
+    let dom = parse_document(code.as_bytes())?;
+    let root = dom.document.clone();
+    return Ok((dom, root));
+  }
+
+  let mut bytes = BufReader::new(code.as_bytes());
+  let dom = html5ever::parse_fragment(
+    RcDom::default(),
+    ParseOpts::default(),
+    QualName::new(None, ns!(html), local_name!("body")),
+    vec![],
+  )
+  .from_utf8()
+  .read_from(&mut bytes)?;
+
+  // Fragments are parsed into an `<html>` element, which is not part of the code
+  let root = dom
+    .document
+    .children
+    .borrow()
+    .first()
+    .cloned()
+    .ok_or_else(|| anyhow!("The HTML fragment has no root element"))?;
+
+  Ok((dom, root))
+}
+
+/// Serializes the node, or only its children when `include_node` is false
+pub fn serialize_node(node: &Handle, include_node: bool) -> Result<String, Error> {
+  let handle: SerializableHandle = node.clone().into();
+  let mut output = Vec::new();
+
+  serialize(
+    &mut output,
+    &handle,
+    SerializeOpts {
+      traversal_scope: if include_node {
+        TraversalScope::IncludeNode
+      } else {
+        TraversalScope::ChildrenOnly(None)
+      },
+      ..SerializeOpts::default()
+    },
+  )?;
+
+  Ok(String::from_utf8(output)?)
+}
+
+/// Returns the element name, if the node is an element
+pub fn element_name(node: &Handle) -> Option<&QualName> {
+  match &node.data {
+    NodeData::Element { name, .. } => Some(name),
+    _ => None,
+  }
+}
+
+pub fn element_attrs(node: &Handle) -> Option<&RefCell<Vec<Attribute>>> {
+  match &node.data {
+    NodeData::Element { attrs, .. } => Some(attrs),
+    _ => None,
+  }
+}
+
+/// Returns the value of an attribute without a namespace
+pub fn get_attr(node: &Handle, local: &str) -> Option<String> {
+  element_attrs(node)?
+    .borrow()
+    .iter()
+    .find(|attr| attr.name.ns.is_empty() && &*attr.name.local == local)
+    .map(|attr| attr.value.to_string())
+}
+
+pub fn is_text(node: &Handle) -> bool {
+  matches!(node.data, NodeData::Text { .. })
+}
+
+/// Concatenates the direct text children of a node
+///
+/// Serializing the children is avoided so that characters within scripts and styles are not
+/// escaped.
+pub fn text_content(node: &Handle) -> String {
+  node
+    .children
+    .borrow()
+    .iter()
+    .filter_map(|child| match &child.data {
+      NodeData::Text { contents } => Some(contents.borrow().to_string()),
+      _ => None,
+    })
+    .collect()
+}
+
+pub fn set_text_content(node: &Handle, contents: String) {
+  *node.children.borrow_mut() = if contents.is_empty() {
+    Vec::new()
+  } else {
+    vec![Node::new(NodeData::Text {
+      contents: RefCell::new(contents.into()),
+    })]
+  };
+}
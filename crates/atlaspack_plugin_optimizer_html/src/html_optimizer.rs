@@ -0,0 +1,539 @@
+use std::collections::HashMap;
+
+use anyhow::{Error, anyhow};
+use markup5ever::{namespace_url, ns};
+use markup5ever_rcdom::{Handle, NodeData};
+use serde::Deserialize;
+
+use crate::dom::{
+  element_attrs, element_name, get_attr, is_text, parse_html, serialize_node, set_text_content,
+  text_content,
+};
+use crate::inline_minifier::{minify_css, minify_json};
+use crate::svg_optimizer::{SvgOptimizerOptions, optimize_svg_element};
+
+/// Elements where whitespace is significant
+const PRESERVE_WHITESPACE_ELEMENTS: &[&str] = &["pre", "textarea", "script", "style"];
+
+/// Elements that do not render inline, so whitespace around them can be removed
+const BLOCK_ELEMENTS: &[&str] = &[
+  "address",
+  "article",
+  "aside",
+  "base",
+  "blockquote",
+  "body",
+  "dd",
+  "details",
+  "dialog",
+  "div",
+  "dl",
+  "dt",
+  "fieldset",
+  "figcaption",
+  "figure",
+  "footer",
+  "form",
+  "h1",
+  "h2",
+  "h3",
+  "h4",
+  "h5",
+  "h6",
+  "head",
+  "header",
+  "hr",
+  "html",
+  "li",
+  "main",
+  "nav",
+  "ol",
+  "p",
+  "section",
+  "select",
+  "style",
+  "summary",
+  "table",
+  "tbody",
+  "td",
+  "template",
+  "tfoot",
+  "th",
+  "thead",
+  "tr",
+  "ul",
+];
+
+/// Attribute values that match the browser defaults, as `(element, attribute, value)`
+const REDUNDANT_ATTRIBUTES: &[(&str, &str, &str)] = &[
+  ("script", "type", "text/javascript"),
+  ("script", "type", "application/javascript"),
+  ("script", "language", "javascript"),
+  ("style", "type", "text/css"),
+  ("link", "type", "text/css"),
+  ("form", "method", "get"),
+  ("input", "type", "text"),
+  ("button", "type", "submit"),
+  ("area", "shape", "rect"),
+];
+
+/// Script types that contain JSON rather than JavaScript
+const JSON_SCRIPT_TYPES: &[&str] = &["application/json", "application/ld+json", "importmap"];
+
+#[derive(Clone, Debug, Deserialize, Hash, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HtmlOptimizerOptions {
+  /// Collapses runs of whitespace, and removes whitespace around block elements
+  pub collapse_whitespace: bool,
+  /// Removes comments, except for conditional comments
+  pub remove_comments: bool,
+  /// Removes attributes that are set to their default value, such as `type="text/javascript"`
+  pub remove_redundant_attributes: bool,
+  /// Minifies the contents of `<style>` elements
+  pub minify_css: bool,
+  /// Minifies the contents of `<script>` elements with the JS optimizers, and minifies inline JSON
+  pub minify_js: bool,
+  /// Optimizes inline `<svg>` elements with the `svg` options
+  pub minify_svg: bool,
+}
+
+impl Default for HtmlOptimizerOptions {
+  fn default() -> Self {
+    HtmlOptimizerOptions {
+      collapse_whitespace: true,
+      remove_comments: true,
+      remove_redundant_attributes: true,
+      minify_css: true,
+      minify_js: true,
+      minify_svg: true,
+    }
+  }
+}
+
+/// The contents of an inline `<script>` element containing JavaScript
+#[derive(Clone, Debug, PartialEq)]
+pub struct InlineScript {
+  pub code: String,
+  /// Whether the script is loaded with `type="module"`
+  pub is_module: bool,
+}
+
+/// Returns the inline JavaScript in an HTML document, so it can be minified by the JS optimizers
+///
+/// The minified code is then passed to [`optimize_html`], keyed by the original code.
+pub fn inline_scripts(code: &str) -> Result<Vec<InlineScript>, Error> {
+  let (_dom, root) = parse_html(code)?;
+  let mut scripts = Vec::new();
+
+  collect_inline_scripts(&root, &mut scripts);
+
+  Ok(scripts)
+}
+
+fn collect_inline_scripts(node: &Handle, scripts: &mut Vec<InlineScript>) {
+  if let Some(name) = element_name(node)
+    && name.ns == ns!(html)
+    && &*name.local == "script"
+    && get_attr(node, "src").is_none()
+  {
+    let is_module = match get_attr(node, "type").as_deref() {
+      None | Some("text/javascript" | "application/javascript") => false,
+      Some("module") => true,
+      Some(_) => return,
+    };
+
+    let code = text_content(node);
+    if !code.trim().is_empty() {
+      scripts.push(InlineScript { code, is_module });
+    }
+
+    return;
+  }
+
+  for child in node.children.borrow().iter() {
+    collect_inline_scripts(child, scripts);
+  }
+}
+
+/// Minifies an HTML document
+///
+/// Inline scripts are replaced with their entry in `minified_scripts`, which is keyed by the
+/// original code. Inline styles and scripts that fail to parse are left untouched, as they may be
+/// templates or use syntax that is only understood by the browser.
+pub fn optimize_html(
+  code: &str,
+  options: &HtmlOptimizerOptions,
+  svg_options: &SvgOptimizerOptions,
+  minified_scripts: &HashMap<String, String>,
+) -> Result<String, Error> {
+  let (_dom, root) = parse_html(code)?;
+  let optimizer = HtmlOptimizer {
+    options,
+    svg_options,
+    minified_scripts,
+  };
+
+  optimizer.optimize_node(&root, false);
+
+  serialize_node(&root, false)
+}
+
+struct HtmlOptimizer<'a> {
+  options: &'a HtmlOptimizerOptions,
+  svg_options: &'a SvgOptimizerOptions,
+  minified_scripts: &'a HashMap<String, String>,
+}
+
+impl HtmlOptimizer<'_> {
+  fn optimize_node(&self, node: &Handle, preserve_whitespace: bool) {
+    if let Some(name) = element_name(node) {
+      if name.ns == ns!(svg) && &*name.local == "svg" {
+        if self.options.minify_svg {
+          optimize_svg_element(node, self.svg_options);
+        }
+
+        return;
+      }
+
+      if name.ns == ns!(html) {
+        match &*name.local {
+          "style" if self.options.minify_css => self.minify_text(node, minify_css),
+          "script" if self.options.minify_js => self.minify_script(node),
+          _ => {}
+        }
+
+        if self.options.remove_redundant_attributes {
+          self.remove_redundant_attributes(node, &name.local);
+        }
+      }
+    }
+
+    let preserve_whitespace = preserve_whitespace || is_preserve_whitespace_element(node);
+
+    if self.options.remove_comments {
+      node
+        .children
+        .borrow_mut()
+        .retain(|child| !is_removable_comment(child));
+    }
+
+    if self.options.collapse_whitespace && !preserve_whitespace {
+      self.collapse_whitespace(node);
+    }
+
+    let children = node.children.borrow().clone();
+    for child in children.iter() {
+      self.optimize_node(child, preserve_whitespace);
+    }
+  }
+
+  fn minify_script(&self, node: &Handle) {
+    if get_attr(node, "src").is_some() {
+      return;
+    }
+
+    match get_attr(node, "type").as_deref() {
+      None | Some("module" | "text/javascript" | "application/javascript") => {
+        self.minify_text(node, |code| {
+          self
+            .minified_scripts
+            .get(code)
+            .cloned()
+            .ok_or_else(|| anyhow!("The script was not minified"))
+        })
+      }
+      Some(script_type) if JSON_SCRIPT_TYPES.contains(&script_type) => {
+        self.minify_text(node, minify_json)
+      }
+      Some(_) => {}
+    }
+  }
+
+  fn minify_text(&self, node: &Handle, minify: impl Fn(&str) -> Result<String, Error>) {
+    let code = text_content(node);
+    if code.trim().is_empty() {
+      return;
+    }
+
+    if let Ok(minified) = minify(&code) {
+      set_text_content(node, minified);
+    }
+  }
+
+  fn remove_redundant_attributes(&self, node: &Handle, element: &str) {
+    let Some(attrs) = element_attrs(node) else {
+      return;
+    };
+
+    attrs.borrow_mut().retain(|attr| {
+      !REDUNDANT_ATTRIBUTES.iter().any(|(tag, name, value)| {
+        *tag == element
+          && attr.name.ns.is_empty()
+          && &*attr.name.local == *name
+          && attr.value.eq_ignore_ascii_case(value)
+      })
+    });
+  }
+
+  fn collapse_whitespace(&self, node: &Handle) {
+    let mut children = node.children.borrow_mut();
+
+    for child in children.iter() {
+      if let NodeData::Text { contents } = &child.data {
+        let collapsed = contents
+          .borrow()
+          .split_ascii_whitespace()
+          .collect::<Vec<&str>>()
+          .join(" ");
+
+        let mut contents = contents.borrow_mut();
+        let leading = contents.starts_with(|c: char| c.is_ascii_whitespace());
+        let trailing = contents.ends_with(|c: char| c.is_ascii_whitespace());
+
+        *contents = match (collapsed.is_empty(), leading, trailing) {
+          (true, _, _) => " ".into(),
+          (false, true, true) => format!(" {collapsed} ").into(),
+          (false, true, false) => format!(" {collapsed}").into(),
+          (false, false, true) => format!("{collapsed} ").into(),
+          (false, false, false) => collapsed.into(),
+        };
+      }
+    }
+
+    // Whitespace only text is removed when it sits next to a block element, or at the edges of one.
+    // Comments and other whitespace are skipped over when finding the neighbouring nodes.
+    let is_block_parent = is_block_element(node) || matches!(node.data, NodeData::Document);
+    let snapshot = children.clone();
+    let is_whitespace = |child: &Handle| is_text(child) && text_content_of(child) == " ";
+    let is_block_boundary = |sibling: Option<&Handle>| match sibling {
+      None => is_block_parent,
+      Some(sibling) => is_block_element(sibling),
+    };
+
+    let mut index = 0;
+    children.retain(|child| {
+      let current = index;
+      index += 1;
+
+      if !is_whitespace(child) {
+        return true;
+      }
+
+      let is_significant = |sibling: &&Handle| {
+        !is_whitespace(sibling) && !matches!(sibling.data, NodeData::Comment { .. })
+      };
+
+      let previous = snapshot[..current].iter().rev().find(is_significant);
+      let next = snapshot[current + 1..].iter().find(is_significant);
+
+      !(is_block_boundary(previous) || is_block_boundary(next))
+    });
+  }
+}
+
+fn text_content_of(node: &Handle) -> String {
+  match &node.data {
+    NodeData::Text { contents } => contents.borrow().to_string(),
+    _ => String::new(),
+  }
+}
+
+fn is_block_element(node: &Handle) -> bool {
+  element_name(node)
+    .is_some_and(|name| name.ns == ns!(html) && BLOCK_ELEMENTS.contains(&&*name.local))
+}
+
+fn is_preserve_whitespace_element(node: &Handle) -> bool {
+  element_name(node).is_some_and(|name| {
+    name.ns == ns!(html) && PRESERVE_WHITESPACE_ELEMENTS.contains(&&*name.local)
+  })
+}
+
+/// Conditional comments such as `<!--[if IE]>` still affect legacy browsers
+fn is_removable_comment(node: &Handle) -> bool {
+  match &node.data {
+    NodeData::Comment { contents } => !contents.starts_with("[if") && !contents.starts_with("<!["),
+    _ => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn optimize(code: &str) -> String {
+    optimize_html(
+      code,
+      &HtmlOptimizerOptions::default(),
+      &SvgOptimizerOptions::default(),
+      &HashMap::new(),
+    )
+    .unwrap()
+  }
+
+  #[test]
+  fn collapses_whitespace_and_removes_comments() {
+    let html = optimize(
+      r#"
+        <!DOCTYPE html>
+        <html>
+          <head>
+            <title>  Page   title </title>
+          </head>
+          <body>
+            <!-- comment -->
+            <!--[if IE]><p>Legacy</p><![endif]-->
+            <p>Some   <b>bold</b>
+              text</p>
+            <pre>  keep
+  this </pre>
+          </body>
+        </html>
+      "#,
+    );
+
+    assert_eq!(
+      html,
+      String::from(concat!(
+        "<!DOCTYPE html><html><head><title> Page title </title></head><body>",
+        "<!--[if IE]><p>Legacy</p><![endif]-->",
+        "<p>Some <b>bold</b> text</p>",
+        "<pre>  keep\n  this </pre>",
+        "</body></html>"
+      ))
+    );
+  }
+
+  #[test]
+  fn keeps_whitespace_around_inline_elements_in_fragments() {
+    let html = optimize(concat!(
+      "<p>Line one <br>\n  line two</p>\n",
+      "<span>a</span> <script>b()</script> <noscript>c</noscript>\n",
+      "<select><option>d</option> <option>e</option></select>",
+    ));
+
+    assert_eq!(
+      html,
+      String::from(concat!(
+        "<p>Line one <br> line two</p>",
+        "<span>a</span> <script>b()</script> <noscript>c</noscript>",
+        "<select><option>d</option> <option>e</option></select>"
+      ))
+    );
+  }
+
+  #[test]
+  fn removes_redundant_attributes() {
+    let html = optimize(
+      r#"<form method="GET"><input type="text" name="q"><button type="submit">Go</button></form>"#,
+    );
+
+    assert_eq!(
+      html,
+      String::from(r#"<form><input name="q"><button>Go</button></form>"#)
+    );
+  }
+
+  #[test]
+  fn minifies_inline_styles_and_json() {
+    let html = optimize(concat!(
+      "<style type=\"text/css\">\n  .a {\n    color: red;\n  }\n</style>",
+      "<script type=\"importmap\">\n{\n  \"imports\": {}\n}\n</script>",
+      "<script type=\"text/x-template\"> <div> </div> </script>",
+    ));
+
+    assert_eq!(
+      html,
+      String::from(concat!(
+        "<style>.a{color:red}</style>",
+        r#"<script type="importmap">{"imports":{}}</script>"#,
+        r#"<script type="text/x-template"> <div> </div> </script>"#,
+      ))
+    );
+  }
+
+  #[test]
+  fn finds_inline_scripts() {
+    let scripts = inline_scripts(concat!(
+      "<script>\n  const a = 1;\n</script>",
+      "<script type=\"module\">import './a.js';</script>",
+      "<script src=\"b.js\"></script>",
+      "<script type=\"importmap\">{}</script>",
+    ))
+    .unwrap();
+
+    assert_eq!(
+      scripts,
+      vec![
+        InlineScript {
+          code: String::from("\n  const a = 1;\n"),
+          is_module: false,
+        },
+        InlineScript {
+          code: String::from("import './a.js';"),
+          is_module: true,
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn replaces_minified_scripts() {
+    let minified_scripts = HashMap::from([(
+      String::from("\n  const a = 1;\n"),
+      String::from("const a=1;"),
+    )]);
+
+    let html = optimize_html(
+      "<script>\n  const a = 1;\n</script><script> unknown </script>",
+      &HtmlOptimizerOptions::default(),
+      &SvgOptimizerOptions::default(),
+      &minified_scripts,
+    )
+    .unwrap();
+
+    assert_eq!(
+      html,
+      String::from("<script>const a=1;</script><script> unknown </script>")
+    );
+  }
+
+  #[test]
+  fn optimizes_inline_svg() {
+    let html = optimize(
+      r#"<body><svg viewBox="0 0 1 1"> <!-- icon --> <path d="M0.33333 0.5"/> </svg></body>"#,
+    );
+
+    assert_eq!(
+      html,
+      String::from(
+        r#"<html><head></head><body><svg viewBox="0 0 1 1"><path d="M.333 .5"></path></svg></body></html>"#
+      )
+    );
+  }
+
+  #[test]
+  fn keeps_the_document_when_disabled() {
+    let options = HtmlOptimizerOptions {
+      collapse_whitespace: false,
+      remove_comments: false,
+      remove_redundant_attributes: false,
+      minify_css: false,
+      minify_js: false,
+      minify_svg: false,
+    };
+
+    let code = "<html><head></head><body> <!-- a -->  <p>b</p>\n</body></html>";
+    let html = optimize_html(
+      code,
+      &options,
+      &SvgOptimizerOptions::default(),
+      &HashMap::new(),
+    )
+    .unwrap();
+
+    assert_eq!(html, String::from(code));
+  }
+}
@@ -0,0 +1,390 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use anyhow::{Error, anyhow};
+use async_trait::async_trait;
+use atlaspack_core::plugin::{
+  BundleGraphView, OptimizeContext, OptimizeResult, OptimizerPlugin, PluginContext,
+};
+use atlaspack_core::types::{
+  Bundle, Diagnostic, Environment, ErrorKind, FileType, OutputFormat, Target,
+};
+use serde::Deserialize;
+
+use crate::html_optimizer::{HtmlOptimizerOptions, InlineScript, inline_scripts, optimize_html};
+use crate::svg_optimizer::{SvgOptimizerOptions, optimize_svg};
+
+/// Options for the HTML and SVG optimizers
+#[derive(Clone, Debug, Default, Deserialize, Hash, PartialEq)]
+#[serde(default)]
+pub struct MarkupOptimizerConfig {
+  pub html: HtmlOptimizerOptions,
+  /// Used for SVG bundles, as well as inline `<svg>` elements within HTML
+  pub svg: SvgOptimizerOptions,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct OptimizerConfig {
+  #[serde(flatten)]
+  defaults: MarkupOptimizerConfig,
+  /// Options that replace the defaults for the named targets
+  targets: BTreeMap<String, MarkupOptimizerConfig>,
+}
+
+#[derive(Deserialize)]
+struct PackageJson {
+  #[serde(rename = "@atlaspack/optimizer-html")]
+  config: Option<OptimizerConfig>,
+}
+
+/// Minifies HTML and SVG bundles
+///
+/// Inline `<script>` elements are minified by the optimizers that are configured for JS bundles,
+/// so they are output the same way as scripts that are bundled separately.
+///
+/// Options are read from the `@atlaspack/optimizer-html` key in package.json, e.g.
+///
+/// ```json
+/// {
+///   "@atlaspack/optimizer-html": {
+///     "html": { "collapseWhitespace": false },
+///     "svg": { "floatPrecision": 2 },
+///     "targets": { "email": { "html": { "removeComments": false } } }
+///   }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AtlaspackHtmlOptimizerPlugin {
+  defaults: MarkupOptimizerConfig,
+  js_optimizers: Vec<Arc<dyn OptimizerPlugin>>,
+  targets: BTreeMap<String, MarkupOptimizerConfig>,
+}
+
+impl AtlaspackHtmlOptimizerPlugin {
+  pub fn new(
+    ctx: &PluginContext,
+    js_optimizers: Vec<Arc<dyn OptimizerPlugin>>,
+  ) -> Result<Self, Error> {
+    let config = ctx.config.load_package_json::<PackageJson>().map_or_else(
+      |err| {
+        let diagnostic = err.downcast_ref::<Diagnostic>();
+
+        if diagnostic.is_some_and(|d| d.kind != ErrorKind::NotFound) {
+          return Err(err);
+        }
+
+        Ok(OptimizerConfig::default())
+      },
+      |config| Ok(config.contents.config.unwrap_or_default()),
+    )?;
+
+    Ok(AtlaspackHtmlOptimizerPlugin {
+      defaults: config.defaults,
+      js_optimizers,
+      targets: config.targets,
+    })
+  }
+
+  /// Returns the options that apply to bundles of the given target
+  pub fn config_for_target(&self, target: &Target) -> &MarkupOptimizerConfig {
+    self.targets.get(&target.name).unwrap_or(&self.defaults)
+  }
+
+  /// Runs an inline script through the JS optimizers, as if it were a bundle of its own
+  async fn minify_script(
+    &self,
+    bundle: &Bundle,
+    bundle_graph: &Arc<BundleGraphView>,
+    script: InlineScript,
+  ) -> Result<String, Error> {
+    let bundle = Arc::new(Bundle {
+      bundle_type: FileType::Js,
+      env: Environment {
+        output_format: if script.is_module {
+          OutputFormat::EsModule
+        } else {
+          OutputFormat::Global
+        },
+        source_map: None,
+        ..bundle.env.clone()
+      },
+      ..bundle.clone()
+    });
+
+    let mut result = OptimizeResult {
+      contents: script.code.into_bytes(),
+      map: None,
+    };
+
+    for optimizer in &self.js_optimizers {
+      result = optimizer
+        .optimize(OptimizeContext {
+          bundle: bundle.clone(),
+          bundle_graph: bundle_graph.clone(),
+          contents: result.contents,
+          map: result.map,
+        })
+        .await?;
+    }
+
+    let code = String::from_utf8(result.contents)?;
+
+    // The script would be closed early, so the original code is kept instead
+    if code.to_ascii_lowercase().contains("</script") {
+      return Err(anyhow!("The minified script contains a closing script tag"));
+    }
+
+    Ok(code.trim_end().to_string())
+  }
+}
+
+#[async_trait]
+impl OptimizerPlugin for AtlaspackHtmlOptimizerPlugin {
+  async fn optimize(&self, ctx: OptimizeContext) -> Result<OptimizeResult, Error> {
+    let bundle = &ctx.bundle;
+    let is_html = match &bundle.bundle_type {
+      FileType::Html => true,
+      FileType::Other(extension) if matches!(extension.as_str(), "htm" | "xhtml") => true,
+      FileType::Other(extension) if extension == "svg" => false,
+      _ => {
+        return Ok(OptimizeResult {
+          contents: ctx.contents,
+          map: ctx.map,
+        });
+      }
+    };
+
+    if !bundle.env.should_optimize {
+      return Ok(OptimizeResult {
+        contents: ctx.contents,
+        map: ctx.map,
+      });
+    }
+
+    let code = String::from_utf8(ctx.contents).map_err(|_| {
+      anyhow!(
+        "{:?} bundles must be valid utf-8 to be optimized",
+        bundle.bundle_type
+      )
+    })?;
+
+    let config = self.config_for_target(&bundle.target);
+    let optimized = if is_html {
+      let mut minified_scripts = HashMap::new();
+
+      if config.html.minify_js {
+        for script in inline_scripts(&code)? {
+          if minified_scripts.contains_key(&script.code) {
+            continue;
+          }
+
+          let original = script.code.clone();
+          match self.minify_script(bundle, &ctx.bundle_graph, script).await {
+            Ok(minified) => {
+              minified_scripts.insert(original, minified);
+            }
+            // Scripts that fail to parse may be templates, so they are left untouched
+            Err(error) => tracing::debug!("Skipped minifying an inline script: {error}"),
+          }
+        }
+      }
+
+      optimize_html(&code, &config.html, &config.svg, &minified_scripts)?
+    } else {
+      optimize_svg(&code, &config.svg)?
+    };
+
+    Ok(OptimizeResult {
+      contents: optimized.into_bytes(),
+      map: None,
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use atlaspack_core::config_loader::ConfigLoader;
+  use atlaspack_core::plugin::{PluginLogger, PluginOptions};
+  use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  /// Replaces scripts with a comment naming the output format they were optimized for
+  #[derive(Debug)]
+  struct TestJsOptimizer;
+
+  #[async_trait]
+  impl OptimizerPlugin for TestJsOptimizer {
+    async fn optimize(&self, ctx: OptimizeContext) -> Result<OptimizeResult, Error> {
+      let code = String::from_utf8(ctx.contents)?;
+      if code.contains("invalid") {
+        return Err(anyhow!("Unexpected token"));
+      }
+
+      Ok(OptimizeResult {
+        contents: format!("/*{:?}*/{}", ctx.bundle.env.output_format, code.trim()).into_bytes(),
+        map: None,
+      })
+    }
+  }
+
+  fn create_plugin(package_json: &str) -> AtlaspackHtmlOptimizerPlugin {
+    let file_system = Arc::new(InMemoryFileSystem::default());
+    let project_root = PathBuf::from("/project-root");
+
+    file_system.write_file(&project_root.join("package.json"), package_json.to_string());
+
+    AtlaspackHtmlOptimizerPlugin::new(
+      &PluginContext {
+        config: Arc::new(ConfigLoader {
+          fs: file_system.clone(),
+          project_root: project_root.clone(),
+          search_path: project_root.clone(),
+          files: Default::default(),
+        }),
+        file_system,
+        logger: PluginLogger::default(),
+        options: Arc::new(PluginOptions {
+          project_root,
+          ..PluginOptions::default()
+        }),
+      },
+      vec![Arc::new(TestJsOptimizer)],
+    )
+    .unwrap()
+  }
+
+  fn target(name: &str) -> Target {
+    Target {
+      name: String::from(name),
+      ..Target::default()
+    }
+  }
+
+  fn optimize_context(bundle_type: FileType, should_optimize: bool, code: &str) -> OptimizeContext {
+    OptimizeContext {
+      bundle: Arc::new(Bundle {
+        bundle_behavior: None,
+        bundle_type,
+        entry_asset_ids: Vec::new(),
+        env: Environment {
+          should_optimize,
+          ..Environment::default()
+        },
+        hash_reference: String::new(),
+        id: String::from("bundle"),
+        is_placeholder: false,
+        is_splittable: None,
+        main_entry_id: None,
+        manual_shared_bundle: None,
+        name: None,
+        needs_stable_name: None,
+        pipeline: None,
+        public_id: None,
+        target: target("default"),
+      }),
+      bundle_graph: Arc::default(),
+      contents: code.as_bytes().to_vec(),
+      map: None,
+    }
+  }
+
+  async fn optimize(plugin: &AtlaspackHtmlOptimizerPlugin, ctx: OptimizeContext) -> String {
+    let result = plugin.optimize(ctx).await.unwrap();
+
+    String::from_utf8(result.contents).unwrap()
+  }
+
+  #[test]
+  fn uses_target_specific_options() {
+    let plugin = create_plugin(
+      r#"{
+        "@atlaspack/optimizer-html": {
+          "html": { "removeComments": false },
+          "targets": { "email": { "svg": { "floatPrecision": 1 } } }
+        }
+      }"#,
+    );
+
+    assert!(
+      !plugin
+        .config_for_target(&target("default"))
+        .html
+        .remove_comments
+    );
+    assert_eq!(
+      plugin.config_for_target(&target("email")),
+      &MarkupOptimizerConfig {
+        svg: SvgOptimizerOptions {
+          float_precision: Some(1),
+          ..SvgOptimizerOptions::default()
+        },
+        ..MarkupOptimizerConfig::default()
+      }
+    );
+  }
+
+  #[tokio::test]
+  async fn skips_bundles_that_are_not_optimized() {
+    let plugin = create_plugin("{}");
+    let code = "<p>  a  </p>";
+
+    assert_eq!(
+      optimize(&plugin, optimize_context(FileType::Html, false, code)).await,
+      String::from(code)
+    );
+    assert_eq!(
+      optimize(&plugin, optimize_context(FileType::Css, true, code)).await,
+      String::from(code)
+    );
+  }
+
+  #[tokio::test]
+  async fn optimizes_svg_bundles() {
+    let plugin = create_plugin("{}");
+
+    let svg = optimize(
+      &plugin,
+      optimize_context(
+        FileType::Other(String::from("svg")),
+        true,
+        r#"<svg> <path d="M0.11111 0"/> </svg>"#,
+      ),
+    )
+    .await;
+
+    assert_eq!(svg, String::from(r#"<svg><path d="M.111 0"></path></svg>"#));
+  }
+
+  #[tokio::test]
+  async fn minifies_inline_scripts_with_the_js_optimizers() {
+    let plugin = create_plugin("{}");
+
+    let html = optimize(
+      &plugin,
+      optimize_context(
+        FileType::Html,
+        true,
+        concat!(
+          "<script>\n  a();\n</script>",
+          "<script type=\"module\">\n  b();\n</script>",
+          "<script>\n  invalid\n</script>",
+        ),
+      ),
+    )
+    .await;
+
+    assert_eq!(
+      html,
+      String::from(concat!(
+        "<script>/*Global*/a();</script>",
+        "<script type=\"module\">/*EsModule*/b();</script>",
+        "<script>\n  invalid\n</script>",
+      ))
+    );
+  }
+}
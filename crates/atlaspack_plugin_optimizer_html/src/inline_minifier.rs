@@ -0,0 +1,51 @@
+use anyhow::{Error, anyhow};
+use lightningcss::stylesheet::{MinifyOptions, ParserOptions, PrinterOptions, StyleSheet};
+
+/// Minifies the contents of an inline `<style>` element
+pub fn minify_css(code: &str) -> Result<String, Error> {
+  let mut stylesheet =
+    StyleSheet::parse(code, ParserOptions::default()).map_err(|err| anyhow!("{err}"))?;
+
+  stylesheet
+    .minify(MinifyOptions::default())
+    .map_err(|err| anyhow!("{err}"))?;
+
+  let result = stylesheet
+    .to_css(PrinterOptions {
+      minify: true,
+      ..PrinterOptions::default()
+    })
+    .map_err(|err| anyhow!("{err}"))?;
+
+  Ok(result.code)
+}
+
+/// Minifies inline JSON such as import maps and structured data
+pub fn minify_json(code: &str) -> Result<String, Error> {
+  let value = serde_json::from_str::<serde_json::Value>(code)?;
+
+  Ok(serde_json::to_string(&value)?)
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn minifies_css() {
+    assert_eq!(
+      minify_css(".a {\n  color: red;\n}\n").unwrap(),
+      String::from(".a{color:red}")
+    );
+  }
+
+  #[test]
+  fn minifies_json() {
+    assert_eq!(
+      minify_json("{\n  \"imports\": { \"a\": \"./a.js\" }\n}").unwrap(),
+      String::from(r#"{"imports":{"a":"./a.js"}}"#)
+    );
+  }
+}
@@ -0,0 +1,11 @@
+mod dom;
+mod html_optimizer;
+mod html_optimizer_plugin;
+mod inline_minifier;
+mod svg_optimizer;
+
+pub use crate::html_optimizer::{
+  HtmlOptimizerOptions, InlineScript, inline_scripts, optimize_html,
+};
+pub use crate::html_optimizer_plugin::{AtlaspackHtmlOptimizerPlugin, MarkupOptimizerConfig};
+pub use crate::svg_optimizer::{SvgOptimizerOptions, optimize_svg};
@@ -0,0 +1,367 @@
+use std::sync::LazyLock;
+
+use anyhow::{Error, anyhow};
+use markup5ever::{Attribute, local_name, namespace_url, ns};
+use markup5ever_rcdom::{Handle, NodeData};
+use regex::Regex;
+use serde::Deserialize;
+
+use crate::dom::{
+  element_attrs, element_name, get_attr, is_text, parse_document, serialize_node, set_text_content,
+  text_content,
+};
+
+/// Prefixes of the elements and attributes that design tools add to exported files
+const EDITOR_PREFIXES: &[&str] = &["sodipodi", "inkscape", "sketch", "serif", "i", "x", "graph"];
+
+/// Elements that render their text content, so whitespace within them is significant
+const TEXT_ELEMENTS: &[&str] = &["text", "tspan", "textPath", "title", "desc"];
+
+/// Processing instructions that reference other assets and must be kept
+static XML_STYLESHEET_RE: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"(?s)<\?xml-stylesheet\s.*?\?>").unwrap());
+
+static NUMBER_RE: LazyLock<Regex> =
+  LazyLock::new(|| Regex::new(r"^[+-]?(?:\d+\.?\d*|\.\d+)(?:[eE][+-]?\d+)?").unwrap());
+
+#[derive(Clone, Debug, Deserialize, Hash, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SvgOptimizerOptions {
+  /// The number of decimal places kept in path data and points, or `None` to keep them all
+  pub float_precision: Option<u8>,
+  pub remove_comments: bool,
+  /// Removes `<metadata>` along with the elements and attributes added by editors
+  pub remove_metadata: bool,
+  /// Merges all `<style>` elements into the first one
+  pub merge_styles: bool,
+  /// Removes whitespace between elements, except within text elements
+  pub collapse_whitespace: bool,
+}
+
+impl Default for SvgOptimizerOptions {
+  fn default() -> Self {
+    SvgOptimizerOptions {
+      float_precision: Some(3),
+      remove_comments: true,
+      remove_metadata: true,
+      merge_styles: true,
+      collapse_whitespace: true,
+    }
+  }
+}
+
+/// Minifies a standalone SVG document
+pub fn optimize_svg(code: &str, options: &SvgOptimizerOptions) -> Result<String, Error> {
+  let processing_instructions = XML_STYLESHEET_RE
+    .find_iter(code)
+    .map(|instruction| instruction.as_str())
+    .collect::<String>();
+
+  let dom = parse_document(code.as_bytes())?;
+  let svg = find_svg(&dom.document).ok_or_else(|| anyhow!("No <svg> element found"))?;
+
+  optimize_svg_element(&svg, options);
+
+  Ok(processing_instructions + &serialize_node(&svg, true)?)
+}
+
+fn find_svg(node: &Handle) -> Option<Handle> {
+  if element_name(node).is_some_and(|name| name.ns == ns!(svg) && name.local == local_name!("svg"))
+  {
+    return Some(node.clone());
+  }
+
+  node.children.borrow().iter().find_map(find_svg)
+}
+
+/// Minifies an `<svg>` element, which may be inline within an HTML document
+pub fn optimize_svg_element(svg: &Handle, options: &SvgOptimizerOptions) {
+  optimize_node(svg, options, false);
+
+  if options.merge_styles {
+    merge_styles(svg);
+  }
+}
+
+fn optimize_node(node: &Handle, options: &SvgOptimizerOptions, preserve_whitespace: bool) {
+  node
+    .children
+    .borrow_mut()
+    .retain(|child| match &child.data {
+      NodeData::Comment { .. } => !options.remove_comments,
+      NodeData::ProcessingInstruction { .. } => !options.remove_metadata,
+      NodeData::Text { contents } => {
+        preserve_whitespace || !options.collapse_whitespace || !contents.borrow().trim().is_empty()
+      }
+      NodeData::Element { name, .. } => {
+        !options.remove_metadata || (&*name.local != "metadata" && !is_editor_name(&name.local))
+      }
+      _ => true,
+    });
+
+  if options.remove_metadata
+    && let Some(attrs) = element_attrs(node)
+  {
+    attrs.borrow_mut().retain(|attr| !is_editor_attr(attr));
+  }
+
+  if let (Some(precision), Some(attrs)) = (options.float_precision, element_attrs(node)) {
+    for attr in attrs.borrow_mut().iter_mut() {
+      if attr.name.ns.is_empty() && matches!(&*attr.name.local, "d" | "points") {
+        attr.value = round_numbers(&attr.value, precision).into();
+      }
+    }
+  }
+
+  let preserve_whitespace = preserve_whitespace
+    || element_name(node).is_some_and(|name| TEXT_ELEMENTS.contains(&&*name.local));
+
+  let children = node.children.borrow().clone();
+  for child in children.iter() {
+    optimize_node(child, options, preserve_whitespace);
+  }
+}
+
+fn is_editor_attr(attr: &Attribute) -> bool {
+  let local = &*attr.name.local;
+
+  // Namespace declarations such as xmlns:inkscape
+  if attr.name.ns == ns!(xmlns) {
+    return EDITOR_PREFIXES.contains(&local);
+  }
+
+  if let Some(prefix) = local.strip_prefix("xmlns:") {
+    return EDITOR_PREFIXES.contains(&prefix);
+  }
+
+  is_editor_name(local)
+}
+
+fn is_editor_name(name: &str) -> bool {
+  name
+    .split_once(':')
+    .is_some_and(|(prefix, _)| EDITOR_PREFIXES.contains(&prefix))
+}
+
+/// Moves the contents of every `<style>` element into the first one
+fn merge_styles(svg: &Handle) {
+  let mut styles = Vec::new();
+  collect_styles(svg, &mut styles);
+
+  let Some((first, rest)) = styles.split_first() else {
+    return;
+  };
+
+  if rest.is_empty() {
+    return;
+  }
+
+  let css = styles
+    .iter()
+    .map(text_content)
+    .collect::<Vec<String>>()
+    .join("\n");
+
+  set_text_content(first, css);
+
+  for style in rest {
+    set_text_content(style, String::new());
+  }
+
+  remove_empty_styles(svg);
+}
+
+fn collect_styles(node: &Handle, styles: &mut Vec<Handle>) {
+  for child in node.children.borrow().iter() {
+    let Some(name) = element_name(child) else {
+      continue;
+    };
+
+    if &*name.local == "foreignObject" {
+      continue;
+    }
+
+    // Styles that only apply to some media cannot be merged with the others
+    if name.local == local_name!("style") && get_attr(child, "media").is_none() {
+      styles.push(child.clone());
+    } else {
+      collect_styles(child, styles);
+    }
+  }
+}
+
+fn remove_empty_styles(node: &Handle) {
+  node.children.borrow_mut().retain(|child| {
+    let is_style = element_name(child).is_some_and(|name| name.local == local_name!("style"));
+    !is_style || !child.children.borrow().is_empty() || get_attr(child, "media").is_some()
+  });
+
+  for child in node.children.borrow().iter() {
+    if !is_text(child) {
+      remove_empty_styles(child);
+    }
+  }
+}
+
+/// Rounds the numbers within path data or a list of points
+///
+/// Arc flags are single characters that may not be separated from the following number (e.g.
+/// `a1 1 0 011 1`), so they are copied as is rather than parsed as numbers. The original data is
+/// returned if it cannot be tokenized.
+fn round_numbers(data: &str, precision: u8) -> String {
+  let mut output = String::with_capacity(data.len());
+  let mut command = ' ';
+  let mut arg_index = 0;
+  let mut index = 0;
+
+  while index < data.len() {
+    let rest = &data[index..];
+    let Some(c) = rest.chars().next() else {
+      break;
+    };
+
+    if c.is_whitespace() || c == ',' {
+      output.push(c);
+      index += c.len_utf8();
+      continue;
+    }
+
+    if c.is_ascii_alphabetic() {
+      command = c;
+      arg_index = 0;
+      output.push(c);
+      index += 1;
+      continue;
+    }
+
+    if matches!(command, 'a' | 'A') && matches!(arg_index % 7, 3 | 4) && matches!(c, '0' | '1') {
+      output.push(c);
+      arg_index += 1;
+      index += 1;
+      continue;
+    }
+
+    let Some(number) = NUMBER_RE.find(rest) else {
+      return data.to_string();
+    };
+
+    let Ok(value) = number.as_str().parse::<f64>() else {
+      return data.to_string();
+    };
+
+    let formatted = format_number(value, precision);
+    index += number.end();
+
+    // Numbers can be separated by a decimal point alone, e.g. "1.5.5" is 1.5 followed by .5
+    output.push_str(&formatted);
+    if data[index..].starts_with('.') && !formatted.contains('.') {
+      output.push(' ');
+    }
+
+    arg_index += 1;
+  }
+
+  output
+}
+
+fn format_number(value: f64, precision: u8) -> String {
+  let factor = 10f64.powi(i32::from(precision));
+  let rounded = (value * factor).round() / factor;
+
+  if rounded == 0.0 {
+    return String::from("0");
+  }
+
+  let formatted = rounded.to_string();
+  if let Some(fraction) = formatted.strip_prefix("0.") {
+    format!(".{fraction}")
+  } else if let Some(fraction) = formatted.strip_prefix("-0.") {
+    format!("-.{fraction}")
+  } else {
+    formatted
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn rounds_path_data() {
+    assert_eq!(
+      round_numbers("M10.123456,20.5 L0.0001-0.56789z", 3),
+      String::from("M10.123,20.5 L0-.568z")
+    );
+  }
+
+  #[test]
+  fn keeps_arc_flags_when_rounding() {
+    assert_eq!(
+      round_numbers("a1.00001 1 0 011.23456 1", 2),
+      String::from("a1 1 0 011.23 1")
+    );
+  }
+
+  #[test]
+  fn separates_numbers_that_lose_their_decimal_point() {
+    assert_eq!(round_numbers("M1.0001.5", 2), String::from("M1 .5"));
+  }
+
+  #[test]
+  fn optimizes_svg_documents() {
+    let svg = optimize_svg(
+      r#"<?xml version="1.0" encoding="UTF-8"?>
+        <?xml-stylesheet href="style.css"?>
+        <!-- Generated by an editor -->
+        <svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" viewBox="0 0 10 10">
+          <metadata>editor data</metadata>
+          <style>.a { fill: red; }</style>
+          <g inkscape:label="Layer 1">
+            <style>.b { fill: blue; }</style>
+            <path class="a" d="M0.12345 1.98765L5 5" />
+            <text> Some  text </text>
+          </g>
+        </svg>
+      "#,
+      &SvgOptimizerOptions::default(),
+    )
+    .unwrap();
+
+    assert_eq!(
+      svg,
+      String::from(concat!(
+        r#"<?xml-stylesheet href="style.css"?>"#,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 10 10">"#,
+        "<style>.a { fill: red; }\n.b { fill: blue; }</style>",
+        r#"<g><path class="a" d="M.123 1.988L5 5"></path><text> Some  text </text></g>"#,
+        "</svg>"
+      ))
+    );
+  }
+
+  #[test]
+  fn keeps_everything_when_disabled() {
+    let options = SvgOptimizerOptions {
+      float_precision: None,
+      remove_comments: false,
+      remove_metadata: false,
+      merge_styles: false,
+      collapse_whitespace: false,
+    };
+
+    let svg = optimize_svg(
+      r#"<svg><!-- comment --><metadata></metadata><path d="M0.12345 0"/></svg>"#,
+      &options,
+    )
+    .unwrap();
+
+    assert_eq!(
+      svg,
+      String::from(
+        r#"<svg><!-- comment --><metadata></metadata><path d="M0.12345 0"></path></svg>"#
+      )
+    );
+  }
+}
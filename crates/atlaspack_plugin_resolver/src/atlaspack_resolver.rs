@@ -516,6 +516,7 @@ mod tests {
         fs: Arc::new(fs),
         project_root: PathBuf::default(),
         search_path: PathBuf::default(),
+        files: Default::default(),
       }),
       file_system: Arc::new(InMemoryFileSystem::default()),
       logger: PluginLogger::default(),
@@ -621,6 +622,7 @@ mod tests {
         fs,
         project_root: PathBuf::default(),
         search_path: PathBuf::from("/foo"),
+        files: Default::default(),
       }),
       file_system: Arc::new(InMemoryFileSystem::default()),
       logger: PluginLogger::default(),
@@ -0,0 +1,78 @@
+// A minimal plugin executable used to test the stdio protocol. It does not use any Atlaspack
+// packages, to show that plugins only need to read and write JSON lines.
+//
+// Transforms upper case the code, resolves every specifier to `/resolved/<specifier>`, and
+// behaves badly when the code or specifier asks it to:
+//
+// - `crash` exits the process
+// - `hang` never responds
+// - `fail` returns an error
+const readline = require('readline');
+
+const cacheKey = process.argv[2] ?? null;
+const input = readline.createInterface({input: process.stdin});
+
+function respond(id, result) {
+  process.stdout.write(JSON.stringify({jsonrpc: '2.0', id, result}) + '\n');
+}
+
+function fail(id, message) {
+  process.stdout.write(
+    JSON.stringify({jsonrpc: '2.0', id, error: {code: 1, message}}) + '\n',
+  );
+}
+
+function misbehave(id, value) {
+  if (value.includes('crash')) {
+    process.exit(1);
+  }
+
+  if (value.includes('hang')) {
+    return true;
+  }
+
+  if (value.includes('fail')) {
+    fail(id, `Failed on ${value}`);
+    return true;
+  }
+
+  return false;
+}
+
+input.on('line', (line) => {
+  const {id, method, params} = JSON.parse(line);
+
+  switch (method) {
+    case 'initialize':
+      console.log('Not a response, which the host should ignore');
+      console.error(`Started ${params.kind} in ${params.options.mode} mode`);
+      respond(id, {protocolVersion: 1, cacheKey});
+      break;
+    case 'transform': {
+      const code = Buffer.from(params.code, 'base64').toString();
+      if (!misbehave(id, code)) {
+        respond(id, {
+          asset: params.asset,
+          code: Buffer.from(code.toUpperCase()).toString('base64'),
+          invalidateOnFileChange: ['/project/transformer.config.json'],
+        });
+      }
+      break;
+    }
+    case 'resolve':
+      if (!misbehave(id, params.specifier)) {
+        respond(id, {
+          invalidations: [],
+          resolution: {
+            type: 'resolved',
+            canDefer: false,
+            filePath: `/resolved/${params.specifier}`,
+            sideEffects: true,
+          },
+        });
+      }
+      break;
+    default:
+      fail(id, `Unknown method ${method}`);
+  }
+});
@@ -1,6 +1,10 @@
 #[cfg(feature = "nodejs")]
 pub mod nodejs;
+#[cfg(feature = "stdio")]
+pub mod stdio;
 pub mod testing;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 use std::sync::Arc;
 
@@ -34,15 +38,50 @@ pub trait RpcFactory: Send + Sync {
 #[automock]
 #[async_trait]
 pub trait RpcWorker: Send + Sync {
+  fn create_compressor(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn CompressorPlugin>>;
+  fn create_namer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn NamerPlugin>>;
+  fn create_optimizer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn OptimizerPlugin>>;
+  fn create_packager(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn PackagerPlugin>>;
+  fn create_reporter(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ReporterPlugin>>;
   fn create_resolver(
     &self,
     ctx: &PluginContext,
     plugin: &PluginNode,
   ) -> anyhow::Result<Arc<dyn ResolverPlugin>>;
+  fn create_runtime(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn RuntimePlugin>>;
   async fn create_transformer(
     &self,
     ctx: &PluginContext,
     plugin: &PluginNode,
     package_manager: PackageManagerRef,
   ) -> anyhow::Result<Arc<dyn TransformerPlugin>>;
+  fn create_validator(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ValidatorPlugin>>;
 }
@@ -18,8 +18,15 @@ pub struct RpcPluginOptions {
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum LoadPluginKind {
+  Compressor,
+  Namer,
+  Optimizer,
+  Packager,
+  Reporter,
   Resolver,
+  Runtime,
   Transformer,
+  Validator,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
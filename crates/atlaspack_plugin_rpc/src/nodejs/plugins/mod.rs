@@ -1,6 +1,24 @@
-mod load_plugin;
+pub(super) mod load_plugin;
+mod nodejs_rpc_bundle_graph;
+mod nodejs_rpc_compressor;
+mod nodejs_rpc_namer;
+mod nodejs_rpc_optimizer;
+mod nodejs_rpc_packager;
+mod nodejs_rpc_plugin_loader;
+mod nodejs_rpc_reporter;
 mod nodejs_rpc_resolver;
+mod nodejs_rpc_runtime;
 mod nodejs_rpc_transformer;
+mod nodejs_rpc_validator;
 
+pub use self::nodejs_rpc_bundle_graph::*;
+pub use self::nodejs_rpc_compressor::*;
+pub use self::nodejs_rpc_namer::*;
+pub use self::nodejs_rpc_optimizer::*;
+pub use self::nodejs_rpc_packager::*;
+pub use self::nodejs_rpc_plugin_loader::*;
+pub use self::nodejs_rpc_reporter::*;
 pub use self::nodejs_rpc_resolver::*;
+pub use self::nodejs_rpc_runtime::*;
 pub use self::nodejs_rpc_transformer::*;
+pub use self::nodejs_rpc_validator::*;
@@ -0,0 +1,68 @@
+use std::collections::{HashMap, HashSet};
+
+use atlaspack_core::plugin::BundleGraphView;
+use atlaspack_core::types::{Asset, Bundle, Dependency};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+
+use super::RpcContentsResult;
+
+/// The bundle graph view sent to packagers and optimizers, where contents are sent as base64
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBundleGraph {
+  pub assets: Vec<RpcBundleAsset>,
+  pub bundles: Vec<Bundle>,
+  pub dependencies: HashMap<String, Vec<Dependency>>,
+  pub inline_bundles: HashMap<String, RpcContentsResult>,
+  pub public_ids: HashMap<String, String>,
+  pub referenced_bundle_ids: Vec<String>,
+  pub resolved_assets: HashMap<String, String>,
+  pub skipped_dependencies: HashSet<String>,
+}
+
+/// The asset is serialized without its code, which is sent as base64
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBundleAsset {
+  pub asset: Asset,
+  pub contents: String,
+}
+
+impl From<&BundleGraphView> for RpcBundleGraph {
+  fn from(view: &BundleGraphView) -> Self {
+    RpcBundleGraph {
+      assets: view
+        .assets
+        .iter()
+        .map(|asset| RpcBundleAsset {
+          asset: asset.clone(),
+          contents: view
+            .asset_code
+            .get(&asset.id)
+            .map(|code| STANDARD.encode(code))
+            .unwrap_or_default(),
+        })
+        .collect(),
+      bundles: view.bundles.clone(),
+      dependencies: view.dependencies.clone(),
+      inline_bundles: view
+        .inline_bundles
+        .iter()
+        .map(|(id, packaged)| {
+          let contents = RpcContentsResult {
+            contents: STANDARD.encode(&packaged.contents),
+            map: packaged.map.clone(),
+          };
+
+          (id.clone(), contents)
+        })
+        .collect(),
+      public_ids: view.public_ids.clone(),
+      referenced_bundle_ids: view.referenced_bundle_ids.clone(),
+      resolved_assets: view.resolved_assets.clone(),
+      skipped_dependencies: view.skipped_dependencies.clone(),
+    }
+  }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+use std::fmt::Debug;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use atlaspack_core::plugin::{CompressedFile, CompressorPlugin};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+
+use super::nodejs_rpc_plugin_loader::NodejsRpcPluginLoader;
+
+pub struct NodejsRpcCompressorPlugin {
+  loader: NodejsRpcPluginLoader,
+}
+
+impl NodejsRpcCompressorPlugin {
+  pub fn new(loader: NodejsRpcPluginLoader) -> Self {
+    Self { loader }
+  }
+}
+
+impl Debug for NodejsRpcCompressorPlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "NodejsRpcCompressorPlugin({})", self.loader)
+  }
+}
+
+#[async_trait]
+impl CompressorPlugin for NodejsRpcCompressorPlugin {
+  fn id(&self) -> u64 {
+    self.loader.id()
+  }
+
+  async fn compress(&self, contents: &[u8]) -> anyhow::Result<Option<CompressedFile>> {
+    let result = self
+      .loader
+      .workers()
+      .await?
+      .next_worker()
+      .run_compressor_compress_fn
+      .call_serde::<_, Option<RpcCompressedFile>>(RunCompressorCompress {
+        key: self.loader.key(),
+        contents: STANDARD.encode(contents),
+      })
+      .await?;
+
+    let Some(result) = result else {
+      return Ok(None);
+    };
+
+    Ok(Some(CompressedFile {
+      contents: STANDARD
+        .decode(result.contents)
+        .with_context(|| format!("Compressor {} returned invalid contents", self.loader))?,
+      extension: result.extension,
+    }))
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunCompressorCompress {
+  pub key: String,
+  pub contents: String,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcCompressedFile {
+  pub contents: String,
+  pub extension: Option<String>,
+}
@@ -0,0 +1,53 @@
+use std::fmt;
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use atlaspack_core::plugin::{NameContext, NamerPlugin};
+use atlaspack_core::types::Bundle;
+use serde::{Deserialize, Serialize};
+
+use super::nodejs_rpc_plugin_loader::NodejsRpcPluginLoader;
+
+pub struct NodejsRpcNamerPlugin {
+  loader: NodejsRpcPluginLoader,
+}
+
+impl NodejsRpcNamerPlugin {
+  pub fn new(loader: NodejsRpcPluginLoader) -> Self {
+    Self { loader }
+  }
+}
+
+impl Debug for NodejsRpcNamerPlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "NodejsRpcNamerPlugin({})", self.loader)
+  }
+}
+
+#[async_trait]
+impl NamerPlugin for NodejsRpcNamerPlugin {
+  fn id(&self) -> u64 {
+    self.loader.id()
+  }
+
+  async fn name(&self, ctx: NameContext) -> anyhow::Result<Option<String>> {
+    self
+      .loader
+      .workers()
+      .await?
+      .next_worker()
+      .run_namer_name_fn
+      .call_serde(RunNamerName {
+        key: self.loader.key(),
+        bundle: (*ctx.bundle).clone(),
+      })
+      .await
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunNamerName {
+  pub key: String,
+  pub bundle: Bundle,
+}
@@ -0,0 +1,71 @@
+use std::fmt;
+use std::fmt::Debug;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use atlaspack_core::plugin::{OptimizeContext, OptimizeResult, OptimizerPlugin};
+use atlaspack_core::types::Bundle;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+
+use super::RpcBundleGraph;
+use super::nodejs_rpc_packager::RpcContentsResult;
+use super::nodejs_rpc_plugin_loader::NodejsRpcPluginLoader;
+
+pub struct NodejsRpcOptimizerPlugin {
+  loader: NodejsRpcPluginLoader,
+}
+
+impl NodejsRpcOptimizerPlugin {
+  pub fn new(loader: NodejsRpcPluginLoader) -> Self {
+    Self { loader }
+  }
+}
+
+impl Debug for NodejsRpcOptimizerPlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "NodejsRpcOptimizerPlugin({})", self.loader)
+  }
+}
+
+#[async_trait]
+impl OptimizerPlugin for NodejsRpcOptimizerPlugin {
+  fn id(&self) -> u64 {
+    self.loader.id()
+  }
+
+  async fn optimize(&self, ctx: OptimizeContext) -> anyhow::Result<OptimizeResult> {
+    let result = self
+      .loader
+      .workers()
+      .await?
+      .next_worker()
+      .run_optimizer_optimize_fn
+      .call_serde::<_, RpcContentsResult>(RunOptimizerOptimize {
+        key: self.loader.key(),
+        bundle: (*ctx.bundle).clone(),
+        bundle_graph: RpcBundleGraph::from(&*ctx.bundle_graph),
+        contents: STANDARD.encode(&ctx.contents),
+        map: ctx.map,
+      })
+      .await?;
+
+    Ok(OptimizeResult {
+      contents: STANDARD
+        .decode(result.contents)
+        .with_context(|| format!("Optimizer {} returned invalid contents", self.loader))?,
+      map: result.map,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunOptimizerOptimize {
+  pub key: String,
+  pub bundle: Bundle,
+  pub bundle_graph: RpcBundleGraph,
+  pub contents: String,
+  pub map: Option<String>,
+}
@@ -0,0 +1,74 @@
+use std::fmt;
+use std::fmt::Debug;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use atlaspack_core::plugin::{PackageContext, PackagedBundle, PackagerPlugin};
+use atlaspack_core::types::Bundle;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::{Deserialize, Serialize};
+
+use super::RpcBundleGraph;
+use super::nodejs_rpc_plugin_loader::NodejsRpcPluginLoader;
+
+pub struct NodejsRpcPackagerPlugin {
+  loader: NodejsRpcPluginLoader,
+}
+
+impl NodejsRpcPackagerPlugin {
+  pub fn new(loader: NodejsRpcPluginLoader) -> Self {
+    Self { loader }
+  }
+}
+
+impl Debug for NodejsRpcPackagerPlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "NodejsRpcPackagerPlugin({})", self.loader)
+  }
+}
+
+#[async_trait]
+impl PackagerPlugin for NodejsRpcPackagerPlugin {
+  fn id(&self) -> u64 {
+    self.loader.id()
+  }
+
+  async fn package(&self, ctx: PackageContext) -> anyhow::Result<PackagedBundle> {
+    let result = self
+      .loader
+      .workers()
+      .await?
+      .next_worker()
+      .run_packager_package_fn
+      .call_serde::<_, RpcContentsResult>(RunPackagerPackage {
+        key: self.loader.key(),
+        bundle: (*ctx.bundle).clone(),
+        bundle_graph: RpcBundleGraph::from(&*ctx.bundle_graph),
+      })
+      .await?;
+
+    Ok(PackagedBundle {
+      contents: STANDARD
+        .decode(result.contents)
+        .with_context(|| format!("Packager {} returned invalid contents", self.loader))?,
+      map: result.map,
+    })
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunPackagerPackage {
+  pub key: String,
+  pub bundle: Bundle,
+  pub bundle_graph: RpcBundleGraph,
+}
+
+/// Contents are sent between Rust and the workers as base64
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcContentsResult {
+  pub contents: String,
+  pub map: Option<String>,
+}
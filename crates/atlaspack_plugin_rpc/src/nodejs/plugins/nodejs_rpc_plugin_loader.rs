@@ -0,0 +1,96 @@
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use atlaspack_config::PluginNode;
+use atlaspack_core::hash::IdentifierHasher;
+use atlaspack_core::plugin::PluginContext;
+use tokio::sync::OnceCell;
+
+use super::super::rpc::nodejs_rpc_worker_farm::NodeJsWorkerCollection;
+use super::load_plugin::{LoadPluginKind, LoadPluginOptions, RpcPluginOptions};
+
+/// Loads a plugin into every Nodejs worker the first time it is used
+///
+/// Plugins for the packaging phases are created synchronously, so they are loaded lazily in the
+/// same way as resolvers.
+pub struct NodejsRpcPluginLoader {
+  kind: LoadPluginKind,
+  nodejs_workers: Arc<NodeJsWorkerCollection>,
+  plugin_node: PluginNode,
+  rpc_options: RpcPluginOptions,
+  started: OnceCell<()>,
+}
+
+impl NodejsRpcPluginLoader {
+  pub fn new(
+    kind: LoadPluginKind,
+    nodejs_workers: Arc<NodeJsWorkerCollection>,
+    ctx: &PluginContext,
+    plugin_node: &PluginNode,
+  ) -> Self {
+    Self {
+      kind,
+      nodejs_workers,
+      plugin_node: plugin_node.clone(),
+      rpc_options: RpcPluginOptions {
+        hmr_options: ctx.options.hmr_options.clone(),
+        project_root: ctx.options.project_root.clone(),
+        mode: ctx.options.mode.clone(),
+        feature_flags: ctx.options.feature_flags.clone(),
+      },
+      started: OnceCell::new(),
+    }
+  }
+
+  /// The key that the plugin is registered with in the workers
+  pub fn key(&self) -> String {
+    self.plugin_node.package_name.clone()
+  }
+
+  pub fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.plugin_node.hash(&mut hasher);
+    hasher.finish()
+  }
+
+  /// Loads the plugin if needed, then returns the workers that can run it
+  pub async fn workers(&self) -> anyhow::Result<&NodeJsWorkerCollection> {
+    self
+      .started
+      .get_or_try_init::<anyhow::Error, _, _>(|| async move {
+        let opts = LoadPluginOptions {
+          kind: self.kind.clone(),
+          specifier: self.plugin_node.package_name.clone(),
+          resolve_from: self.plugin_node.resolve_from.as_ref().clone(),
+          options: self.rpc_options.clone(),
+        };
+
+        let mut set = Vec::new();
+
+        for worker in self.nodejs_workers.all_workers() {
+          let opts = opts.clone();
+          set.push(tokio::spawn(async move {
+            worker
+              .load_plugin_fn
+              .call_serde::<LoadPluginOptions, ()>(opts)
+              .await
+          }));
+        }
+
+        while let Some(res) = set.pop() {
+          res.await??;
+        }
+
+        Ok(())
+      })
+      .await?;
+
+    Ok(&self.nodejs_workers)
+  }
+}
+
+impl std::fmt::Display for NodejsRpcPluginLoader {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}", self.plugin_node.package_name)
+  }
+}
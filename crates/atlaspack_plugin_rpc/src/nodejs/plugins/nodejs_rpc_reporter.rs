@@ -0,0 +1,52 @@
+use std::fmt;
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use atlaspack_core::plugin::{ReporterEvent, ReporterPlugin};
+use serde::Serialize;
+
+use super::nodejs_rpc_plugin_loader::NodejsRpcPluginLoader;
+
+pub struct NodejsRpcReporterPlugin {
+  loader: NodejsRpcPluginLoader,
+}
+
+impl NodejsRpcReporterPlugin {
+  pub fn new(loader: NodejsRpcPluginLoader) -> Self {
+    Self { loader }
+  }
+}
+
+impl Debug for NodejsRpcReporterPlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "NodejsRpcReporterPlugin({})", self.loader)
+  }
+}
+
+#[async_trait]
+impl ReporterPlugin for NodejsRpcReporterPlugin {
+  fn id(&self) -> u64 {
+    self.loader.id()
+  }
+
+  async fn report(&self, event: &ReporterEvent) -> anyhow::Result<()> {
+    self
+      .loader
+      .workers()
+      .await?
+      .next_worker()
+      .run_reporter_report_fn
+      .call_serde(RunReporterReport {
+        key: self.loader.key(),
+        event: event.clone(),
+      })
+      .await
+  }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunReporterReport {
+  pub key: String,
+  pub event: ReporterEvent,
+}
@@ -0,0 +1,53 @@
+use std::fmt;
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use atlaspack_core::plugin::{RuntimeAsset, RuntimeContext, RuntimePlugin};
+use atlaspack_core::types::Bundle;
+use serde::{Deserialize, Serialize};
+
+use super::nodejs_rpc_plugin_loader::NodejsRpcPluginLoader;
+
+pub struct NodejsRpcRuntimePlugin {
+  loader: NodejsRpcPluginLoader,
+}
+
+impl NodejsRpcRuntimePlugin {
+  pub fn new(loader: NodejsRpcPluginLoader) -> Self {
+    Self { loader }
+  }
+}
+
+impl Debug for NodejsRpcRuntimePlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "NodejsRpcRuntimePlugin({})", self.loader)
+  }
+}
+
+#[async_trait]
+impl RuntimePlugin for NodejsRpcRuntimePlugin {
+  fn id(&self) -> u64 {
+    self.loader.id()
+  }
+
+  async fn apply(&self, ctx: RuntimeContext) -> anyhow::Result<Vec<RuntimeAsset>> {
+    self
+      .loader
+      .workers()
+      .await?
+      .next_worker()
+      .run_runtime_apply_fn
+      .call_serde(RunRuntimeApply {
+        key: self.loader.key(),
+        bundle: (*ctx.bundle).clone(),
+      })
+      .await
+  }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRuntimeApply {
+  pub key: String,
+  pub bundle: Bundle,
+}
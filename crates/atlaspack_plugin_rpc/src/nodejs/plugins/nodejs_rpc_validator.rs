@@ -0,0 +1,58 @@
+use std::fmt;
+use std::fmt::Debug;
+
+use async_trait::async_trait;
+use atlaspack_core::plugin::{ValidateContext, ValidateResult, ValidatorPlugin};
+use atlaspack_core::types::Asset;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use serde::Serialize;
+
+use super::nodejs_rpc_plugin_loader::NodejsRpcPluginLoader;
+
+pub struct NodejsRpcValidatorPlugin {
+  loader: NodejsRpcPluginLoader,
+}
+
+impl NodejsRpcValidatorPlugin {
+  pub fn new(loader: NodejsRpcPluginLoader) -> Self {
+    Self { loader }
+  }
+}
+
+impl Debug for NodejsRpcValidatorPlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "NodejsRpcValidatorPlugin({})", self.loader)
+  }
+}
+
+#[async_trait]
+impl ValidatorPlugin for NodejsRpcValidatorPlugin {
+  fn id(&self) -> u64 {
+    self.loader.id()
+  }
+
+  async fn validate(&self, ctx: ValidateContext) -> anyhow::Result<ValidateResult> {
+    self
+      .loader
+      .workers()
+      .await?
+      .next_worker()
+      .run_validator_validate_fn
+      .call_serde(RunValidatorValidate {
+        key: self.loader.key(),
+        contents: STANDARD.encode(ctx.asset.code.bytes()),
+        asset: (*ctx.asset).clone(),
+      })
+      .await
+  }
+}
+
+/// The asset is serialized without its code, which is sent as base64
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunValidatorValidate {
+  pub key: String,
+  pub asset: Asset,
+  pub contents: String,
+}
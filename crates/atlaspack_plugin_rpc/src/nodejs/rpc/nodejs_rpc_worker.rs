@@ -4,7 +4,14 @@ use napi::JsObject;
 /// NodejsWorker is the connection to a single JavaScript worker thread
 pub struct NodejsWorker {
   pub load_plugin_fn: JsCallable,
+  pub run_compressor_compress_fn: JsCallable,
+  pub run_namer_name_fn: JsCallable,
+  pub run_optimizer_optimize_fn: JsCallable,
+  pub run_packager_package_fn: JsCallable,
+  pub run_reporter_report_fn: JsCallable,
   pub run_resolver_resolve_fn: JsCallable,
+  pub run_runtime_apply_fn: JsCallable,
+  pub run_validator_validate_fn: JsCallable,
   pub transformer_register_fn: JsCallable,
 }
 
@@ -14,7 +21,14 @@ impl NodejsWorker {
 
     Ok(Self {
       load_plugin_fn: bind("loadPlugin")?,
+      run_compressor_compress_fn: bind("runCompressorCompress")?,
+      run_namer_name_fn: bind("runNamerName")?,
+      run_optimizer_optimize_fn: bind("runOptimizerOptimize")?,
+      run_packager_package_fn: bind("runPackagerPackage")?,
+      run_reporter_report_fn: bind("runReporterReport")?,
       run_resolver_resolve_fn: bind("runResolverResolve")?,
+      run_runtime_apply_fn: bind("runRuntimeApply")?,
+      run_validator_validate_fn: bind("runValidatorValidate")?,
       transformer_register_fn: bind("runTransformerTransform")?,
     })
   }
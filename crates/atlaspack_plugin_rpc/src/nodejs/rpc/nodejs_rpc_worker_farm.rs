@@ -9,6 +9,7 @@ use atlaspack_core::plugin::*;
 use atlaspack_package_manager::PackageManagerRef;
 
 use super::super::super::RpcWorker;
+use super::super::plugins::load_plugin::LoadPluginKind;
 use super::super::plugins::*;
 use super::nodejs_rpc_worker::NodejsWorker;
 
@@ -36,10 +37,79 @@ impl NodejsWorkerFarm {
       }),
     }
   }
+
+  fn loader(
+    &self,
+    kind: LoadPluginKind,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> NodejsRpcPluginLoader {
+    NodejsRpcPluginLoader::new(kind, self.workers.clone(), ctx, plugin)
+  }
 }
 
 #[async_trait]
 impl RpcWorker for NodejsWorkerFarm {
+  fn create_compressor(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn CompressorPlugin>> {
+    Ok(Arc::new(NodejsRpcCompressorPlugin::new(self.loader(
+      LoadPluginKind::Compressor,
+      ctx,
+      plugin,
+    ))))
+  }
+
+  fn create_namer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn NamerPlugin>> {
+    Ok(Arc::new(NodejsRpcNamerPlugin::new(self.loader(
+      LoadPluginKind::Namer,
+      ctx,
+      plugin,
+    ))))
+  }
+
+  fn create_optimizer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn OptimizerPlugin>> {
+    Ok(Arc::new(NodejsRpcOptimizerPlugin::new(self.loader(
+      LoadPluginKind::Optimizer,
+      ctx,
+      plugin,
+    ))))
+  }
+
+  fn create_packager(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn PackagerPlugin>> {
+    Ok(Arc::new(NodejsRpcPackagerPlugin::new(self.loader(
+      LoadPluginKind::Packager,
+      ctx,
+      plugin,
+    ))))
+  }
+
+  fn create_reporter(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ReporterPlugin>> {
+    Ok(Arc::new(NodejsRpcReporterPlugin::new(self.loader(
+      LoadPluginKind::Reporter,
+      ctx,
+      plugin,
+    ))))
+  }
+
   fn create_resolver(
     &self,
     ctx: &PluginContext,
@@ -52,6 +122,18 @@ impl RpcWorker for NodejsWorkerFarm {
     )?))
   }
 
+  fn create_runtime(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn RuntimePlugin>> {
+    Ok(Arc::new(NodejsRpcRuntimePlugin::new(self.loader(
+      LoadPluginKind::Runtime,
+      ctx,
+      plugin,
+    ))))
+  }
+
   async fn create_transformer(
     &self,
     ctx: &PluginContext,
@@ -62,6 +144,18 @@ impl RpcWorker for NodejsWorkerFarm {
       NodejsRpcTransformerPlugin::new(self.workers.clone(), ctx, plugin, package_manager).await?,
     ))
   }
+
+  fn create_validator(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ValidatorPlugin>> {
+    Ok(Arc::new(NodejsRpcValidatorPlugin::new(self.loader(
+      LoadPluginKind::Validator,
+      ctx,
+      plugin,
+    ))))
+  }
 }
 
 pub struct NodeJsWorkerCollection {
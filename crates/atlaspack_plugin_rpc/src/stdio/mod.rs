@@ -0,0 +1,35 @@
+//! Runs resolvers and transformers as executables that communicate over stdio
+//!
+//! This allows plugins to be written in any language. Each plugin runs in a pool of processes,
+//! which receive one request at a time.
+//!
+//! ## Protocol
+//!
+//! Messages are [JSON-RPC 2.0](https://www.jsonrpc.org/specification) requests and responses,
+//! each written as a single line of JSON. Requests are written to stdin, and responses are read
+//! from stdout. Lines on stdout that are not responses are ignored, and stderr is logged.
+//!
+//! * `initialize` is sent once when a process starts, with `{ protocolVersion, kind, options }`,
+//!   where `kind` is `"resolver"` or `"transformer"`. The plugin responds with
+//!   `{ protocolVersion, cacheKey }`. Transformers are only cached when they return a cache key.
+//! * `transform` is sent with `{ asset, code }`, where `asset` is the serialized `Asset` and
+//!   `code` is base64. The plugin responds with `{ asset, code, dependencies?,
+//!   invalidateOnFileChange? }`.
+//! * `resolve` is sent with `{ dependency, specifier, pipeline }`, and the plugin responds with
+//!   `{ invalidations, resolution }`.
+//!
+//! Plugins report failures with a JSON-RPC error response, which fails the request. A process
+//! that exits, writes an invalid response, or does not respond within the timeout is killed and
+//! restarted by the next request. Plugins should exit when stdin is closed.
+mod protocol;
+mod stdio_process;
+mod stdio_resolver;
+mod stdio_rpc_factory;
+mod stdio_transformer;
+mod stdio_worker_pool;
+
+pub use self::protocol::{EXEC_PREFIX, PROTOCOL_VERSION};
+pub use self::stdio_resolver::*;
+pub use self::stdio_rpc_factory::*;
+pub use self::stdio_transformer::*;
+pub use self::stdio_worker_pool::*;
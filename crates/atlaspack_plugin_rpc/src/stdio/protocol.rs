@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+
+use atlaspack_config::PluginNode;
+use atlaspack_core::types::{Asset, BuildMode, Dependency};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The version of the protocol implemented by the host
+///
+/// Plugins return the version they implement from `initialize`, and are rejected when it does
+/// not match. The version is incremented for any change that is not backwards compatible.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Plugins that run as executables are declared in `.atlaspackrc` as `exec:<command> [args...]`
+pub const EXEC_PREFIX: &str = "exec:";
+
+/// The command that starts a plugin executable
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub struct StdioCommand {
+  pub program: PathBuf,
+  pub args: Vec<String>,
+  /// The directory of the config that declared the plugin, which relative arguments are
+  /// resolved from
+  pub cwd: PathBuf,
+}
+
+impl StdioCommand {
+  /// Parses the command of a plugin declared with [`EXEC_PREFIX`]
+  ///
+  /// Arguments are separated by whitespace. A relative program path, such as `./plugin`, is
+  /// resolved from the config directory, whereas a bare name, such as `python3`, is looked up in
+  /// the `PATH`.
+  pub fn parse(plugin: &PluginNode) -> Option<Self> {
+    let command = plugin.package_name.strip_prefix(EXEC_PREFIX)?;
+    let mut parts = command.split_whitespace();
+    let program = parts.next()?;
+
+    let cwd = plugin
+      .resolve_from
+      .parent()
+      .map(Path::to_path_buf)
+      .unwrap_or_default();
+
+    let program = if program.starts_with('.') {
+      cwd.join(program)
+    } else {
+      PathBuf::from(program)
+    };
+
+    Some(StdioCommand {
+      program,
+      args: parts.map(String::from).collect(),
+      cwd,
+    })
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Request<'a, P> {
+  pub jsonrpc: &'static str,
+  pub id: u64,
+  pub method: &'a str,
+  pub params: &'a P,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Response {
+  pub id: Option<u64>,
+  #[serde(default)]
+  pub result: Value,
+  pub error: Option<ResponseError>,
+}
+
+/// An error returned by the plugin, which does not affect the plugin process
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct ResponseError {
+  pub code: i64,
+  pub message: String,
+  #[serde(default)]
+  pub data: Option<Value>,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PluginKind {
+  Resolver,
+  Transformer,
+}
+
+/// Options passed to `initialize`
+#[derive(Clone, Debug, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StdioPluginOptions {
+  pub mode: BuildMode,
+  pub project_root: PathBuf,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeParams {
+  pub protocol_version: u32,
+  pub kind: PluginKind,
+  pub options: StdioPluginOptions,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeResult {
+  pub protocol_version: u32,
+  /// The plugin is uncachable unless it returns a cache key, which must change whenever its
+  /// output may change
+  #[serde(default)]
+  pub cache_key: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveParams<'a> {
+  pub dependency: &'a Dependency,
+  pub pipeline: Option<&'a str>,
+  pub specifier: &'a str,
+}
+
+/// The asset is serialized without its code or source map, and the code is sent as base64
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformParams<'a> {
+  pub asset: &'a Asset,
+  pub code: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformOutput {
+  pub asset: Asset,
+  pub code: String,
+  #[serde(default)]
+  pub dependencies: Vec<Dependency>,
+  #[serde(default)]
+  pub invalidate_on_file_change: Vec<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn plugin_node(package_name: &str) -> PluginNode {
+    PluginNode {
+      package_name: String::from(package_name),
+      resolve_from: Arc::new(PathBuf::from("/project/.atlaspackrc")),
+    }
+  }
+
+  #[test]
+  fn parses_exec_commands() {
+    assert_eq!(
+      StdioCommand::parse(&plugin_node("exec:python3 tools/transformer.py --fast")),
+      Some(StdioCommand {
+        program: PathBuf::from("python3"),
+        args: vec![String::from("tools/transformer.py"), String::from("--fast")],
+        cwd: PathBuf::from("/project"),
+      })
+    );
+
+    assert_eq!(
+      StdioCommand::parse(&plugin_node("exec:./bin/transformer")),
+      Some(StdioCommand {
+        program: PathBuf::from("/project/./bin/transformer"),
+        args: Vec::new(),
+        cwd: PathBuf::from("/project"),
+      })
+    );
+  }
+
+  #[test]
+  fn ignores_other_plugins() {
+    assert_eq!(
+      StdioCommand::parse(&plugin_node("@atlaspack/transformer-js")),
+      None
+    );
+    assert_eq!(StdioCommand::parse(&plugin_node("exec:")), None);
+  }
+}
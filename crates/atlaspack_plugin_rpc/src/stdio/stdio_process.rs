@@ -0,0 +1,139 @@
+use std::process::Stdio;
+
+use anyhow::{Context, anyhow};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+
+use super::protocol::*;
+
+/// A running plugin executable, which handles one request at a time
+pub struct StdioProcess {
+  // The process is killed when this is dropped
+  _child: Child,
+  next_id: u64,
+  stdin: ChildStdin,
+  stdout: Lines<BufReader<ChildStdout>>,
+}
+
+impl StdioProcess {
+  /// Starts the executable, then initializes the plugin
+  pub async fn spawn(
+    command: &StdioCommand,
+    params: &InitializeParams,
+  ) -> anyhow::Result<(Self, InitializeResult)> {
+    let mut child = Command::new(&command.program)
+      .args(&command.args)
+      .current_dir(&command.cwd)
+      .stdin(Stdio::piped())
+      .stdout(Stdio::piped())
+      .stderr(Stdio::piped())
+      .kill_on_drop(true)
+      .spawn()
+      .with_context(|| format!("Failed to start plugin {}", command.program.display()))?;
+
+    let stdin = child.stdin.take().context("Missing plugin stdin")?;
+    let stdout = child.stdout.take().context("Missing plugin stdout")?;
+    let stderr = child.stderr.take().context("Missing plugin stderr")?;
+
+    // Plugins log to stderr, as stdout is reserved for responses
+    let program = command.program.display().to_string();
+    tokio::spawn(async move {
+      let mut lines = BufReader::new(stderr).lines();
+      while let Ok(Some(line)) = lines.next_line().await {
+        tracing::info!(plugin = %program, "{line}");
+      }
+    });
+
+    let mut process = StdioProcess {
+      _child: child,
+      next_id: 0,
+      stdin,
+      stdout: BufReader::new(stdout).lines(),
+    };
+
+    let result = process
+      .request::<_, InitializeResult>("initialize", params)
+      .await?
+      .map_err(|error| anyhow!("Failed to initialize plugin: {}", error.message))?;
+
+    if result.protocol_version != PROTOCOL_VERSION {
+      return Err(anyhow!(
+        "Plugin {} uses protocol version {}, but version {PROTOCOL_VERSION} is required",
+        command.program.display(),
+        result.protocol_version
+      ));
+    }
+
+    Ok((process, result))
+  }
+
+  /// Sends a request and waits for its response
+  ///
+  /// The outer result fails when the process cannot be communicated with, in which case it should
+  /// be restarted, and the inner result fails when the plugin returns an error.
+  pub async fn request<P: Serialize, R: DeserializeOwned>(
+    &mut self,
+    method: &str,
+    params: &P,
+  ) -> anyhow::Result<Result<R, ResponseError>> {
+    self.next_id += 1;
+
+    let id = self.next_id;
+    let mut message = serde_json::to_vec(&Request {
+      jsonrpc: "2.0",
+      id,
+      method,
+      params,
+    })?;
+
+    message.push(b'\n');
+
+    self
+      .stdin
+      .write_all(&message)
+      .await
+      .context("Failed to write to plugin")?;
+    self
+      .stdin
+      .flush()
+      .await
+      .context("Failed to write to plugin")?;
+
+    loop {
+      let line = self
+        .stdout
+        .next_line()
+        .await
+        .context("Failed to read from plugin")?
+        .ok_or_else(|| anyhow!("Plugin exited before responding to {method}"))?;
+
+      if line.trim().is_empty() {
+        continue;
+      }
+
+      // Plugins may print unrelated output, which is logged rather than failing the request
+      let Ok(response) = serde_json::from_str::<Response>(&line) else {
+        tracing::warn!("Ignoring plugin output that is not a response: {line}");
+        continue;
+      };
+
+      if response.id != Some(id) {
+        tracing::warn!(
+          "Ignoring plugin response with unexpected id {:?}",
+          response.id
+        );
+        continue;
+      }
+
+      return Ok(match response.error {
+        Some(error) => Err(error),
+        None => Ok(
+          serde_json::from_value(response.result)
+            .with_context(|| format!("Plugin returned an invalid {method} result"))?,
+        ),
+      });
+    }
+  }
+}
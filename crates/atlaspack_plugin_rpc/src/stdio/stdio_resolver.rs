@@ -0,0 +1,73 @@
+use std::fmt;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use atlaspack_config::PluginNode;
+use atlaspack_core::hash::IdentifierHasher;
+use atlaspack_core::plugin::{ResolveContext, Resolved, ResolverPlugin};
+use tokio::sync::OnceCell;
+
+use super::protocol::*;
+use super::stdio_worker_pool::StdioWorkerPool;
+
+/// Runs a resolver in a pool of plugin executables
+///
+/// Resolvers are created synchronously, so the first process is started by the first resolve.
+pub struct StdioResolverPlugin {
+  plugin_node: PluginNode,
+  pool: StdioWorkerPool,
+  started: OnceCell<()>,
+}
+
+impl Debug for StdioResolverPlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "StdioResolverPlugin({})", self.plugin_node.package_name)
+  }
+}
+
+impl StdioResolverPlugin {
+  pub fn new(pool: StdioWorkerPool, plugin_node: &PluginNode) -> Self {
+    Self {
+      plugin_node: plugin_node.clone(),
+      pool,
+      started: OnceCell::new(),
+    }
+  }
+}
+
+#[async_trait]
+impl ResolverPlugin for StdioResolverPlugin {
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.plugin_node.hash(&mut hasher);
+    hasher.finish()
+  }
+
+  async fn resolve(&self, ctx: ResolveContext) -> Result<Resolved, anyhow::Error> {
+    self
+      .started
+      .get_or_try_init(|| async { self.pool.initialize().await.map(|_| ()) })
+      .await
+      .with_context(|| format!("Failed to load plugin {}", self.plugin_node.package_name))?;
+
+    self
+      .pool
+      .request(
+        "resolve",
+        &ResolveParams {
+          dependency: &ctx.dependency,
+          pipeline: ctx.pipeline.as_deref(),
+          specifier: &ctx.specifier,
+        },
+      )
+      .await
+      .with_context(|| {
+        format!(
+          "Failed to resolve '{}' with resolver '{}'",
+          ctx.specifier, self.plugin_node.package_name
+        )
+      })
+  }
+}
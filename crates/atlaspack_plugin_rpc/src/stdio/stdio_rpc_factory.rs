@@ -0,0 +1,439 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use atlaspack_config::PluginNode;
+use atlaspack_core::diagnostic_error;
+use atlaspack_core::plugin::*;
+use atlaspack_package_manager::PackageManagerRef;
+
+use super::protocol::*;
+use super::stdio_resolver::StdioResolverPlugin;
+use super::stdio_transformer::StdioTransformerPlugin;
+use super::stdio_worker_pool::StdioWorkerPool;
+use crate::{RpcFactory, RpcFactoryRef, RpcWorker, RpcWorkerRef};
+
+#[derive(Clone, Debug)]
+pub struct StdioRpcOptions {
+  /// The maximum number of processes started for each plugin
+  pub workers: usize,
+  /// How long a plugin can take to respond to a request before it is restarted
+  pub timeout: Duration,
+}
+
+impl Default for StdioRpcOptions {
+  fn default() -> Self {
+    Self {
+      workers: std::thread::available_parallelism()
+        .map(|workers| workers.get())
+        .unwrap_or(1)
+        .min(4),
+      timeout: Duration::from_secs(60),
+    }
+  }
+}
+
+/// StdioRpcFactory runs plugins as executables that communicate over stdio
+///
+/// Plugins are declared in `.atlaspackrc` as `exec:<command>`, and can be written in any
+/// language that implements the [protocol](super). All other plugins are created by the
+/// fallback factory, e.g. Nodejs.
+pub struct StdioRpcFactory {
+  fallback: RpcFactoryRef,
+  options: StdioRpcOptions,
+}
+
+impl StdioRpcFactory {
+  pub fn new(fallback: RpcFactoryRef, options: StdioRpcOptions) -> Self {
+    Self { fallback, options }
+  }
+}
+
+impl RpcFactory for StdioRpcFactory {
+  fn start(&self) -> anyhow::Result<Arc<dyn RpcWorker>> {
+    Ok(Arc::new(StdioRpcWorker {
+      fallback: self.fallback.start()?,
+      options: self.options.clone(),
+    }))
+  }
+}
+
+pub struct StdioRpcWorker {
+  fallback: RpcWorkerRef,
+  options: StdioRpcOptions,
+}
+
+impl StdioRpcWorker {
+  fn create_pool(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+    command: StdioCommand,
+    kind: PluginKind,
+  ) -> anyhow::Result<StdioWorkerPool> {
+    if !ctx.file_system.is_dir(&command.cwd) {
+      return Err(diagnostic_error!(
+        "Plugin {} is declared in {}, which is not in a directory",
+        plugin.package_name,
+        plugin.resolve_from.display()
+      ));
+    }
+
+    Ok(StdioWorkerPool::new(
+      command,
+      InitializeParams {
+        protocol_version: PROTOCOL_VERSION,
+        kind,
+        options: StdioPluginOptions {
+          mode: ctx.options.mode.clone(),
+          project_root: ctx.options.project_root.clone(),
+        },
+      },
+      self.options.workers,
+      self.options.timeout,
+    ))
+  }
+
+  /// The protocol only supports resolvers and transformers so far, so other phases fail rather
+  /// than being sent to the fallback, which cannot run executables
+  fn unsupported_phase(plugin: &PluginNode, phase: &str) -> anyhow::Error {
+    diagnostic_error!(
+      "Plugin {} (configured in {}) cannot be used as a {phase}, as executable plugins only support resolvers and transformers",
+      plugin.package_name,
+      plugin.resolve_from.display(),
+    )
+  }
+}
+
+#[async_trait]
+impl RpcWorker for StdioRpcWorker {
+  fn create_resolver(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ResolverPlugin>> {
+    let Some(command) = StdioCommand::parse(plugin) else {
+      return self.fallback.create_resolver(ctx, plugin);
+    };
+
+    let pool = self.create_pool(ctx, plugin, command, PluginKind::Resolver)?;
+
+    Ok(Arc::new(StdioResolverPlugin::new(pool, plugin)))
+  }
+
+  async fn create_transformer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+    package_manager: PackageManagerRef,
+  ) -> anyhow::Result<Arc<dyn TransformerPlugin>> {
+    let Some(command) = StdioCommand::parse(plugin) else {
+      return self
+        .fallback
+        .create_transformer(ctx, plugin, package_manager)
+        .await;
+    };
+
+    let pool = self.create_pool(ctx, plugin, command, PluginKind::Transformer)?;
+
+    Ok(Arc::new(StdioTransformerPlugin::new(pool, plugin).await?))
+  }
+
+  fn create_compressor(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn CompressorPlugin>> {
+    if StdioCommand::parse(plugin).is_some() {
+      return Err(Self::unsupported_phase(plugin, "compressor"));
+    }
+
+    self.fallback.create_compressor(ctx, plugin)
+  }
+
+  fn create_namer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn NamerPlugin>> {
+    if StdioCommand::parse(plugin).is_some() {
+      return Err(Self::unsupported_phase(plugin, "namer"));
+    }
+
+    self.fallback.create_namer(ctx, plugin)
+  }
+
+  fn create_optimizer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn OptimizerPlugin>> {
+    if StdioCommand::parse(plugin).is_some() {
+      return Err(Self::unsupported_phase(plugin, "optimizer"));
+    }
+
+    self.fallback.create_optimizer(ctx, plugin)
+  }
+
+  fn create_packager(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn PackagerPlugin>> {
+    if StdioCommand::parse(plugin).is_some() {
+      return Err(Self::unsupported_phase(plugin, "packager"));
+    }
+
+    self.fallback.create_packager(ctx, plugin)
+  }
+
+  fn create_reporter(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ReporterPlugin>> {
+    if StdioCommand::parse(plugin).is_some() {
+      return Err(Self::unsupported_phase(plugin, "reporter"));
+    }
+
+    self.fallback.create_reporter(ctx, plugin)
+  }
+
+  fn create_runtime(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn RuntimePlugin>> {
+    if StdioCommand::parse(plugin).is_some() {
+      return Err(Self::unsupported_phase(plugin, "runtime"));
+    }
+
+    self.fallback.create_runtime(ctx, plugin)
+  }
+
+  fn create_validator(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ValidatorPlugin>> {
+    if StdioCommand::parse(plugin).is_some() {
+      return Err(Self::unsupported_phase(plugin, "validator"));
+    }
+
+    self.fallback.create_validator(ctx, plugin)
+  }
+}
+
+/// Conformance tests for the protocol, using the fixture plugin in `fixtures/stdio_plugin.js`
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use atlaspack_core::config_loader::ConfigLoader;
+  use atlaspack_core::plugin::{
+    CacheKey, CacheStatus, PluginLogger, PluginOptions, Resolution, ResolveContext,
+    ResolvedResolution,
+  };
+  use atlaspack_core::types::{Asset, Code, Dependency, FileType};
+  use atlaspack_filesystem::os_file_system::OsFileSystem;
+  use atlaspack_package_manager::MockPackageManager;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::{MockRpcFactory, MockRpcWorker};
+
+  fn plugin_context() -> PluginContext {
+    let fs = Arc::new(OsFileSystem);
+    let project_root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    PluginContext {
+      config: Arc::new(ConfigLoader {
+        fs: fs.clone(),
+        project_root: project_root.clone(),
+        search_path: project_root.clone(),
+        files: Default::default(),
+      }),
+      file_system: fs,
+      logger: PluginLogger::default(),
+      options: Arc::new(PluginOptions {
+        project_root,
+        ..PluginOptions::default()
+      }),
+    }
+  }
+
+  fn plugin_node(args: &str) -> PluginNode {
+    PluginNode {
+      package_name: format!("exec:node ./fixtures/stdio_plugin.js {args}"),
+      resolve_from: Arc::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(".atlaspackrc")),
+    }
+  }
+
+  fn worker(workers: usize) -> Arc<dyn RpcWorker> {
+    let mut fallback = MockRpcFactory::new();
+
+    fallback
+      .expect_start()
+      .returning(|| Ok(Arc::new(MockRpcWorker::new())));
+
+    StdioRpcFactory::new(
+      Arc::new(fallback),
+      StdioRpcOptions {
+        workers,
+        timeout: Duration::from_secs(5),
+      },
+    )
+    .start()
+    .unwrap()
+  }
+
+  async fn transformer(workers: usize, args: &str) -> Arc<dyn TransformerPlugin> {
+    worker(workers)
+      .create_transformer(
+        &plugin_context(),
+        &plugin_node(args),
+        Arc::new(MockPackageManager::new()),
+      )
+      .await
+      .unwrap()
+  }
+
+  fn asset(code: &str) -> Asset {
+    Asset {
+      id: String::from("a"),
+      code: Code::from(code),
+      file_path: PathBuf::from("/project/index.txt"),
+      file_type: FileType::Other(String::from("txt")),
+      ..Asset::default()
+    }
+  }
+
+  async fn transform(
+    transformer: &Arc<dyn TransformerPlugin>,
+    code: &str,
+  ) -> Result<String, String> {
+    transformer
+      .transform(asset(code))
+      .await
+      .map(|result| String::from(result.asset.code.as_str().unwrap()))
+      .map_err(|error| format!("{error:#}"))
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn transforms_assets() {
+    let transformer = transformer(1, "v1").await;
+    let result = transformer.transform(asset("hello")).await.unwrap();
+
+    assert_eq!(result.asset, asset("HELLO"));
+    assert_eq!(result.dependencies, Vec::<Dependency>::new());
+    assert_eq!(
+      result.invalidate_on_file_change,
+      vec![PathBuf::from("/project/transformer.config.json")]
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn uses_the_plugin_cache_key() {
+    let cached = transformer(1, "v1").await;
+    let uncached = transformer(1, "").await;
+
+    assert!(matches!(cached.cache_key().as_ref(), CacheStatus::Hash(_)));
+    assert_eq!(uncached.cache_key().as_ref(), &CacheStatus::Uncachable);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn resolves_dependencies() {
+    let resolver = worker(1)
+      .create_resolver(&plugin_context(), &plugin_node(""))
+      .unwrap();
+
+    let resolved = resolver
+      .resolve(ResolveContext {
+        dependency: Arc::new(Dependency::default()),
+        pipeline: None,
+        specifier: String::from("a.js"),
+      })
+      .await
+      .unwrap();
+
+    assert_eq!(
+      resolved.resolution,
+      Resolution::Resolved(ResolvedResolution {
+        file_path: PathBuf::from("/resolved/a.js"),
+        side_effects: true,
+        ..ResolvedResolution::default()
+      })
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn returns_plugin_errors() {
+    let transformer = transformer(1, "v1").await;
+
+    assert_eq!(
+      transform(&transformer, "fail").await,
+      Err(String::from(
+        "Failed to transform asset '/project/index.txt' with transformer 'exec:node ./fixtures/stdio_plugin.js v1': Failed on fail"
+      ))
+    );
+    assert_eq!(transform(&transformer, "ok").await, Ok(String::from("OK")));
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn restarts_plugins_that_crash() {
+    let transformer = transformer(1, "v1").await;
+
+    let error = transform(&transformer, "crash").await.unwrap_err();
+    assert!(error.contains("crashed and will be restarted"), "{error}");
+
+    assert_eq!(transform(&transformer, "ok").await, Ok(String::from("OK")));
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn restarts_plugins_that_time_out() {
+    let mut fallback = MockRpcFactory::new();
+    fallback
+      .expect_start()
+      .returning(|| Ok(Arc::new(MockRpcWorker::new())));
+
+    let transformer = StdioRpcFactory::new(
+      Arc::new(fallback),
+      StdioRpcOptions {
+        workers: 1,
+        timeout: Duration::from_millis(500),
+      },
+    )
+    .start()
+    .unwrap()
+    .create_transformer(
+      &plugin_context(),
+      &plugin_node("v1"),
+      Arc::new(MockPackageManager::new()),
+    )
+    .await
+    .unwrap();
+
+    let error = transform(&transformer, "hang").await.unwrap_err();
+    assert!(
+      error.contains("did not respond to transform within 500ms"),
+      "{error}"
+    );
+
+    assert_eq!(transform(&transformer, "ok").await, Ok(String::from("OK")));
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn runs_requests_across_workers() {
+    let transformer = transformer(4, "v1").await;
+    let handles = (0..16)
+      .map(|index| {
+        let transformer = transformer.clone();
+        tokio::spawn(async move { transform(&transformer, &format!("asset {index}")).await })
+      })
+      .collect::<Vec<_>>();
+
+    for (index, handle) in handles.into_iter().enumerate() {
+      assert_eq!(handle.await.unwrap(), Ok(format!("ASSET {index}")));
+    }
+  }
+}
@@ -0,0 +1,117 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use atlaspack_config::PluginNode;
+use atlaspack_core::cache_key;
+use atlaspack_core::hash::IdentifierHasher;
+use atlaspack_core::plugin::{CacheKey, CacheStatus, TransformResult, TransformerPlugin};
+use atlaspack_core::types::{Asset, Code};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+
+use super::protocol::*;
+use super::stdio_worker_pool::StdioWorkerPool;
+
+/// Runs a transformer in a pool of plugin executables
+///
+/// The asset is sent without its source map, and the map of the input asset is kept, as the
+/// protocol does not support source maps yet.
+pub struct StdioTransformerPlugin {
+  cache_key: CacheStatus,
+  plugin_node: PluginNode,
+  pool: StdioWorkerPool,
+}
+
+impl Debug for StdioTransformerPlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "StdioTransformerPlugin({})",
+      self.plugin_node.package_name
+    )
+  }
+}
+
+impl StdioTransformerPlugin {
+  pub async fn new(pool: StdioWorkerPool, plugin_node: &PluginNode) -> anyhow::Result<Self> {
+    let setup = pool
+      .initialize()
+      .await
+      .with_context(|| format!("Failed to load plugin {}", plugin_node.package_name))?;
+
+    let cache_key = match setup.cache_key {
+      Some(key) => cache_key!(plugin_node, key),
+      None => {
+        tracing::info!(
+          "Transformer {} did not return a cache key, so it is uncachable",
+          plugin_node.package_name
+        );
+        CacheStatus::Uncachable
+      }
+    };
+
+    Ok(Self {
+      cache_key,
+      plugin_node: plugin_node.clone(),
+      pool,
+    })
+  }
+}
+
+impl CacheKey for StdioTransformerPlugin {
+  fn cache_key(&self) -> Cow<'_, CacheStatus> {
+    Cow::Borrowed(&self.cache_key)
+  }
+}
+
+#[async_trait]
+impl TransformerPlugin for StdioTransformerPlugin {
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.plugin_node.hash(&mut hasher);
+    hasher.finish()
+  }
+
+  async fn transform(&self, asset: Asset) -> Result<TransformResult, anyhow::Error> {
+    let params = TransformParams {
+      asset: &asset,
+      code: STANDARD.encode(asset.code.bytes()),
+    };
+
+    let output = self
+      .pool
+      .request::<_, TransformOutput>("transform", &params)
+      .await
+      .and_then(|output| {
+        let code = STANDARD
+          .decode(&output.code)
+          .context("Plugin returned code that is not valid base64")?;
+        Ok((output, code))
+      })
+      .with_context(|| {
+        format!(
+          "Failed to transform asset '{}' with transformer '{}'",
+          asset.file_path.display(),
+          self.plugin_node.package_name
+        )
+      });
+
+    let (output, code) = output?;
+
+    Ok(TransformResult {
+      asset: Asset {
+        code: Code::new(code),
+        map: asset.map,
+        stats: asset.stats,
+        ..output.asset
+      },
+      dependencies: output.dependencies,
+      invalidate_on_file_change: output.invalidate_on_file_change,
+      ..Default::default()
+    })
+  }
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use anyhow::anyhow;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tokio::sync::Mutex;
+
+use super::protocol::*;
+use super::stdio_process::StdioProcess;
+
+/// A pool of processes running the same plugin executable
+///
+/// Processes are started when they are first needed, and restarted after they crash or time out.
+pub struct StdioWorkerPool {
+  command: StdioCommand,
+  params: InitializeParams,
+  timeout: Duration,
+  workers: Vec<Arc<StdioWorker>>,
+}
+
+struct StdioWorker {
+  active_tasks: AtomicUsize,
+  process: Mutex<Option<StdioProcess>>,
+}
+
+/// A guard that tracks a worker's busyness and automatically decrements
+/// the active task count when dropped
+struct BusyWorkerGuard {
+  worker: Arc<StdioWorker>,
+}
+
+impl Drop for BusyWorkerGuard {
+  fn drop(&mut self) {
+    self.worker.active_tasks.fetch_sub(1, Ordering::Relaxed);
+  }
+}
+
+impl StdioWorkerPool {
+  pub fn new(
+    command: StdioCommand,
+    params: InitializeParams,
+    workers: usize,
+    timeout: Duration,
+  ) -> Self {
+    Self {
+      command,
+      params,
+      timeout,
+      workers: (0..workers.max(1))
+        .map(|_| {
+          Arc::new(StdioWorker {
+            active_tasks: AtomicUsize::new(0),
+            process: Mutex::new(None),
+          })
+        })
+        .collect(),
+    }
+  }
+
+  /// Starts the first process, returning the result of initializing the plugin
+  pub async fn initialize(&self) -> anyhow::Result<InitializeResult> {
+    let mut process = self.workers[0].process.lock().await;
+    let (started, result) = self.spawn().await?;
+
+    *process = Some(started);
+
+    Ok(result)
+  }
+
+  /// Sends a request to the least busy process
+  ///
+  /// A process that crashes or times out is killed, and restarted by the next request it
+  /// receives, so that one bad input does not fail the rest of the build.
+  pub async fn request<P: Serialize, R: DeserializeOwned>(
+    &self,
+    method: &str,
+    params: &P,
+  ) -> anyhow::Result<R> {
+    let worker = self.next_worker();
+    let mut process = worker.worker.process.lock().await;
+
+    let running = match process.take() {
+      Some(running) => process.insert(running),
+      None => process.insert(self.spawn().await?.0),
+    };
+
+    let result = tokio::time::timeout(self.timeout, running.request::<P, R>(method, params)).await;
+
+    match result {
+      Ok(Ok(Ok(result))) => Ok(result),
+      Ok(Ok(Err(error))) => Err(anyhow!(error.message)),
+      Ok(Err(error)) => {
+        *process = None;
+        Err(error.context(format!(
+          "Plugin {} crashed and will be restarted",
+          self.command.program.display()
+        )))
+      }
+      Err(_) => {
+        *process = None;
+        Err(anyhow!(
+          "Plugin {} did not respond to {method} within {}ms and will be restarted",
+          self.command.program.display(),
+          self.timeout.as_millis()
+        ))
+      }
+    }
+  }
+
+  async fn spawn(&self) -> anyhow::Result<(StdioProcess, InitializeResult)> {
+    tokio::time::timeout(
+      self.timeout,
+      StdioProcess::spawn(&self.command, &self.params),
+    )
+    .await
+    .map_err(|_| {
+      anyhow!(
+        "Plugin {} did not initialize within {}ms",
+        self.command.program.display(),
+        self.timeout.as_millis()
+      )
+    })?
+  }
+
+  fn next_worker(&self) -> BusyWorkerGuard {
+    // Find the worker with the least number of active tasks
+    let worker = self
+      .workers
+      .iter()
+      .min_by_key(|worker| worker.active_tasks.load(Ordering::Relaxed))
+      .unwrap_or(&self.workers[0]);
+
+    worker.active_tasks.fetch_add(1, Ordering::Relaxed);
+
+    BusyWorkerGuard {
+      worker: worker.clone(),
+    }
+  }
+}
@@ -41,6 +41,62 @@ pub mod testing {
     ) -> anyhow::Result<Arc<dyn TransformerPlugin>> {
       Ok(Arc::new(TestingRpcPlugin("RpcTransformerPlugin".into())))
     }
+
+    fn create_compressor(
+      &self,
+      _ctx: &PluginContext,
+      _plugin: &PluginNode,
+    ) -> anyhow::Result<Arc<dyn CompressorPlugin>> {
+      Ok(Arc::new(TestingRpcPlugin("RpcCompressorPlugin".into())))
+    }
+
+    fn create_namer(
+      &self,
+      _ctx: &PluginContext,
+      _plugin: &PluginNode,
+    ) -> anyhow::Result<Arc<dyn NamerPlugin>> {
+      Ok(Arc::new(TestingRpcPlugin("RpcNamerPlugin".into())))
+    }
+
+    fn create_optimizer(
+      &self,
+      _ctx: &PluginContext,
+      _plugin: &PluginNode,
+    ) -> anyhow::Result<Arc<dyn OptimizerPlugin>> {
+      Ok(Arc::new(TestingRpcPlugin("RpcOptimizerPlugin".into())))
+    }
+
+    fn create_packager(
+      &self,
+      _ctx: &PluginContext,
+      _plugin: &PluginNode,
+    ) -> anyhow::Result<Arc<dyn PackagerPlugin>> {
+      Ok(Arc::new(TestingRpcPlugin("RpcPackagerPlugin".into())))
+    }
+
+    fn create_reporter(
+      &self,
+      _ctx: &PluginContext,
+      _plugin: &PluginNode,
+    ) -> anyhow::Result<Arc<dyn ReporterPlugin>> {
+      Ok(Arc::new(TestingRpcPlugin("RpcReporterPlugin".into())))
+    }
+
+    fn create_runtime(
+      &self,
+      _ctx: &PluginContext,
+      _plugin: &PluginNode,
+    ) -> anyhow::Result<Arc<dyn RuntimePlugin>> {
+      Ok(Arc::new(TestingRpcPlugin("RpcRuntimePlugin".into())))
+    }
+
+    fn create_validator(
+      &self,
+      _ctx: &PluginContext,
+      _plugin: &PluginNode,
+    ) -> anyhow::Result<Arc<dyn ValidatorPlugin>> {
+      Ok(Arc::new(TestingRpcPlugin("RpcValidatorPlugin".into())))
+    }
   }
 
   pub struct TestingRpcPlugin(String);
@@ -78,4 +134,59 @@ pub mod testing {
       })
     }
   }
+
+  #[async_trait]
+  impl CompressorPlugin for TestingRpcPlugin {
+    async fn compress(&self, _contents: &[u8]) -> anyhow::Result<Option<CompressedFile>> {
+      Ok(None)
+    }
+  }
+
+  #[async_trait]
+  impl NamerPlugin for TestingRpcPlugin {
+    async fn name(&self, _ctx: NameContext) -> anyhow::Result<Option<String>> {
+      Ok(None)
+    }
+  }
+
+  #[async_trait]
+  impl OptimizerPlugin for TestingRpcPlugin {
+    async fn optimize(&self, ctx: OptimizeContext) -> anyhow::Result<OptimizeResult> {
+      Ok(OptimizeResult {
+        contents: ctx.contents,
+        map: ctx.map,
+      })
+    }
+  }
+
+  #[async_trait]
+  impl PackagerPlugin for TestingRpcPlugin {
+    async fn package(&self, _ctx: PackageContext) -> anyhow::Result<PackagedBundle> {
+      Ok(PackagedBundle {
+        contents: Vec::new(),
+        map: None,
+      })
+    }
+  }
+
+  #[async_trait]
+  impl ReporterPlugin for TestingRpcPlugin {
+    async fn report(&self, _event: &ReporterEvent) -> anyhow::Result<()> {
+      Ok(())
+    }
+  }
+
+  #[async_trait]
+  impl RuntimePlugin for TestingRpcPlugin {
+    async fn apply(&self, _ctx: RuntimeContext) -> anyhow::Result<Vec<RuntimeAsset>> {
+      Ok(Vec::new())
+    }
+  }
+
+  #[async_trait]
+  impl ValidatorPlugin for TestingRpcPlugin {
+    async fn validate(&self, _ctx: ValidateContext) -> anyhow::Result<ValidateResult> {
+      Ok(ValidateResult::default())
+    }
+  }
 }
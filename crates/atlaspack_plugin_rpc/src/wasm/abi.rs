@@ -0,0 +1,188 @@
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use atlaspack_core::types::{Asset, BuildMode, Dependency};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// The version of the ABI implemented by the host
+///
+/// Modules export their version from `atlaspack_abi_version`, and are rejected when it does not
+/// match. The version is incremented for any change that is not backwards compatible.
+pub const ABI_VERSION: i32 = 1;
+
+/// `() -> i32`
+pub const ABI_VERSION_EXPORT: &str = "atlaspack_abi_version";
+
+/// `(len: i32) -> i32`, allocates `len` bytes of guest memory and returns the pointer
+pub const ALLOC_EXPORT: &str = "atlaspack_alloc";
+
+/// `() -> i64`, optional, returns a packed pointer to a UTF-8 cache key
+///
+/// An empty cache key marks the plugin as uncachable. Modules without this export are cached by
+/// the hash of the module.
+pub const CACHE_KEY_EXPORT: &str = "atlaspack_cache_key";
+
+/// `(ptr: i32, len: i32) -> i64`, receives a [`ResolveInput`] message and returns a
+/// [`GuestResult`] of [`Resolved`](atlaspack_core::plugin::Resolved)
+pub const RESOLVE_EXPORT: &str = "atlaspack_resolve";
+
+/// `(ptr: i32, len: i32) -> i64`, receives a [`TransformInput`] message followed by the asset
+/// code, and returns a [`GuestResult`] of [`TransformOutput`] followed by the transformed code
+pub const TRANSFORM_EXPORT: &str = "atlaspack_transform";
+
+/// The module that host functions are imported from
+pub const HOST_MODULE: &str = "atlaspack";
+
+/// `(path_ptr: i32, path_len: i32) -> i64`, returns a packed pointer to the file contents, or -1
+/// if the file cannot be read
+pub const HOST_READ_FILE: &str = "read_file";
+
+/// `(path_ptr: i32, path_len: i32) -> i32`, returns 1 if the path is a file and 0 otherwise
+pub const HOST_IS_FILE: &str = "is_file";
+
+/// `(level: i32, ptr: i32, len: i32)`, logs a UTF-8 message where the level is one of 0 (error),
+/// 1 (warn), 2 (info) or 3 (verbose)
+pub const HOST_LOG: &str = "log";
+
+/// Packs a pointer and length into the i64 returned by exports and host functions
+pub fn pack(ptr: u32, len: u32) -> i64 {
+  ((u64::from(ptr) << 32) | u64::from(len)) as i64
+}
+
+/// Unpacks a pointer and length returned by an export
+pub fn unpack(value: i64) -> (usize, usize) {
+  let value = value as u64;
+  ((value >> 32) as usize, (value & 0xFFFF_FFFF) as usize)
+}
+
+/// Encodes a message as a little-endian u32 header length, the JSON header, then the body
+pub fn encode_message(header: &impl Serialize, body: &[u8]) -> anyhow::Result<Vec<u8>> {
+  let header = serde_json::to_vec(header)?;
+  let header_len = u32::try_from(header.len())?;
+
+  let mut message = Vec::with_capacity(4 + header.len() + body.len());
+  message.extend_from_slice(&header_len.to_le_bytes());
+  message.extend_from_slice(&header);
+  message.extend_from_slice(body);
+
+  Ok(message)
+}
+
+/// Decodes a message into its JSON header and body
+pub fn decode_message<T: DeserializeOwned>(message: &[u8]) -> anyhow::Result<(T, &[u8])> {
+  let (header_len, rest) = message
+    .split_first_chunk::<4>()
+    .ok_or_else(|| anyhow!("Message is missing the header length"))?;
+
+  let header_len = u32::from_le_bytes(*header_len) as usize;
+  if rest.len() < header_len {
+    return Err(anyhow!(
+      "Message header length {header_len} exceeds the message length {}",
+      rest.len()
+    ));
+  }
+
+  let (header, body) = rest.split_at(header_len);
+
+  Ok((serde_json::from_slice(header)?, body))
+}
+
+/// Options passed to every plugin call
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WasmPluginOptions {
+  pub mode: BuildMode,
+  pub project_root: PathBuf,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolveInput<'a> {
+  pub dependency: &'a Dependency,
+  pub options: &'a WasmPluginOptions,
+  pub pipeline: Option<&'a str>,
+  pub specifier: &'a str,
+}
+
+/// The asset is serialized without its code or source map, as the code is sent as the message body
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformInput<'a> {
+  pub asset: &'a Asset,
+  pub options: &'a WasmPluginOptions,
+}
+
+/// Extra fields such as `options` are ignored, so a module can return its input unchanged
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformOutput {
+  pub asset: Asset,
+  #[serde(default)]
+  pub dependencies: Vec<Dependency>,
+  #[serde(default)]
+  pub invalidate_on_file_change: Vec<PathBuf>,
+}
+
+/// The result of a plugin call, which is either `{ "error": "message" }` or the output
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum GuestResult<T> {
+  Error { error: String },
+  Ok(T),
+}
+
+impl<T> GuestResult<T> {
+  pub fn into_result(self) -> anyhow::Result<T> {
+    match self {
+      GuestResult::Error { error } => Err(anyhow!(error)),
+      GuestResult::Ok(output) => Ok(output),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use pretty_assertions::assert_eq;
+  use serde_json::{Value, json};
+
+  use super::*;
+
+  #[test]
+  fn packs_pointers() {
+    assert_eq!(unpack(pack(1024, 12)), (1024, 12));
+    assert_eq!(
+      unpack(pack(u32::MAX, u32::MAX)),
+      (u32::MAX as usize, u32::MAX as usize)
+    );
+  }
+
+  #[test]
+  fn encodes_messages() {
+    let message = encode_message(&json!({ "a": 1 }), b"body").unwrap();
+
+    assert_eq!(&message[..4], &7u32.to_le_bytes());
+
+    let (header, body) = decode_message::<Value>(&message).unwrap();
+
+    assert_eq!(header, json!({ "a": 1 }));
+    assert_eq!(body, b"body");
+  }
+
+  #[test]
+  fn rejects_truncated_messages() {
+    assert!(decode_message::<Value>(&[1, 0]).is_err());
+    assert!(decode_message::<Value>(&[10, 0, 0, 0, b'{', b'}']).is_err());
+  }
+
+  #[test]
+  fn reads_guest_errors() {
+    let result =
+      serde_json::from_value::<GuestResult<Value>>(json!({ "error": "Failed" })).unwrap();
+
+    assert_eq!(
+      result.into_result().map_err(|err| err.to_string()),
+      Err(String::from("Failed"))
+    );
+  }
+}
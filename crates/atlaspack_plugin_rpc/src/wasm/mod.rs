@@ -0,0 +1,21 @@
+//! Runs resolvers and transformers that are compiled to WebAssembly
+//!
+//! Modules communicate with the host through a small ABI, versioned by [`abi::ABI_VERSION`].
+//! Inputs and outputs are written to the linear memory of the module as messages made of a JSON
+//! header, containing the serialized `Asset` or `Dependency`, followed by the raw asset code.
+//!
+//! Modules cannot access the file system or network directly. Files are read through the
+//! `atlaspack.read_file` import, which is restricted to the project root, after resolving
+//! symlinks, and goes through the `FileSystem` of the build, so that every file read invalidates
+//! the result. Each call is limited in memory and fuel, so a misbehaving module fails the build
+//! rather than hanging it.
+pub mod abi;
+mod wasm_module;
+mod wasm_resolver;
+mod wasm_rpc_factory;
+mod wasm_transformer;
+
+pub use self::wasm_module::*;
+pub use self::wasm_resolver::*;
+pub use self::wasm_rpc_factory::*;
+pub use self::wasm_transformer::*;
@@ -0,0 +1,429 @@
+use std::hash::{Hash, Hasher};
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, anyhow};
+use atlaspack_config::PluginNode;
+use atlaspack_core::diagnostic_error;
+use atlaspack_core::hash::IdentifierHasher;
+use atlaspack_core::plugin::{CacheStatus, PluginContext};
+use atlaspack_filesystem::FileSystemRef;
+use wasmtime::{
+  Caller, Engine, Extern, Instance, InstancePre, Linker, Memory, Module, Store, StoreLimits,
+  StoreLimitsBuilder, Trap,
+};
+
+use super::abi::*;
+
+/// The maximum amount of linear memory a module can use during a single call
+const MAX_MEMORY_BYTES: usize = 512 * 1024 * 1024;
+
+/// The default amount of fuel a module can consume during a single call
+///
+/// Fuel is consumed roughly once per instruction, so a module that is stuck in a loop fails the
+/// build after a few seconds rather than hanging it.
+pub const DEFAULT_MAX_FUEL: u64 = 10_000_000_000;
+
+/// A compiled plugin module, ready to be instantiated for each call
+///
+/// Every call runs in a fresh instance, so modules do not need to free memory between calls and
+/// cannot keep state between them. This keeps calls independent, which is required for their
+/// results to be cached.
+pub struct WasmModule {
+  canonical_root: PathBuf,
+  file_system: FileSystemRef,
+  instance_pre: InstancePre<HostState>,
+  max_fuel: u64,
+  module_hash: u64,
+  path: PathBuf,
+  project_root: PathBuf,
+}
+
+impl std::fmt::Debug for WasmModule {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "WasmModule({})", self.path.display())
+  }
+}
+
+/// The result of a call into a module
+pub struct WasmCallOutput {
+  pub output: Vec<u8>,
+  /// Files that were read by the module, which invalidate the result when they change
+  pub read_files: Vec<PathBuf>,
+}
+
+struct HostState {
+  /// The project root with symlinks resolved, which files must be within once their symlinks
+  /// are resolved too
+  canonical_root: PathBuf,
+  file_system: FileSystemRef,
+  limits: StoreLimits,
+  plugin: String,
+  project_root: PathBuf,
+  read_files: Vec<PathBuf>,
+}
+
+impl WasmModule {
+  /// Loads the module declared by `plugin`, relative to the config file that declared it
+  pub fn load(
+    engine: &Engine,
+    max_fuel: u64,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Self> {
+    let path = plugin
+      .resolve_from
+      .parent()
+      .map(|dir| dir.join(&plugin.package_name))
+      .unwrap_or_else(|| PathBuf::from(&plugin.package_name));
+
+    let bytes = ctx.file_system.read(&path).map_err(|error| {
+      diagnostic_error!(
+        "Failed to read WebAssembly plugin {} from {}: {error}",
+        plugin.package_name,
+        path.display()
+      )
+    })?;
+
+    let module = Module::new(engine, &bytes)
+      .with_context(|| format!("Failed to compile WebAssembly plugin {}", path.display()))?;
+
+    let mut hasher = IdentifierHasher::new();
+    bytes.hash(&mut hasher);
+
+    let project_root = ctx.options.project_root.clone();
+    let canonical_root = ctx
+      .file_system
+      .canonicalize_base(&project_root)
+      .unwrap_or_else(|_| project_root.clone());
+
+    let wasm_module = WasmModule {
+      canonical_root,
+      file_system: ctx.file_system.clone(),
+      instance_pre: create_linker(engine)?.instantiate_pre(&module)?,
+      max_fuel,
+      module_hash: hasher.finish(),
+      path,
+      project_root,
+    };
+
+    let (mut store, instance) = wasm_module.instantiate()?;
+    let version = instance
+      .get_typed_func::<(), i32>(&mut store, ABI_VERSION_EXPORT)
+      .map_err(|_| {
+        diagnostic_error!(
+          "WebAssembly plugin {} does not export {ABI_VERSION_EXPORT}",
+          wasm_module.path.display()
+        )
+      })?
+      .call(&mut store, ())
+      .map_err(|error| wasm_module.trap_error(error))?;
+
+    if version != ABI_VERSION {
+      return Err(diagnostic_error!(
+        "WebAssembly plugin {} uses ABI version {version}, but version {ABI_VERSION} is required",
+        wasm_module.path.display()
+      ));
+    }
+
+    Ok(wasm_module)
+  }
+
+  pub fn has_export(&self, name: &str) -> bool {
+    self.instance_pre.module().get_export(name).is_some()
+  }
+
+  /// Combines the hash of the module with the key provided by the module, if any
+  pub fn cache_key(&self) -> anyhow::Result<CacheStatus> {
+    if !self.has_export(CACHE_KEY_EXPORT) {
+      return Ok(CacheStatus::Hash(self.module_hash));
+    }
+
+    let (mut store, instance) = self.instantiate()?;
+    let packed = instance
+      .get_typed_func::<(), i64>(&mut store, CACHE_KEY_EXPORT)?
+      .call(&mut store, ())
+      .map_err(|error| self.trap_error(error))?;
+
+    let memory = get_memory(&instance, &mut store)?;
+    let key = read_memory(&memory, &store, packed)?;
+
+    if key.is_empty() {
+      return Ok(CacheStatus::Uncachable);
+    }
+
+    let mut hasher = IdentifierHasher::new();
+    self.module_hash.hash(&mut hasher);
+    key.hash(&mut hasher);
+
+    Ok(CacheStatus::Hash(hasher.finish()))
+  }
+
+  /// Calls `export` with a message, returning the message written back by the module
+  pub fn call(&self, export: &str, input: &[u8]) -> anyhow::Result<WasmCallOutput> {
+    let (mut store, instance) = self.instantiate()?;
+    let memory = get_memory(&instance, &mut store)?;
+
+    let input_ptr = instance
+      .get_typed_func::<i32, i32>(&mut store, ALLOC_EXPORT)?
+      .call(&mut store, i32::try_from(input.len())?)
+      .map_err(|error| self.trap_error(error))?;
+
+    memory.write(&mut store, input_ptr as u32 as usize, input)?;
+
+    let packed = instance
+      .get_typed_func::<(i32, i32), i64>(&mut store, export)?
+      .call(&mut store, (input_ptr, input.len() as i32))
+      .map_err(|error| self.trap_error(error))?;
+
+    let output = read_memory(&memory, &store, packed)?;
+
+    Ok(WasmCallOutput {
+      output,
+      read_files: std::mem::take(&mut store.data_mut().read_files),
+    })
+  }
+
+  fn instantiate(&self) -> anyhow::Result<(Store<HostState>, Instance)> {
+    let mut store = Store::new(
+      self.instance_pre.module().engine(),
+      HostState {
+        canonical_root: self.canonical_root.clone(),
+        file_system: self.file_system.clone(),
+        limits: StoreLimitsBuilder::new()
+          .memory_size(MAX_MEMORY_BYTES)
+          .build(),
+        plugin: self.path.display().to_string(),
+        project_root: self.project_root.clone(),
+        read_files: Vec::new(),
+      },
+    );
+
+    store.limiter(|state| &mut state.limits);
+    store.set_fuel(self.max_fuel)?;
+
+    let instance = self.instance_pre.instantiate(&mut store)?;
+
+    Ok((store, instance))
+  }
+
+  /// Adds the plugin to errors raised by a call, and explains when it ran out of fuel
+  fn trap_error(&self, error: anyhow::Error) -> anyhow::Error {
+    if error.downcast_ref::<Trap>() == Some(&Trap::OutOfFuel) {
+      return diagnostic_error!(
+        "WebAssembly plugin {} exceeded its execution limit of {} instructions",
+        self.path.display(),
+        self.max_fuel
+      );
+    }
+
+    error.context(format!(
+      "WebAssembly plugin {} trapped",
+      self.path.display()
+    ))
+  }
+}
+
+fn create_linker(engine: &Engine) -> anyhow::Result<Linker<HostState>> {
+  let mut linker = Linker::new(engine);
+
+  linker.func_wrap(
+    HOST_MODULE,
+    HOST_READ_FILE,
+    |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> anyhow::Result<i64> {
+      let path = read_guest_string(&mut caller, ptr, len)?;
+      let state = caller.data();
+      let Some(path) = resolve_sandboxed(state, Path::new(&path)) else {
+        return Ok(-1);
+      };
+
+      let Ok(contents) = state.file_system.read(&path) else {
+        return Ok(-1);
+      };
+
+      caller.data_mut().read_files.push(path);
+      write_guest_bytes(&mut caller, &contents)
+    },
+  )?;
+
+  linker.func_wrap(
+    HOST_MODULE,
+    HOST_IS_FILE,
+    |mut caller: Caller<'_, HostState>, ptr: i32, len: i32| -> anyhow::Result<i32> {
+      let path = read_guest_string(&mut caller, ptr, len)?;
+      let state = caller.data();
+      let is_file = resolve_sandboxed(state, Path::new(&path))
+        .is_some_and(|path| state.file_system.is_file(&path));
+
+      Ok(i32::from(is_file))
+    },
+  )?;
+
+  linker.func_wrap(
+    HOST_MODULE,
+    HOST_LOG,
+    |mut caller: Caller<'_, HostState>, level: i32, ptr: i32, len: i32| -> anyhow::Result<()> {
+      let message = read_guest_string(&mut caller, ptr, len)?;
+      let plugin = &caller.data().plugin;
+
+      match level {
+        0 => tracing::error!(plugin = %plugin, "{message}"),
+        1 => tracing::warn!(plugin = %plugin, "{message}"),
+        2 => tracing::info!(plugin = %plugin, "{message}"),
+        _ => tracing::debug!(plugin = %plugin, "{message}"),
+      }
+
+      Ok(())
+    },
+  )?;
+
+  Ok(linker)
+}
+
+/// Resolves a path requested by a module against the project root, rejecting paths outside of it
+///
+/// The path is normalized lexically, as it may not exist and the file system may not be the
+/// real one.
+fn sandbox_path(project_root: &Path, path: &Path) -> Option<PathBuf> {
+  let mut resolved = PathBuf::new();
+
+  for component in project_root.join(path).components() {
+    match component {
+      Component::CurDir => {}
+      Component::ParentDir => {
+        if !resolved.pop() {
+          return None;
+        }
+      }
+      component => resolved.push(component),
+    }
+  }
+
+  resolved.starts_with(project_root).then_some(resolved)
+}
+
+/// Resolves a path requested by a module to a real path within the project root
+///
+/// The lexical check alone can be escaped by a symlink inside the project that points outside
+/// of it, so the path is also canonicalized. Paths that cannot be canonicalized, e.g. because
+/// they do not exist, are rejected.
+fn resolve_sandboxed(state: &HostState, path: &Path) -> Option<PathBuf> {
+  let path = sandbox_path(&state.project_root, path)?;
+  let real_path = state.file_system.canonicalize_base(&path).ok()?;
+
+  real_path
+    .starts_with(&state.canonical_root)
+    .then_some(real_path)
+}
+
+fn get_memory(instance: &Instance, store: &mut Store<HostState>) -> anyhow::Result<Memory> {
+  instance
+    .get_memory(store, "memory")
+    .ok_or_else(|| anyhow!("WebAssembly plugin does not export its memory"))
+}
+
+fn read_memory(memory: &Memory, store: &Store<HostState>, packed: i64) -> anyhow::Result<Vec<u8>> {
+  let (ptr, len) = unpack(packed);
+  let mut output = vec![0; len];
+
+  memory
+    .read(store, ptr, &mut output)
+    .map_err(|_| anyhow!("WebAssembly plugin returned an out of bounds pointer"))?;
+
+  Ok(output)
+}
+
+fn caller_memory(caller: &mut Caller<'_, HostState>) -> anyhow::Result<Memory> {
+  match caller.get_export("memory") {
+    Some(Extern::Memory(memory)) => Ok(memory),
+    _ => Err(anyhow!("WebAssembly plugin does not export its memory")),
+  }
+}
+
+fn read_guest_string(
+  caller: &mut Caller<'_, HostState>,
+  ptr: i32,
+  len: i32,
+) -> anyhow::Result<String> {
+  let memory = caller_memory(caller)?;
+  let mut bytes = vec![0; len as u32 as usize];
+
+  memory
+    .read(&caller, ptr as u32 as usize, &mut bytes)
+    .map_err(|_| anyhow!("WebAssembly plugin passed an out of bounds pointer"))?;
+
+  Ok(String::from_utf8(bytes)?)
+}
+
+fn write_guest_bytes(caller: &mut Caller<'_, HostState>, bytes: &[u8]) -> anyhow::Result<i64> {
+  let alloc = match caller.get_export(ALLOC_EXPORT) {
+    Some(Extern::Func(alloc)) => alloc.typed::<i32, i32>(&caller)?,
+    _ => return Err(anyhow!("WebAssembly plugin does not export {ALLOC_EXPORT}")),
+  };
+
+  let ptr = alloc.call(&mut *caller, i32::try_from(bytes.len())?)?;
+  let memory = caller_memory(caller)?;
+
+  memory.write(&mut *caller, ptr as u32 as usize, bytes)?;
+
+  Ok(pack(ptr as u32, bytes.len() as u32))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  #[test]
+  fn sandboxes_paths_to_the_project_root() {
+    let root = Path::new("/project");
+
+    assert_eq!(
+      sandbox_path(root, Path::new("src/index.js")),
+      Some(PathBuf::from("/project/src/index.js"))
+    );
+    assert_eq!(
+      sandbox_path(root, Path::new("/project/./src/../package.json")),
+      Some(PathBuf::from("/project/package.json"))
+    );
+    assert_eq!(sandbox_path(root, Path::new("../secret")), None);
+    assert_eq!(sandbox_path(root, Path::new("/etc/passwd")), None);
+    assert_eq!(sandbox_path(root, Path::new("/../../etc/passwd")), None);
+  }
+
+  #[cfg(unix)]
+  #[test]
+  fn rejects_symlinks_that_escape_the_project_root() {
+    use atlaspack_filesystem::os_file_system::OsFileSystem;
+
+    let dir = tempfile::tempdir().unwrap();
+    let project_root = dir.path().join("project");
+    let secret = dir.path().join("secret.txt");
+
+    std::fs::create_dir_all(project_root.join("src")).unwrap();
+    std::fs::write(&secret, "secret").unwrap();
+    std::fs::write(project_root.join("src/index.js"), "").unwrap();
+    std::os::unix::fs::symlink(&secret, project_root.join("link.txt")).unwrap();
+    std::os::unix::fs::symlink(project_root.join("src"), project_root.join("lib")).unwrap();
+
+    let file_system: FileSystemRef = Arc::new(OsFileSystem);
+    let canonical_root = file_system.canonicalize_base(&project_root).unwrap();
+    let state = HostState {
+      canonical_root: canonical_root.clone(),
+      file_system,
+      limits: StoreLimitsBuilder::new().build(),
+      plugin: String::from("test.wasm"),
+      project_root,
+      read_files: Vec::new(),
+    };
+
+    assert_eq!(resolve_sandboxed(&state, Path::new("link.txt")), None);
+    assert_eq!(resolve_sandboxed(&state, Path::new("missing.txt")), None);
+    assert_eq!(
+      resolve_sandboxed(&state, Path::new("lib/index.js")),
+      Some(canonical_root.join("src/index.js"))
+    );
+  }
+}
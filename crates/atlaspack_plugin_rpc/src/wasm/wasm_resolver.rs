@@ -0,0 +1,90 @@
+use std::fmt;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use atlaspack_config::PluginNode;
+use atlaspack_core::hash::IdentifierHasher;
+use atlaspack_core::plugin::{ResolveContext, Resolved, ResolverPlugin};
+use atlaspack_core::types::Invalidation;
+
+use super::abi::*;
+use super::wasm_module::WasmModule;
+
+/// Runs a resolver compiled to WebAssembly
+pub struct WasmResolverPlugin {
+  module: Arc<WasmModule>,
+  options: WasmPluginOptions,
+  plugin_node: PluginNode,
+}
+
+impl Debug for WasmResolverPlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "WasmResolverPlugin({})", self.plugin_node.package_name)
+  }
+}
+
+impl WasmResolverPlugin {
+  pub fn new(
+    module: WasmModule,
+    options: WasmPluginOptions,
+    plugin_node: &PluginNode,
+  ) -> anyhow::Result<Self> {
+    if !module.has_export(RESOLVE_EXPORT) {
+      return Err(atlaspack_core::diagnostic_error!(
+        "WebAssembly plugin {} does not export {RESOLVE_EXPORT}",
+        plugin_node.package_name
+      ));
+    }
+
+    Ok(Self {
+      module: Arc::new(module),
+      options,
+      plugin_node: plugin_node.clone(),
+    })
+  }
+}
+
+#[async_trait]
+impl ResolverPlugin for WasmResolverPlugin {
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.plugin_node.hash(&mut hasher);
+    hasher.finish()
+  }
+
+  async fn resolve(&self, ctx: ResolveContext) -> Result<Resolved, anyhow::Error> {
+    let input = encode_message(
+      &ResolveInput {
+        dependency: &ctx.dependency,
+        options: &self.options,
+        pipeline: ctx.pipeline.as_deref(),
+        specifier: &ctx.specifier,
+      },
+      &[],
+    )?;
+
+    let module = self.module.clone();
+    let call = tokio::task::spawn_blocking(move || module.call(RESOLVE_EXPORT, &input))
+      .await?
+      .and_then(|call| {
+        let (result, _) = decode_message::<GuestResult<Resolved>>(&call.output)?;
+        Ok((result.into_result()?, call.read_files))
+      })
+      .with_context(|| {
+        format!(
+          "Failed to resolve '{}' with resolver '{}'",
+          ctx.specifier, self.plugin_node.package_name
+        )
+      })?;
+
+    let (mut resolved, read_files) = call;
+    resolved
+      .invalidations
+      .extend(read_files.into_iter().map(Invalidation::FileChange));
+
+    Ok(resolved)
+  }
+}
@@ -0,0 +1,491 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use atlaspack_config::PluginNode;
+use atlaspack_core::diagnostic_error;
+use atlaspack_core::plugin::*;
+use atlaspack_package_manager::PackageManagerRef;
+use wasmtime::{Config, Engine};
+
+use super::abi::WasmPluginOptions;
+use super::wasm_module::{DEFAULT_MAX_FUEL, WasmModule};
+use super::wasm_resolver::WasmResolverPlugin;
+use super::wasm_transformer::WasmTransformerPlugin;
+use crate::{RpcFactory, RpcFactoryRef, RpcWorker, RpcWorkerRef};
+
+/// WasmRpcFactory runs plugins that are compiled to WebAssembly
+///
+/// Plugins are declared in `.atlaspackrc` by a path to a `.wasm` file, relative to the config
+/// file. All other plugins are created by the fallback factory, e.g. Nodejs.
+///
+/// Calls are limited by fuel, so that a plugin that never returns fails the build rather than
+/// hanging it.
+pub struct WasmRpcFactory {
+  engine: Engine,
+  fallback: RpcFactoryRef,
+  max_fuel: u64,
+}
+
+impl WasmRpcFactory {
+  pub fn new(fallback: RpcFactoryRef) -> Self {
+    let mut config = Config::new();
+    config.consume_fuel(true);
+
+    Self {
+      engine: Engine::new(&config).expect("fuel metering is supported on every target"),
+      fallback,
+      max_fuel: DEFAULT_MAX_FUEL,
+    }
+  }
+
+  /// Sets the amount of fuel, roughly the number of instructions, a plugin can use in each call
+  pub fn with_max_fuel(mut self, max_fuel: u64) -> Self {
+    self.max_fuel = max_fuel;
+    self
+  }
+}
+
+impl RpcFactory for WasmRpcFactory {
+  fn start(&self) -> anyhow::Result<Arc<dyn RpcWorker>> {
+    Ok(Arc::new(WasmRpcWorker {
+      engine: self.engine.clone(),
+      fallback: self.fallback.start()?,
+      max_fuel: self.max_fuel,
+    }))
+  }
+}
+
+pub struct WasmRpcWorker {
+  engine: Engine,
+  fallback: RpcWorkerRef,
+  max_fuel: u64,
+}
+
+impl WasmRpcWorker {
+  fn is_wasm_plugin(plugin: &PluginNode) -> bool {
+    plugin.package_name.ends_with(".wasm")
+  }
+
+  fn plugin_options(ctx: &PluginContext) -> WasmPluginOptions {
+    WasmPluginOptions {
+      mode: ctx.options.mode.clone(),
+      project_root: ctx.options.project_root.clone(),
+    }
+  }
+
+  /// Only resolvers and transformers can be WebAssembly plugins so far, so other phases fail
+  /// rather than being sent to the fallback, which cannot load them either
+  fn unsupported_phase(plugin: &PluginNode, phase: &str) -> anyhow::Error {
+    diagnostic_error!(
+      "WebAssembly plugin {} (configured in {}) cannot be used as a {phase}, only resolvers and transformers are supported",
+      plugin.package_name,
+      plugin.resolve_from.display(),
+    )
+  }
+}
+
+#[async_trait]
+impl RpcWorker for WasmRpcWorker {
+  fn create_resolver(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ResolverPlugin>> {
+    if !Self::is_wasm_plugin(plugin) {
+      return self.fallback.create_resolver(ctx, plugin);
+    }
+
+    let module = WasmModule::load(&self.engine, self.max_fuel, ctx, plugin)?;
+
+    Ok(Arc::new(WasmResolverPlugin::new(
+      module,
+      Self::plugin_options(ctx),
+      plugin,
+    )?))
+  }
+
+  async fn create_transformer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+    package_manager: PackageManagerRef,
+  ) -> anyhow::Result<Arc<dyn TransformerPlugin>> {
+    if !Self::is_wasm_plugin(plugin) {
+      return self
+        .fallback
+        .create_transformer(ctx, plugin, package_manager)
+        .await;
+    }
+
+    let module = WasmModule::load(&self.engine, self.max_fuel, ctx, plugin)?;
+
+    Ok(Arc::new(WasmTransformerPlugin::new(
+      module,
+      Self::plugin_options(ctx),
+      plugin,
+    )?))
+  }
+
+  fn create_compressor(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn CompressorPlugin>> {
+    if Self::is_wasm_plugin(plugin) {
+      return Err(Self::unsupported_phase(plugin, "compressor"));
+    }
+
+    self.fallback.create_compressor(ctx, plugin)
+  }
+
+  fn create_namer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn NamerPlugin>> {
+    if Self::is_wasm_plugin(plugin) {
+      return Err(Self::unsupported_phase(plugin, "namer"));
+    }
+
+    self.fallback.create_namer(ctx, plugin)
+  }
+
+  fn create_optimizer(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn OptimizerPlugin>> {
+    if Self::is_wasm_plugin(plugin) {
+      return Err(Self::unsupported_phase(plugin, "optimizer"));
+    }
+
+    self.fallback.create_optimizer(ctx, plugin)
+  }
+
+  fn create_packager(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn PackagerPlugin>> {
+    if Self::is_wasm_plugin(plugin) {
+      return Err(Self::unsupported_phase(plugin, "packager"));
+    }
+
+    self.fallback.create_packager(ctx, plugin)
+  }
+
+  fn create_reporter(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ReporterPlugin>> {
+    if Self::is_wasm_plugin(plugin) {
+      return Err(Self::unsupported_phase(plugin, "reporter"));
+    }
+
+    self.fallback.create_reporter(ctx, plugin)
+  }
+
+  fn create_runtime(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn RuntimePlugin>> {
+    if Self::is_wasm_plugin(plugin) {
+      return Err(Self::unsupported_phase(plugin, "runtime"));
+    }
+
+    self.fallback.create_runtime(ctx, plugin)
+  }
+
+  fn create_validator(
+    &self,
+    ctx: &PluginContext,
+    plugin: &PluginNode,
+  ) -> anyhow::Result<Arc<dyn ValidatorPlugin>> {
+    if Self::is_wasm_plugin(plugin) {
+      return Err(Self::unsupported_phase(plugin, "validator"));
+    }
+
+    self.fallback.create_validator(ctx, plugin)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use atlaspack_core::config_loader::ConfigLoader;
+  use atlaspack_core::plugin::{
+    CacheKey, CacheStatus, PluginLogger, PluginOptions, Resolution, ResolveContext,
+  };
+  use atlaspack_core::types::{Asset, Code, Dependency, FileType, Invalidation};
+  use atlaspack_filesystem::FileSystemRef;
+  use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
+  use atlaspack_package_manager::MockPackageManager;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+  use crate::{MockRpcFactory, MockRpcWorker};
+
+  /// Allocates by bumping a pointer, and returns its input unchanged as the output
+  const ECHO_TRANSFORMER: &str = r#"
+    (module
+      (memory (export "memory") 1)
+      (global $next (mut i32) (i32.const 1024))
+      (func (export "atlaspack_abi_version") (result i32) (i32.const 1))
+      (func $alloc (export "atlaspack_alloc") (param $len i32) (result i32)
+        (local $ptr i32)
+        (local.set $ptr (global.get $next))
+        (global.set $next (i32.add (global.get $next) (local.get $len)))
+        (drop (memory.grow (i32.const 1)))
+        (local.get $ptr))
+      (func (export "atlaspack_cache_key") (result i64)
+        (i64.or (i64.shl (i64.const 16) (i64.const 32)) (i64.const 2)))
+      (data (i32.const 16) "v1")
+      (func (export "atlaspack_transform") (param $ptr i32) (param $len i32) (result i64)
+        (i64.or
+          (i64.shl (i64.extend_i32_u (local.get $ptr)) (i64.const 32))
+          (i64.extend_i32_u (local.get $len)))))
+  "#;
+
+  /// Reads `config.json` from the project root, and resolves every specifier to it
+  const CONFIG_RESOLVER: &str = r#"
+    (module
+      (import "atlaspack" "read_file" (func $read_file (param i32 i32) (result i64)))
+      (memory (export "memory") 1)
+      (global $next (mut i32) (i32.const 1024))
+      (data (i32.const 16) "config.json")
+      (data (i32.const 32) "\7c\00\00\00{\"invalidations\":[],\"resolution\":{\"type\":\"resolved\",\"canDefer\":false,\"filePath\":\"/project/config.json\",\"sideEffects\":false}}")
+      (func (export "atlaspack_abi_version") (result i32) (i32.const 1))
+      (func (export "atlaspack_alloc") (param $len i32) (result i32)
+        (local $ptr i32)
+        (local.set $ptr (global.get $next))
+        (global.set $next (i32.add (global.get $next) (local.get $len)))
+        (local.get $ptr))
+      (func (export "atlaspack_resolve") (param i32 i32) (result i64)
+        (drop (call $read_file (i32.const 16) (i32.const 11)))
+        (i64.or (i64.shl (i64.const 32) (i64.const 32)) (i64.const 128))))
+  "#;
+
+  /// Never returns from transform
+  const LOOPING_TRANSFORMER: &str = r#"
+    (module
+      (memory (export "memory") 1)
+      (func (export "atlaspack_abi_version") (result i32) (i32.const 1))
+      (func (export "atlaspack_alloc") (param $len i32) (result i32) (i32.const 1024))
+      (func (export "atlaspack_transform") (param i32 i32) (result i64)
+        (loop $forever (br $forever))
+        (i64.const 0)))
+  "#;
+
+  const OLD_ABI: &str = r#"
+    (module
+      (memory (export "memory") 1)
+      (func (export "atlaspack_abi_version") (result i32) (i32.const 0)))
+  "#;
+
+  fn plugin_context(fs: FileSystemRef) -> PluginContext {
+    PluginContext {
+      config: Arc::new(ConfigLoader {
+        fs: fs.clone(),
+        project_root: PathBuf::from("/project"),
+        search_path: PathBuf::from("/project"),
+        files: Default::default(),
+      }),
+      file_system: fs,
+      logger: PluginLogger::default(),
+      options: Arc::new(PluginOptions {
+        project_root: PathBuf::from("/project"),
+        ..PluginOptions::default()
+      }),
+    }
+  }
+
+  fn plugin_node(package_name: &str) -> PluginNode {
+    PluginNode {
+      package_name: String::from(package_name),
+      resolve_from: Arc::new(PathBuf::from("/project/.atlaspackrc")),
+    }
+  }
+
+  fn worker() -> Arc<dyn RpcWorker> {
+    let mut fallback = MockRpcFactory::new();
+
+    fallback
+      .expect_start()
+      .returning(|| Ok(Arc::new(MockRpcWorker::new())));
+
+    WasmRpcFactory::new(Arc::new(fallback)).start().unwrap()
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn runs_wasm_transformers() {
+    let fs = Arc::new(InMemoryFileSystem::default());
+    fs.write_file(
+      &PathBuf::from("/project/plugins/echo.wasm"),
+      String::from(ECHO_TRANSFORMER),
+    );
+
+    let transformer = worker()
+      .create_transformer(
+        &plugin_context(fs.clone()),
+        &plugin_node("./plugins/echo.wasm"),
+        Arc::new(MockPackageManager::new()),
+      )
+      .await
+      .unwrap();
+
+    assert!(matches!(
+      transformer.cache_key().as_ref(),
+      CacheStatus::Hash(_)
+    ));
+
+    let asset = Asset {
+      id: String::from("a"),
+      code: Code::from("export default 1;"),
+      file_path: PathBuf::from("/project/index.js"),
+      file_type: FileType::Js,
+      ..Asset::default()
+    };
+
+    let result = transformer.transform(asset.clone()).await.unwrap();
+
+    assert_eq!(result.asset, asset);
+    assert_eq!(result.dependencies, Vec::<Dependency>::new());
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn runs_wasm_resolvers() {
+    let fs = Arc::new(InMemoryFileSystem::default());
+    fs.write_file(
+      &PathBuf::from("/project/resolver.wasm"),
+      String::from(CONFIG_RESOLVER),
+    );
+    fs.write_file(&PathBuf::from("/project/config.json"), String::from("{}"));
+
+    let resolver = worker()
+      .create_resolver(&plugin_context(fs.clone()), &plugin_node("resolver.wasm"))
+      .unwrap();
+
+    let resolved = resolver
+      .resolve(ResolveContext {
+        dependency: Arc::new(Dependency::default()),
+        pipeline: None,
+        specifier: String::from("config"),
+      })
+      .await
+      .unwrap();
+
+    assert!(matches!(resolved.resolution, Resolution::Resolved(_)));
+    assert_eq!(
+      resolved.invalidations,
+      vec![Invalidation::FileChange(PathBuf::from(
+        "/project/config.json"
+      ))]
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn stops_plugins_that_exceed_the_fuel_limit() {
+    let fs = Arc::new(InMemoryFileSystem::default());
+    fs.write_file(
+      &PathBuf::from("/project/loop.wasm"),
+      String::from(LOOPING_TRANSFORMER),
+    );
+
+    let mut fallback = MockRpcFactory::new();
+    fallback
+      .expect_start()
+      .returning(|| Ok(Arc::new(MockRpcWorker::new())));
+
+    let transformer = WasmRpcFactory::new(Arc::new(fallback))
+      .with_max_fuel(100_000)
+      .start()
+      .unwrap()
+      .create_transformer(
+        &plugin_context(fs.clone()),
+        &plugin_node("loop.wasm"),
+        Arc::new(MockPackageManager::new()),
+      )
+      .await
+      .unwrap();
+
+    let error = transformer
+      .transform(Asset {
+        file_path: PathBuf::from("/project/index.js"),
+        ..Asset::default()
+      })
+      .await
+      .unwrap_err();
+
+    assert_eq!(
+      error.root_cause().to_string(),
+      "WebAssembly plugin /project/loop.wasm exceeded its execution limit of 100000 instructions"
+    );
+  }
+
+  #[test]
+  fn rejects_modules_with_another_abi_version() {
+    let fs = Arc::new(InMemoryFileSystem::default());
+    fs.write_file(&PathBuf::from("/project/old.wasm"), String::from(OLD_ABI));
+
+    let error = worker()
+      .create_resolver(&plugin_context(fs.clone()), &plugin_node("old.wasm"))
+      .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "WebAssembly plugin /project/old.wasm uses ABI version 0, but version 1 is required"
+    );
+  }
+
+  #[test]
+  fn rejects_wasm_plugins_in_phases_other_than_resolvers_and_transformers() {
+    let ctx = plugin_context(Arc::new(InMemoryFileSystem::default()));
+    let plugin = plugin_node("plugin.wasm");
+    let worker = worker();
+
+    let errors = [
+      worker.create_namer(&ctx, &plugin).unwrap_err(),
+      worker.create_reporter(&ctx, &plugin).unwrap_err(),
+      worker.create_runtime(&ctx, &plugin).unwrap_err(),
+      worker.create_validator(&ctx, &plugin).unwrap_err(),
+    ];
+
+    assert_eq!(
+      errors.map(|error| error.to_string()),
+      ["namer", "reporter", "runtime", "validator"].map(|phase| format!(
+        "WebAssembly plugin plugin.wasm (configured in /project/.atlaspackrc) cannot be used as a {phase}, only resolvers and transformers are supported"
+      ))
+    );
+  }
+
+  #[test]
+  fn delegates_other_plugins_to_the_fallback() {
+    let fs = Arc::new(InMemoryFileSystem::default());
+    let mut fallback_worker = MockRpcWorker::new();
+
+    fallback_worker
+      .expect_create_resolver()
+      .times(1)
+      .returning(|_, _| Err(anyhow::anyhow!("fallback")));
+
+    let fallback_worker = Arc::new(fallback_worker);
+    let mut fallback = MockRpcFactory::new();
+    fallback
+      .expect_start()
+      .returning(move || Ok(fallback_worker.clone()));
+
+    let error = WasmRpcFactory::new(Arc::new(fallback))
+      .start()
+      .unwrap()
+      .create_resolver(
+        &plugin_context(fs),
+        &plugin_node("@atlaspack/resolver-default"),
+      )
+      .unwrap_err();
+
+    assert_eq!(error.to_string(), "fallback");
+  }
+}
@@ -0,0 +1,114 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use atlaspack_config::PluginNode;
+use atlaspack_core::hash::IdentifierHasher;
+use atlaspack_core::plugin::{CacheKey, CacheStatus, TransformResult, TransformerPlugin};
+use atlaspack_core::types::{Asset, Code};
+
+use super::abi::*;
+use super::wasm_module::WasmModule;
+
+/// Runs a transformer compiled to WebAssembly
+///
+/// The asset is sent without its source map, and the map of the input asset is kept, as the ABI
+/// does not support source maps yet.
+pub struct WasmTransformerPlugin {
+  cache_key: CacheStatus,
+  module: Arc<WasmModule>,
+  options: WasmPluginOptions,
+  plugin_node: PluginNode,
+}
+
+impl Debug for WasmTransformerPlugin {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "WasmTransformerPlugin({})",
+      self.plugin_node.package_name
+    )
+  }
+}
+
+impl WasmTransformerPlugin {
+  pub fn new(
+    module: WasmModule,
+    options: WasmPluginOptions,
+    plugin_node: &PluginNode,
+  ) -> anyhow::Result<Self> {
+    if !module.has_export(TRANSFORM_EXPORT) {
+      return Err(atlaspack_core::diagnostic_error!(
+        "WebAssembly plugin {} does not export {TRANSFORM_EXPORT}",
+        plugin_node.package_name
+      ));
+    }
+
+    Ok(Self {
+      cache_key: module.cache_key()?,
+      module: Arc::new(module),
+      options,
+      plugin_node: plugin_node.clone(),
+    })
+  }
+}
+
+impl CacheKey for WasmTransformerPlugin {
+  fn cache_key(&self) -> Cow<'_, CacheStatus> {
+    Cow::Borrowed(&self.cache_key)
+  }
+}
+
+#[async_trait]
+impl TransformerPlugin for WasmTransformerPlugin {
+  fn id(&self) -> u64 {
+    let mut hasher = IdentifierHasher::new();
+    self.plugin_node.hash(&mut hasher);
+    hasher.finish()
+  }
+
+  async fn transform(&self, asset: Asset) -> Result<TransformResult, anyhow::Error> {
+    let input = encode_message(
+      &TransformInput {
+        asset: &asset,
+        options: &self.options,
+      },
+      asset.code.bytes(),
+    )?;
+
+    let module = self.module.clone();
+    let call = tokio::task::spawn_blocking(move || module.call(TRANSFORM_EXPORT, &input))
+      .await?
+      .and_then(|call| {
+        let (result, contents) = decode_message::<GuestResult<TransformOutput>>(&call.output)?;
+        Ok((result.into_result()?, contents.to_vec(), call.read_files))
+      })
+      .with_context(|| {
+        format!(
+          "Failed to transform asset '{}' with transformer '{}'",
+          asset.file_path.display(),
+          self.plugin_node.package_name
+        )
+      })?;
+
+    let (output, contents, read_files) = call;
+    let mut invalidate_on_file_change = output.invalidate_on_file_change;
+    invalidate_on_file_change.extend(read_files);
+
+    Ok(TransformResult {
+      asset: Asset {
+        code: Code::new(contents),
+        map: asset.map,
+        stats: asset.stats,
+        ..output.asset
+      },
+      dependencies: output.dependencies,
+      invalidate_on_file_change,
+      ..Default::default()
+    })
+  }
+}
@@ -6,29 +6,35 @@ use std::sync::Arc;
 use anyhow::{Error, anyhow};
 use async_trait::async_trait;
 use atlaspack_core::plugin::TransformResult;
-use atlaspack_core::plugin::{PluginContext, TransformerPlugin};
+use atlaspack_core::plugin::{PluginContext, PluginLogger, TransformerPlugin};
 use atlaspack_core::types::engines::{Engines, EnginesBrowsers};
 use atlaspack_core::types::{
-  Asset, AssetWithDependencies, Code, Dependency, DependencyBuilder, Diagnostic,
-  EnvironmentContext, ErrorKind, ExportsCondition, FileType, Priority, SourceMap, SpecifierType,
-  Symbol,
+  Asset, AssetWithDependencies, Code, CodeFrame, CodeHighlight, Dependency, DependencyBuilder,
+  Diagnostic, DiagnosticBuilder, EnvironmentContext, ErrorKind, ExportsCondition, File, FileType,
+  Priority, SourceMap, SpecifierType, Symbol,
 };
-use lightningcss::css_modules::{CssModuleExport, CssModuleReference};
+use lightningcss::css_modules::{CssModuleExport, CssModuleReference, Pattern};
 use lightningcss::dependencies::DependencyOptions;
-use lightningcss::printer::PrinterOptions;
+use lightningcss::printer::{PrinterOptions, PseudoClasses};
 use lightningcss::stylesheet::{ParserFlags, ParserOptions, StyleSheet};
 use lightningcss::targets::{Browsers, Targets};
 // Lightning CSS uses the upstream Parcel SourceMap, but Atlaspack uses the in-sourced version (which will eventually be renamed)
 use parcel_sourcemap_ext::SourceMap as ParcelSourceMapExt;
 use serde::Deserialize;
 
-use crate::css_transformer_config::{CssModulesConfig, CssModulesFullConfig, CssTransformerConfig};
+use crate::css_transformer_config::{
+  CssModulesConfig, CssModulesFullConfig, CssTransformerConfig, DraftsConfig, PseudoClassesConfig,
+};
 
 #[derive(Debug, Hash)]
 pub struct AtlaspackCssTransformerPlugin {
   project_root: PathBuf,
   css_modules_config: CssModulesFullConfig,
+  drafts: DraftsConfig,
+  pseudo_classes: PseudoClassesConfig,
+  error_recovery: bool,
   is_compiled_css_in_js_transformer_enabled: bool,
+  logger: PluginLogger,
 }
 
 #[derive(Deserialize)]
@@ -63,6 +69,11 @@ impl AtlaspackCssTransformerPlugin {
       })
       .unwrap_or_default();
 
+    if let Some(pattern) = css_modules_config.pattern.as_deref() {
+      Pattern::parse(pattern)
+        .map_err(|err| anyhow!("Invalid @atlaspack/transformer-css config: {err}"))?;
+    }
+
     let is_compiled_css_in_js_transformer_enabled = ctx
       .options
       .feature_flags
@@ -71,7 +82,11 @@ impl AtlaspackCssTransformerPlugin {
     Ok(AtlaspackCssTransformerPlugin {
       project_root: ctx.options.project_root.clone(),
       css_modules_config,
+      drafts: config.drafts.unwrap_or_default(),
+      pseudo_classes: config.pseudo_classes.unwrap_or_default(),
+      error_recovery: config.error_recovery.unwrap_or_default(),
       is_compiled_css_in_js_transformer_enabled,
+      logger: ctx.logger.clone(),
     })
   }
 
@@ -86,16 +101,67 @@ impl AtlaspackCssTransformerPlugin {
       .file_name()
       .is_some_and(|name| name.to_string_lossy().ends_with(".module.css"));
 
-    // If it matches the *.module.css pattern, it is a CSS module
-    if matches_css_module_file_pattern {
-      return true;
+    if !asset.is_source {
+      return matches_css_module_file_pattern;
+    }
+
+    let project_path = asset
+      .file_path
+      .strip_prefix(&self.project_root)
+      .unwrap_or(&asset.file_path)
+      .to_string_lossy()
+      .replace('\\', "/");
+
+    if self
+      .css_modules_config
+      .exclude
+      .as_ref()
+      .is_some_and(|exclude| exclude.matches(&project_path))
+    {
+      return false;
+    }
+
+    // When include globs are configured they replace the global setting
+    if let Some(include) = self.css_modules_config.include.as_ref() {
+      return include.matches(&project_path);
+    }
+
+    matches_css_module_file_pattern || self.css_modules_config.global.unwrap_or_default()
+  }
+
+  fn parser_flags(&self) -> ParserFlags {
+    let mut flags = ParserFlags::empty();
+    if self.drafts.custom_media.unwrap_or_default() {
+      flags |= ParserFlags::CUSTOM_MEDIA;
     }
+    flags
+  }
 
-    // TODO: Implement include and exclude globs
+  fn pseudo_classes(&self) -> Option<PseudoClasses<'_>> {
+    let PseudoClassesConfig {
+      hover,
+      active,
+      focus,
+      focus_visible,
+      focus_within,
+    } = &self.pseudo_classes;
+
+    if hover.is_none()
+      && active.is_none()
+      && focus.is_none()
+      && focus_visible.is_none()
+      && focus_within.is_none()
+    {
+      return None;
+    }
 
-    // Otherwise if the asset is a source asset and global CSS modules are
-    // enabled
-    asset.is_source && self.css_modules_config.global.unwrap_or_default()
+    Some(PseudoClasses {
+      hover: hover.as_deref(),
+      active: active.as_deref(),
+      focus: focus.as_deref(),
+      focus_visible: focus_visible.as_deref(),
+      focus_within: focus_within.as_deref(),
+    })
   }
 
   fn handle_compiled_css_asset(&self, asset: &mut Asset) -> Result<(), Error> {
@@ -128,14 +194,30 @@ impl TransformerPlugin for AtlaspackCssTransformerPlugin {
       });
     }
     let css_modules = if self.is_css_module(&asset) {
+      let config = &self.css_modules_config;
+      let defaults = lightningcss::css_modules::Config::default();
+
       Some(lightningcss::css_modules::Config {
-        dashed_idents: asset.is_source && self.css_modules_config.dashed_idents.unwrap_or_default(),
-        ..Default::default()
+        pattern: match config.pattern.as_deref() {
+          // The pattern is validated when the plugin is created
+          Some(pattern) => Pattern::parse(pattern)?,
+          None => defaults.pattern,
+        },
+        dashed_idents: asset.is_source && config.dashed_idents.unwrap_or_default(),
+        animation: config.animation.unwrap_or(defaults.animation),
+        grid: config.grid.unwrap_or(defaults.grid),
+        custom_idents: config.custom_idents.unwrap_or(defaults.custom_idents),
+        ..defaults
       })
     } else {
       None
     };
 
+    #[allow(clippy::disallowed_methods, clippy::disallowed_types)]
+    let warnings = self
+      .error_recovery
+      .then(|| Arc::new(std::sync::RwLock::new(Vec::new())));
+
     let stylesheet = StyleSheet::parse(
       asset.code.as_str()?,
       ParserOptions {
@@ -147,9 +229,9 @@ impl TransformerPlugin for AtlaspackCssTransformerPlugin {
           .map_err(|_e| anyhow!("Couldn't convert file path to String"))?,
         css_modules,
         source_index: Default::default(),
-        error_recovery: false,
-        warnings: None,
-        flags: ParserFlags::empty(),
+        error_recovery: self.error_recovery,
+        warnings: warnings.clone(),
+        flags: self.parser_flags(),
       },
     )
     .map_err(|_err| {
@@ -157,6 +239,37 @@ impl TransformerPlugin for AtlaspackCssTransformerPlugin {
       anyhow!("Failed to parse CSS {}", asset.file_path.display())
     })?;
 
+    if let Some(warnings) = warnings
+      && let Ok(warnings) = warnings.read()
+    {
+      let code_frame = CodeFrame::from(File {
+        contents: asset.code.as_str()?.to_string(),
+        path: asset.file_path.clone(),
+      });
+
+      for warning in warnings.iter() {
+        self.logger.warn(
+          DiagnosticBuilder::default()
+            .code_frames(vec![CodeFrame {
+              code_highlights: warning
+                .loc
+                .as_ref()
+                .map(|loc| {
+                  vec![CodeHighlight::from([
+                    loc.line as usize + 1,
+                    loc.column as usize,
+                  ])]
+                })
+                .unwrap_or_default(),
+              ..code_frame.clone()
+            }])
+            .message(warning.kind.to_string())
+            .origin(Some(String::from("@atlaspack/transformer-css")))
+            .build()?,
+        );
+      }
+    }
+
     let mut asset = asset.clone();
 
     // Normalize the asset's environment so that properties that only affect JS don't cause CSS to be duplicated.
@@ -203,7 +316,7 @@ impl TransformerPlugin for AtlaspackCssTransformerPlugin {
       analyze_dependencies: Some(DependencyOptions {
         remove_imports: !preserve_imports,
       }),
-      pseudo_classes: None,
+      pseudo_classes: self.pseudo_classes(),
     })?;
 
     let mut dependencies: Vec<Dependency> = css
@@ -551,29 +664,61 @@ mod tests {
   use pretty_assertions::assert_eq;
   use std::{path::PathBuf, sync::Arc};
 
-  use atlaspack_core::{
-    config_loader::ConfigLoader,
-    plugin::{PluginLogger, PluginOptions},
-    types::Environment,
-  };
+  use atlaspack_core::{config_loader::ConfigLoader, plugin::PluginOptions, types::Environment};
   use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
 
   use super::*;
 
-  async fn run_plugin(asset: &Asset) -> anyhow::Result<TransformResult> {
+  fn create_plugin(package_json: Option<&str>) -> anyhow::Result<AtlaspackCssTransformerPlugin> {
+    create_plugin_with_logger(package_json, PluginLogger::default())
+  }
+
+  fn create_plugin_with_logger(
+    package_json: Option<&str>,
+    logger: PluginLogger,
+  ) -> anyhow::Result<AtlaspackCssTransformerPlugin> {
     let file_system = Arc::new(InMemoryFileSystem::default());
-    let plugin = AtlaspackCssTransformerPlugin::new(&PluginContext {
+
+    if let Some(package_json) = package_json {
+      file_system.write_file(&PathBuf::from("package.json"), package_json.to_string());
+    }
+
+    AtlaspackCssTransformerPlugin::new(&PluginContext {
       config: Arc::new(ConfigLoader {
         fs: file_system.clone(),
         project_root: PathBuf::default(),
         search_path: PathBuf::default(),
+        files: Default::default(),
       }),
       file_system,
-      logger: PluginLogger::default(),
+      logger,
       options: Arc::new(PluginOptions::default()),
-    })?;
+    })
+  }
+
+  async fn run_plugin(asset: &Asset) -> anyhow::Result<TransformResult> {
+    create_plugin(None)?.transform(asset.clone()).await
+  }
+
+  async fn run_plugin_with_config(
+    asset: &Asset,
+    config: serde_json::Value,
+  ) -> anyhow::Result<TransformResult> {
+    let package_json = serde_json::json!({ "@atlaspack/transformer-css": config }).to_string();
+
+    create_plugin(Some(&package_json))?
+      .transform(asset.clone())
+      .await
+  }
 
-    plugin.transform(asset.clone()).await
+  fn source_asset(file_path: &str, code: &str) -> Asset {
+    Asset {
+      id: "css-asset".into(),
+      file_path: file_path.into(),
+      is_source: true,
+      code: Code::from(code),
+      ..Default::default()
+    }
   }
 
   #[tokio::test(flavor = "multi_thread")]
@@ -780,4 +925,108 @@ mod tests {
       }
     );
   }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn treats_files_matching_include_globs_as_css_modules() {
+    let config = serde_json::json!({ "cssModules": { "include": "src/**/*.css" } });
+
+    let included = run_plugin_with_config(&source_asset("src/styles.css", ".a {}"), config.clone())
+      .await
+      .unwrap();
+    let not_included = run_plugin_with_config(&source_asset("lib/styles.css", ".a {}"), config)
+      .await
+      .unwrap();
+
+    assert_eq!(included.discovered_assets.len(), 1);
+    assert_eq!(not_included.discovered_assets.len(), 0);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn does_not_treat_files_matching_exclude_globs_as_css_modules() {
+    let result = run_plugin_with_config(
+      &source_asset("vendor/styles.css", ".a {}"),
+      serde_json::json!({ "cssModules": { "global": true, "exclude": ["vendor/**"] } }),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(result.discovered_assets.len(), 0);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn supports_css_modules_pattern() {
+    let result = run_plugin_with_config(
+      &source_asset("styles.module.css", ".root {}"),
+      serde_json::json!({ "cssModules": { "pattern": "app-[local]" } }),
+    )
+    .await
+    .unwrap();
+
+    assert!(result.asset.code.as_str().unwrap().contains(".app-root"));
+  }
+
+  #[test]
+  fn errors_for_invalid_css_modules_pattern() {
+    let package_json = serde_json::json!({
+      "@atlaspack/transformer-css": { "cssModules": { "pattern": "[unknown]" } }
+    })
+    .to_string();
+
+    assert!(create_plugin(Some(&package_json)).is_err());
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn supports_pseudo_classes() {
+    let result = run_plugin_with_config(
+      &source_asset("styles.css", ".a:hover { color: red }"),
+      serde_json::json!({ "pseudoClasses": { "hover": "is-hovered" } }),
+    )
+    .await
+    .unwrap();
+
+    assert!(
+      result
+        .asset
+        .code
+        .as_str()
+        .unwrap()
+        .contains(".a.is-hovered")
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn recovers_from_invalid_rules_with_error_recovery() {
+    let asset = source_asset("styles.css", ".a { color: red } h1(>h2) { color: blue }");
+
+    assert!(run_plugin(&asset).await.is_err());
+
+    let result = run_plugin_with_config(&asset, serde_json::json!({ "errorRecovery": true }))
+      .await
+      .unwrap();
+
+    assert!(result.asset.code.as_str().unwrap().contains(".a"));
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn logs_recovered_errors_as_warnings() {
+    let logger = PluginLogger::default();
+    let package_json =
+      serde_json::json!({ "@atlaspack/transformer-css": { "errorRecovery": true } }).to_string();
+
+    create_plugin_with_logger(Some(&package_json), logger.clone())
+      .unwrap()
+      .transform(source_asset(
+        "styles.css",
+        ".a { color: red }\nh1(>h2) { color: blue }",
+      ))
+      .await
+      .unwrap();
+
+    let warnings = logger.take_warnings();
+    assert_eq!(warnings.len(), 1);
+
+    let code_frame = &warnings[0].code_frames[0];
+    assert_eq!(code_frame.file_path, Some(PathBuf::from("styles.css")));
+    assert_eq!(code_frame.code_highlights[0].start.line, 2);
+  }
 }
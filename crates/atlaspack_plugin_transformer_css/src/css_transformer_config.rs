@@ -1,10 +1,37 @@
+use glob_match::glob_match;
 use serde::Deserialize;
 
+/// One or more globs, matched against project relative paths
+#[derive(Debug, Deserialize, Hash)]
+#[serde(untagged)]
+pub enum GlobList {
+  Single(String),
+  Multiple(Vec<String>),
+}
+
+impl GlobList {
+  pub fn matches(&self, path: &str) -> bool {
+    match self {
+      GlobList::Single(glob) => glob_match(glob, path),
+      GlobList::Multiple(globs) => globs.iter().any(|glob| glob_match(glob, path)),
+    }
+  }
+}
+
 #[derive(Debug, Default, Deserialize, Hash)]
 #[serde(rename_all = "camelCase")]
 pub struct CssModulesFullConfig {
   pub global: Option<bool>,
   pub dashed_idents: Option<bool>,
+  /// The name pattern used when renaming identifiers, e.g. `[hash]_[local]`
+  pub pattern: Option<String>,
+  /// Source files matching these globs are treated as CSS modules
+  pub include: Option<GlobList>,
+  /// Source files matching these globs are never treated as CSS modules
+  pub exclude: Option<GlobList>,
+  pub animation: Option<bool>,
+  pub grid: Option<bool>,
+  pub custom_idents: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -14,8 +41,29 @@ pub enum CssModulesConfig {
   Full(CssModulesFullConfig),
 }
 
+#[derive(Debug, Default, Deserialize, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct DraftsConfig {
+  pub custom_media: Option<bool>,
+}
+
+/// Class names used to replace user action pseudo classes
+#[derive(Debug, Default, Deserialize, Hash)]
+#[serde(rename_all = "camelCase")]
+pub struct PseudoClassesConfig {
+  pub hover: Option<String>,
+  pub active: Option<String>,
+  pub focus: Option<String>,
+  pub focus_visible: Option<String>,
+  pub focus_within: Option<String>,
+}
+
 #[derive(Debug, Default, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CssTransformerConfig {
   pub css_modules: Option<CssModulesConfig>,
+  pub drafts: Option<DraftsConfig>,
+  pub pseudo_classes: Option<PseudoClassesConfig>,
+  /// Turns invalid rules and declarations into warnings rather than errors
+  pub error_recovery: Option<bool>,
 }
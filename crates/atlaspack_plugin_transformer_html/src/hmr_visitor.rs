@@ -5,17 +5,27 @@ use html5ever::namespace_url;
 use markup5ever::{QualName, expanded_name, local_name, ns};
 use markup5ever_rcdom::{Handle, Node, NodeData};
 
-use atlaspack_core::types::{Asset, DependencyBuilder, FileType, Priority, SpecifierType};
+use atlaspack_core::types::{
+  Asset, Code, Dependency, DependencyBuilder, FileType, JSONObject, Priority, SpecifierType,
+};
 
 use crate::{
   attrs::Attrs,
   dom_visitor::{DomTraversalOperation, DomVisitor},
+  html_dependencies_visitor::inline_asset_file_path,
   html_transformer::HTMLTransformationContext,
 };
 
+/// The unique key of the HMR asset, which the script dependency points to
+const HMR_SPECIFIER: &str = "hmr.js";
+
 /// Insert a tag for HMR and create its dependency and asset
+///
+/// The HMR runtime is added to every JavaScript bundle, so the tag only needs to be inserted when
+/// the page does not have any module scripts of its own.
 #[derive(Default)]
 pub struct HMRVisitor {
+  pub dependency: Option<Dependency>,
   pub hmr_asset: Option<Asset>,
   context: Rc<HTMLTransformationContext>,
 }
@@ -27,6 +37,25 @@ impl HMRVisitor {
       ..HMRVisitor::default()
     }
   }
+
+  fn create_hmr_asset(&self) -> Asset {
+    // The HMR runtime can only accept JavaScript and CSS updates, so the HTML hash is embedded in
+    // the script. Any change to the page then also changes this asset, which is never accepted
+    // and results in a full reload rather than a stale page.
+    let code = format!("// {}", self.context.source_hash);
+
+    Asset::new_inline(
+      Code::from(code),
+      self.context.env.clone(),
+      inline_asset_file_path(&self.context.source_path, &FileType::Js),
+      FileType::Js,
+      JSONObject::new(),
+      &self.context.project_root,
+      self.context.side_effects,
+      Some(String::from(HMR_SPECIFIER)),
+      None,
+    )
+  }
 }
 
 impl DomVisitor for HMRVisitor {
@@ -41,22 +70,17 @@ impl DomVisitor for HMRVisitor {
             let dependency = DependencyBuilder::default()
               .env(self.context.env.clone())
               .priority(Priority::Parallel)
-              .specifier("".to_owned())
+              .specifier(String::from(HMR_SPECIFIER))
               .specifier_type(SpecifierType::Url)
               .source_asset_id(self.context.source_asset_id.clone())
               .source_asset_type(FileType::Html)
               .source_path_option(self.context.source_path.clone())
               .build();
 
-            let src = dependency.id;
-
-            self.hmr_asset = Some(Asset {
-              file_type: FileType::Js,
-              unique_key: Some(src.clone()),
-              ..Asset::default()
-            });
+            attrs.set(expanded_name!("", "src"), &dependency.id());
 
-            attrs.set(expanded_name!("", "src"), &src);
+            self.hmr_asset = Some(self.create_hmr_asset());
+            self.dependency = Some(dependency);
           }
 
           let script_node = Node::new(NodeData::Element {
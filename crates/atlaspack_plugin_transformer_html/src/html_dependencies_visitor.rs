@@ -6,7 +6,8 @@ use std::sync::Arc;
 use atlaspack_core::types::{Environment, JSONObject};
 use html5ever::{ExpandedName, LocalName};
 use markup5ever::{expanded_name, local_name, namespace_url, ns};
-use markup5ever_rcdom::{Handle, NodeData};
+use markup5ever_rcdom::{Handle, Node, NodeData};
+use serde_json::Value;
 
 use atlaspack_core::{
   hash::IdentifierHasher,
@@ -22,6 +23,54 @@ use crate::{
   html_transformer::HTMLTransformationContext,
 };
 
+/// Meta tags that reference a url in their `content` attribute, keyed by the attribute that
+/// identifies them
+///
+/// Based on:
+/// - http://schema.org/
+/// - http://ogp.me
+/// - https://developer.twitter.com/en/docs/tweets/optimize-with-cards/overview/markup
+/// - https://msdn.microsoft.com/en-us/library/dn255024.aspx
+/// - https://vk.com/dev/publications
+const META_URL_PROPERTIES: &[(&str, &[&str])] = &[
+  (
+    "property",
+    &[
+      "og:image",
+      "og:image:url",
+      "og:image:secure_url",
+      "og:audio",
+      "og:audio:secure_url",
+      "og:video",
+      "og:video:secure_url",
+      "vk:image",
+    ],
+  ),
+  (
+    "name",
+    &[
+      "twitter:image",
+      "msapplication-square150x150logo",
+      "msapplication-square310x310logo",
+      "msapplication-square70x70logo",
+      "msapplication-wide310x150logo",
+      "msapplication-TileImage",
+      "msapplication-config",
+    ],
+  ),
+  (
+    "itemprop",
+    &[
+      "image",
+      "logo",
+      "screenshot",
+      "thumbnailUrl",
+      "contentUrl",
+      "downloadUrl",
+    ],
+  ),
+];
+
 /// Find all <script ...>, <link ...>, <a ...> etc. tags and create dependencies that correspond
 /// to them.
 #[derive(Default)]
@@ -29,6 +78,8 @@ pub struct HtmlDependenciesVisitor {
   context: Rc<HTMLTransformationContext>,
   pub dependencies: Vec<Dependency>,
   pub discovered_assets: Vec<AssetWithDependencies>,
+  /// Whether the page loads any module scripts, which will include the HMR runtime
+  pub has_module_scripts: bool,
 }
 
 impl HtmlDependenciesVisitor {
@@ -39,8 +90,8 @@ impl HtmlDependenciesVisitor {
     }
   }
 
-  fn add_url_dependency(&mut self, specifier: String) -> String {
-    let dependency = DependencyBuilder::default()
+  fn url_dependency(&self, specifier: String) -> DependencyBuilder {
+    DependencyBuilder::default()
       .env(self.context.env.clone())
       .priority(Priority::Lazy)
       .source_asset_id(self.context.source_asset_id.clone())
@@ -48,8 +99,9 @@ impl HtmlDependenciesVisitor {
       .source_path_option(self.context.source_path.clone())
       .specifier(specifier)
       .specifier_type(SpecifierType::Url)
-      .build();
+  }
 
+  fn add_dependency(&mut self, dependency: Dependency) -> String {
     let dependency_id = dependency.id();
 
     self.dependencies.push(dependency);
@@ -57,6 +109,11 @@ impl HtmlDependenciesVisitor {
     dependency_id
   }
 
+  fn add_url_dependency(&mut self, specifier: String) -> String {
+    let dependency = self.url_dependency(specifier).build();
+    self.add_dependency(dependency)
+  }
+
   fn add_resource(&mut self, attrs: &mut Attrs, name: ExpandedName) {
     if let Some(url) = attrs.get(name) {
       if url.starts_with("/") {
@@ -67,6 +124,128 @@ impl HtmlDependenciesVisitor {
     }
   }
 
+  /// Adds a dependency for each candidate url in a `srcset` style attribute
+  fn add_srcset(&mut self, attrs: &mut Attrs, name: &str) {
+    let local = LocalName::from(name);
+    let name = ExpandedName {
+      ns: &ns!(),
+      local: &local,
+    };
+
+    let Some(srcset) = attrs.get(name).map(|srcset| srcset.to_string()) else {
+      return;
+    };
+
+    let candidates = parse_srcset(&srcset)
+      .into_iter()
+      .map(|(url, descriptor)| {
+        let url = if url.starts_with("/") {
+          url.to_string()
+        } else {
+          self.add_url_dependency(url.to_string())
+        };
+
+        match descriptor {
+          "" => url,
+          descriptor => format!("{url} {descriptor}"),
+        }
+      })
+      .collect::<Vec<String>>();
+
+    attrs.set(name, &candidates.join(", "));
+  }
+
+  /// Adds dependencies for social media and application images referenced by meta tags
+  fn add_meta_dependency(&mut self, attrs: &mut Attrs) {
+    let content = expanded_name!("", "content");
+    let Some(url) = attrs.get(content).map(|url| url.to_string()) else {
+      return;
+    };
+
+    let name = attrs.get(expanded_name!("", "name")).map(|n| n.to_string());
+    if url.is_empty() || (name.as_deref() == Some("msapplication-config") && url == "none") {
+      return;
+    }
+
+    let is_url_property = META_URL_PROPERTIES.iter().any(|(attr, values)| {
+      let attr = ExpandedName {
+        ns: &ns!(),
+        local: &LocalName::from(*attr),
+      };
+
+      attrs
+        .get(attr)
+        .is_some_and(|value| values.contains(&&**value))
+    });
+
+    if !is_url_property {
+      return;
+    }
+
+    let needs_stable_name = !name.is_some_and(|name| name.contains("msapplication"));
+    let dependency = self
+      .url_dependency(url)
+      .needs_stable_name(needs_stable_name)
+      .build();
+
+    attrs.set(content, &self.add_dependency(dependency));
+  }
+
+  /// Replaces the urls in an import map with dependencies, so they point at the final bundles
+  fn add_import_map_dependencies(&mut self, node: &Handle) {
+    let Ok(mut import_map) = serde_json::from_slice::<Value>(&text_content(node)) else {
+      return;
+    };
+
+    let env = Arc::new(Environment {
+      output_format: OutputFormat::EsModule,
+      source_type: SourceType::Module,
+      ..(*self.context.env).clone()
+    });
+
+    self.add_import_map_entries(import_map.get_mut("imports"), &env);
+
+    if let Some(Value::Object(scopes)) = import_map.get_mut("scopes") {
+      for scope in scopes.values_mut() {
+        self.add_import_map_entries(Some(scope), &env);
+      }
+    }
+
+    let Ok(contents) = serde_json::to_string(&import_map) else {
+      return;
+    };
+
+    *node.children.borrow_mut() = vec![Node::new(NodeData::Text {
+      contents: std::cell::RefCell::new(contents.into()),
+    })];
+  }
+
+  fn add_import_map_entries(&mut self, imports: Option<&mut Value>, env: &Arc<Environment>) {
+    let Some(Value::Object(imports)) = imports else {
+      return;
+    };
+
+    for url in imports.values_mut() {
+      let Value::String(specifier) = url else {
+        continue;
+      };
+
+      // Package prefixes such as "lodash/" and remote urls cannot be bundled
+      if specifier.ends_with('/') || specifier.contains(':') {
+        continue;
+      }
+
+      let dependency = self
+        .url_dependency(specifier.clone())
+        .env(env.clone())
+        .is_esm(true)
+        .priority(Priority::Parallel)
+        .build();
+
+      *specifier = self.add_dependency(dependency);
+    }
+  }
+
   fn inline_asset_id(&self) -> String {
     let mut hasher = IdentifierHasher::default();
 
@@ -86,27 +265,49 @@ impl DomVisitor for HtmlDependenciesVisitor {
 
       match name.expanded() {
         expanded_name!(html "link") => {
-          // TODO: imagesrcset
+          self.add_srcset(&mut attrs, "imagesrcset");
+
           if let Some(href) = attrs.get(expanded_name!("", "href")) {
-            let rel = attrs.get(expanded_name!("", "rel"));
-            if rel.map(|r| r.to_string()).is_some_and(|r| r != "manifest") {
+            let is_manifest = attrs.get(expanded_name!("", "rel")).is_some_and(|rel| {
+              rel
+                .split_ascii_whitespace()
+                .any(|rel| rel.eq_ignore_ascii_case("manifest"))
+            });
+            if !is_manifest {
               return DomTraversalOperation::Continue;
             }
 
-            attrs.set(
-              expanded_name!("", "href"),
-              &self.add_url_dependency(href.to_string()),
-            );
+            // Web manifests are processed as their own asset type, so that the icons they
+            // reference are bundled. This also allows manifest.json rather than manifest.webmanifest.
+            let href = href.to_string();
+            let specifier = if href.contains(':') {
+              href
+            } else {
+              format!("webmanifest:{href}")
+            };
+
+            let dependency = self
+              .url_dependency(specifier)
+              .needs_stable_name(true)
+              .build();
+
+            attrs.set(expanded_name!("", "href"), &self.add_dependency(dependency));
           }
         }
-        // TODO: Handle meta
-        expanded_name!(html "meta") => {}
+        expanded_name!(html "meta") => {
+          self.add_meta_dependency(&mut attrs);
+        }
         expanded_name!(html "script") => {
           let type_attr = attrs.get(expanded_name!("", "type")).map(|t| t.to_string());
 
+          if type_attr.as_deref() == Some("importmap") {
+            self.add_import_map_dependencies(&node);
+            return DomTraversalOperation::Continue;
+          }
+
           if type_attr
             .as_ref()
-            .is_some_and(|t| matches!(t.as_str(), "application/json" | "importmap" | "text/html"))
+            .is_some_and(|t| matches!(t.as_str(), "application/json" | "text/html"))
           {
             return DomTraversalOperation::Continue;
           }
@@ -117,6 +318,10 @@ impl DomVisitor for HtmlDependenciesVisitor {
             SourceType::Script
           };
 
+          if source_type == SourceType::Module {
+            self.has_module_scripts = true;
+          }
+
           let mut output_format = OutputFormat::Global;
           if source_type == SourceType::Module && self.context.env.should_scope_hoist {
             output_format = OutputFormat::EsModule;
@@ -299,7 +504,7 @@ impl DomVisitor for HtmlDependenciesVisitor {
         }
         expanded_name!(html "img") | expanded_name!(html "source") => {
           self.add_resource(&mut attrs, expanded_name!("", "src"));
-          // TODO: srcset
+          self.add_srcset(&mut attrs, "srcset");
         }
         expanded_name!(html "audio")
         | expanded_name!(html "embed")
@@ -322,7 +527,60 @@ impl DomVisitor for HtmlDependenciesVisitor {
   }
 }
 
-fn inline_asset_file_path(source_asset_path: &Option<PathBuf>, file_type: &FileType) -> PathBuf {
+/// Splits a `srcset` attribute into its `(url, descriptor)` image candidates, following the
+/// HTML spec so that commas within urls (e.g. `data:` urls) or descriptors are preserved.
+///
+/// See <https://html.spec.whatwg.org/multipage/images.html#parse-a-srcset-attribute>
+fn parse_srcset(srcset: &str) -> Vec<(&str, &str)> {
+  let mut candidates = Vec::new();
+  let mut rest = srcset;
+
+  loop {
+    rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+    if rest.is_empty() {
+      return candidates;
+    }
+
+    let url_end = rest
+      .find(|c: char| c.is_ascii_whitespace())
+      .unwrap_or(rest.len());
+    let (url, remainder) = rest.split_at(url_end);
+
+    // A url ending in commas has no descriptors, and the commas separate it from the next candidate
+    if url.ends_with(',') {
+      candidates.push((url.trim_end_matches(','), ""));
+      rest = remainder;
+      continue;
+    }
+
+    let remainder = remainder.trim_start_matches(|c: char| c.is_ascii_whitespace());
+    let mut in_parens = false;
+    let descriptor_end = remainder
+      .char_indices()
+      .find(|(_, c)| match c {
+        '(' => {
+          in_parens = true;
+          false
+        }
+        ')' => {
+          in_parens = false;
+          false
+        }
+        ',' => !in_parens,
+        _ => false,
+      })
+      .map(|(index, _)| index)
+      .unwrap_or(remainder.len());
+
+    candidates.push((url, remainder[..descriptor_end].trim_end()));
+    rest = &remainder[descriptor_end..];
+  }
+}
+
+pub(crate) fn inline_asset_file_path(
+  source_asset_path: &Option<PathBuf>,
+  file_type: &FileType,
+) -> PathBuf {
   source_asset_path
     .clone()
     .unwrap_or_else(|| PathBuf::from(format!("index.{}", file_type.extension())))
@@ -1,3 +1,4 @@
+use std::hash::{Hash, Hasher};
 use std::io::BufReader;
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -13,19 +14,22 @@ use markup5ever_rcdom::{RcDom, SerializableHandle};
 use crate::dom_visitor::walk;
 use crate::hmr_visitor::HMRVisitor;
 use crate::html_dependencies_visitor::HtmlDependenciesVisitor;
+use atlaspack_core::hash::IdentifierHasher;
 use atlaspack_core::plugin::{PluginContext, TransformResult, TransformerPlugin};
 use atlaspack_core::types::{
-  Asset, AssetId, AssetWithDependencies, BundleBehavior, Code, Dependency, Environment,
+  Asset, AssetId, AssetWithDependencies, BuildMode, BundleBehavior, Code, Dependency, Environment,
 };
 
 #[derive(Debug, Hash)]
 pub struct AtlaspackHtmlTransformerPlugin {
+  enable_hmr: bool,
   project_root: PathBuf,
 }
 
 impl AtlaspackHtmlTransformerPlugin {
   pub fn new(ctx: &PluginContext) -> Self {
     AtlaspackHtmlTransformerPlugin {
+      enable_hmr: ctx.options.hmr_options.is_some() && ctx.options.mode != BuildMode::Production,
       project_root: ctx.options.project_root.clone(),
     }
   }
@@ -36,13 +40,16 @@ impl TransformerPlugin for AtlaspackHtmlTransformerPlugin {
   async fn transform(&self, input: Asset) -> Result<TransformResult, Error> {
     let bytes: &[u8] = input.code.bytes();
     let mut dom = parse_html(bytes)?;
+    let mut hasher = IdentifierHasher::default();
+    bytes.hash(&mut hasher);
+
     let context = HTMLTransformationContext {
-      // TODO: Where is this?
-      enable_hmr: false,
+      enable_hmr: self.enable_hmr,
       env: input.env.clone(),
       project_root: self.project_root.clone(),
       side_effects: input.side_effects,
       source_asset_id: input.id.clone(),
+      source_hash: hasher.finish(),
       source_path: Some(input.file_path.clone()),
     };
 
@@ -90,6 +97,8 @@ pub struct HTMLTransformationContext {
   pub project_root: PathBuf,
   pub side_effects: bool,
   pub source_asset_id: AssetId,
+  /// Hash of the HTML source, used to reload the page during HMR when it changes
+  pub source_hash: u64,
   pub source_path: Option<PathBuf>,
 }
 
@@ -113,29 +122,40 @@ fn run_html_transformations(
   let mut dependencies_visitor = HtmlDependenciesVisitor::new(context.clone());
   walk(node.clone(), &mut dependencies_visitor);
 
-  let discovered_assets = dependencies_visitor.discovered_assets;
+  let mut dependencies = dependencies_visitor.dependencies;
+  let mut discovered_assets = dependencies_visitor.discovered_assets;
 
-  if context.enable_hmr {
+  if context.enable_hmr && !dependencies_visitor.has_module_scripts {
     let mut hmr_visitor = HMRVisitor::new(context);
     walk(node.clone(), &mut hmr_visitor);
-    // TODO This creates an infinite loop, but should be added properly later
-    // if let Some(asset) = hmr_visitor.hmr_asset {
-    //   discovered_assets.push(asset);
-    // }
+
+    if let Some(dependency) = hmr_visitor.dependency {
+      dependencies.push(dependency);
+    }
+
+    if let Some(asset) = hmr_visitor.hmr_asset {
+      discovered_assets.push(AssetWithDependencies {
+        asset,
+        dependencies: Vec::new(),
+      });
+    }
   }
 
   HtmlTransformation {
-    dependencies: dependencies_visitor.dependencies,
+    dependencies,
     discovered_assets,
   }
 }
 
 #[cfg(test)]
 mod test {
+  use atlaspack_core::config_loader::ConfigLoader;
+  use atlaspack_core::plugin::{HmrOptions, PluginLogger, PluginOptions};
   use atlaspack_core::types::{
     DependencyBuilder, FileType, JSONObject, Priority, SourceType, SpecifierType,
   };
-  use pretty_assertions::assert_eq;
+  use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
+  use pretty_assertions::{assert_eq, assert_ne};
 
   use super::*;
 
@@ -181,22 +201,221 @@ mod test {
 
     let mut dom = parse_html(bytes.as_bytes()).unwrap();
 
-    run_html_transformations(transformation_context(), &mut dom);
+    let transformation = run_html_transformations(transformation_context(), &mut dom);
 
     let html = String::from_utf8(serialize_html(dom).unwrap()).unwrap();
+    let dependency = &transformation.dependencies[0];
 
+    assert_eq!(dependency.specifier, "webmanifest:manifest.json");
+    assert!(dependency.needs_stable_name);
     assert_eq!(
       &normalize_html(&html),
-      &normalize_html(
+      &normalize_html(&format!(
         r#"
           <html>
             <head>
-              <link href="492e1268e5326028" rel="manifest" />
+              <link href="{}" rel="manifest" />
             </head>
             <body></body>
           </html>
-        "#
-      )
+        "#,
+        dependency.id()
+      ))
+    );
+  }
+
+  #[test]
+  fn transforms_srcset_attributes() {
+    let bytes = html_body(
+      r#"
+        <picture>
+          <source srcset="small.webp 1x, large.webp 2x" />
+          <img src="image.png" srcset="/static.png 100w,image@2x.png 200w" />
+        </picture>
+      "#,
+    );
+
+    let mut dom = parse_html(bytes.as_bytes()).unwrap();
+
+    let transformation = run_html_transformations(transformation_context(), &mut dom);
+
+    let html = String::from_utf8(serialize_html(dom).unwrap()).unwrap();
+    let ids = transformation
+      .dependencies
+      .iter()
+      .map(|dependency| dependency.id())
+      .collect::<Vec<String>>();
+
+    assert_eq!(
+      transformation
+        .dependencies
+        .iter()
+        .map(|dependency| dependency.specifier.as_str())
+        .collect::<Vec<&str>>(),
+      vec!["small.webp", "large.webp", "image.png", "image@2x.png"]
+    );
+    assert_eq!(
+      &normalize_html(&html),
+      &normalize_html(&html_body(&format!(
+        r#"
+          <picture>
+            <source srcset="{} 1x, {} 2x" />
+            <img src="{}" srcset="/static.png 100w, {} 200w" />
+          </picture>
+        "#,
+        ids[0], ids[1], ids[2], ids[3]
+      )))
+    );
+  }
+
+  #[test]
+  fn ignores_link_tags_without_manifest_rel() {
+    let bytes = r#"
+      <html>
+        <head>
+          <link href="manifest.json" />
+          <link href="styles.css" rel="preload" />
+          <link href="app.webmanifest" rel="Manifest" />
+        </head>
+      </html>
+    "#;
+
+    let mut dom = parse_html(bytes.as_bytes()).unwrap();
+
+    let transformation = run_html_transformations(transformation_context(), &mut dom);
+
+    assert_eq!(
+      transformation
+        .dependencies
+        .iter()
+        .map(|dependency| dependency.specifier.as_str())
+        .collect::<Vec<&str>>(),
+      vec!["webmanifest:app.webmanifest"]
+    );
+  }
+
+  #[test]
+  fn transforms_srcset_attributes_with_commas_in_urls() {
+    let data_url = "data:image/png;base64,iVBORw0KGgo=";
+    let bytes = html_body(&format!(
+      r#"
+        <img srcset="{data_url} 1x,image@2x.png 2x, image@3x.png,, image@4x.png" />
+      "#
+    ));
+
+    let mut dom = parse_html(bytes.as_bytes()).unwrap();
+
+    let transformation = run_html_transformations(transformation_context(), &mut dom);
+
+    let html = String::from_utf8(serialize_html(dom).unwrap()).unwrap();
+    let ids = transformation
+      .dependencies
+      .iter()
+      .map(|dependency| dependency.id())
+      .collect::<Vec<String>>();
+
+    assert_eq!(
+      transformation
+        .dependencies
+        .iter()
+        .map(|dependency| dependency.specifier.as_str())
+        .collect::<Vec<&str>>(),
+      vec![data_url, "image@2x.png", "image@3x.png", "image@4x.png"]
+    );
+    assert_eq!(
+      &normalize_html(&html),
+      &normalize_html(&html_body(&format!(
+        r#"
+          <img srcset="{} 1x, {} 2x, {}, {}" />
+        "#,
+        ids[0], ids[1], ids[2], ids[3]
+      )))
+    );
+  }
+
+  #[test]
+  fn transforms_meta_images() {
+    let bytes = r#"
+      <html>
+        <head>
+          <meta property="og:image" content="og.png" />
+          <meta name="msapplication-TileImage" content="tile.png" />
+          <meta name="msapplication-config" content="none" />
+          <meta name="description" content="not-a-url.png" />
+        </head>
+      </html>
+    "#;
+
+    let mut dom = parse_html(bytes.as_bytes()).unwrap();
+
+    let transformation = run_html_transformations(transformation_context(), &mut dom);
+
+    let html = String::from_utf8(serialize_html(dom).unwrap()).unwrap();
+    let dependencies = transformation.dependencies;
+
+    assert_eq!(
+      dependencies
+        .iter()
+        .map(|dependency| (dependency.specifier.as_str(), dependency.needs_stable_name))
+        .collect::<Vec<(&str, bool)>>(),
+      vec![("og.png", true), ("tile.png", false)]
+    );
+    assert_eq!(
+      &normalize_html(&html),
+      &normalize_html(&format!(
+        r#"
+          <html>
+            <head>
+              <meta property="og:image" content="{}" />
+              <meta name="msapplication-TileImage" content="{}" />
+              <meta name="msapplication-config" content="none" />
+              <meta name="description" content="not-a-url.png" />
+            </head>
+            <body></body>
+          </html>
+        "#,
+        dependencies[0].id(),
+        dependencies[1].id()
+      ))
+    );
+  }
+
+  #[test]
+  fn transforms_import_maps() {
+    let bytes = html_body(
+      r#"
+        <script type="importmap">
+          {
+            "imports": { "app": "./app.js", "lodash/": "./node_modules/lodash/" },
+            "scopes": { "/legacy/": { "app": "./legacy.js", "cdn": "https://cdn.example.com/x.js" } }
+          }
+        </script>
+      "#,
+    );
+
+    let mut dom = parse_html(bytes.as_bytes()).unwrap();
+
+    let transformation = run_html_transformations(transformation_context(), &mut dom);
+
+    let html = String::from_utf8(serialize_html(dom).unwrap()).unwrap();
+    let dependencies = transformation.dependencies;
+
+    assert_eq!(
+      dependencies
+        .iter()
+        .map(|dependency| dependency.specifier.as_str())
+        .collect::<Vec<&str>>(),
+      vec!["./app.js", "./legacy.js"]
+    );
+    assert_eq!(dependencies[0].priority, Priority::Parallel);
+    assert_eq!(dependencies[0].env.source_type, SourceType::Module);
+    assert_eq!(
+      &normalize_html(&html),
+      &normalize_html(&html_body(&format!(
+        r#"<script type="importmap">{{"imports":{{"app":"{}","lodash/":"./node_modules/lodash/"}},"scopes":{{"/legacy/":{{"app":"{}","cdn":"https://cdn.example.com/x.js"}}}}}}</script>"#,
+        dependencies[0].id(),
+        dependencies[1].id()
+      )))
     );
   }
 
@@ -419,16 +638,99 @@ mod test {
 
     let mut dom = parse_html(bytes.as_bytes()).unwrap();
 
-    run_html_transformations(context, &mut dom);
+    let transformation = run_html_transformations(context, &mut dom);
 
     let html = String::from_utf8(serialize_html(dom).unwrap()).unwrap();
+    let dependency = &transformation.dependencies[0];
 
+    assert_eq!(dependency.specifier, "hmr.js");
+    assert_eq!(dependency.priority, Priority::Parallel);
+    assert_eq!(
+      transformation.discovered_assets[0].asset.unique_key,
+      Some(String::from("hmr.js"))
+    );
     assert_eq!(
       &normalize_html(&html),
-      &normalize_html(&html_body(r#"<script src="8321472594eb517f"></script>"#))
+      &normalize_html(&html_body(&format!(
+        r#"<script src="{}"></script>"#,
+        dependency.id()
+      )))
     );
   }
 
+  #[test]
+  fn changes_hmr_asset_when_html_changes() {
+    let hmr_code = |source_hash: u64| {
+      let context = HTMLTransformationContext {
+        enable_hmr: true,
+        source_hash,
+        ..transformation_context()
+      };
+
+      let mut dom = parse_html(html_body("").as_bytes()).unwrap();
+      let transformation = run_html_transformations(context, &mut dom);
+
+      transformation.discovered_assets[0]
+        .asset
+        .code
+        .as_str()
+        .unwrap()
+        .to_string()
+    };
+
+    assert_ne!(hmr_code(1), hmr_code(2));
+  }
+
+  #[test]
+  fn does_not_insert_hmr_tag_with_module_scripts() {
+    let bytes = html_body(r#"<script type="module" src="input.js"></script>"#);
+    let context = HTMLTransformationContext {
+      enable_hmr: true,
+      ..transformation_context()
+    };
+
+    let mut dom = parse_html(bytes.as_bytes()).unwrap();
+
+    let transformation = run_html_transformations(context, &mut dom);
+
+    assert_eq!(
+      transformation
+        .dependencies
+        .iter()
+        .map(|dependency| dependency.specifier.as_str())
+        .collect::<Vec<&str>>(),
+      vec!["input.js"]
+    );
+    assert_eq!(transformation.discovered_assets, Vec::new());
+  }
+
+  #[test]
+  fn enables_hmr_from_plugin_options() {
+    let create_plugin = |hmr_options: Option<HmrOptions>, mode: BuildMode| {
+      let file_system = Arc::new(InMemoryFileSystem::default());
+
+      AtlaspackHtmlTransformerPlugin::new(&PluginContext {
+        config: Arc::new(ConfigLoader {
+          fs: file_system.clone(),
+          project_root: PathBuf::default(),
+          search_path: PathBuf::default(),
+          files: Default::default(),
+        }),
+        file_system,
+        logger: PluginLogger::default(),
+        options: Arc::new(PluginOptions {
+          hmr_options,
+          mode,
+          ..PluginOptions::default()
+        }),
+      })
+    };
+
+    assert!(create_plugin(Some(HmrOptions::default()), BuildMode::Development).enable_hmr);
+    assert!(!create_plugin(Some(HmrOptions::default()), BuildMode::Production).enable_hmr);
+    assert!(!create_plugin(None, BuildMode::Development).enable_hmr);
+  }
+
   fn transformation_context() -> HTMLTransformationContext {
     let mut context = HTMLTransformationContext::default();
 
@@ -1356,6 +1356,7 @@ mod tests {
         fs: Arc::new(InMemoryFileSystem::default()),
         project_root: PathBuf::from("/"),
         search_path: PathBuf::from("/"),
+        files: Default::default(),
       }),
       file_system: Arc::new(InMemoryFileSystem::default()),
       logger: PluginLogger::default(),
@@ -2332,6 +2333,7 @@ mod tests {
         fs: file_system.clone(),
         project_root: project_root.to_path_buf(),
         search_path: project_root.to_path_buf(),
+        files: Default::default(),
       }),
       file_system: file_system.clone(),
       logger: PluginLogger::default(),
@@ -2414,6 +2416,7 @@ mod tests {
       fs: file_system,
       project_root: project_root.clone(),
       search_path,
+      files: Default::default(),
     });
 
     let config =
@@ -2447,6 +2450,7 @@ mod tests {
       fs: file_system,
       project_root: project_root.clone(),
       search_path,
+      files: Default::default(),
     });
 
     let config = AtlaspackJsTransformerPlugin::load_compiled_css_in_js_config(
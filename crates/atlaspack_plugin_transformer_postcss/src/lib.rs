@@ -0,0 +1,4 @@
+pub use postcss_transformer::AtlaspackPostcssTransformerPlugin;
+
+mod postcss_transformer;
+mod postcss_transformer_config;
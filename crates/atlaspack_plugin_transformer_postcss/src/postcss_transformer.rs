@@ -0,0 +1,696 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Error, anyhow};
+use async_trait::async_trait;
+use atlaspack_core::config_loader::ConfigFile;
+use atlaspack_core::diagnostic_error;
+use atlaspack_core::plugin::{PluginContext, PluginLogger, TransformResult, TransformerPlugin};
+use atlaspack_core::types::{
+  Asset, Code, CodeFrame, Diagnostic, DiagnosticBuilder, ErrorKind, File, SourceMap,
+};
+use atlassian_swc_compiled_css::{
+  StandaloneOptions, StandalonePlugin, resolve_browserslist_queries, resolve_browserslist_targets,
+  standalone_plugins,
+};
+use postcss::ProcessOptions;
+use postcss::source_map::{MapAnnotation, MapOptions};
+use serde_json::Value;
+
+use crate::postcss_transformer_config::{POSTCSS_CONFIG_FILES, PostcssConfig};
+
+/// Runs the plugins declared in a PostCSS config using native ports of those plugins
+///
+/// Only plugins that have a Rust implementation are supported. Any other plugin results in an
+/// error when the transformer is created, rather than the config being silently ignored. Plugin
+/// options that the native ports do not implement are reported as warnings.
+#[derive(Debug, Hash)]
+pub struct AtlaspackPostcssTransformerPlugin {
+  autoprefixer_browsers: Option<Vec<String>>,
+  config_path: Option<PathBuf>,
+  plugins: Vec<StandalonePlugin>,
+  project_root: PathBuf,
+  mode: String,
+  logger: PluginLogger,
+  warned_default_browsers: WarnOnce,
+}
+
+/// Tracks whether a warning has been logged, without contributing to the plugin cache key
+#[derive(Debug, Default)]
+struct WarnOnce(AtomicBool);
+
+impl WarnOnce {
+  /// Returns true the first time it is called
+  fn first(&self) -> bool {
+    !self.0.swap(true, Ordering::Relaxed)
+  }
+}
+
+impl std::hash::Hash for WarnOnce {
+  fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
+
+impl AtlaspackPostcssTransformerPlugin {
+  pub fn new(ctx: &PluginContext) -> Result<Self, Error> {
+    let mut autoprefixer_browsers = None;
+    let mut config_path = None;
+    let mut plugins = Vec::new();
+
+    if let Some(config) = Self::load_config(ctx)? {
+      for (name, options) in config.contents.plugins.enabled() {
+        let plugin = Self::native_plugin(name).ok_or_else(|| {
+          diagnostic_error!(
+            DiagnosticBuilder::default()
+              .code_frames(vec![CodeFrame::from(File {
+                contents: config.raw.clone(),
+                path: config.path.clone(),
+              })])
+              .hints(vec![
+                String::from(
+                  "Supported plugins are autoprefixer, postcss-nested, postcss-nesting and cssnano"
+                ),
+                String::from("Remove the plugin or use the JavaScript build to run it"),
+              ])
+              .message(format!(
+                "PostCSS plugin {name} in {} cannot be run natively",
+                config.path.display()
+              ))
+          )
+        })?;
+
+        let mut unsupported = Vec::new();
+        for (option, value) in Self::plugin_options(options, &mut unsupported) {
+          match (&plugin, option.as_str()) {
+            (StandalonePlugin::Autoprefixer, "overrideBrowserslist") => {
+              match Self::browserslist_queries(value) {
+                Some(queries) => autoprefixer_browsers = Some(queries),
+                None => unsupported.push(option),
+              }
+            }
+            (StandalonePlugin::Normalize, "preset") if Self::is_default_preset(value) => {}
+            _ => unsupported.push(option),
+          }
+        }
+
+        if !unsupported.is_empty() {
+          ctx.logger.warn(
+            DiagnosticBuilder::default()
+              .code_frames(vec![CodeFrame::from(File {
+                contents: config.raw.clone(),
+                path: config.path.clone(),
+              })])
+              .hints(vec![String::from(
+                "Remove the options or use the JavaScript build to apply them",
+              )])
+              .message(format!(
+                "PostCSS plugin {name} options {} in {} cannot be applied natively and are ignored",
+                unsupported.join(", "),
+                config.path.display()
+              ))
+              .origin(Some(String::from("@atlaspack/transformer-postcss")))
+              .build()?,
+          );
+        }
+
+        if !plugins.contains(&plugin) {
+          plugins.push(plugin);
+        }
+      }
+
+      config_path = Some(config.path);
+    }
+
+    Ok(AtlaspackPostcssTransformerPlugin {
+      autoprefixer_browsers,
+      config_path,
+      plugins,
+      project_root: ctx.options.project_root.clone(),
+      mode: ctx.options.mode.to_string(),
+      logger: ctx.logger.clone(),
+      warned_default_browsers: WarnOnce::default(),
+    })
+  }
+
+  /// Returns the named options of a plugin entry
+  ///
+  /// Options that are not an object, other than `true` to enable the plugin, are recorded as
+  /// unsupported.
+  fn plugin_options<'a>(
+    options: Option<&'a Value>,
+    unsupported: &mut Vec<String>,
+  ) -> Vec<(String, &'a Value)> {
+    match options {
+      None | Some(Value::Null) | Some(Value::Bool(true)) => Vec::new(),
+      Some(Value::Object(options)) => options
+        .iter()
+        .map(|(option, value)| (option.clone(), value))
+        .collect(),
+      Some(options) => {
+        unsupported.push(options.to_string());
+        Vec::new()
+      }
+    }
+  }
+
+  /// Reads a browserslist query, or list of queries, from an `overrideBrowserslist` option
+  fn browserslist_queries(value: &Value) -> Option<Vec<String>> {
+    match value {
+      Value::String(query) => Some(vec![query.clone()]),
+      Value::Array(queries) => queries
+        .iter()
+        .map(|query| query.as_str().map(str::to_string))
+        .collect(),
+      _ => None,
+    }
+  }
+
+  /// Whether a cssnano `preset` option selects the default preset without options, which is what
+  /// the native port implements
+  fn is_default_preset(value: &Value) -> bool {
+    let is_default =
+      |name: &Value| matches!(name.as_str(), Some("default" | "cssnano-preset-default"));
+
+    match value {
+      Value::Array(preset) => match preset.as_slice() {
+        [name] => is_default(name),
+        [name, options] => {
+          is_default(name)
+            && options
+              .as_object()
+              .is_some_and(|options| options.is_empty())
+        }
+        _ => false,
+      },
+      name => is_default(name),
+    }
+  }
+
+  /// Resolves the browsers autoprefixer targets for an asset
+  ///
+  /// `overrideBrowserslist` takes precedence, followed by the browsers the asset is built for and
+  /// then the project's browserslist config. When none of these resolve to any browsers, such as
+  /// for an invalid query, the browserslist defaults are used rather than disabling prefixing.
+  fn autoprefixer_targets(&self, asset: &Asset) -> Result<Vec<String>, Error> {
+    let targets = match (&self.autoprefixer_browsers, &asset.env.engines.browsers) {
+      (Some(queries), _) => resolve_browserslist_queries(queries),
+      (None, Some(browsers)) => resolve_browserslist_queries(&browsers.list()),
+      (None, None) => {
+        resolve_browserslist_targets(Some(self.project_root.as_path()), Some(self.mode.as_str()))
+      }
+    };
+
+    if !targets.is_empty() {
+      return Ok(targets);
+    }
+
+    if self.warned_default_browsers.first() {
+      self.logger.warn(
+        DiagnosticBuilder::default()
+          .hints(vec![String::from(
+            "Check the browserslist queries for the target, the project and overrideBrowserslist",
+          )])
+          .message(format!(
+            "No browsers were resolved for autoprefixer while transforming {}, so the browserslist defaults are used",
+            asset.file_path.display()
+          ))
+          .origin(Some(String::from("@atlaspack/transformer-postcss")))
+          .build()?,
+      );
+    }
+
+    Ok(resolve_browserslist_queries(&[String::from("defaults")]))
+  }
+
+  fn load_config(ctx: &PluginContext) -> Result<Option<ConfigFile<PostcssConfig>>, Error> {
+    for filename in POSTCSS_CONFIG_FILES {
+      match ctx.config.load_json_config::<PostcssConfig>(filename) {
+        Ok(config) => return Ok(Some(config)),
+        Err(err) => {
+          let not_found = err
+            .downcast_ref::<Diagnostic>()
+            .is_some_and(|d| d.kind == ErrorKind::NotFound);
+
+          if !not_found {
+            return Err(err);
+          }
+        }
+      }
+    }
+
+    Ok(None)
+  }
+
+  /// Converts the PostCSS map into an Atlaspack map and chains it onto the incoming map
+  fn source_map(
+    &self,
+    asset: &Asset,
+    map: &str,
+    original_map: Option<SourceMap>,
+  ) -> Result<SourceMap, Error> {
+    // PostCSS writes sources relative to the output file, but Atlaspack maps are relative to the
+    // project root, so point the only source back at the asset before converting
+    let mut json: serde_json::Value = serde_json::from_str(map)?;
+    json["sources"] = serde_json::json!([asset.file_path]);
+
+    let mut source_map = SourceMap::from_json(&self.project_root, &json.to_string())?;
+    if let Some(mut original_map) = original_map {
+      source_map.extends(&mut original_map)?;
+    }
+
+    Ok(source_map)
+  }
+
+  /// Maps a PostCSS plugin package name to its native implementation
+  fn native_plugin(name: &str) -> Option<StandalonePlugin> {
+    match name {
+      "autoprefixer" => Some(StandalonePlugin::Autoprefixer),
+      "postcss-nested" | "postcss-nesting" => Some(StandalonePlugin::Nested),
+      "cssnano" => Some(StandalonePlugin::Normalize),
+      _ => None,
+    }
+  }
+}
+
+#[async_trait]
+impl TransformerPlugin for AtlaspackPostcssTransformerPlugin {
+  fn should_skip(&self, _asset: &Asset) -> anyhow::Result<bool> {
+    Ok(self.plugins.is_empty())
+  }
+
+  async fn transform(&self, asset: Asset) -> Result<TransformResult, Error> {
+    let mut asset = asset;
+    let mut invalidate_on_file_change = Vec::new();
+
+    if let Some(config_path) = &self.config_path {
+      invalidate_on_file_change.push(config_path.clone());
+    }
+
+    if !self.plugins.is_empty() {
+      let autoprefixer_targets = if self.plugins.contains(&StandalonePlugin::Autoprefixer) {
+        self.autoprefixer_targets(&asset)?
+      } else {
+        Vec::new()
+      };
+
+      let processor = postcss::postcss_with_plugins(standalone_plugins(
+        &self.plugins,
+        &StandaloneOptions {
+          autoprefixer_targets,
+          browserslist_config_path: Some(self.project_root.clone()),
+          browserslist_env: Some(self.mode.clone()),
+        },
+      ));
+
+      let file_path = asset.file_path.to_string_lossy().into_owned();
+      let options = ProcessOptions::new()
+        .from_path(file_path.clone())
+        .to_path(file_path);
+
+      let options = if asset.env.source_map.is_some() {
+        options.enable_map_with(MapOptions {
+          annotation: MapAnnotation::Disabled,
+          inline: Some(false),
+          sources_content: Some(true),
+          ..MapOptions::default()
+        })
+      } else {
+        options.disable_map()
+      };
+
+      let to_error = |err: postcss::processor::ProcessorError| {
+        anyhow!(
+          "Failed to run PostCSS on {}: {err}",
+          asset.file_path.display()
+        )
+      };
+
+      let mut result = processor
+        .process_with_options(asset.code.as_str()?, options)
+        .map_err(to_error)?;
+
+      let css = result.css().map_err(to_error)?.to_string();
+      let map = result.map().map_err(to_error)?.map(str::to_string);
+
+      asset.map = match map {
+        Some(map) => Some(self.source_map(&asset, &map, asset.map.clone())?),
+        None => None,
+      };
+      asset.code = Code::from(css);
+    }
+
+    Ok(TransformResult {
+      asset,
+      invalidate_on_file_change,
+      ..Default::default()
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::Path;
+  use std::sync::Arc;
+
+  use atlaspack_core::config_loader::ConfigLoader;
+  use atlaspack_core::plugin::{PluginLogger, PluginOptions};
+  use atlaspack_core::types::engines::{Engines, EnginesBrowsers};
+  use atlaspack_core::types::{Environment, FileType, TargetSourceMapOptions};
+  use atlaspack_filesystem::FileSystemRef;
+  use atlaspack_filesystem::MockFileSystem;
+  use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn create_plugin(files: &[(&str, &str)]) -> anyhow::Result<AtlaspackPostcssTransformerPlugin> {
+    let file_system = Arc::new(InMemoryFileSystem::default());
+
+    for (path, contents) in files {
+      file_system.write_file(&Path::new("/project-root").join(path), contents.to_string());
+    }
+
+    create_plugin_with_fs(file_system)
+  }
+
+  fn create_plugin_with_fs(
+    file_system: FileSystemRef,
+  ) -> anyhow::Result<AtlaspackPostcssTransformerPlugin> {
+    let project_root = PathBuf::from("/project-root");
+
+    AtlaspackPostcssTransformerPlugin::new(&PluginContext {
+      config: Arc::new(ConfigLoader {
+        fs: file_system.clone(),
+        project_root: project_root.clone(),
+        search_path: project_root.clone(),
+        files: Default::default(),
+      }),
+      file_system,
+      logger: PluginLogger::default(),
+      options: Arc::new(PluginOptions {
+        project_root,
+        ..PluginOptions::default()
+      }),
+    })
+  }
+
+  fn css_asset(code: &str) -> Asset {
+    Asset {
+      file_path: "/project-root/styles.css".into(),
+      file_type: FileType::Css,
+      code: Code::from(code),
+      ..Default::default()
+    }
+  }
+
+  fn css_asset_with_env(code: &str, env: Environment) -> Asset {
+    Asset {
+      env: Arc::new(env),
+      ..css_asset(code)
+    }
+  }
+
+  #[test]
+  fn skips_assets_without_a_config() {
+    let plugin = create_plugin(&[]).unwrap();
+
+    assert!(plugin.should_skip(&css_asset(".a{}")).unwrap());
+  }
+
+  #[test]
+  fn maps_supported_plugins_in_declaration_order() {
+    let plugin = create_plugin(&[(
+      ".postcssrc",
+      r#"{ "plugins": { "postcss-nested": {}, "autoprefixer": {}, "cssnano": false } }"#,
+    )])
+    .unwrap();
+
+    assert_eq!(
+      plugin.plugins,
+      vec![StandalonePlugin::Nested, StandalonePlugin::Autoprefixer]
+    );
+    assert_eq!(
+      plugin.config_path.as_deref(),
+      Some(Path::new("/project-root/.postcssrc"))
+    );
+  }
+
+  #[test]
+  fn supports_the_array_plugin_format() {
+    let plugin = create_plugin(&[(
+      "postcss.config.json",
+      r#"{ "plugins": ["cssnano", ["postcss-nesting", {}]] }"#,
+    )])
+    .unwrap();
+
+    assert_eq!(
+      plugin.plugins,
+      vec![StandalonePlugin::Normalize, StandalonePlugin::Nested]
+    );
+  }
+
+  #[test]
+  fn errors_for_plugins_that_cannot_run_natively() {
+    let error = create_plugin(&[(".postcssrc.json", r#"{ "plugins": { "tailwindcss": {} } }"#)])
+      .map(|_| ())
+      .map_err(|err| err.to_string());
+
+    assert_eq!(
+      error,
+      Err(String::from(
+        "PostCSS plugin tailwindcss in /project-root/.postcssrc.json cannot be run natively"
+      ))
+    );
+  }
+
+  #[test]
+  fn propagates_errors_reading_the_config() {
+    let mut file_system = MockFileSystem::new();
+
+    file_system
+      .expect_is_file()
+      .returning(|path| path == Path::new("/project-root/.postcssrc"));
+    file_system
+      .expect_read_to_string()
+      .returning(|_| Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied)));
+
+    let error = create_plugin_with_fs(Arc::new(file_system))
+      .map(|_| ())
+      .map_err(|err| err.to_string());
+
+    assert_eq!(
+      error,
+      Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied).to_string())
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn prefixes_for_the_asset_browsers() {
+    let plugin = create_plugin(&[(".postcssrc", r#"{ "plugins": ["autoprefixer"] }"#)]).unwrap();
+    let env = |browsers: &str| Environment {
+      engines: Engines {
+        browsers: Some(EnginesBrowsers::String(browsers.to_string())),
+        ..Engines::default()
+      },
+      ..Environment::default()
+    };
+
+    let old_safari = plugin
+      .transform(css_asset_with_env(".a{user-select:none}", env("safari 9")))
+      .await
+      .unwrap();
+    let new_chrome = plugin
+      .transform(css_asset_with_env(
+        ".a{user-select:none}",
+        env("chrome 120"),
+      ))
+      .await
+      .unwrap();
+
+    assert!(
+      old_safari
+        .asset
+        .code
+        .as_str()
+        .unwrap()
+        .contains("-webkit-user-select")
+    );
+    assert!(
+      !new_chrome
+        .asset
+        .code
+        .as_str()
+        .unwrap()
+        .contains("-webkit-user-select")
+    );
+  }
+
+  #[test]
+  fn warns_for_plugin_options_that_cannot_be_applied_natively() {
+    let plugin = create_plugin(&[(
+      ".postcssrc",
+      r#"{
+        "plugins": {
+          "autoprefixer": { "overrideBrowserslist": ["safari 9"], "grid": "autoplace" },
+          "cssnano": { "preset": "advanced" },
+          "postcss-nested": true
+        }
+      }"#,
+    )])
+    .unwrap();
+
+    let warnings = plugin
+      .logger
+      .take_warnings()
+      .into_iter()
+      .map(|warning| warning.message)
+      .collect::<Vec<String>>();
+
+    assert_eq!(
+      warnings,
+      vec![
+        String::from(
+          "PostCSS plugin autoprefixer options grid in /project-root/.postcssrc cannot be applied natively and are ignored"
+        ),
+        String::from(
+          "PostCSS plugin cssnano options preset in /project-root/.postcssrc cannot be applied natively and are ignored"
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn accepts_the_default_cssnano_preset() {
+    let plugin = create_plugin(&[(
+      ".postcssrc",
+      r#"{ "plugins": [["cssnano", { "preset": ["default", {}] }]] }"#,
+    )])
+    .unwrap();
+
+    assert_eq!(plugin.plugins, vec![StandalonePlugin::Normalize]);
+    assert_eq!(plugin.logger.take_warnings(), Vec::new());
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn prefixes_for_the_override_browserslist() {
+    let plugin = create_plugin(&[(
+      ".postcssrc",
+      r#"{ "plugins": { "autoprefixer": { "overrideBrowserslist": "safari 9" } } }"#,
+    )])
+    .unwrap();
+
+    let result = plugin
+      .transform(css_asset_with_env(
+        ".a{user-select:none}",
+        Environment {
+          engines: Engines {
+            browsers: Some(EnginesBrowsers::String(String::from("chrome 120"))),
+            ..Engines::default()
+          },
+          ..Environment::default()
+        },
+      ))
+      .await
+      .unwrap();
+
+    assert!(
+      result
+        .asset
+        .code
+        .as_str()
+        .unwrap()
+        .contains("-webkit-user-select")
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn warns_once_and_uses_the_default_browsers_when_none_resolve() {
+    let plugin = create_plugin(&[(".postcssrc", r#"{ "plugins": ["autoprefixer"] }"#)]).unwrap();
+    let asset = || {
+      css_asset_with_env(
+        ".a{user-select:none}",
+        Environment {
+          engines: Engines {
+            browsers: Some(EnginesBrowsers::String(String::from("not a browser 1"))),
+            ..Engines::default()
+          },
+          ..Environment::default()
+        },
+      )
+    };
+
+    assert_eq!(
+      plugin.autoprefixer_targets(&asset()).unwrap(),
+      resolve_browserslist_queries(&[String::from("defaults")])
+    );
+    plugin.transform(asset()).await.unwrap();
+
+    let warnings = plugin.logger.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(
+      warnings[0].message,
+      "No browsers were resolved for autoprefixer while transforming /project-root/styles.css, so the browserslist defaults are used"
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn chains_the_incoming_source_map() {
+    let plugin = create_plugin(&[(".postcssrc", r#"{ "plugins": ["postcss-nested"] }"#)]).unwrap();
+    let original_map = SourceMap::from_json(
+      Path::new("/project-root"),
+      r#"{"version":3,"sources":["styles.scss"],"names":[],"mappings":"AAAA"}"#,
+    )
+    .unwrap();
+
+    let result = plugin
+      .transform(Asset {
+        map: Some(original_map),
+        ..css_asset_with_env(
+          ".a{color:red;.b{color:blue}}",
+          Environment {
+            source_map: Some(TargetSourceMapOptions::default()),
+            ..Environment::default()
+          },
+        )
+      })
+      .await
+      .unwrap();
+
+    let map = result.asset.map.expect("source map");
+    assert_eq!(map.get_sources(), &vec![String::from("styles.scss")]);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn generates_a_source_map_for_the_asset() {
+    let plugin = create_plugin(&[(".postcssrc", r#"{ "plugins": ["postcss-nested"] }"#)]).unwrap();
+
+    let result = plugin
+      .transform(css_asset_with_env(
+        ".a{color:red;.b{color:blue}}",
+        Environment {
+          source_map: Some(TargetSourceMapOptions::default()),
+          ..Environment::default()
+        },
+      ))
+      .await
+      .unwrap();
+
+    let map = result.asset.map.expect("source map");
+    assert_eq!(map.get_sources(), &vec![String::from("styles.css")]);
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn transforms_nested_css() {
+    let plugin = create_plugin(&[(".postcssrc", r#"{ "plugins": ["postcss-nested"] }"#)]).unwrap();
+
+    let result = plugin
+      .transform(css_asset(".a{color:red;.b{color:blue}}"))
+      .await
+      .unwrap();
+
+    assert!(result.asset.code.as_str().unwrap().contains(".a .b"));
+    assert_eq!(
+      result.invalidate_on_file_change,
+      vec![PathBuf::from("/project-root/.postcssrc")]
+    );
+  }
+}
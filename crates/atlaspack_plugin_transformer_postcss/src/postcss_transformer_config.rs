@@ -0,0 +1,59 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The config files that are searched for, in order of precedence
+///
+/// JavaScript configs (e.g. `postcss.config.js`) cannot be evaluated natively.
+pub const POSTCSS_CONFIG_FILES: &[&str] = &[".postcssrc", ".postcssrc.json", "postcss.config.json"];
+
+/// A plugin entry in the array form of the plugins config
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum PostcssPluginEntry {
+  Name(String),
+  WithOptions(String, Value),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum PostcssPlugins {
+  /// `{ "plugins": ["autoprefixer", ["postcss-nested", {}]] }`
+  List(Vec<PostcssPluginEntry>),
+  /// `{ "plugins": { "autoprefixer": {}, "cssnano": false } }`
+  Map(serde_json::Map<String, Value>),
+}
+
+impl Default for PostcssPlugins {
+  fn default() -> Self {
+    PostcssPlugins::List(Vec::new())
+  }
+}
+
+impl PostcssPlugins {
+  /// Returns the names and options of all enabled plugins, in the order they were declared
+  ///
+  /// Plugins configured with `false` are treated as disabled, matching postcss-load-config.
+  pub fn enabled(&self) -> Vec<(&str, Option<&Value>)> {
+    match self {
+      PostcssPlugins::List(entries) => entries
+        .iter()
+        .filter_map(|entry| match entry {
+          PostcssPluginEntry::Name(name) => Some((name.as_str(), None)),
+          PostcssPluginEntry::WithOptions(_, Value::Bool(false)) => None,
+          PostcssPluginEntry::WithOptions(name, options) => Some((name.as_str(), Some(options))),
+        })
+        .collect(),
+      PostcssPlugins::Map(plugins) => plugins
+        .iter()
+        .filter(|(_, options)| !matches!(options, Value::Bool(false)))
+        .map(|(name, options)| (name.as_str(), Some(options)))
+        .collect(),
+    }
+  }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PostcssConfig {
+  #[serde(default)]
+  pub plugins: PostcssPlugins,
+}
@@ -0,0 +1,3 @@
+pub use webmanifest_transformer::AtlaspackWebManifestTransformerPlugin;
+
+mod webmanifest_transformer;
@@ -0,0 +1,291 @@
+use anyhow::Error;
+use async_trait::async_trait;
+use atlaspack_core::diagnostic_error;
+use atlaspack_core::plugin::{PluginContext, TransformResult, TransformerPlugin};
+use atlaspack_core::types::{
+  Asset, Code, CodeFrame, CodeHighlight, Dependency, DependencyBuilder, DiagnosticBuilder,
+  ErrorKind, File, FileType, Priority, SpecifierType,
+};
+use serde_json::Value;
+
+/// Lists of resources with a `src` url
+const RESOURCE_LISTS: &[&str] = &["icons", "screenshots"];
+
+/// Lists of objects that each contain an `icons` resource list
+const NESTED_RESOURCE_LISTS: &[&str] = &["shortcuts", "file_handlers"];
+
+/// Processes web app manifests, adding dependencies for the icons, screenshots and start url
+///
+/// See https://developer.mozilla.org/en-US/docs/Web/Manifest
+#[derive(Debug, Hash)]
+pub struct AtlaspackWebManifestTransformerPlugin {}
+
+impl AtlaspackWebManifestTransformerPlugin {
+  pub fn new(_ctx: &PluginContext) -> Self {
+    AtlaspackWebManifestTransformerPlugin {}
+  }
+}
+
+struct WebManifestDependencies<'a> {
+  asset: &'a Asset,
+  dependencies: Vec<Dependency>,
+}
+
+impl WebManifestDependencies<'_> {
+  fn add_url_dependency(&mut self, specifier: &str) -> String {
+    let dependency = DependencyBuilder::default()
+      .env(self.asset.env.clone())
+      .priority(Priority::Lazy)
+      .source_asset_id(self.asset.id.clone())
+      .source_asset_type(FileType::Other(String::from("webmanifest")))
+      .source_path(self.asset.file_path.clone())
+      .specifier(specifier.to_string())
+      .specifier_type(SpecifierType::Url)
+      .build();
+
+    let dependency_id = dependency.id();
+    self.dependencies.push(dependency);
+    dependency_id
+  }
+
+  fn add_resource_list(&mut self, list: Option<&mut Value>, path: &str) -> Result<(), String> {
+    let Some(list) = list else {
+      return Ok(());
+    };
+
+    let Value::Array(resources) = list else {
+      return Err(format!("{path} must be an array"));
+    };
+
+    for (index, resource) in resources.iter_mut().enumerate() {
+      let src = match resource.get_mut("src") {
+        Some(Value::String(src)) if !src.is_empty() => src,
+        Some(Value::String(_)) => return Err(format!("{path}[{index}].src must not be empty")),
+        Some(_) => return Err(format!("{path}[{index}].src must be a string")),
+        None => return Err(format!("{path}[{index}].src is required")),
+      };
+
+      *src = self.add_url_dependency(src);
+    }
+
+    Ok(())
+  }
+
+  fn add_start_url(&mut self, manifest: &mut Value) {
+    if let Some(Value::String(start_url)) = manifest.get_mut("start_url") {
+      // Only files are bundled, so skip scopes such as "/" or "./" and absolute urls
+      let path = start_url.split(['#', '?']).next().unwrap_or_default();
+      if start_url.starts_with('/')
+        || start_url.contains(':')
+        || path.rfind('.').is_none_or(|index| index < 1)
+      {
+        return;
+      }
+
+      *start_url = self.add_url_dependency(start_url);
+    }
+  }
+}
+
+fn invalid_manifest(asset: &Asset, code: &str, message: String) -> Error {
+  diagnostic_error!(
+    DiagnosticBuilder::default()
+      .code_frames(vec![CodeFrame::from(File {
+        contents: code.to_string(),
+        path: asset.file_path.clone(),
+      })])
+      .message(format!("Invalid webmanifest: {message}"))
+      .origin(Some(String::from("@atlaspack/transformer-webmanifest")))
+  )
+}
+
+#[async_trait]
+impl TransformerPlugin for AtlaspackWebManifestTransformerPlugin {
+  async fn transform(&self, asset: Asset) -> Result<TransformResult, Error> {
+    let code = asset.code.as_str()?;
+    let mut manifest = serde_json::from_str::<Value>(code).map_err(|error| {
+      diagnostic_error!(
+        DiagnosticBuilder::default()
+          .kind(ErrorKind::ParseError)
+          .code_frames(vec![CodeFrame {
+            code_highlights: vec![CodeHighlight::from([error.line(), error.column()])],
+            ..CodeFrame::from(File {
+              contents: code.to_string(),
+              path: asset.file_path.clone(),
+            })
+          }])
+          .message(format!(
+            "Error parsing {}: {error}",
+            asset.file_path.display()
+          ))
+      )
+    })?;
+
+    if !manifest.is_object() {
+      return Err(invalid_manifest(
+        &asset,
+        code,
+        String::from("the manifest must be an object"),
+      ));
+    }
+
+    let mut collector = WebManifestDependencies {
+      asset: &asset,
+      dependencies: Vec::new(),
+    };
+
+    for key in RESOURCE_LISTS {
+      collector
+        .add_resource_list(manifest.get_mut(*key), key)
+        .map_err(|message| invalid_manifest(&asset, code, message))?;
+    }
+
+    for key in NESTED_RESOURCE_LISTS {
+      let Some(list) = manifest.get_mut(*key) else {
+        continue;
+      };
+
+      let Value::Array(entries) = list else {
+        return Err(invalid_manifest(
+          &asset,
+          code,
+          format!("{key} must be an array"),
+        ));
+      };
+
+      for (index, entry) in entries.iter_mut().enumerate() {
+        collector
+          .add_resource_list(entry.get_mut("icons"), &format!("{key}[{index}].icons"))
+          .map_err(|message| invalid_manifest(&asset, code, message))?;
+      }
+    }
+
+    collector.add_start_url(&mut manifest);
+
+    let dependencies = collector.dependencies;
+    let code = serde_json::to_string(&manifest)?;
+
+    Ok(TransformResult {
+      asset: Asset {
+        code: Code::from(code),
+        file_type: FileType::Other(String::from("webmanifest")),
+        ..asset
+      },
+      dependencies,
+      ..Default::default()
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{path::PathBuf, sync::Arc};
+
+  use atlaspack_core::{
+    config_loader::ConfigLoader,
+    plugin::{PluginLogger, PluginOptions},
+  };
+  use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
+  use pretty_assertions::assert_eq;
+
+  use super::*;
+
+  fn create_webmanifest_plugin() -> AtlaspackWebManifestTransformerPlugin {
+    let file_system = Arc::new(InMemoryFileSystem::default());
+
+    AtlaspackWebManifestTransformerPlugin::new(&PluginContext {
+      config: Arc::new(ConfigLoader {
+        fs: file_system.clone(),
+        project_root: PathBuf::default(),
+        search_path: PathBuf::default(),
+        files: Default::default(),
+      }),
+      file_system,
+      logger: PluginLogger::default(),
+      options: Arc::new(PluginOptions::default()),
+    })
+  }
+
+  fn manifest_asset(code: &str) -> Asset {
+    Asset {
+      id: String::from("manifest"),
+      file_path: PathBuf::from("manifest.json"),
+      file_type: FileType::Json,
+      code: Code::from(code.to_string()),
+      ..Asset::default()
+    }
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn adds_dependencies_for_manifest_resources() {
+    let plugin = create_webmanifest_plugin();
+
+    let result = plugin
+      .transform(manifest_asset(
+        r#"
+          {
+            "name": "App",
+            "start_url": "index.html",
+            "icons": [{ "src": "icon.png", "sizes": "192x192" }],
+            "screenshots": [{ "src": "screenshot.png" }],
+            "shortcuts": [{ "name": "Open", "icons": [{ "src": "shortcut.png" }] }]
+          }
+        "#,
+      ))
+      .await
+      .unwrap();
+
+    let specifiers = result
+      .dependencies
+      .iter()
+      .map(|dependency| dependency.specifier.as_str())
+      .collect::<Vec<&str>>();
+
+    assert_eq!(
+      specifiers,
+      vec!["icon.png", "screenshot.png", "shortcut.png", "index.html"]
+    );
+
+    let manifest = serde_json::from_str::<Value>(result.asset.code.as_str().unwrap()).unwrap();
+    assert_eq!(manifest["icons"][0]["src"], result.dependencies[0].id());
+    assert_eq!(manifest["icons"][0]["sizes"], "192x192");
+    assert_eq!(manifest["start_url"], result.dependencies[3].id());
+    assert_eq!(
+      result.asset.file_type,
+      FileType::Other(String::from("webmanifest"))
+    );
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn does_not_add_dependencies_for_start_url_scopes() {
+    let plugin = create_webmanifest_plugin();
+
+    for start_url in ["/", "./", ".", "https://example.com/app.html"] {
+      let result = plugin
+        .transform(manifest_asset(&format!(
+          r#"{{ "start_url": "{start_url}" }}"#
+        )))
+        .await
+        .unwrap();
+
+      assert_eq!(result.dependencies, Vec::new());
+    }
+  }
+
+  #[tokio::test(flavor = "multi_thread")]
+  async fn errors_for_resources_without_src() {
+    let plugin = create_webmanifest_plugin();
+
+    let error = plugin
+      .transform(manifest_asset(r#"{ "icons": [{ "sizes": "192x192" }] }"#))
+      .await
+      .map_err(|err| err.to_string());
+
+    assert_eq!(
+      error.map(|_| ()),
+      Err(String::from(
+        "Invalid webmanifest: icons[0].src is required"
+      ))
+    );
+  }
+}
@@ -52,6 +52,7 @@ mod tests {
         fs: file_system.clone(),
         project_root: PathBuf::default(),
         search_path: PathBuf::default(),
+        files: Default::default(),
       }),
       file_system,
       logger: PluginLogger::default(),
@@ -0,0 +1,54 @@
+use xxhash_rust::xxh3::xxh3_128;
+
+/// Generates a debug ID for a bundle from its contents
+///
+/// Debug IDs are formatted as UUIDs, following the source map debug ID proposal. They are derived
+/// from the bundle contents, so rebuilding the same bundle produces the same ID.
+pub fn generate_debug_id(contents: &[u8]) -> String {
+  let mut bytes = xxh3_128(contents).to_be_bytes();
+
+  // Mark the ID as a version 4 UUID with the RFC 4122 variant
+  bytes[6] = (bytes[6] & 0x0f) | 0x40;
+  bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+  let hex = bytes
+    .iter()
+    .map(|byte| format!("{byte:02x}"))
+    .collect::<String>();
+
+  format!(
+    "{}-{}-{}-{}-{}",
+    &hex[0..8],
+    &hex[8..12],
+    &hex[12..16],
+    &hex[16..20],
+    &hex[20..32]
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn generates_uuids() {
+    let debug_id = generate_debug_id(b"console.log('test');");
+    let groups = debug_id.split('-').map(str::len).collect::<Vec<_>>();
+
+    assert_eq!(groups, vec![8, 4, 4, 4, 12]);
+    assert_eq!(&debug_id[14..15], "4");
+    assert!(matches!(&debug_id[19..20], "8" | "9" | "a" | "b"));
+  }
+
+  #[test]
+  fn generates_the_same_id_for_the_same_contents() {
+    assert_eq!(
+      generate_debug_id(b"console.log('test');"),
+      generate_debug_id(b"console.log('test');")
+    );
+    assert_ne!(
+      generate_debug_id(b"console.log('test');"),
+      generate_debug_id(b"console.log('other');")
+    );
+  }
+}
@@ -1,14 +1,20 @@
+mod debug_id;
 mod find_source_map_url;
 mod load_source_map_url;
 mod mapping;
 mod mapping_line;
 mod source_map;
 mod sourcemap_error;
+mod symbolicate;
 mod utils;
 mod vlq_utils;
 
+pub use debug_id::generate_debug_id;
 pub use find_source_map_url::find_sourcemap_url;
 pub use load_source_map_url::load_sourcemap_url;
 pub use mapping::{Mapping, OriginalLocation};
 pub use source_map::SourceMap;
 pub use sourcemap_error::{SourceMapError, SourceMapErrorType};
+pub use symbolicate::{
+  OriginalFrame, StackFrame, StackTraceSymbolicator, SymbolicatedFrame, parse_stack_trace,
+};
@@ -5,7 +5,7 @@ use std::path::Path;
 use crate::mapping::{Mapping, OriginalLocation};
 use crate::mapping_line::MappingLine;
 use crate::sourcemap_error::{SourceMapError, SourceMapErrorType};
-use crate::utils::make_relative_path;
+use crate::utils::{is_abs_path, make_relative_path};
 use crate::vlq_utils::{is_mapping_separator, read_relative_vlq};
 use data_url::DataUrl;
 use rkyv::AlignedVec;
@@ -26,6 +26,9 @@ pub struct SourceMapInner {
   pub sources_content: Vec<String>,
   pub names: Vec<String>,
   pub mapping_lines: Vec<MappingLine>,
+  pub debug_id: Option<String>,
+  /// Sorted indexes of the sources that debuggers should hide from stack traces
+  pub ignore_list: Vec<u32>,
 }
 
 #[derive(Clone, Debug)]
@@ -339,6 +342,61 @@ impl SourceMap {
     &self.inner.sources_content
   }
 
+  pub fn set_debug_id(&mut self, debug_id: Option<String>) {
+    self.inner.debug_id = debug_id;
+  }
+
+  pub fn get_debug_id(&self) -> Option<&str> {
+    self.inner.debug_id.as_deref()
+  }
+
+  pub fn add_ignored_source(&mut self, source_index: u32) -> Result<(), SourceMapError> {
+    if source_index as usize >= self.inner.sources.len() {
+      return Err(SourceMapError::new(SourceMapErrorType::SourceOutOfRange));
+    }
+
+    if let Err(position) = self.inner.ignore_list.binary_search(&source_index) {
+      self.inner.ignore_list.insert(position, source_index);
+    }
+
+    Ok(())
+  }
+
+  pub fn is_source_ignored(&self, source_index: u32) -> bool {
+    self.inner.ignore_list.binary_search(&source_index).is_ok()
+  }
+
+  pub fn get_ignore_list(&self) -> &Vec<u32> {
+    &self.inner.ignore_list
+  }
+
+  /// Adds the sources outside the project root, or matching any of the globs, to the ignore list
+  ///
+  /// Globs are matched against source paths relative to the project root, such as
+  /// `**/node_modules/**`.
+  pub fn populate_ignore_list<I: AsRef<str>>(&mut self, globs: &[I]) -> Result<(), SourceMapError> {
+    let ignored_sources = self
+      .inner
+      .sources
+      .iter()
+      .enumerate()
+      .filter(|(_, source)| {
+        source.starts_with("../")
+          || is_abs_path(source)
+          || globs
+            .iter()
+            .any(|glob| glob_match::glob_match(glob.as_ref(), source))
+      })
+      .map(|(index, _)| index as u32)
+      .collect::<Vec<_>>();
+
+    for source_index in ignored_sources {
+      self.add_ignored_source(source_index)?;
+    }
+
+    Ok(())
+  }
+
   // Write the sourcemap instance to a buffer (for node-bindings compatibility)
   pub fn to_buffer(&self, output: &mut AlignedVec) -> Result<(), SourceMapError> {
     output.clear();
@@ -399,6 +457,13 @@ impl SourceMap {
       }
     }
 
+    let ignore_list = std::mem::take(&mut sourcemap.inner.ignore_list);
+    for ignored_source in ignore_list {
+      if let Some(source_index) = source_indexes.get(ignored_source as usize) {
+        self.add_ignored_source(*source_index)?;
+      }
+    }
+
     let mapping_lines = std::mem::take(&mut sourcemap.inner.mapping_lines);
     for (line, mapping_line) in mapping_lines.into_iter().enumerate() {
       let generated_line = (line as i64) + line_offset;
@@ -463,6 +528,12 @@ impl SourceMap {
       }
     }
 
+    for ignored_source in original_sourcemap.inner.ignore_list.iter() {
+      if let Some(source_index) = source_indexes.get(*ignored_source as usize) {
+        self.add_ignored_source(*source_index)?;
+      }
+    }
+
     for line_content in self.inner.mapping_lines.iter_mut() {
       for mapping in line_content.mappings.iter_mut() {
         let original_location_option = &mut mapping.original;
@@ -676,9 +747,23 @@ impl SourceMap {
       #[serde(borrow, default)]
       sources_content: Vec<Option<Cow<'a, str>>>,
       names: Vec<Cow<'a, str>>,
+      #[serde(default, alias = "debug_id")]
+      debug_id: Option<String>,
+      #[serde(default)]
+      ignore_list: Option<Vec<u32>>,
+      #[serde(default, rename = "x_google_ignoreList")]
+      x_google_ignore_list: Option<Vec<u32>>,
     }
 
     let json: JSONSourceMap = serde_json::from_str(input)?;
+    let ignored_sources = json
+      .ignore_list
+      .or(json.x_google_ignore_list)
+      .unwrap_or_default()
+      .iter()
+      .filter_map(|index| json.sources.get(*index as usize).cloned())
+      .collect::<Vec<_>>();
+
     let mut sources_content = Vec::with_capacity(json.sources.len());
     for i in 0..json.sources.len() {
       sources_content.push(if let Some(Some(content)) = json.sources_content.get(i) {
@@ -697,6 +782,14 @@ impl SourceMap {
       0,
       0,
     )?;
+
+    sm.set_debug_id(json.debug_id);
+    for ignored_source in ignored_sources {
+      if let Some(source_index) = sm.get_source_index(&ignored_source)? {
+        sm.add_ignored_source(source_index)?;
+      }
+    }
+
     Ok(sm)
   }
 
@@ -713,8 +806,19 @@ impl SourceMap {
       sources: &'a Vec<String>,
       sources_content: &'a Vec<String>,
       names: &'a Vec<String>,
+      #[serde(skip_serializing_if = "Option::is_none")]
+      debug_id: Option<&'a str>,
+      #[serde(skip_serializing_if = "Option::is_none")]
+      ignore_list: Option<&'a Vec<u32>>,
+      /// The name of `ignoreList` before it was standardised, which older versions of Chrome read
+      #[serde(
+        rename = "x_google_ignoreList",
+        skip_serializing_if = "Option::is_none"
+      )]
+      x_google_ignore_list: Option<&'a Vec<u32>>,
     }
 
+    let ignore_list = Some(self.get_ignore_list()).filter(|ignore_list| !ignore_list.is_empty());
     let sm = JSONSourceMap {
       version: 3,
       source_root,
@@ -722,6 +826,9 @@ impl SourceMap {
       sources: self.get_sources(),
       sources_content: self.get_sources_content(),
       names: self.get_names(),
+      debug_id: self.get_debug_id(),
+      ignore_list,
+      x_google_ignore_list: ignore_list,
     };
 
     Ok(serde_json::to_string(&sm)?)
@@ -869,6 +976,118 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn json_contains_debug_id_and_ignore_list() -> anyhow::Result<()> {
+    let json = r#"{
+      "mappings": "AAAA,SCAS",
+      "names": [],
+      "sources": ["src/index.js", "node_modules/react/index.js"],
+      "debugId": "85314830-023f-4cf1-a267-535f4e37bb17",
+      "ignoreList": [1]
+    }"#;
+
+    let mut source_map = SourceMap::from_json(&PathBuf::default(), json)?;
+    assert_eq!(
+      source_map.get_debug_id(),
+      Some("85314830-023f-4cf1-a267-535f4e37bb17")
+    );
+    assert_eq!(source_map.get_ignore_list(), &vec![1]);
+
+    let output: serde_json::Value = serde_json::from_str(&source_map.to_json(None)?)?;
+    assert_eq!(output["debugId"], "85314830-023f-4cf1-a267-535f4e37bb17");
+    assert_eq!(output["ignoreList"], serde_json::json!([1]));
+    assert_eq!(output["x_google_ignoreList"], serde_json::json!([1]));
+
+    Ok(())
+  }
+
+  #[test]
+  fn json_reads_legacy_ignore_list() -> anyhow::Result<()> {
+    let json = r#"{
+      "mappings": "AAAA,SCAS",
+      "names": [],
+      "sources": ["src/index.js", "node_modules/react/index.js"],
+      "x_google_ignoreList": [1]
+    }"#;
+
+    let source_map = SourceMap::from_json(&PathBuf::default(), json)?;
+    assert_eq!(source_map.get_ignore_list(), &vec![1]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn populates_ignore_list_from_globs_and_sources_outside_project() -> anyhow::Result<()> {
+    let mut source_map = SourceMap::new(Path::new("/project"));
+    source_map.add_sources(vec![
+      "/project/src/index.js",
+      "/project/node_modules/react/index.js",
+      "/shared/utils.js",
+      "/project/src/vendor/lib.js",
+    ]);
+
+    source_map.populate_ignore_list(&["**/node_modules/**", "src/vendor/**"])?;
+
+    assert_eq!(source_map.get_ignore_list(), &vec![1, 2, 3]);
+    assert!(!source_map.is_source_ignored(0));
+
+    Ok(())
+  }
+
+  #[test]
+  fn add_sourcemap_preserves_ignore_list() -> anyhow::Result<()> {
+    let mut source_map = SourceMap::new(Path::new("/project"));
+    source_map.add_source("/project/src/index.js");
+
+    let mut child_source_map = SourceMap::new(Path::new("/project"));
+    child_source_map.add_sources(vec![
+      "/project/node_modules/react/index.js",
+      "/project/src/index.js",
+    ]);
+    child_source_map.add_ignored_source(0)?;
+
+    source_map.add_sourcemap(&mut child_source_map, 0)?;
+
+    assert_eq!(
+      source_map.get_sources(),
+      &vec![
+        String::from("src/index.js"),
+        String::from("node_modules/react/index.js")
+      ]
+    );
+    assert_eq!(source_map.get_ignore_list(), &vec![1]);
+
+    Ok(())
+  }
+
+  #[test]
+  fn extends_preserves_ignore_list() -> anyhow::Result<()> {
+    let mut source_map = SourceMap::new(Path::new("/project"));
+    let source = source_map.add_source("/project/dist/index.js");
+    source_map.add_mapping(0, 0, Some(OriginalLocation::new(0, 0, source, None)));
+
+    let mut original_source_map = SourceMap::new(Path::new("/project"));
+    let original_source = original_source_map.add_source("/project/node_modules/react/index.js");
+    original_source_map.add_mapping(
+      0,
+      0,
+      Some(OriginalLocation::new(0, 0, original_source, None)),
+    );
+    original_source_map.add_ignored_source(original_source)?;
+
+    source_map.extends(&mut original_source_map)?;
+
+    assert_eq!(source_map.get_ignore_list(), &vec![1]);
+    assert_eq!(
+      source_map.get_mappings()[0]
+        .original
+        .map(|original| original.source),
+      Some(1)
+    );
+
+    Ok(())
+  }
+
   struct ExpectedSourceMap {
     json: String,
     mappings: Vec<Mapping>,
@@ -0,0 +1,495 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use atlaspack_filesystem::FileSystemRef;
+use regex::Regex;
+use serde::Serialize;
+
+use crate::{SourceMap, find_sourcemap_url, load_sourcemap_url};
+
+const DEFAULT_CONTEXT_LINES: usize = 5;
+
+/// A frame of a stack trace, with the 1-based line and column printed by the browser
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StackFrame {
+  pub function_name: Option<String>,
+  /// The bundle url or path as it appears in the stack trace
+  pub file: String,
+  pub line: u32,
+  pub column: u32,
+}
+
+/// The location in the original source that a stack frame maps to, with 1-based line and column
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OriginalFrame {
+  /// The source path as it appears in the source map
+  pub source: String,
+  pub line: u32,
+  pub column: u32,
+  pub function_name: Option<String>,
+  /// The source lines before `context_line`
+  pub pre_context: Vec<String>,
+  /// The source line containing the original location, when the source is available
+  pub context_line: Option<String>,
+  /// The source lines after `context_line`
+  pub post_context: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolicatedFrame {
+  pub generated: StackFrame,
+  /// `None` when the bundle or its source map could not be found, or the position is not mapped
+  pub original: Option<OriginalFrame>,
+}
+
+/// Matches V8 frames such as `at fn (https://example.com/index.js:1:2)` and `at index.js:1:2`
+static V8_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(r"^\s*at (?:(?<name>.+?) \()?(?<file>.+?):(?<line>\d+):(?<column>\d+)\)?$").unwrap()
+});
+
+/// Matches Firefox and Safari frames such as `fn@https://example.com/index.js:1:2`
+static GECKO_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+  Regex::new(r"^\s*(?:(?<name>[^@]*)@)?(?<file>.+?):(?<line>\d+):(?<column>\d+)$").unwrap()
+});
+
+/// Parses the frames of a V8, Firefox or Safari stack trace
+///
+/// Lines that aren't frames, such as the error message or native code frames, are skipped.
+pub fn parse_stack_trace(stack: &str) -> Vec<StackFrame> {
+  stack.lines().filter_map(parse_stack_frame).collect()
+}
+
+fn parse_stack_frame(line: &str) -> Option<StackFrame> {
+  let captures = if line.trim_start().starts_with("at ") {
+    V8_FRAME.captures(line)?
+  } else {
+    GECKO_FRAME.captures(line)?
+  };
+
+  let function_name = captures
+    .name("name")
+    .map(|name| name.as_str().trim_start_matches("async ").trim())
+    .filter(|name| !name.is_empty() && *name != "<anonymous>")
+    .map(String::from);
+
+  Some(StackFrame {
+    function_name,
+    file: captures["file"].to_string(),
+    line: captures["line"].parse().ok()?,
+    column: captures["column"].parse().ok()?,
+  })
+}
+
+/// Maps stack traces from production bundles back to their original sources
+///
+/// Bundles are looked up within the dist directory, and their source maps are found through their
+/// `sourceMappingURL` comment, falling back to `<bundle>.map`. Source maps are cached, so the same
+/// symbolicator can be reused for many stack traces from the same build.
+pub struct StackTraceSymbolicator {
+  fs: FileSystemRef,
+  project_root: PathBuf,
+  dist_dir: PathBuf,
+  context_lines: usize,
+  source_maps: HashMap<PathBuf, Option<SourceMap>>,
+}
+
+impl StackTraceSymbolicator {
+  pub fn new(fs: FileSystemRef, project_root: &Path, dist_dir: &Path) -> Self {
+    Self {
+      fs,
+      project_root: project_root.to_path_buf(),
+      dist_dir: dist_dir.to_path_buf(),
+      context_lines: DEFAULT_CONTEXT_LINES,
+      source_maps: HashMap::new(),
+    }
+  }
+
+  /// Sets how many source lines are included before and after each original location
+  pub fn with_context_lines(mut self, context_lines: usize) -> Self {
+    self.context_lines = context_lines;
+    self
+  }
+
+  pub fn symbolicate(&mut self, stack: &str) -> Vec<SymbolicatedFrame> {
+    let frames = parse_stack_trace(stack);
+    let locations = frames
+      .iter()
+      .map(|frame| self.find_original_location(frame))
+      .collect::<Vec<_>>();
+
+    frames
+      .into_iter()
+      .zip(&locations)
+      .enumerate()
+      .map(|(index, (frame, location))| {
+        let original = location.as_ref().map(|(original, _)| {
+          // The caller is positioned on the call expression, so its name is the original name of
+          // the function called by this frame
+          let caller_name = locations
+            .get(index + 1)
+            .and_then(|caller| caller.as_ref())
+            .and_then(|(_, name)| name.clone());
+
+          OriginalFrame {
+            function_name: caller_name.or_else(|| frame.function_name.clone()),
+            ..original.clone()
+          }
+        });
+
+        SymbolicatedFrame {
+          generated: frame,
+          original,
+        }
+      })
+      .collect()
+  }
+
+  /// Maps a frame to its original location, along with the original name at that location
+  fn find_original_location(
+    &mut self,
+    frame: &StackFrame,
+  ) -> Option<(OriginalFrame, Option<String>)> {
+    let bundle_path = self.resolve_bundle_path(&frame.file)?;
+    let source_map = self
+      .source_maps
+      .entry(bundle_path.clone())
+      .or_insert_with(|| load_bundle_source_map(&self.fs, &self.project_root, &bundle_path))
+      .as_mut()?;
+
+    let mapping = source_map
+      .find_closest_mapping(frame.line.checked_sub(1)?, frame.column.saturating_sub(1))?;
+    let original = mapping.original?;
+
+    let source = source_map.get_source(original.source).ok()?.to_string();
+    let name = original
+      .name
+      .and_then(|name| source_map.get_name(name).ok())
+      .map(String::from);
+
+    let source_content = match source_map.get_source_content(original.source) {
+      Ok(source_content) if !source_content.is_empty() => Some(source_content.to_string()),
+      _ => read_source(&self.fs, &self.project_root, &bundle_path, &source),
+    };
+
+    let (pre_context, context_line, post_context) = source_content
+      .map(|source_content| {
+        source_context(
+          &source_content,
+          original.original_line as usize,
+          self.context_lines,
+        )
+      })
+      .unwrap_or_default();
+
+    Some((
+      OriginalFrame {
+        source,
+        line: original.original_line + 1,
+        column: original.original_column + 1,
+        function_name: None,
+        pre_context,
+        context_line,
+        post_context,
+      },
+      name,
+    ))
+  }
+
+  /// Finds the bundle referenced by a stack frame
+  ///
+  /// Bundles served over http are matched by the longest suffix of the url path that exists
+  /// within the dist directory, as the public url may add leading segments.
+  fn resolve_bundle_path(&self, file: &str) -> Option<PathBuf> {
+    let file = file.split(['?', '#']).next().unwrap_or(file);
+    if let Some(path) = file.strip_prefix("file://") {
+      return Some(PathBuf::from(path));
+    }
+
+    if let Some((_, url)) = file.split_once("://") {
+      let (_, path) = url.split_once('/')?;
+      let segments = path
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .collect::<Vec<_>>();
+
+      return (0..segments.len())
+        .map(|start| self.dist_dir.join(segments[start..].join("/")))
+        .find(|bundle_path| self.fs.is_file(bundle_path));
+    }
+
+    let path = Path::new(file);
+    if path.is_absolute() {
+      Some(path.to_path_buf())
+    } else {
+      Some(self.dist_dir.join(path))
+    }
+  }
+}
+
+fn load_bundle_source_map(
+  fs: &FileSystemRef,
+  project_root: &Path,
+  bundle_path: &Path,
+) -> Option<SourceMap> {
+  let url = match fs
+    .read_to_string(bundle_path)
+    .ok()
+    .and_then(|code| find_sourcemap_url(&code))
+  {
+    Some(source_map_url) => source_map_url.url,
+    None => format!("{}.map", bundle_path.file_name()?.to_string_lossy()),
+  };
+
+  load_sourcemap_url(fs, project_root, bundle_path, &url).ok()
+}
+
+/// Reads a source that has no content in the source map, which may be relative to either the
+/// project root or the bundle
+fn read_source(
+  fs: &FileSystemRef,
+  project_root: &Path,
+  bundle_path: &Path,
+  source: &str,
+) -> Option<String> {
+  let bundle_dir = bundle_path.parent().unwrap_or(Path::new(""));
+
+  [project_root.join(source), bundle_dir.join(source)]
+    .iter()
+    .find_map(|source_path| fs.read_to_string(source_path).ok())
+}
+
+fn source_context(
+  source_content: &str,
+  line: usize,
+  context_lines: usize,
+) -> (Vec<String>, Option<String>, Vec<String>) {
+  let lines = source_content.lines().collect::<Vec<_>>();
+  let Some(context_line) = lines.get(line) else {
+    return Default::default();
+  };
+
+  let to_strings = |lines: &[&str]| lines.iter().map(|line| line.to_string()).collect();
+  let post_context_end = (line + 1 + context_lines).min(lines.len());
+
+  (
+    to_strings(&lines[line.saturating_sub(context_lines)..line]),
+    Some(context_line.to_string()),
+    to_strings(&lines[line + 1..post_context_end]),
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use atlaspack_filesystem::in_memory_file_system::InMemoryFileSystem;
+  use pretty_assertions::assert_eq;
+
+  use crate::OriginalLocation;
+
+  use super::*;
+
+  fn frame(function_name: Option<&str>, file: &str, line: u32, column: u32) -> StackFrame {
+    StackFrame {
+      function_name: function_name.map(String::from),
+      file: String::from(file),
+      line,
+      column,
+    }
+  }
+
+  #[test]
+  fn parses_v8_stack_traces() {
+    let stack = r"TypeError: Cannot read properties of undefined (reading 'id')
+    at r (https://example.com/assets/index.js:1:120)
+    at async Object.load (https://example.com/assets/index.js:1:240)
+    at https://example.com/assets/index.js:2:10
+    at Array.forEach (<anonymous>)";
+
+    assert_eq!(
+      parse_stack_trace(stack),
+      vec![
+        frame(Some("r"), "https://example.com/assets/index.js", 1, 120),
+        frame(
+          Some("Object.load"),
+          "https://example.com/assets/index.js",
+          1,
+          240
+        ),
+        frame(None, "https://example.com/assets/index.js", 2, 10),
+      ]
+    );
+  }
+
+  #[test]
+  fn parses_firefox_and_safari_stack_traces() {
+    let stack = r"r@https://example.com/assets/index.js:1:120
+@https://example.com/assets/index.js:2:10
+forEach@[native code]
+global code@https://example.com/assets/index.js:3:1";
+
+    assert_eq!(
+      parse_stack_trace(stack),
+      vec![
+        frame(Some("r"), "https://example.com/assets/index.js", 1, 120),
+        frame(None, "https://example.com/assets/index.js", 2, 10),
+        frame(
+          Some("global code"),
+          "https://example.com/assets/index.js",
+          3,
+          1
+        ),
+      ]
+    );
+  }
+
+  /// Writes a bundle where `n()` calls `t()` on the first line, built from this source
+  ///
+  /// ```js
+  /// function load() {
+  ///   throw new Error('boom');
+  /// }
+  ///
+  /// function main() {
+  ///   load();
+  /// }
+  /// ```
+  fn create_file_system(source_content: Option<&str>) -> FileSystemRef {
+    let source =
+      "function load() {\n  throw new Error('boom');\n}\n\nfunction main() {\n  load();\n}";
+    let bundle = "function t(){throw new Error('boom')}function n(){t()}";
+
+    let mut source_map = SourceMap::new(Path::new("/project"));
+    let source_index = source_map.add_source("/project/src/index.js");
+    let load_name = source_map.add_name("load");
+    let main_name = source_map.add_name("main");
+    if let Some(source_content) = source_content {
+      source_map
+        .set_source_content(source_index as usize, source_content)
+        .unwrap();
+    }
+
+    // function t(){
+    source_map.add_mapping(
+      0,
+      9,
+      Some(OriginalLocation::new(0, 9, source_index, Some(load_name))),
+    );
+    // throw new Error('boom')
+    source_map.add_mapping(0, 13, Some(OriginalLocation::new(1, 2, source_index, None)));
+    // function n(){
+    source_map.add_mapping(
+      0,
+      46,
+      Some(OriginalLocation::new(4, 9, source_index, Some(main_name))),
+    );
+    // t()
+    source_map.add_mapping(
+      0,
+      50,
+      Some(OriginalLocation::new(5, 2, source_index, Some(load_name))),
+    );
+
+    let fs = InMemoryFileSystem::default();
+    fs.write_file(
+      Path::new("/project/dist/index.js"),
+      format!("{bundle}\n//# sourceMappingURL=index.js.map"),
+    );
+    fs.write_file(
+      Path::new("/project/dist/index.js.map"),
+      source_map.to_json(None).unwrap(),
+    );
+    fs.write_file(Path::new("/project/src/index.js"), source.to_string());
+
+    Arc::new(fs)
+  }
+
+  #[test]
+  fn symbolicates_stack_traces() {
+    let mut symbolicator = StackTraceSymbolicator::new(
+      create_file_system(None),
+      Path::new("/project"),
+      Path::new("/project/dist"),
+    )
+    .with_context_lines(1);
+
+    let frames = symbolicator.symbolicate(
+      r"Error: boom
+    at t (https://cdn.example.com/static/index.js:1:14)
+    at n (https://cdn.example.com/static/index.js:1:51)",
+    );
+
+    assert_eq!(
+      frames,
+      vec![
+        SymbolicatedFrame {
+          generated: frame(Some("t"), "https://cdn.example.com/static/index.js", 1, 14),
+          original: Some(OriginalFrame {
+            source: String::from("src/index.js"),
+            line: 2,
+            column: 3,
+            function_name: Some(String::from("load")),
+            pre_context: vec![String::from("function load() {")],
+            context_line: Some(String::from("  throw new Error('boom');")),
+            post_context: vec![String::from("}")],
+          }),
+        },
+        SymbolicatedFrame {
+          generated: frame(Some("n"), "https://cdn.example.com/static/index.js", 1, 51),
+          original: Some(OriginalFrame {
+            source: String::from("src/index.js"),
+            line: 6,
+            column: 3,
+            function_name: Some(String::from("n")),
+            pre_context: vec![String::from("function main() {")],
+            context_line: Some(String::from("  load();")),
+            post_context: vec![String::from("}")],
+          }),
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn prefers_sources_content_from_the_source_map() {
+    let mut symbolicator = StackTraceSymbolicator::new(
+      create_file_system(Some("function load() {\n  throw 'inlined';\n}")),
+      Path::new("/project"),
+      Path::new("/project/dist"),
+    )
+    .with_context_lines(0);
+
+    let frames = symbolicator.symbolicate("t@/project/dist/index.js:1:14");
+    let original = frames[0].original.as_ref().unwrap();
+
+    assert_eq!(original.pre_context, Vec::<String>::new());
+    assert_eq!(
+      original.context_line,
+      Some(String::from("  throw 'inlined';"))
+    );
+    assert_eq!(original.post_context, Vec::<String>::new());
+  }
+
+  #[test]
+  fn leaves_frames_without_source_maps_unmapped() {
+    let mut symbolicator = StackTraceSymbolicator::new(
+      create_file_system(None),
+      Path::new("/project"),
+      Path::new("/project/dist"),
+    );
+
+    let frames = symbolicator.symbolicate("at vendor (https://example.com/vendor.js:1:1)");
+
+    assert_eq!(
+      frames,
+      vec![SymbolicatedFrame {
+        generated: frame(Some("vendor"), "https://example.com/vendor.js", 1, 1),
+        original: None,
+      }]
+    );
+  }
+}
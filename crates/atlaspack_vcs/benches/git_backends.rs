@@ -0,0 +1,115 @@
+use std::path::Path;
+use std::process::Command;
+
+use atlaspack_vcs::gix_integration::{
+  get_diff_with_gix, get_file_contents_at_commit_with_gix, get_status_with_gix, open_repository,
+  rev_parse_with_gix,
+};
+use atlaspack_vcs::{
+  get_diff_with_git_cli, get_file_contents_at_commit, get_status_with_git_cli, rev_parse,
+};
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+
+const NUM_DIRECTORIES: usize = 50;
+const FILES_PER_DIRECTORY: usize = 100;
+
+fn git(repo_path: &Path, args: &[&str]) {
+  let output = Command::new("git")
+    .args(args)
+    .current_dir(repo_path)
+    .output()
+    .unwrap();
+
+  assert!(output.status.success(), "git {args:?} failed: {output:?}");
+}
+
+/// Creates a repository with two commits that differ in every tenth file, and a work-tree
+/// with further uncommitted changes
+fn create_repository() -> (tempfile::TempDir, String, String) {
+  let temp_dir = tempfile::tempdir().unwrap();
+  let repo_path = temp_dir.path();
+
+  git(repo_path, &["init"]);
+  git(
+    repo_path,
+    &["config", "user.email", "test-user@atlassian.com"],
+  );
+  git(repo_path, &["config", "user.name", "test-user"]);
+
+  let write_files = |contents: &str, step: usize| {
+    for directory in 0..NUM_DIRECTORIES {
+      let directory_path = repo_path.join(format!("packages/package-{directory}/src"));
+      std::fs::create_dir_all(&directory_path).unwrap();
+
+      for file in (0..FILES_PER_DIRECTORY).step_by(step) {
+        std::fs::write(
+          directory_path.join(format!("file-{file}.js")),
+          format!("export default '{contents} {file}';"),
+        )
+        .unwrap();
+      }
+    }
+  };
+
+  write_files("initial", 1);
+  git(repo_path, &["add", "."]);
+  git(repo_path, &["commit", "-m", "Initial commit"]);
+  let old_commit = rev_parse(repo_path, "HEAD").unwrap();
+
+  write_files("changed", 10);
+  git(repo_path, &["add", "."]);
+  git(repo_path, &["commit", "-m", "Change files"]);
+  let new_commit = rev_parse(repo_path, "HEAD").unwrap();
+
+  write_files("dirty", 25);
+
+  (temp_dir, old_commit, new_commit)
+}
+
+fn benchmark_git_backends(c: &mut Criterion) {
+  let (temp_dir, old_commit, new_commit) = create_repository();
+  let repo_path = temp_dir.path();
+  let repo = open_repository(repo_path).unwrap();
+  let file_path = Path::new("packages/package-0/src/file-0.js");
+
+  let mut group = c.benchmark_group("rev_parse");
+  group.bench_function("cli", |b| {
+    b.iter(|| rev_parse(black_box(repo_path), "HEAD").unwrap())
+  });
+  group.bench_function("gix", |b| {
+    b.iter(|| rev_parse_with_gix(black_box(&repo), "HEAD").unwrap())
+  });
+  group.finish();
+
+  let mut group = c.benchmark_group("diff");
+  group.bench_function("cli", |b| {
+    b.iter(|| get_diff_with_git_cli(black_box(repo_path), &old_commit, &new_commit).unwrap())
+  });
+  group.bench_function("gix", |b| {
+    b.iter(|| get_diff_with_gix(black_box(&repo), &old_commit, &new_commit).unwrap())
+  });
+  group.finish();
+
+  let mut group = c.benchmark_group("status");
+  group.bench_function("cli", |b| {
+    b.iter(|| get_status_with_git_cli(black_box(repo_path)).unwrap())
+  });
+  group.bench_function("gix", |b| {
+    b.iter(|| get_status_with_gix(black_box(&repo)).unwrap())
+  });
+  group.finish();
+
+  let mut group = c.benchmark_group("file_contents_at_commit");
+  group.bench_function("cli", |b| {
+    b.iter(|| get_file_contents_at_commit(black_box(repo_path), &old_commit, file_path).unwrap())
+  });
+  group.bench_function("gix", |b| {
+    b.iter(|| {
+      get_file_contents_at_commit_with_gix(black_box(&repo), &old_commit, file_path).unwrap()
+    })
+  });
+  group.finish();
+}
+
+criterion_group!(benches, benchmark_git_backends);
+criterion_main!(benches);
@@ -1,4 +1,4 @@
-use atlaspack_vcs::{FailureMode, VCSState, get_changed_files};
+use atlaspack_vcs::{FailureMode, GitFallback, VCSState, get_changed_files};
 use clap::Parser;
 
 #[derive(Debug, clap::Parser)]
@@ -45,6 +45,7 @@ fn main() {
         &repository_root,
         &exclude,
         FailureMode::IgnoreMissingNodeModules,
+        GitFallback::Cli,
       )
       .unwrap();
       println!("{}", serde_json::to_string(&vcs_state).unwrap());
@@ -59,6 +60,7 @@ fn main() {
         &VCSState::from_git_hash(start_rev),
         end_rev.as_deref(),
         FailureMode::IgnoreMissingNodeModules,
+        GitFallback::Cli,
       )
       .unwrap();
 
@@ -0,0 +1,231 @@
+//! Native implementations of the git queries this crate makes, using `gitoxide`
+//!
+//! These mirror the `git` CLI commands used elsewhere in the crate, and return the same
+//! repository-relative paths, without spawning a process or parsing its output.
+
+use std::path::{Path, PathBuf};
+
+use gix::status::index_worktree::Item;
+use gix::status::plumbing::index_as_worktree::{Change, EntryStatus};
+
+use crate::FileChangeType;
+
+pub fn open_repository(repo_path: &Path) -> anyhow::Result<gix::Repository> {
+  Ok(gix::discover(repo_path)?)
+}
+
+/// Resolves a revision, such as `HEAD` or a branch name, to a commit hash
+pub fn rev_parse_with_gix(repo: &gix::Repository, rev: &str) -> anyhow::Result<String> {
+  Ok(repo.rev_parse_single(rev)?.detach().to_string())
+}
+
+/// Reads the contents of a file at a commit, or `None` if the file does not exist in it
+pub fn get_file_contents_at_commit_with_gix(
+  repo: &gix::Repository,
+  commit: &str,
+  path: &Path,
+) -> anyhow::Result<Option<String>> {
+  let tree = repo
+    .rev_parse_single(commit)?
+    .object()?
+    .peel_to_commit()?
+    .tree()?;
+
+  let Some(entry) = tree.lookup_entry_by_path(path)? else {
+    return Ok(None);
+  };
+
+  let blob = entry.object()?.try_into_blob()?;
+  Ok(Some(String::from_utf8(blob.detach().data)?))
+}
+
+/// Lists the files that differ between the trees of two commits, like
+/// `git diff --name-status --no-renames`
+pub fn get_diff_with_gix(
+  repo: &gix::Repository,
+  old_commit: &str,
+  new_commit: &str,
+) -> anyhow::Result<Vec<(PathBuf, FileChangeType)>> {
+  let tree = |rev: &str| -> anyhow::Result<gix::Tree<'_>> {
+    Ok(
+      repo
+        .rev_parse_single(rev)?
+        .object()?
+        .peel_to_commit()?
+        .tree()?,
+    )
+  };
+
+  let old_tree = tree(old_commit)?;
+  let new_tree = tree(new_commit)?;
+
+  let changes = repo.diff_tree_to_tree(&old_tree, &new_tree, gix::diff::Options::default())?;
+
+  let mut results = Vec::new();
+  for change in changes {
+    // Directories are listed alongside their contents, while git only lists files
+    if change.entry_mode().is_tree() {
+      continue;
+    }
+
+    let change_type = match change {
+      gix::object::tree::diff::ChangeDetached::Addition { .. } => FileChangeType::Create,
+      gix::object::tree::diff::ChangeDetached::Deletion { .. } => FileChangeType::Delete,
+      _ => FileChangeType::Update,
+    };
+
+    results.push((path_from_bytes(change.location())?, change_type));
+  }
+
+  Ok(results)
+}
+
+/// Lists the tracked files that differ between the index and the work-tree, like the second
+/// column of `git status --porcelain`
+///
+/// Untracked files are not listed, and submodules are not checked for changes.
+pub fn get_status_with_gix(
+  repo: &gix::Repository,
+) -> anyhow::Result<Vec<(PathBuf, FileChangeType)>> {
+  let status = repo
+    .status(gix::progress::Discard)?
+    .untracked_files(gix::status::UntrackedFiles::None)
+    .index_worktree_submodules(None)
+    .into_index_worktree_iter(Vec::new())?;
+
+  let mut results = Vec::new();
+  for item in status {
+    let Item::Modification {
+      rela_path, status, ..
+    } = item?
+    else {
+      continue;
+    };
+
+    let change_type = match status {
+      EntryStatus::Change(Change::Removed) => FileChangeType::Delete,
+      EntryStatus::Change(_) => FileChangeType::Update,
+      EntryStatus::IntentToAdd => FileChangeType::Create,
+      EntryStatus::Conflict { .. } | EntryStatus::NeedsUpdate(_) => continue,
+    };
+
+    results.push((path_from_bytes(rela_path.as_ref())?, change_type));
+  }
+
+  Ok(results)
+}
+
+fn path_from_bytes(path: &gix::bstr::BStr) -> anyhow::Result<PathBuf> {
+  Ok(gix::path::try_from_bstr(path)?.into_owned())
+}
+
+#[cfg(test)]
+mod test {
+  use std::process::Command;
+
+  use super::*;
+
+  fn git(repo_path: &Path, args: &[&str]) {
+    let output = Command::new("git")
+      .args(args)
+      .current_dir(repo_path)
+      .output()
+      .unwrap();
+
+    assert!(output.status.success(), "git {args:?} failed: {output:?}");
+  }
+
+  fn create_repo() -> tempfile::TempDir {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let repo_path = temp_dir.path();
+
+    git(repo_path, &["init"]);
+    git(
+      repo_path,
+      &["config", "user.email", "test-user@atlassian.com"],
+    );
+    git(repo_path, &["config", "user.name", "test-user"]);
+
+    std::fs::create_dir_all(repo_path.join("src")).unwrap();
+    std::fs::write(repo_path.join("src/a.js"), "a").unwrap();
+    std::fs::write(repo_path.join("src/b.js"), "b").unwrap();
+    git(repo_path, &["add", "."]);
+    git(repo_path, &["commit", "-m", "Initial commit"]);
+
+    temp_dir
+  }
+
+  #[test]
+  fn test_rev_parse_with_gix() {
+    let temp_dir = create_repo();
+    let repo = open_repository(temp_dir.path()).unwrap();
+
+    assert_eq!(
+      rev_parse_with_gix(&repo, "HEAD").unwrap(),
+      crate::rev_parse(temp_dir.path(), "HEAD").unwrap()
+    );
+  }
+
+  #[test]
+  fn test_get_file_contents_at_commit_with_gix() {
+    let temp_dir = create_repo();
+    let repo = open_repository(temp_dir.path()).unwrap();
+
+    assert_eq!(
+      get_file_contents_at_commit_with_gix(&repo, "HEAD", Path::new("src/a.js")).unwrap(),
+      Some(String::from("a"))
+    );
+    assert_eq!(
+      get_file_contents_at_commit_with_gix(&repo, "HEAD", Path::new("src/c.js")).unwrap(),
+      None
+    );
+  }
+
+  #[test]
+  fn test_get_diff_with_gix() {
+    let temp_dir = create_repo();
+    let repo_path = temp_dir.path();
+    let old_commit = crate::rev_parse(repo_path, "HEAD").unwrap();
+
+    std::fs::write(repo_path.join("src/a.js"), "a2").unwrap();
+    std::fs::remove_file(repo_path.join("src/b.js")).unwrap();
+    std::fs::create_dir_all(repo_path.join("src/nested")).unwrap();
+    std::fs::write(repo_path.join("src/nested/c.js"), "c").unwrap();
+    git(repo_path, &["add", "-A"]);
+    git(repo_path, &["commit", "-m", "Change files"]);
+
+    let repo = open_repository(repo_path).unwrap();
+    let new_commit = rev_parse_with_gix(&repo, "HEAD").unwrap();
+
+    assert_eq!(
+      get_diff_with_gix(&repo, &old_commit, &new_commit).unwrap(),
+      vec![
+        (PathBuf::from("src/a.js"), FileChangeType::Update),
+        (PathBuf::from("src/b.js"), FileChangeType::Delete),
+        (PathBuf::from("src/nested/c.js"), FileChangeType::Create),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_get_status_with_gix() {
+    let temp_dir = create_repo();
+    let repo_path = temp_dir.path();
+
+    std::fs::write(repo_path.join("src/a.js"), "a2").unwrap();
+    std::fs::remove_file(repo_path.join("src/b.js")).unwrap();
+    std::fs::write(repo_path.join("src/untracked.js"), "untracked").unwrap();
+
+    let repo = open_repository(repo_path).unwrap();
+    let mut status = get_status_with_gix(&repo).unwrap();
+    status.sort_by(|a, b| a.0.cmp(&b.0));
+
+    assert_eq!(
+      status,
+      vec![
+        (PathBuf::from("src/a.js"), FileChangeType::Update),
+        (PathBuf::from("src/b.js"), FileChangeType::Delete),
+      ]
+    );
+  }
+}
@@ -1,4 +1,4 @@
-//! This crate provides integration with `git` and `yarn.lock` such that
+//! This crate provides integration with `git` and package manager lockfiles such that
 //! **atlaspack** can perform cache invalidation based on version-control
 //! information, as opposed to filesystem events.
 //!
@@ -36,6 +36,20 @@
 //!     - 'node_modules/lodash'
 //! ```
 //!
+//! ## npm and pnpm lockfiles
+//!
+//! `npm` 7 and above writes `package-lock.json` files, whose `packages` map is keyed
+//! by install location, such as `node_modules/lodash`, so changed locations can be
+//! read from the lockfile without a state file.
+//!
+//! `pnpm` writes `pnpm-lock.yaml` files keyed by **dependency path**, such as
+//! `react-dom@18.3.1(react@18.3.1)`. Each dependency path is installed into its own
+//! directory within the virtual store, `node_modules/.pnpm/react-dom@18.3.1_react@18.3.1`,
+//! so changed dependency paths are mapped to those directories.
+//!
+//! Both are diffed between revisions like `yarn.lock` files, and produce the same
+//! events for the files within changed packages.
+//!
 //! ## Overview
 //!
 //! The overall idea would be to modify the `getEventsSince` and `writeSnapshot`
@@ -85,16 +99,20 @@
 //!
 //! ### git integration
 //!
-//! The crate integrates with `libgit` and the `git` binary. Currently `libgit` is
-//! linked dynamically with the binary, which means it must be present on the client
-//! machine, but doesn't require us to bump `atlaspack` whenever security fixes are
-//! done to `git`.
+//! The crate queries git natively with `gitoxide`, which avoids spawning a process
+//! for each query and parsing output that varies across git versions and locales.
+//! The `git` binary is retained as a fallback, which is used when `gitoxide` can't
+//! open the repository or a query fails, unless `GitFallback::Disabled` is given. The
+//! `git_backends` benchmark compares the two.
+//!
+//! Listing dirty and lockfile paths with `git ls-files` still uses the `git` binary.
 //!
 //! Git is used to:
 //!
 //! - List files that are dirty/untracked/removed/modified in a repository
 //! - Diff two revisions to find changed files between revisions
-//! - Get the contents of the `yarn.lock` files at different revisions
+//! - Resolve revisions to commit hashes
+//! - Get the contents of lockfiles at different revisions
 //!
 //! # Roll-out and validation
 //!
@@ -113,6 +131,8 @@
 //!
 
 use anyhow::anyhow;
+use npm_integration::{NpmLock, parse_npm_lock};
+use pnpm_integration::{PnpmLock, parse_pnpm_lock};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -124,6 +144,9 @@ use std::{
 };
 use yarn_integration::{YarnLock, YarnStateFile, parse_yarn_lock, parse_yarn_state_file};
 
+pub mod gix_integration;
+pub mod npm_integration;
+pub mod pnpm_integration;
 pub mod yarn_integration;
 
 /// A snapshot of the current VCS state of the repository.
@@ -132,7 +155,7 @@ pub mod yarn_integration;
 ///
 /// * Content hashes of dirty files
 /// * The current git revision
-/// * All yarn lockfile states found
+/// * All yarn, npm and pnpm lockfile states found
 ///
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -141,7 +164,18 @@ pub struct VCSState {
   pub dirty_files: Vec<VCSFile>,
   pub dirty_files_execution_time: u32,
   pub yarn_states: Vec<YarnSnapshot>,
+  /// Time taken to list the yarn lockfile states
   pub yarn_states_execution_time: u32,
+  #[serde(default)]
+  pub npm_states: Vec<NpmSnapshot>,
+  /// Time taken to list the npm lockfile states
+  #[serde(default)]
+  pub npm_states_execution_time: u32,
+  #[serde(default)]
+  pub pnpm_states: Vec<PnpmSnapshot>,
+  /// Time taken to list the pnpm lockfile states
+  #[serde(default)]
+  pub pnpm_states_execution_time: u32,
 }
 
 impl VCSState {
@@ -152,6 +186,10 @@ impl VCSState {
       dirty_files_execution_time: 0,
       yarn_states: vec![],
       yarn_states_execution_time: 0,
+      npm_states: vec![],
+      npm_states_execution_time: 0,
+      pnpm_states: vec![],
+      pnpm_states_execution_time: 0,
     }
   }
 
@@ -161,26 +199,20 @@ impl VCSState {
     path: &Path,
     exclude_patterns: &[String],
     failure_mode: FailureMode,
+    git_fallback: GitFallback,
   ) -> anyhow::Result<VCSState> {
     tracing::info!("Reading VCS state");
-    let git_hash = rev_parse(path, "HEAD")?;
+    let git_hash = Git::open(path, git_fallback)?.rev_parse("HEAD")?;
     tracing::info!("Found head commit");
-    let files_listing_start_time = Instant::now();
-    let file_listing = vcs_list_dirty_files(path, exclude_patterns)?;
-    let files_listing_duration = files_listing_start_time
-      .elapsed()
-      .as_millis()
-      .try_into()
-      .unwrap_or(u32::MAX);
+    let (file_listing, files_listing_duration) =
+      timed(|| vcs_list_dirty_files(path, exclude_patterns))?;
     tracing::info!("Listed dirty files in: {:?} ms", files_listing_duration);
-    let yarn_states_start_time = Instant::now();
-    let yarn_states = list_yarn_states(path, failure_mode)?;
-    let yarn_states_duration = yarn_states_start_time
-      .elapsed()
-      .as_millis()
-      .try_into()
-      .unwrap_or(u32::MAX);
+    let (yarn_states, yarn_states_duration) = timed(|| list_yarn_states(path, failure_mode))?;
     tracing::info!("Listed yarn states in: {:?} ms", yarn_states_duration);
+    let (npm_states, npm_states_duration) = timed(|| list_npm_states(path, failure_mode))?;
+    tracing::info!("Listed npm states in: {:?} ms", npm_states_duration);
+    let (pnpm_states, pnpm_states_duration) = timed(|| list_pnpm_states(path, failure_mode))?;
+    tracing::info!("Listed pnpm states in: {:?} ms", pnpm_states_duration);
 
     Ok(VCSState {
       git_hash,
@@ -188,10 +220,27 @@ impl VCSState {
       dirty_files_execution_time: files_listing_duration,
       yarn_states,
       yarn_states_execution_time: yarn_states_duration,
+      npm_states,
+      npm_states_execution_time: npm_states_duration,
+      pnpm_states,
+      pnpm_states_execution_time: pnpm_states_duration,
     })
   }
 }
 
+/// Runs a fallible step, returning its result along with how many milliseconds it took
+fn timed<T>(step: impl FnOnce() -> anyhow::Result<T>) -> anyhow::Result<(T, u32)> {
+  let start_time = Instant::now();
+  let result = step()?;
+  let duration = start_time
+    .elapsed()
+    .as_millis()
+    .try_into()
+    .unwrap_or(u32::MAX);
+
+  Ok((result, duration))
+}
+
 #[tracing::instrument(level = "info", skip_all)]
 pub fn list_yarn_states(
   repo: &Path,
@@ -264,6 +313,81 @@ pub struct YarnSnapshot {
   pub yarn_state: YarnStateFile,
 }
 
+#[tracing::instrument(level = "info", skip_all)]
+pub fn list_npm_states(repo: &Path, failure_mode: FailureMode) -> anyhow::Result<Vec<NpmSnapshot>> {
+  let npm_states = list_lockfiles(repo, "package-lock.json", failure_mode, parse_npm_lock)?
+    .into_iter()
+    .map(|(npm_lock_path, npm_lock)| NpmSnapshot {
+      npm_lock_path,
+      npm_lock,
+    })
+    .collect();
+
+  Ok(npm_states)
+}
+
+#[tracing::instrument(level = "info", skip_all)]
+pub fn list_pnpm_states(
+  repo: &Path,
+  failure_mode: FailureMode,
+) -> anyhow::Result<Vec<PnpmSnapshot>> {
+  let pnpm_states = list_lockfiles(repo, "pnpm-lock.yaml", failure_mode, parse_pnpm_lock)?
+    .into_iter()
+    .map(|(pnpm_lock_path, pnpm_lock)| PnpmSnapshot {
+      pnpm_lock_path,
+      pnpm_lock,
+    })
+    .collect();
+
+  Ok(pnpm_states)
+}
+
+/// Reads and parses the tracked lockfiles with a file name, returning their paths relative to the
+/// repository
+///
+/// `npm` and `pnpm` record install locations in the lockfile itself, so unlike `yarn` there is no
+/// state file to read from `node_modules`.
+fn list_lockfiles<T: Send>(
+  repo: &Path,
+  file_name: &str,
+  failure_mode: FailureMode,
+  parse: fn(&str) -> anyhow::Result<T>,
+) -> anyhow::Result<Vec<(PathBuf, T)>> {
+  let lockfiles = vcs_list_lock_files(repo, file_name)?;
+  tracing::info!(?failure_mode, "Found {file_name} files");
+
+  lockfiles
+    .par_iter()
+    .map(|file| -> anyhow::Result<_> {
+      let lockfile_path = repo.join(file);
+      let lockfile = std::fs::read_to_string(&lockfile_path)
+        .map_err(|err| anyhow!("Failed to read {lockfile_path:?} from FS: {err}"))
+        .and_then(|contents| {
+          parse(&contents).map_err(|err| anyhow!("Failed to parse {lockfile_path:?}: {err}"))
+        });
+
+      if failure_mode != FailureMode::FailOnMissingNodeModules && lockfile.is_err() {
+        return Ok(None);
+      };
+
+      Ok(Some((PathBuf::from(file), lockfile?)))
+    })
+    .filter_map(|result| result.transpose())
+    .collect()
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NpmSnapshot {
+  pub npm_lock_path: PathBuf,
+  pub npm_lock: NpmLock,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PnpmSnapshot {
+  pub pnpm_lock_path: PathBuf,
+  pub pnpm_lock: PnpmLock,
+}
+
 /// "Dirty" files are files modified in the current work-tree and uncommitted.
 ///
 /// These files are hashed and stored in the snapshot.
@@ -278,7 +402,7 @@ pub struct VCSFile {
   pub hash: Option<String>,
 }
 
-fn get_file_contents_at_commit(
+pub fn get_file_contents_at_commit(
   repo: &Path,
   commit: &str,
   path: &Path,
@@ -313,12 +437,25 @@ fn get_file_contents_at_commit(
   }
 }
 
-#[derive(Debug, PartialEq)]
+/// How to handle missing `node_modules` state
+///
+/// When ignoring failures, packages without state are skipped.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum FailureMode {
   IgnoreMissingNodeModules,
   FailOnMissingNodeModules,
 }
 
+/// Whether git queries that fail with `gitoxide` are retried with the `git` CLI
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GitFallback {
+  /// Use the `git` CLI when `gitoxide` can't open the repository or a query fails
+  #[default]
+  Cli,
+  /// Return the `gitoxide` error, e.g. to verify the native implementation
+  Disabled,
+}
+
 #[derive(Debug, PartialEq)]
 pub struct FileChangeEvent {
   path: PathBuf,
@@ -355,23 +492,38 @@ pub fn get_changed_files_from_git(
   old_commit: &str,
   new_commit: &str,
   dirty_files: &[VCSFile],
+  git_fallback: GitFallback,
+) -> anyhow::Result<Vec<FileChangeEvent>> {
+  let git = Git::open(repo_path, git_fallback)?;
+  list_changed_files(&git, old_commit, new_commit, dirty_files)
+}
+
+fn list_changed_files(
+  git: &Git<'_>,
+  old_commit: &str,
+  new_commit: &str,
+  dirty_files: &[VCSFile],
 ) -> anyhow::Result<Vec<FileChangeEvent>> {
   let mut tracked_changes = HashSet::new();
   let mut changed_files = Vec::new();
+  let mut push_changes = |changes: Vec<(PathBuf, FileChangeType)>| {
+    for (relative_path, change_type) in changes {
+      changed_files.push(FileChangeEvent {
+        path: git.repo_path.join(&relative_path),
+        change_type,
+      });
+      tracked_changes.insert(relative_path);
+    }
+  };
 
   // list current dirty files
   tracing::info!("Listing dirty files...");
-  get_status_with_git_cli(repo_path, &mut tracked_changes, &mut changed_files)?;
-  tracing::info!(num_dirty_files = changed_files.len(), "Listed dirty files");
+  push_changes(git.status()?);
 
   tracing::info!(?old_commit, ?new_commit, "Calculating git diff...");
-  get_diff_with_git_cli(
-    repo_path,
-    old_commit,
-    new_commit,
-    &mut tracked_changes,
-    &mut changed_files,
-  )?;
+  if old_commit != new_commit {
+    push_changes(git.diff(old_commit, new_commit)?);
+  }
 
   tracing::info!(
     num_changed_files = changed_files.len(),
@@ -382,7 +534,7 @@ pub fn get_changed_files_from_git(
   // relevant
   for dirty_file in dirty_files {
     if !tracked_changes.contains(&dirty_file.path) {
-      let path = repo_path.join(dirty_file.path.clone());
+      let path = git.repo_path.join(dirty_file.path.clone());
       changed_files.push(FileChangeEvent {
         path,
         change_type: FileChangeType::Update,
@@ -393,17 +545,93 @@ pub fn get_changed_files_from_git(
   Ok(changed_files)
 }
 
-fn get_diff_with_git_cli(
+/// Runs git queries natively with `gitoxide`, falling back to the `git` CLI when the repository
+/// can't be opened or a query fails and the fallback is enabled
+struct Git<'a> {
+  repo_path: &'a Path,
+  repo: Option<gix::Repository>,
+  fallback: GitFallback,
+}
+
+impl<'a> Git<'a> {
+  fn open(repo_path: &'a Path, fallback: GitFallback) -> anyhow::Result<Self> {
+    let repo = match gix_integration::open_repository(repo_path) {
+      Ok(repo) => Some(repo),
+      Err(err) if fallback == GitFallback::Cli => {
+        tracing::warn!("Failed to open {repo_path:?} natively, using the git CLI instead: {err}");
+        None
+      }
+      Err(err) => return Err(err),
+    };
+
+    Ok(Git {
+      repo_path,
+      repo,
+      fallback,
+    })
+  }
+
+  fn run<T>(
+    &self,
+    query: &str,
+    native: impl FnOnce(&gix::Repository) -> anyhow::Result<T>,
+    cli: impl FnOnce() -> anyhow::Result<T>,
+  ) -> anyhow::Result<T> {
+    let Some(repo) = &self.repo else {
+      return cli();
+    };
+
+    match native(repo) {
+      Ok(result) => Ok(result),
+      Err(err) if self.fallback == GitFallback::Cli => {
+        tracing::warn!("Native git {query} failed, using the git CLI instead: {err}");
+        cli()
+      }
+      Err(err) => Err(err),
+    }
+  }
+
+  fn rev_parse(&self, rev: &str) -> anyhow::Result<String> {
+    self.run(
+      "rev-parse",
+      |repo| gix_integration::rev_parse_with_gix(repo, rev),
+      || rev_parse(self.repo_path, rev),
+    )
+  }
+
+  fn file_contents_at_commit(&self, commit: &str, path: &Path) -> anyhow::Result<Option<String>> {
+    self.run(
+      "cat-file",
+      |repo| gix_integration::get_file_contents_at_commit_with_gix(repo, commit, path),
+      || get_file_contents_at_commit(self.repo_path, commit, path),
+    )
+  }
+
+  fn diff(
+    &self,
+    old_commit: &str,
+    new_commit: &str,
+  ) -> anyhow::Result<Vec<(PathBuf, FileChangeType)>> {
+    self.run(
+      "diff",
+      |repo| gix_integration::get_diff_with_gix(repo, old_commit, new_commit),
+      || get_diff_with_git_cli(self.repo_path, old_commit, new_commit),
+    )
+  }
+
+  fn status(&self) -> anyhow::Result<Vec<(PathBuf, FileChangeType)>> {
+    self.run("status", gix_integration::get_status_with_gix, || {
+      get_status_with_git_cli(self.repo_path)
+    })
+  }
+}
+
+/// Lists the files that differ between two commits with the `git` CLI
+pub fn get_diff_with_git_cli(
   repo_path: &Path,
   old_commit: &str,
   new_commit: &str,
-  tracked_changes: &mut HashSet<PathBuf>,
-  changed_files: &mut Vec<FileChangeEvent>,
-) -> anyhow::Result<()> {
-  if old_commit == new_commit {
-    return Ok(());
-  }
-
+) -> anyhow::Result<Vec<(PathBuf, FileChangeType)>> {
   let output = Command::new("git")
     .arg("diff")
     .arg("--name-status")
@@ -421,14 +649,13 @@ fn get_diff_with_git_cli(
 
   let output = String::from_utf8(output.stdout)?;
   let lines = output.split_terminator('\n');
+  let mut changes = Vec::new();
   for line in lines {
     let status = line
       .chars()
       .next()
       .ok_or_else(|| anyhow!("Invalid git diff line: {}", line))?;
     let path = line.split_whitespace().skip(1).collect::<String>();
-    let relative_path = PathBuf::from(path);
-    let path = repo_path.join(&relative_path);
     let change_type = match status {
       'A' => FileChangeType::Create,
       'D' => FileChangeType::Delete,
@@ -436,20 +663,15 @@ fn get_diff_with_git_cli(
       _ => FileChangeType::Update,
     };
 
-    tracked_changes.insert(relative_path.clone());
-    changed_files.push(FileChangeEvent { path, change_type });
+    changes.push((PathBuf::from(path), change_type));
   }
 
-  Ok(())
+  Ok(changes)
 }
 
 /// Query git status from the CLI. This is because libgit2 does not support
 /// sparse checkouts.
-fn get_status_with_git_cli(
-  repo_path: &Path,
-  tracked_changes: &mut HashSet<PathBuf>,
-  changed_files: &mut Vec<FileChangeEvent>,
-) -> anyhow::Result<()> {
+pub fn get_status_with_git_cli(repo_path: &Path) -> anyhow::Result<Vec<(PathBuf, FileChangeType)>> {
   let output = Command::new("git")
     .arg("status")
     .arg("--porcelain")
@@ -462,24 +684,22 @@ fn get_status_with_git_cli(
   }
   let output = String::from_utf8(output.stdout)?;
   let lines = output.split_terminator('\n');
+  let mut changes = Vec::new();
   for line in lines {
     let status = line
       .chars()
       .nth(1)
       .ok_or_else(|| anyhow!("Invalid git status line: {}", line))?;
     let path = line.split_whitespace().skip(1).collect::<String>();
-    let relative_path = PathBuf::from(path);
-    let path = repo_path.join(&relative_path);
     let change_type = match status {
       'A' => FileChangeType::Create,
       'D' => FileChangeType::Delete,
       'M' => FileChangeType::Update,
       _ => continue,
     };
-    tracked_changes.insert(relative_path.clone());
-    changed_files.push(FileChangeEvent { path, change_type });
+    changes.push((PathBuf::from(path), change_type));
   }
-  Ok(())
+  Ok(changes)
 }
 
 pub fn get_changed_files(
@@ -487,21 +707,19 @@ pub fn get_changed_files(
   vcs_state: &VCSState,
   new_rev: Option<&str>,
   failure_mode: FailureMode,
+  git_fallback: GitFallback,
 ) -> anyhow::Result<Vec<FileChangeEvent>> {
+  let git = Git::open(repo_path, git_fallback)?;
   let old_rev = &vcs_state.git_hash;
-  let old_commit = rev_parse(repo_path, old_rev)?;
-  let new_commit = rev_parse(repo_path, new_rev.unwrap_or("HEAD"))?;
+  let old_commit = git.rev_parse(old_rev)?;
+  let new_commit = git.rev_parse(new_rev.unwrap_or("HEAD"))?;
 
   let mut changed_files =
-    get_changed_files_from_git(repo_path, &old_commit, &new_commit, &vcs_state.dirty_files)?;
+    list_changed_files(&git, &old_commit, &new_commit, &vcs_state.dirty_files)?;
   tracing::trace!("Changed files: {:?}", changed_files);
 
   tracing::debug!("Reading yarn.lock from {} and {:?}", old_rev, new_rev);
-  let yarn_lock_changes = changed_files
-    .iter()
-    .filter(|file| file.path.file_name().unwrap() == "yarn.lock")
-    .map(|file| file.path.clone())
-    .collect::<Vec<_>>();
+  let yarn_lock_changes = lockfile_changes(&changed_files, "yarn.lock");
 
   let yarn_snapshots_by_path = vcs_state
     .yarn_states
@@ -532,15 +750,15 @@ pub fn get_changed_files(
       Some(yarn_snapshot.yarn_lock.clone())
     } else {
       tracing::debug!("Reading yarn.lock from git");
-      let maybe_old_yarn_lock_blob =
-        get_file_contents_at_commit(repo_path, &old_commit, yarn_lock_path)?;
+      let maybe_old_yarn_lock_blob = git.file_contents_at_commit(&old_commit, yarn_lock_path)?;
       maybe_old_yarn_lock_blob
         .map(|s| parse_yarn_lock(&s))
         .transpose()?
     };
 
     let new_yarn_lock_blob: String = if new_rev.is_some() {
-      get_file_contents_at_commit(repo_path, &new_commit, yarn_lock_path)?
+      git
+        .file_contents_at_commit(&new_commit, yarn_lock_path)?
         .ok_or_else(|| anyhow!("Expected lockfile to exist in current revision"))?
     } else {
       tracing::debug!("Reading raw yarn.lock on current file-system",);
@@ -574,16 +792,74 @@ pub fn get_changed_files(
       &yarn_state,
     );
 
-    for change in node_modules_changes {
-      changed_files.push(FileChangeEvent {
-        path: change.clone(),
-        change_type: FileChangeType::Delete,
-      });
-      changed_files.push(FileChangeEvent {
-        path: change,
-        change_type: FileChangeType::Create,
-      });
-    }
+    push_node_modules_changes(&mut changed_files, node_modules_changes);
+  }
+
+  let npm_snapshots_by_path = vcs_state
+    .npm_states
+    .iter()
+    .map(|npm_snapshot| (npm_snapshot.npm_lock_path.as_path(), &npm_snapshot.npm_lock))
+    .collect::<HashMap<_, _>>();
+
+  for npm_lock_path in lockfile_changes(&changed_files, "package-lock.json") {
+    tracing::debug!(
+      "Found package-lock.json in changed files: {}",
+      npm_lock_path.display()
+    );
+    let npm_lock_path = npm_lock_path.strip_prefix(repo_path)?;
+    let (old_npm_lock, new_npm_lock) = read_lockfile_revisions(
+      &git,
+      npm_lock_path,
+      &old_commit,
+      &new_commit,
+      new_rev,
+      npm_snapshots_by_path.get(npm_lock_path).copied(),
+      parse_npm_lock,
+    )?;
+
+    let node_modules_changes = npm_integration::generate_events(
+      &repo_path.join(npm_lock_path.parent().unwrap()),
+      &old_npm_lock,
+      &new_npm_lock,
+    );
+
+    push_node_modules_changes(&mut changed_files, node_modules_changes);
+  }
+
+  let pnpm_snapshots_by_path = vcs_state
+    .pnpm_states
+    .iter()
+    .map(|pnpm_snapshot| {
+      (
+        pnpm_snapshot.pnpm_lock_path.as_path(),
+        &pnpm_snapshot.pnpm_lock,
+      )
+    })
+    .collect::<HashMap<_, _>>();
+
+  for pnpm_lock_path in lockfile_changes(&changed_files, "pnpm-lock.yaml") {
+    tracing::debug!(
+      "Found pnpm-lock.yaml in changed files: {}",
+      pnpm_lock_path.display()
+    );
+    let pnpm_lock_path = pnpm_lock_path.strip_prefix(repo_path)?;
+    let (old_pnpm_lock, new_pnpm_lock) = read_lockfile_revisions(
+      &git,
+      pnpm_lock_path,
+      &old_commit,
+      &new_commit,
+      new_rev,
+      pnpm_snapshots_by_path.get(pnpm_lock_path).copied(),
+      parse_pnpm_lock,
+    )?;
+
+    let node_modules_changes = pnpm_integration::generate_events(
+      &repo_path.join(pnpm_lock_path.parent().unwrap()),
+      &old_pnpm_lock,
+      &new_pnpm_lock,
+    );
+
+    push_node_modules_changes(&mut changed_files, node_modules_changes);
   }
 
   tracing::debug!("Done");
@@ -591,6 +867,72 @@ pub fn get_changed_files(
   Ok(changed_files)
 }
 
+/// Lists the changed lockfiles with a file name, such as `yarn.lock`
+fn lockfile_changes(changed_files: &[FileChangeEvent], file_name: &str) -> Vec<PathBuf> {
+  changed_files
+    .iter()
+    .filter(|file| file.path.file_name().unwrap() == file_name)
+    .map(|file| file.path.clone())
+    .collect()
+}
+
+/// Reads a lockfile at the old and new revisions
+///
+/// The old lockfile is read from its snapshot when there is one, since it may have been dirty
+/// when the snapshot was taken. The new lockfile is read from the file-system unless a new
+/// revision was given.
+fn read_lockfile_revisions<T: Clone>(
+  git: &Git<'_>,
+  lockfile_path: &Path,
+  old_commit: &str,
+  new_commit: &str,
+  new_rev: Option<&str>,
+  snapshot: Option<&T>,
+  parse: fn(&str) -> anyhow::Result<T>,
+) -> anyhow::Result<(Option<T>, T)> {
+  let old_lockfile = match snapshot {
+    Some(lockfile) => {
+      tracing::debug!("Using lockfile snapshot for {}", lockfile_path.display());
+      Some(lockfile.clone())
+    }
+    None => git
+      .file_contents_at_commit(old_commit, lockfile_path)?
+      .map(|contents| parse(&contents))
+      .transpose()?,
+  };
+
+  let new_lockfile_contents = if new_rev.is_some() {
+    git
+      .file_contents_at_commit(new_commit, lockfile_path)?
+      .ok_or_else(|| anyhow!("Expected lockfile to exist in current revision"))?
+  } else {
+    std::fs::read_to_string(git.repo_path.join(lockfile_path))
+      .map_err(|err| anyhow!("Failed to read {lockfile_path:?} from file-system: {err}"))?
+  };
+
+  Ok((old_lockfile, parse(&new_lockfile_contents)?))
+}
+
+/// Marks every file within changed `node_modules` packages as re-created
+fn push_node_modules_changes(changed_files: &mut Vec<FileChangeEvent>, changes: Vec<PathBuf>) {
+  for change in changes {
+    changed_files.push(FileChangeEvent {
+      path: change.clone(),
+      change_type: FileChangeType::Delete,
+    });
+    changed_files.push(FileChangeEvent {
+      path: change,
+      change_type: FileChangeType::Create,
+    });
+  }
+}
+
+/// Returns the root of the repository that contains a directory, which the paths reported by
+/// git are relative to
+pub fn repository_root(dir: &Path) -> anyhow::Result<PathBuf> {
+  rev_parse(dir, "--show-toplevel").map(PathBuf::from)
+}
+
 pub fn rev_parse(dir: &Path, rev: &str) -> anyhow::Result<String> {
   let mut command = Command::new("git");
   command.arg("rev-parse").arg(rev).current_dir(dir);
@@ -608,11 +950,16 @@ pub fn rev_parse(dir: &Path, rev: &str) -> anyhow::Result<String> {
 }
 
 pub fn vcs_list_yarn_lock_files(dir: &Path) -> Result<Vec<String>, anyhow::Error> {
+  vcs_list_lock_files(dir, "yarn.lock")
+}
+
+/// Lists the tracked files with a file name, such as `package-lock.json`, relative to `dir`
+pub fn vcs_list_lock_files(dir: &Path, file_name: &str) -> Result<Vec<String>, anyhow::Error> {
   let mut command = Command::new("git");
   command
     .arg("ls-files")
     .arg("--cached")
-    .arg("--exclude=yarn.lock")
+    .arg(format!("--exclude={file_name}"))
     .arg("--ignored")
     .arg("-z") // We separate rows by \0 to avoid issues with newlines
     .current_dir(dir);
@@ -774,6 +1121,21 @@ mod test {
     assert_ne!(git_hash, "HEAD");
   }
 
+  #[test]
+  fn test_repository_root() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let repo_path = create_test_repo(&temp_dir).unwrap();
+    std::fs::create_dir_all(repo_path.join("packages/app")).unwrap();
+
+    assert_eq!(
+      repository_root(&repo_path.join("packages/app"))
+        .unwrap()
+        .canonicalize()
+        .unwrap(),
+      repo_path.canonicalize().unwrap()
+    );
+  }
+
   #[test]
   fn test_get_file_contents_at_commit() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -816,28 +1178,146 @@ mod test {
     assert!(contents.is_none());
   }
 
+  #[test]
+  fn test_git_falls_back_to_the_cli_independently_of_the_failure_mode() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let not_a_repo = temp_dir.path().join("not-a-repo");
+    std::fs::create_dir_all(&not_a_repo).unwrap();
+
+    assert!(Git::open(&not_a_repo, GitFallback::Disabled).is_err());
+
+    let git = Git::open(&not_a_repo, GitFallback::Cli).unwrap();
+    assert!(git.repo.is_none());
+
+    let repo_path = create_test_repo(&temp_dir).unwrap();
+    let git = Git::open(&repo_path, GitFallback::Cli).unwrap();
+    let result = git
+      .run(
+        "rev-parse",
+        |_| Err(anyhow::anyhow!("native failure")),
+        || Ok("cli"),
+      )
+      .unwrap();
+    assert_eq!(result, "cli");
+
+    let git = Git::open(&repo_path, GitFallback::Disabled).unwrap();
+    assert!(
+      git
+        .run(
+          "rev-parse",
+          |_| Err::<&str, _>(anyhow::anyhow!("native failure")),
+          || Ok("cli")
+        )
+        .is_err()
+    );
+  }
+
   #[test]
   fn test_get_changed_files_from_git() {
     let temp_dir = tempfile::tempdir().unwrap();
     let repo_path = create_test_repo(&temp_dir).unwrap();
     let head_hash = rev_parse(&repo_path, "HEAD").unwrap();
 
-    let changes = get_changed_files_from_git(&repo_path, &head_hash, &head_hash, &[]).unwrap();
+    let changes = get_changed_files_from_git(
+      &repo_path,
+      &head_hash,
+      &head_hash,
+      &[],
+      GitFallback::Disabled,
+    )
+    .unwrap();
     assert_eq!(changes.len(), 0);
 
     std::fs::write(repo_path.join("file.txt"), "new contents").unwrap();
-    let changes = get_changed_files_from_git(&repo_path, &head_hash, &head_hash, &[]).unwrap();
+    let changes = get_changed_files_from_git(
+      &repo_path,
+      &head_hash,
+      &head_hash,
+      &[],
+      GitFallback::Disabled,
+    )
+    .unwrap();
     assert_eq!(changes.len(), 1);
     assert_eq!(changes[0].path(), repo_path.join("file.txt"));
   }
 
+  #[test]
+  fn test_get_changed_files_with_npm_lock_changes() -> anyhow::Result<()> {
+    let temp_dir = tempfile::tempdir()?;
+    let repo_path = create_test_repo(&temp_dir)?;
+    let samples_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("samples");
+
+    let commit_lockfile = |sample: &str| -> anyhow::Result<String> {
+      std::fs::copy(
+        samples_path.join(sample),
+        repo_path.join("package-lock.json"),
+      )?;
+      run_command(
+        Command::new("git")
+          .arg("add")
+          .arg(".")
+          .current_dir(&repo_path),
+      )?;
+      run_command(
+        Command::new("git")
+          .arg("commit")
+          .arg("-m")
+          .arg(sample)
+          .current_dir(&repo_path),
+      )?;
+      rev_parse(&repo_path, "HEAD")
+    };
+
+    let old_hash = commit_lockfile("old/package-lock.json")?;
+    commit_lockfile("new/package-lock.json")?;
+
+    let lodash_path = repo_path.join("node_modules/lodash");
+    std::fs::create_dir_all(&lodash_path)?;
+    std::fs::write(lodash_path.join("lodash.js"), "")?;
+
+    let changes = get_changed_files(
+      &repo_path,
+      &VCSState::from_git_hash(old_hash),
+      Some("HEAD"),
+      FailureMode::FailOnMissingNodeModules,
+      GitFallback::Disabled,
+    )?;
+
+    assert_eq!(
+      changes,
+      vec![
+        FileChangeEvent {
+          path: repo_path.join("package-lock.json"),
+          change_type: FileChangeType::Update,
+        },
+        FileChangeEvent {
+          path: lodash_path.join("lodash.js"),
+          change_type: FileChangeType::Delete,
+        },
+        FileChangeEvent {
+          path: lodash_path.join("lodash.js"),
+          change_type: FileChangeType::Create,
+        },
+      ]
+    );
+
+    Ok(())
+  }
+
   #[test]
   fn test_get_changed_files_from_git_on_rename() {
     let temp_dir = tempfile::tempdir().unwrap();
     let repo_path = create_test_repo(&temp_dir).unwrap();
     let head_hash = rev_parse(&repo_path, "HEAD").unwrap();
 
-    let changes = get_changed_files_from_git(&repo_path, &head_hash, &head_hash, &[]).unwrap();
+    let changes = get_changed_files_from_git(
+      &repo_path,
+      &head_hash,
+      &head_hash,
+      &[],
+      GitFallback::Disabled,
+    )
+    .unwrap();
     assert_eq!(changes.len(), 0);
 
     std::fs::rename(repo_path.join("file.txt"), repo_path.join("file2.txt")).unwrap();
@@ -853,7 +1333,14 @@ mod test {
     run_command(&mut command).unwrap();
 
     let new_head_hash = rev_parse(&repo_path, "HEAD").unwrap();
-    let changes = get_changed_files_from_git(&repo_path, &head_hash, &new_head_hash, &[]).unwrap();
+    let changes = get_changed_files_from_git(
+      &repo_path,
+      &head_hash,
+      &new_head_hash,
+      &[],
+      GitFallback::Disabled,
+    )
+    .unwrap();
     assert_eq!(
       changes,
       vec![
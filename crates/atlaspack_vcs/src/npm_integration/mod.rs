@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::yarn_integration::expand_paths;
+
+/// A `package-lock.json` file, as written by `npm` 7 and above
+///
+/// Unlike `yarn`, `npm` records the install location of each package within the lockfile itself,
+/// so no state file is needed to find the directories of changed packages. Version 1 lockfiles
+/// only contain the dependency tree, so they are not supported.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NpmLock {
+  pub lockfile_version: u32,
+  /// Packages keyed by their location relative to the lockfile, such as `node_modules/lodash` or
+  /// `packages/app/node_modules/react`. The root package is keyed by an empty string.
+  #[serde(default)]
+  pub packages: HashMap<String, NpmPackage>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct NpmPackage {
+  pub version: Option<String>,
+  pub resolved: Option<String>,
+  pub integrity: Option<String>,
+  /// Set for workspace packages, which are symlinked from `node_modules` and tracked by git
+  #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+  pub link: bool,
+}
+
+pub fn parse_npm_lock(contents: &str) -> anyhow::Result<NpmLock> {
+  let npm_lock: NpmLock = serde_json::from_str(contents)?;
+  if npm_lock.lockfile_version < 2 {
+    return Err(anyhow::anyhow!(
+      "Unsupported package-lock.json version {}, expected version 2 or above",
+      npm_lock.lockfile_version
+    ));
+  }
+
+  Ok(npm_lock)
+}
+
+/// Lists the installed package directories whose version, tarball or integrity changed
+pub fn get_changed_node_modules(
+  lockfile_parent_path: &Path,
+  old_npm_lock: &Option<NpmLock>,
+  new_npm_lock: &NpmLock,
+) -> Vec<PathBuf> {
+  let mut changed_node_modules = new_npm_lock
+    .packages
+    .iter()
+    .filter(|(location, package)| location.contains("node_modules/") && !package.link)
+    .filter(|(location, package)| {
+      old_npm_lock
+        .as_ref()
+        .and_then(|old_npm_lock| old_npm_lock.packages.get(*location))
+        .is_none_or(|old_package| old_package != *package)
+    })
+    .map(|(location, _)| lockfile_parent_path.join(location))
+    .collect::<Vec<_>>();
+
+  changed_node_modules.sort();
+  changed_node_modules
+}
+
+pub fn generate_events(
+  lockfile_parent_path: &Path,
+  old_npm_lock: &Option<NpmLock>,
+  new_npm_lock: &NpmLock,
+) -> Vec<PathBuf> {
+  let changed_node_modules =
+    get_changed_node_modules(lockfile_parent_path, old_npm_lock, new_npm_lock);
+
+  expand_paths(&changed_node_modules)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn read_sample(path: &str) -> anyhow::Result<NpmLock> {
+    let samples_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("samples");
+    parse_npm_lock(&std::fs::read_to_string(samples_path.join(path))?)
+  }
+
+  #[test]
+  fn test_get_changed_node_modules() -> anyhow::Result<()> {
+    let old_npm_lock = read_sample("old/package-lock.json")?;
+    let new_npm_lock = read_sample("new/package-lock.json")?;
+
+    let changes = get_changed_node_modules(Path::new(""), &Some(old_npm_lock), &new_npm_lock);
+    let changes = changes
+      .iter()
+      .map(|path| path.to_str().unwrap())
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      changes,
+      vec!["node_modules/lodash", "packages/app/node_modules/react"]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_get_changed_node_modules_when_old_file_is_missing() -> anyhow::Result<()> {
+    let new_npm_lock = read_sample("new/package-lock.json")?;
+
+    let changes = get_changed_node_modules(Path::new(""), &None, &new_npm_lock);
+    let changes = changes
+      .iter()
+      .map(|path| path.to_str().unwrap())
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      changes,
+      vec![
+        "node_modules/left-pad",
+        "node_modules/lodash",
+        "packages/app/node_modules/react"
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_npm_lock_rejects_version_1() {
+    let result = parse_npm_lock(r#"{ "lockfileVersion": 1, "dependencies": {} }"#);
+    assert!(result.is_err());
+  }
+}
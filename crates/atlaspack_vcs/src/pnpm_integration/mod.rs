@@ -0,0 +1,313 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::yarn_integration::expand_paths;
+
+/// pnpm hashes virtual store directory names longer than this
+const VIRTUAL_STORE_DIR_MAX_LENGTH: usize = 120;
+
+/// A `pnpm-lock.yaml` file, as written by `pnpm` 8 and above
+///
+/// `pnpm` installs each package into its own directory within the virtual store at
+/// `node_modules/.pnpm`, named after the **dependency path** of the package. For example
+/// `react-dom@18.3.1(react@18.3.1)` is installed into
+/// `node_modules/.pnpm/react-dom@18.3.1_react@18.3.1/node_modules/react-dom`.
+///
+/// Dependency paths contain the version of the package and of its peer dependencies, so a changed
+/// package will have a new dependency path.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PnpmLock {
+  /// Either a string such as `'9.0'` or a number such as `5.4`
+  pub lockfile_version: serde_yaml_ng::Value,
+  /// Package resolutions keyed by dependency path. Version 6 lockfiles include peer dependencies
+  /// in these keys, while version 9 lockfiles list them under `snapshots` instead.
+  #[serde(default)]
+  pub packages: HashMap<String, PnpmPackage>,
+  #[serde(default)]
+  pub snapshots: HashMap<String, PnpmPackageSnapshot>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct PnpmPackage {
+  pub resolution: Option<serde_yaml_ng::Value>,
+}
+
+/// The dependencies of a package within a version 9 lockfile, which are already part of the
+/// dependency path, so no fields are kept
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct PnpmPackageSnapshot {}
+
+impl PnpmLock {
+  fn major_version(&self) -> Option<u64> {
+    match &self.lockfile_version {
+      serde_yaml_ng::Value::String(version) => version.split('.').next()?.parse().ok(),
+      serde_yaml_ng::Value::Number(version) => version.as_f64().map(|version| version as u64),
+      _ => None,
+    }
+  }
+
+  /// Lists the installed dependency paths with the resolution of their package
+  fn dependency_paths(&self) -> HashMap<&str, Option<&PnpmPackage>> {
+    if self.snapshots.is_empty() {
+      return self
+        .packages
+        .iter()
+        .map(|(dependency_path, package)| (dependency_path.as_str(), Some(package)))
+        .collect();
+    }
+
+    self
+      .snapshots
+      .keys()
+      .map(|dependency_path| {
+        let package_key = dependency_path
+          .split_once('(')
+          .map_or(dependency_path.as_str(), |(package_key, _)| package_key);
+
+        (dependency_path.as_str(), self.packages.get(package_key))
+      })
+      .collect()
+  }
+}
+
+pub fn parse_pnpm_lock(contents: &str) -> anyhow::Result<PnpmLock> {
+  let pnpm_lock: PnpmLock = serde_yaml_ng::from_str(contents)?;
+  match pnpm_lock.major_version() {
+    Some(version) if version >= 6 => Ok(pnpm_lock),
+    _ => Err(anyhow::anyhow!(
+      "Unsupported pnpm-lock.yaml version {:?}, expected version 6 or above",
+      pnpm_lock.lockfile_version
+    )),
+  }
+}
+
+/// Lists the dependency paths that were added or whose resolution changed
+pub fn get_changed_packages(
+  old_pnpm_lock: &Option<PnpmLock>,
+  new_pnpm_lock: &PnpmLock,
+) -> Vec<String> {
+  let old_dependency_paths = old_pnpm_lock
+    .as_ref()
+    .map(|old_pnpm_lock| old_pnpm_lock.dependency_paths())
+    .unwrap_or_default();
+
+  let mut changed_packages = new_pnpm_lock
+    .dependency_paths()
+    .into_iter()
+    .filter(|(dependency_path, package)| {
+      old_dependency_paths
+        .get(dependency_path)
+        .is_none_or(|old_package| old_package != package)
+    })
+    .map(|(dependency_path, _)| dependency_path.to_string())
+    .collect::<Vec<_>>();
+
+  changed_packages.sort();
+  changed_packages
+}
+
+/// Converts a dependency path into the name of its directory within the virtual store, and
+/// whether pnpm would have hashed that name
+///
+/// This mirrors `depPathToFilename` from `@pnpm/dependency-path`.
+fn virtual_store_dir_name(dependency_path: &str) -> (String, bool) {
+  let name = match dependency_path.strip_prefix("file:") {
+    Some(path) => format!("file+{path}"),
+    None => dependency_path
+      .strip_prefix('/')
+      .unwrap_or(dependency_path)
+      .to_string(),
+  };
+
+  let mut name = name.replace(['\\', '/', ':', '*', '?', '"', '<', '>', '|'], "+");
+  if name.contains('(') {
+    name = name
+      .strip_suffix(')')
+      .unwrap_or(&name)
+      .replace(")(", "_")
+      .replace(['(', ')'], "_");
+  }
+
+  let is_hashed = name.len() > VIRTUAL_STORE_DIR_MAX_LENGTH
+    || (name != name.to_lowercase() && !name.starts_with("file+"));
+
+  (name, is_hashed)
+}
+
+/// Lists the virtual store directories of the packages that changed
+///
+/// pnpm appends a hash to names that are too long or contain upper-case characters, which can't
+/// be reproduced from the lockfile, so those are matched by prefix within the virtual store. This
+/// may include other peer dependency variants of the same package.
+pub fn get_changed_node_modules(
+  lockfile_parent_path: &Path,
+  old_pnpm_lock: &Option<PnpmLock>,
+  new_pnpm_lock: &PnpmLock,
+) -> Vec<PathBuf> {
+  let virtual_store_path = lockfile_parent_path.join("node_modules/.pnpm");
+  let mut virtual_store_entries: Option<Vec<String>> = None;
+
+  let mut changed_node_modules = Vec::new();
+  for dependency_path in get_changed_packages(old_pnpm_lock, new_pnpm_lock) {
+    let (name, is_hashed) = virtual_store_dir_name(&dependency_path);
+    if !is_hashed {
+      changed_node_modules.push(virtual_store_path.join(name));
+      continue;
+    }
+
+    let prefix = format!(
+      "{}_",
+      name
+        .chars()
+        .take(VIRTUAL_STORE_DIR_MAX_LENGTH - 27)
+        .collect::<String>()
+    );
+
+    tracing::debug!("Matching hashed virtual store directories for {dependency_path}");
+    let entries = virtual_store_entries.get_or_insert_with(|| {
+      std::fs::read_dir(&virtual_store_path)
+        .map(|entries| {
+          entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect()
+        })
+        .unwrap_or_default()
+    });
+
+    changed_node_modules.extend(
+      entries
+        .iter()
+        .filter(|entry| entry.starts_with(&prefix))
+        .map(|entry| virtual_store_path.join(entry)),
+    );
+  }
+
+  changed_node_modules
+}
+
+pub fn generate_events(
+  lockfile_parent_path: &Path,
+  old_pnpm_lock: &Option<PnpmLock>,
+  new_pnpm_lock: &PnpmLock,
+) -> Vec<PathBuf> {
+  let changed_node_modules =
+    get_changed_node_modules(lockfile_parent_path, old_pnpm_lock, new_pnpm_lock);
+
+  expand_paths(&changed_node_modules)
+}
+
+#[cfg(test)]
+mod test {
+  use super::*;
+
+  fn read_sample(path: &str) -> anyhow::Result<PnpmLock> {
+    let samples_path = Path::new(env!("CARGO_MANIFEST_DIR")).join("samples");
+    parse_pnpm_lock(&std::fs::read_to_string(samples_path.join(path))?)
+  }
+
+  #[test]
+  fn test_get_changed_node_modules() -> anyhow::Result<()> {
+    let old_pnpm_lock = read_sample("old/pnpm-lock.yaml")?;
+    let new_pnpm_lock = read_sample("new/pnpm-lock.yaml")?;
+
+    let changes = get_changed_node_modules(Path::new(""), &Some(old_pnpm_lock), &new_pnpm_lock);
+    let changes = changes
+      .iter()
+      .map(|path| path.to_str().unwrap())
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      changes,
+      vec![
+        "node_modules/.pnpm/lodash@4.17.21",
+        "node_modules/.pnpm/react-dom@18.3.1_react@18.3.1",
+        "node_modules/.pnpm/react@18.3.1",
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_get_changed_node_modules_when_old_file_is_missing() -> anyhow::Result<()> {
+    let new_pnpm_lock = read_sample("new/pnpm-lock.yaml")?;
+
+    let changes = get_changed_node_modules(Path::new(""), &None, &new_pnpm_lock);
+    let changes = changes
+      .iter()
+      .map(|path| path.to_str().unwrap())
+      .collect::<Vec<_>>();
+
+    assert_eq!(
+      changes,
+      vec![
+        "node_modules/.pnpm/@babel+runtime@7.24.0",
+        "node_modules/.pnpm/lodash@4.17.21",
+        "node_modules/.pnpm/react-dom@18.3.1_react@18.3.1",
+        "node_modules/.pnpm/react@18.3.1",
+      ]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_get_changed_packages_on_version_6_lockfiles() -> anyhow::Result<()> {
+    let old_pnpm_lock = parse_pnpm_lock(
+      r#"
+lockfileVersion: '6.0'
+packages:
+  /lodash@4.17.20:
+    resolution: {integrity: sha512-old}
+  /react-dom@18.2.0(react@18.2.0):
+    resolution: {integrity: sha512-react-dom}
+"#,
+    )?;
+    let new_pnpm_lock = parse_pnpm_lock(
+      r#"
+lockfileVersion: '6.0'
+packages:
+  /lodash@4.17.20:
+    resolution: {integrity: sha512-new}
+  /react-dom@18.2.0(react@18.2.0):
+    resolution: {integrity: sha512-react-dom}
+"#,
+    )?;
+
+    assert_eq!(
+      get_changed_packages(&Some(old_pnpm_lock), &new_pnpm_lock),
+      vec!["/lodash@4.17.20"]
+    );
+
+    Ok(())
+  }
+
+  #[test]
+  fn test_parse_pnpm_lock_rejects_version_5() {
+    assert!(parse_pnpm_lock("lockfileVersion: 5.4\npackages: {}\n").is_err());
+  }
+
+  #[test]
+  fn test_virtual_store_dir_name() {
+    assert_eq!(
+      virtual_store_dir_name("/@babel/core@7.24.0"),
+      (String::from("@babel+core@7.24.0"), false)
+    );
+    assert_eq!(
+      virtual_store_dir_name("a@1.0.0(b@1.0.0)(c@2.0.0)"),
+      (String::from("a@1.0.0_b@1.0.0_c@2.0.0"), false)
+    );
+    assert_eq!(
+      virtual_store_dir_name("file:packages/app"),
+      (String::from("file+packages+app"), false)
+    );
+    assert_eq!(
+      virtual_store_dir_name("JSONStream@1.3.5"),
+      (String::from("JSONStream@1.3.5"), true)
+    );
+  }
+}
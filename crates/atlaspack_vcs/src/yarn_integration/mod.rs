@@ -189,11 +189,14 @@ fn get_changed_node_modules(
   changed_node_modules
 }
 
-fn expand_paths(directories: &Vec<PathBuf>) -> Vec<PathBuf> {
+pub(crate) fn expand_paths(directories: &[PathBuf]) -> Vec<PathBuf> {
   directories
     .par_iter()
     .flat_map(|path| {
+      // Each directory is walked serially, as jwalk gives up on walks that can't get a thread
+      // from the rayon pool that this iterator is already occupying
       jwalk::WalkDir::new(path)
+        .parallelism(jwalk::Parallelism::Serial)
         .into_iter()
         .filter_map(|entry| entry.ok())
         .filter(|entry| !entry.path().is_dir())
@@ -303,7 +306,7 @@ mod test {
     std::fs::write(&file3, "file3").unwrap();
     std::fs::write(&file4, "file4").unwrap();
 
-    let files = expand_paths(&vec![temp_dir_path.to_path_buf()]);
+    let files = expand_paths(&[temp_dir_path.to_path_buf()]);
     let mut files = files
       .iter()
       .map(|path| path.to_str().unwrap())
@@ -149,5 +149,10 @@ pub use errors::{
   TransformResult, create_diagnostic, diagnostic_from_panic, init_panic_suppression,
 };
 pub use index_module::*;
+pub use postcss::plugins::vendor_autoprefixer::{
+  resolve_browserslist_queries, resolve_browserslist_targets,
+};
 pub use postcss::{SortOptions, sort_atomic_style_sheet};
+#[cfg(feature = "postcss_engine")]
+pub use postcss::{StandaloneOptions, StandalonePlugin, standalone_plugins};
 pub use types::*;
@@ -9,10 +9,14 @@ pub mod transform;
 
 #[cfg(feature = "postcss_engine")]
 pub mod postcss_pipeline;
+#[cfg(feature = "postcss_engine")]
+pub mod standalone;
 pub mod utils;
 pub mod value_parser;
 
 #[allow(unused_imports)]
 pub use sort::{SortOptions, sort_atomic_style_sheet};
+#[cfg(feature = "postcss_engine")]
+pub use standalone::{StandaloneOptions, StandalonePlugin, standalone_plugins};
 #[allow(unused_imports)]
 pub use transform::{TransformCssOptions, TransformCssResult, transform_css};
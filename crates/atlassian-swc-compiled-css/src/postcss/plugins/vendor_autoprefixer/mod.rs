@@ -303,6 +303,9 @@ struct AutoprefixerCache {
 static AUTOPREFIXER_CACHE: std::sync::OnceLock<Option<AutoprefixerCache>> =
   std::sync::OnceLock::new();
 
+static TARGETS_CACHE: Lazy<parking_lot::Mutex<HashMap<Vec<String>, Arc<AutoprefixerData>>>> =
+  Lazy::new(Default::default);
+
 #[derive(Clone, Debug)]
 pub struct PrefixedDecl {
   pub property: String,
@@ -339,6 +342,22 @@ impl AutoprefixerData {
       .map(|cache| Arc::clone(&cache.data))
   }
 
+  /// Returns the prefix data for an explicit list of browser targets.
+  ///
+  /// Unlike `init`, which fixes a single set of targets for the whole process,
+  /// this allows each caller to use its own targets. Results are cached per
+  /// target list.
+  pub fn for_targets(targets: &[String]) -> Option<Arc<Self>> {
+    let mut cache = TARGETS_CACHE.lock();
+    if let Some(data) = cache.get(targets) {
+      return Some(Arc::clone(data));
+    }
+
+    let data = Arc::new(AutoprefixerData::from_db(PrefixDB::load()?, targets));
+    cache.insert(targets.to_vec(), Arc::clone(&data));
+    Some(data)
+  }
+
   pub fn from_db(db: &PrefixDB, targets: &[String]) -> Self {
     let (add, remove) = db.select_add_remove(targets);
     let property_add = build_property_prefix_map(db, &add);
@@ -567,6 +586,15 @@ pub fn resolve_browserslist_targets(
   }
 }
 
+/// Resolves browserslist queries, such as those in an `engines.browsers`
+/// field, to the list of targeted browser versions.
+pub fn resolve_browserslist_queries(queries: &[String]) -> Vec<String> {
+  match oxc_browserslist::resolve(queries, &oxc_browserslist::Opts::default()) {
+    Ok(list) => list.into_iter().map(|item| item.to_string()).collect(),
+    Err(_) => Vec::new(),
+  }
+}
+
 fn at_rule_name(name: &AtRuleName) -> Option<&str> {
   match name {
     AtRuleName::Ident(i) => Some(&i.value),
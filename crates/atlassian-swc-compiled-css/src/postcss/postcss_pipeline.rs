@@ -364,7 +364,7 @@ fn discard_empty_in_container(container: &pc::RootLike) {
 }
 
 #[cfg(feature = "postcss_engine")]
-fn discard_empty_rules_plugin() -> pc::BuiltPlugin {
+pub(crate) fn discard_empty_rules_plugin() -> pc::BuiltPlugin {
   pc::plugin("discard-empty-rules")
     .once_exit(|root, _result| {
       discard_empty_in_container(root);
@@ -638,7 +638,7 @@ fn normalize_keyframe_selector_text(selector: String) -> String {
 }
 
 #[cfg(feature = "postcss_engine")]
-fn normalize_whitespace_plugin() -> pc::BuiltPlugin {
+pub(crate) fn normalize_whitespace_plugin() -> pc::BuiltPlugin {
   use crate::postcss::value_parser as vp;
   use postcss::ast::nodes::{as_at_rule, as_declaration, as_rule};
 
@@ -905,7 +905,7 @@ fn vendor_prefixing_lite_plugin() -> pc::BuiltPlugin {
 }
 
 #[cfg(feature = "postcss_engine")]
-fn discard_duplicates_plugin() -> pc::BuiltPlugin {
+pub(crate) fn discard_duplicates_plugin() -> pc::BuiltPlugin {
   use postcss::ast::nodes::as_declaration;
   use std::collections::HashMap;
 
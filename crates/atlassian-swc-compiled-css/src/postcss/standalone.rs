@@ -0,0 +1,288 @@
+//! Builds PostCSS engine plugins that can run over arbitrary stylesheets.
+//!
+//! The compiled pipeline in `postcss_pipeline.rs` is tailored to atomic
+//! CSS-in-JS output. This module exposes the general purpose plugins (nesting,
+//! autoprefixing and cssnano-style normalisation) so they can be used by other
+//! transformers, such as a native replacement for `@atlaspack/transformer-postcss`.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use postcss as pc;
+use postcss::ast::NodeAccess;
+use postcss::ast::nodes::{AtRule, Rule, as_at_rule, as_declaration, as_rule};
+
+use super::plugins::normalize_css_engine as nce;
+use super::plugins::vendor_autoprefixer::AutoprefixerData;
+use super::postcss_pipeline::{
+  discard_duplicates_plugin, discard_empty_rules_plugin, normalize_whitespace_plugin,
+};
+
+/// At-rules that are moved out of a rule with the rule's declarations wrapped
+/// inside them, mirroring the `bubble` option of `postcss-nested`.
+const BUBBLE_AT_RULES: &[&str] = &["media", "supports", "layer", "container", "scope"];
+
+/// A plugin that can be executed outside of the compiled CSS-in-JS pipeline.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum StandalonePlugin {
+  /// Equivalent of `autoprefixer`
+  Autoprefixer,
+  /// Equivalent of `postcss-nested`
+  Nested,
+  /// Equivalent of the `cssnano` default preset
+  Normalize,
+}
+
+/// Options used to build the standalone plugins for a single stylesheet.
+#[derive(Clone, Debug, Default)]
+pub struct StandaloneOptions {
+  /// Resolved browser versions used by autoprefixer, e.g. `chrome 120`
+  pub autoprefixer_targets: Vec<String>,
+  /// Directory to resolve the browserslist config from for the cssnano plugins
+  pub browserslist_config_path: Option<PathBuf>,
+  /// Browserslist environment for the cssnano plugins
+  pub browserslist_env: Option<String>,
+}
+
+/// Builds the PostCSS engine plugins for `plugins`, in order.
+pub fn standalone_plugins(
+  plugins: &[StandalonePlugin],
+  options: &StandaloneOptions,
+) -> Vec<pc::BuiltPlugin> {
+  let mut pipeline = Vec::new();
+
+  for plugin in plugins {
+    match plugin {
+      StandalonePlugin::Autoprefixer => {
+        if let Some(data) = AutoprefixerData::for_targets(&options.autoprefixer_targets) {
+          pipeline.push(autoprefixer_plugin(data));
+        }
+      }
+      StandalonePlugin::Nested => pipeline.push(nested_plugin()),
+      StandalonePlugin::Normalize => {
+        pipeline.push(discard_duplicates_plugin());
+        pipeline.push(discard_empty_rules_plugin());
+        pipeline.push(nce::discard_comments_plugin());
+        pipeline.push(nce::minify_selectors::plugin());
+        pipeline.push(nce::minify_params::plugin());
+        pipeline.push(nce::minify_gradients::plugin());
+        pipeline.push(nce::reduce_initial::plugin(
+          options.browserslist_config_path.clone(),
+          options.browserslist_env.clone(),
+        ));
+        pipeline.push(nce::colormin::plugin(
+          options.browserslist_config_path.clone(),
+          options.browserslist_env.clone(),
+        ));
+        pipeline.push(nce::normalize_timing_functions::plugin());
+        pipeline.push(nce::calc::plugin());
+        pipeline.push(nce::convert_values::plugin());
+        pipeline.push(nce::ordered_values::plugin());
+        pipeline.push(nce::normalize_string::plugin());
+        pipeline.push(nce::normalize_unicode::plugin());
+        pipeline.push(nce::normalize_url::plugin());
+        pipeline.push(nce::normalize_positions::plugin());
+        pipeline.push(nce::normalize_current_color_plugin());
+        pipeline.push(normalize_whitespace_plugin());
+      }
+    }
+  }
+
+  pipeline
+}
+
+/// Adds vendor prefixed declarations and `@keyframes` for the given prefix data.
+fn autoprefixer_plugin(data: Arc<AutoprefixerData>) -> pc::BuiltPlugin {
+  let keyframes_data = Arc::clone(&data);
+
+  pc::plugin("autoprefixer")
+    .decl(move |decl, _result| {
+      let prefixed = data.prefixed_decls(&decl.prop(), &decl.value());
+      if prefixed.is_empty() {
+        return Ok(());
+      }
+
+      let siblings: Vec<(String, String)> = decl
+        .parent()
+        .map(|parent| parent.borrow().nodes.clone())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(as_declaration)
+        .map(|sibling| (sibling.prop(), sibling.value()))
+        .collect();
+
+      let nodes: Vec<_> = prefixed
+        .into_iter()
+        .filter(|p| !siblings.contains(&(p.property.clone(), p.value.clone())))
+        .map(|p| {
+          let prefixed_decl = pc::decl(p.property, p.value);
+          prefixed_decl.set_important(decl.important());
+          prefixed_decl.to_node()
+        })
+        .collect();
+
+      decl.before(nodes);
+      Ok(())
+    })
+    .at_rule_filter("keyframes", move |at_rule, _result| {
+      let Some(prefixes) = keyframes_data.add.get("@keyframes") else {
+        return Ok(());
+      };
+
+      let nodes: Vec<_> = prefixes
+        .iter()
+        .map(|prefix| {
+          let prefixed = at_rule.clone();
+          prefixed.set_name(format!("{prefix}keyframes"));
+          prefixed.to_node()
+        })
+        .collect();
+
+      at_rule.before(nodes);
+      Ok(())
+    })
+    .build()
+}
+
+/// Unwraps rules and at-rules nested inside a rule, like `postcss-nested`.
+fn nested_plugin() -> pc::BuiltPlugin {
+  pc::plugin("postcss-nested")
+    .rule(|rule, _result| {
+      unwrap_nested(rule);
+      Ok(())
+    })
+    .build()
+}
+
+fn unwrap_nested(rule: &Rule) {
+  let parent_selectors = pc::comma(&rule.selector());
+  let mut anchor = rule.to_node();
+
+  for child in rule.nodes() {
+    if let Some(child_rule) = as_rule(&child) {
+      child_rule.set_selector(combine_selectors(&parent_selectors, &child_rule.selector()));
+    } else if let Some(at_rule) = as_at_rule(&child) {
+      let name = at_rule.name().to_ascii_lowercase();
+      if BUBBLE_AT_RULES.contains(&name.as_str()) {
+        bubble_at_rule(&at_rule, rule, &parent_selectors);
+      }
+    } else {
+      continue;
+    }
+
+    pc::ast::Node::insert_after(&anchor, [child.clone()]);
+    anchor = child;
+  }
+
+  if rule.nodes().is_empty() {
+    rule.remove();
+  }
+}
+
+/// Wraps the declarations of a nested at-rule in a copy of the parent rule and
+/// prefixes any rules inside it with the parent selectors.
+fn bubble_at_rule(at_rule: &AtRule, parent: &Rule, parent_selectors: &[String]) {
+  let wrapper = pc::rule(parent.selector());
+
+  for child in at_rule.nodes() {
+    if let Some(nested) = as_rule(&child) {
+      nested.set_selector(combine_selectors(parent_selectors, &nested.selector()));
+    } else if let Some(decl) = as_declaration(&child) {
+      decl.move_to(&wrapper.to_node());
+    }
+  }
+
+  if !wrapper.nodes().is_empty() {
+    at_rule.prepend(wrapper.to_node());
+  }
+}
+
+/// Combines every parent selector with every child selector, replacing `&` with
+/// the parent or joining them with a descendant combinator.
+fn combine_selectors(parent_selectors: &[String], child_selector: &str) -> String {
+  let child_selectors = pc::comma(child_selector);
+  let mut combined = Vec::with_capacity(parent_selectors.len() * child_selectors.len());
+
+  for parent in parent_selectors {
+    for child in &child_selectors {
+      let child = child.trim();
+      if child.contains('&') {
+        combined.push(child.replace('&', parent));
+      } else {
+        combined.push(format!("{parent} {child}"));
+      }
+    }
+  }
+
+  combined.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn run(css: &str, plugins: &[StandalonePlugin], options: &StandaloneOptions) -> String {
+    pc::postcss_with_plugins(standalone_plugins(plugins, options))
+      .process(css)
+      .and_then(|mut result| result.css().map(str::to_string))
+      .expect("valid css")
+  }
+
+  #[test]
+  fn returns_the_stylesheet_unchanged_without_plugins() {
+    let css = run(".a{color:red}", &[], &StandaloneOptions::default());
+
+    assert_eq!(css, ".a{color:red}");
+  }
+
+  #[test]
+  fn unwraps_nested_rules() {
+    let css = run(
+      ".a{color:red;.b{color:blue}&:hover{color:green}}",
+      &[StandalonePlugin::Nested],
+      &StandaloneOptions::default(),
+    );
+
+    assert!(css.contains(".a .b"));
+    assert!(css.contains(".a:hover"));
+    assert!(!css.contains('&'));
+  }
+
+  #[test]
+  fn bubbles_nested_media_queries() {
+    let css = run(
+      ".a{@media (min-width: 100px){color:blue}}",
+      &[StandalonePlugin::Nested],
+      &StandaloneOptions::default(),
+    );
+
+    assert!(css.starts_with("@media (min-width: 100px)"));
+    assert!(css.contains(".a"));
+  }
+
+  #[test]
+  fn prefixes_for_the_given_targets() {
+    let plugins = [StandalonePlugin::Autoprefixer];
+    let css = ".a{user-select:none}";
+
+    let old_safari = run(
+      css,
+      &plugins,
+      &StandaloneOptions {
+        autoprefixer_targets: vec![String::from("safari 9")],
+        ..StandaloneOptions::default()
+      },
+    );
+    let new_chrome = run(
+      css,
+      &plugins,
+      &StandaloneOptions {
+        autoprefixer_targets: vec![String::from("chrome 120")],
+        ..StandaloneOptions::default()
+      },
+    );
+
+    assert!(old_safari.contains("-webkit-user-select"));
+    assert!(!new_chrome.contains("-webkit-user-select"));
+  }
+}
@@ -1,5 +1,6 @@
 use core::str;
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use std::thread;
 
@@ -29,6 +30,8 @@ use napi_derive::napi;
 
 use atlaspack::file_system::FileSystemRef;
 use atlaspack::rpc::nodejs::NodejsRpcFactory;
+use atlaspack::rpc::stdio::{StdioRpcFactory, StdioRpcOptions};
+use atlaspack::rpc::wasm::WasmRpcFactory;
 use atlaspack_package_manager::PackageManagerRef;
 use parking_lot::RwLock;
 
@@ -103,7 +106,12 @@ pub fn atlaspack_napi_create(
         )
         .unwrap();
 
-      let rpc = Arc::new(NodejsRpcFactory::new(workers).unwrap());
+      // WebAssembly and `exec:` plugins run natively, and every other plugin runs in the
+      // Node.js workers
+      let rpc = Arc::new(WasmRpcFactory::new(Arc::new(StdioRpcFactory::new(
+        Arc::new(NodejsRpcFactory::new(workers).unwrap()),
+        StdioRpcOptions::default(),
+      ))));
       let atlaspack = Atlaspack::new(AtlaspackInitOptions {
         db,
         fs,
@@ -159,14 +167,15 @@ pub fn atlaspack_napi_build_asset_graph(
   thread::spawn({
     let atlaspack_ref = atlaspack_napi.clone();
     move || {
-      let result = {
+      let (result, warnings) = {
         let atlaspack = atlaspack_ref.write();
         let report_fn: Option<ReportFn> = tsfn.map(|tsfn| -> ReportFn {
           Arc::new(move |event| {
             tsfn.call(event.to_json(), ThreadsafeFunctionCallMode::NonBlocking);
           })
         });
-        atlaspack.build_asset_graph_with_report_fn(report_fn)
+        let result = atlaspack.build_asset_graph_with_report_fn(report_fn);
+        (result, atlaspack.take_warnings())
       };
 
       // "deferred.resolve" closure executes on the JavaScript thread.
@@ -174,7 +183,7 @@ pub fn atlaspack_napi_build_asset_graph(
       // not supplied as JavaScript Error types. The JavaScript layer needs to handle conversions
       let mut commit_deferred_opt = Some(second_deferred);
       deferred.resolve(move |env| {
-        match result {
+        let js_result = match result {
           Ok((symbol_tracker, asset_graph, had_previous_graph)) => {
             let serialize_result = serialize_asset_graph(
               &env,
@@ -202,7 +211,9 @@ pub fn atlaspack_napi_build_asset_graph(
             let js_object = env.to_js_value(&AtlaspackError::from(&error))?;
             NapiAtlaspackResult::error(&env, js_object)
           }
-        }
+        }?;
+
+        NapiAtlaspackResult::with_warnings(&env, js_result, &warnings)
       })
     }
   });
@@ -225,13 +236,18 @@ pub fn atlaspack_napi_respond_to_fs_events(
     move || {
       let atlaspack = atlaspack.write();
       let result = atlaspack.respond_to_fs_events(options);
+      let warnings = atlaspack.take_warnings();
 
-      deferred.resolve(move |env| match result {
-        Ok(should_rebuild) => NapiAtlaspackResult::ok(&env, should_rebuild),
-        Err(error) => {
-          let js_object = env.to_js_value(&AtlaspackError::from(&error))?;
-          NapiAtlaspackResult::error(&env, js_object)
-        }
+      deferred.resolve(move |env| {
+        let js_result = match result {
+          Ok(should_rebuild) => NapiAtlaspackResult::ok(&env, should_rebuild),
+          Err(error) => {
+            let js_object = env.to_js_value(&AtlaspackError::from(&error))?;
+            NapiAtlaspackResult::error(&env, js_object)
+          }
+        }?;
+
+        NapiAtlaspackResult::with_warnings(&env, js_result, &warnings)
       })
     }
   });
@@ -266,7 +282,7 @@ pub fn atlaspack_napi_build_bundle_graph(
   thread::spawn({
     let atlaspack_ref = atlaspack_napi.clone();
     move || {
-      let result = {
+      let (result, warnings) = {
         let atlaspack = atlaspack_ref.write();
 
         let report_fn: Option<ReportFn> = tsfn.map(|tsfn| -> ReportFn {
@@ -274,34 +290,39 @@ pub fn atlaspack_napi_build_bundle_graph(
             tsfn.call(event.to_json(), ThreadsafeFunctionCallMode::NonBlocking);
           })
         });
-        atlaspack.build_bundle_graph_with_report_fn(report_fn)
+        let result = atlaspack.build_bundle_graph_with_report_fn(report_fn);
+        (result, atlaspack.take_warnings())
       };
 
       let mut commit_deferred_opt = Some(second_deferred);
-      deferred.resolve(move |env| match result {
-        Ok((asset_graph, bundle_graph_delta, had_previous_graph)) => {
-          let serialize_result =
-            serialize_bundle_graph(&env, &bundle_graph_delta.bundle_graph, had_previous_graph)?;
-
-          if let Some(commit_deferred) = commit_deferred_opt.take() {
-            thread::spawn(move || {
-              {
-                let atlaspack = atlaspack_ref.write();
-                atlaspack.commit_assets(&asset_graph).unwrap();
-              }
-              commit_deferred.resolve(resolve_commit_ok)
-            });
-          }
+      deferred.resolve(move |env| {
+        let js_result = match result {
+          Ok((asset_graph, bundle_graph_delta, had_previous_graph)) => {
+            let serialize_result =
+              serialize_bundle_graph(&env, &bundle_graph_delta.bundle_graph, had_previous_graph)?;
 
-          NapiAtlaspackResult::ok(&env, serialize_result)
-        }
-        Err(error) => {
-          if let Some(commit_deferred) = commit_deferred_opt.take() {
-            commit_deferred.resolve(resolve_commit_ok);
+            if let Some(commit_deferred) = commit_deferred_opt.take() {
+              thread::spawn(move || {
+                {
+                  let atlaspack = atlaspack_ref.write();
+                  atlaspack.commit_assets(&asset_graph).unwrap();
+                }
+                commit_deferred.resolve(resolve_commit_ok)
+              });
+            }
+
+            NapiAtlaspackResult::ok(&env, serialize_result)
           }
-          let js_object = env.to_js_value(&AtlaspackError::from(&error))?;
-          NapiAtlaspackResult::error(&env, js_object)
-        }
+          Err(error) => {
+            if let Some(commit_deferred) = commit_deferred_opt.take() {
+              commit_deferred.resolve(resolve_commit_ok);
+            }
+            let js_object = env.to_js_value(&AtlaspackError::from(&error))?;
+            NapiAtlaspackResult::error(&env, js_object)
+          }
+        }?;
+
+        NapiAtlaspackResult::with_warnings(&env, js_result, &warnings)
       })
     }
   });
@@ -328,45 +349,50 @@ pub fn atlaspack_napi_build(
   thread::spawn({
     let atlaspack_ref = atlaspack_napi.clone();
     move || {
-      let result = {
+      let (result, warnings) = {
         let atlaspack = atlaspack_ref.write();
 
         let report_fn: ReportFn = Arc::new(move |event| {
           tsfn.call(event.to_json(), ThreadsafeFunctionCallMode::NonBlocking);
         });
-        atlaspack.build_with_report_fn(Some(report_fn))
+        let result = atlaspack.build_with_report_fn(Some(report_fn));
+        (result, atlaspack.take_warnings())
       };
 
-      deferred.resolve(move |env| match result {
-        Ok(build_output) => {
-          let serialize_result = serialize_bundle_graph(
-            &env,
-            &build_output.bundle_graph.bundle_graph,
-            build_output.bundle_graph.had_previous_graph,
-          )?;
-
-          let bundle_info: Vec<JsPackagedBundleInfo> = build_output
-            .packaging
-            .bundles
-            .into_iter()
-            .map(|(bundle_id, out)| JsPackagedBundleInfo {
-              bundle_id,
-              file_path: out.file_path.to_string_lossy().into_owned(),
-              r#type: out.bundle_type.extension().to_owned(),
-              size: out.size as u32,
-              time: out.time as u32,
-            })
-            .collect();
-
-          let mut js_result = serialize_result;
-          js_result.set_named_property("bundleInfo", env.to_js_value(&bundle_info)?)?;
-
-          NapiAtlaspackResult::ok(&env, js_result)
-        }
-        Err(error) => {
-          let js_object = env.to_js_value(&AtlaspackError::from(&error))?;
-          NapiAtlaspackResult::error(&env, js_object)
-        }
+      deferred.resolve(move |env| {
+        let js_result = match result {
+          Ok(build_output) => {
+            let serialize_result = serialize_bundle_graph(
+              &env,
+              &build_output.bundle_graph.bundle_graph,
+              build_output.bundle_graph.had_previous_graph,
+            )?;
+
+            let bundle_info: Vec<JsPackagedBundleInfo> = build_output
+              .packaging
+              .bundles
+              .into_iter()
+              .map(|(bundle_id, out)| JsPackagedBundleInfo {
+                bundle_id,
+                file_path: out.file_path.to_string_lossy().into_owned(),
+                r#type: out.bundle_type.extension().to_owned(),
+                size: out.size as u32,
+                time: out.time as u32,
+              })
+              .collect();
+
+            let mut js_result = serialize_result;
+            js_result.set_named_property("bundleInfo", env.to_js_value(&bundle_info)?)?;
+
+            NapiAtlaspackResult::ok(&env, js_result)
+          }
+          Err(error) => {
+            let js_object = env.to_js_value(&AtlaspackError::from(&error))?;
+            NapiAtlaspackResult::error(&env, js_object)
+          }
+        }?;
+
+        NapiAtlaspackResult::with_warnings(&env, js_result, &warnings)
       })
     }
   });
@@ -463,12 +489,17 @@ pub fn atlaspack_napi_package(
     move || {
       let atlaspack = atlaspack.read();
       let result = atlaspack.package(bundle_id);
-      deferred.resolve(move |env| match result {
-        Ok(result) => NapiAtlaspackResult::ok(&env, JsPackageResult::from(result)),
-        Err(error) => {
-          let js_object = env.to_js_value(&AtlaspackError::from(&error))?;
-          NapiAtlaspackResult::error(&env, js_object)
-        }
+      let warnings = atlaspack.take_warnings();
+      deferred.resolve(move |env| {
+        let js_result = match result {
+          Ok(result) => NapiAtlaspackResult::ok(&env, JsPackageResult::from(result)),
+          Err(error) => {
+            let js_object = env.to_js_value(&AtlaspackError::from(&error))?;
+            NapiAtlaspackResult::error(&env, js_object)
+          }
+        }?;
+
+        NapiAtlaspackResult::with_warnings(&env, js_result, &warnings)
       })
     }
   });
@@ -501,6 +532,53 @@ pub fn atlaspack_napi_complete_session(
   Ok(promise)
 }
 
+/// Resolves the transformers, optimizers, packager and compressors that apply to a file path,
+/// along with the config file and glob that contributed each of them
+#[tracing::instrument(level = "debug", skip_all)]
+#[napi]
+pub fn atlaspack_napi_explain_plugins(
+  env: Env,
+  atlaspack_napi: AtlaspackNapi,
+  file_path: String,
+  pipeline: Option<String>,
+) -> napi::Result<JsObject> {
+  let (deferred, promise) = env.create_deferred()?;
+
+  thread::spawn({
+    let atlaspack_ref = atlaspack_napi.clone();
+    move || {
+      let explanation = atlaspack_ref
+        .read()
+        .explain_plugins(Path::new(&file_path), pipeline.as_deref());
+
+      deferred.resolve(move |env| env.to_js_value(&explanation))
+    }
+  });
+
+  Ok(promise)
+}
+
+/// Returns the fully merged config, with the location that each plugin was configured at
+#[tracing::instrument(level = "debug", skip_all)]
+#[napi]
+pub fn atlaspack_napi_print_config(
+  env: Env,
+  atlaspack_napi: AtlaspackNapi,
+) -> napi::Result<JsObject> {
+  let (deferred, promise) = env.create_deferred()?;
+
+  thread::spawn({
+    let atlaspack_ref = atlaspack_napi.clone();
+    move || {
+      let config = atlaspack_ref.read().print_config();
+
+      deferred.resolve(move |env| env.to_js_value(&config))
+    }
+  });
+
+  Ok(promise)
+}
+
 /// Check that the LMDB database is healthy
 ///
 /// JavaScript does all its writes through a single thread, which is not this handle. If we want
@@ -3,7 +3,7 @@ use std::{
   path::{Path, PathBuf},
 };
 
-use atlaspack::file_system::FileSystem;
+use atlaspack::file_system::{DirEntry, FileSystem};
 use napi::{Env, JsObject};
 
 use atlaspack_napi_helpers::js_callable::JsCallable;
@@ -79,7 +79,7 @@ impl FileSystem for FileSystemNapi {
       .map_err(io::Error::other)
   }
 
-  fn read_dir(&self, _path: &Path) -> std::io::Result<std::fs::ReadDir> {
+  fn read_dir(&self, _path: &Path) -> std::io::Result<Vec<DirEntry>> {
     todo!("FileSystemNapi::read_dir")
   }
 
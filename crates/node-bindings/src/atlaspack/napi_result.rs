@@ -1,8 +1,9 @@
+use atlaspack_core::types::Diagnostic;
 use napi::{Env, JsObject, bindgen_prelude::ToNapiValue};
 
 /// This creates the following JavaScript tuple
 /// ```
-/// [result: any | null, error: any | null]
+/// [result: any | null, error: any | null, warnings?: Array<Diagnostic>]
 /// ```
 pub struct NapiAtlaspackResult;
 
@@ -28,4 +29,17 @@ impl NapiAtlaspackResult {
     obj.set(1, target)?;
     obj.coerce_to_object()
   }
+
+  /// Appends the warnings that were reported while producing the result
+  /// ```
+  /// [JsAny | null, JsAny | null, Array<Diagnostic>]
+  /// ```
+  pub fn with_warnings(
+    env: &Env,
+    mut result: JsObject,
+    warnings: &[Diagnostic],
+  ) -> napi::Result<JsObject> {
+    result.set_element(2, env.to_js_value(&warnings)?)?;
+    Ok(result)
+  }
 }
@@ -0,0 +1,68 @@
+use std::collections::HashMap;
+
+use atlaspack_plugin_optimizer_html::{
+  MarkupOptimizerConfig, inline_scripts, optimize_html, optimize_svg,
+};
+use napi::{Env, JsObject};
+use napi_derive::napi;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarkupOptimizerInput {
+  code: String,
+  /// Whether the bundle is an SVG image rather than an HTML document
+  is_svg: bool,
+  /// The `@atlaspack/optimizer-html` options that apply to the bundle's target
+  #[serde(default)]
+  config: MarkupOptimizerConfig,
+  /// Inline scripts that have been minified by the JS optimizers, keyed by their original code
+  #[serde(default)]
+  minified_scripts: HashMap<String, String>,
+}
+
+#[napi(object)]
+pub struct HtmlInlineScript {
+  pub code: String,
+  pub is_module: bool,
+}
+
+/// Returns the inline scripts of an HTML document, so they can be minified by the JS optimizers
+/// before the document is passed to `runMarkupOptimizer`
+#[napi]
+pub fn get_html_inline_scripts(code: String) -> napi::Result<Vec<HtmlInlineScript>> {
+  let scripts = inline_scripts(&code)
+    .map_err(|err| napi::Error::from_reason(format!("[napi] Failed to parse HTML: {}", err)))?;
+
+  Ok(
+    scripts
+      .into_iter()
+      .map(|script| HtmlInlineScript {
+        code: script.code,
+        is_module: script.is_module,
+      })
+      .collect(),
+  )
+}
+
+/// Minifies an HTML or SVG bundle with the native HTML optimizer
+#[napi]
+pub fn run_markup_optimizer(env: Env, input: JsObject) -> napi::Result<String> {
+  let input = env.from_js_value::<MarkupOptimizerInput, _>(input)?;
+  let config = input.config;
+
+  let result = if input.is_svg {
+    optimize_svg(&input.code, &config.svg)
+  } else {
+    optimize_html(
+      &input.code,
+      &config.html,
+      &config.svg,
+      &input.minified_scripts,
+    )
+  };
+
+  result.map_err(|err| {
+    napi::Error::from_reason(format!("[napi] Failed to run markup optimizer: {}", err))
+  })
+}
@@ -1 +1,2 @@
 mod inline_requires_optimizer;
+mod markup_optimizer;
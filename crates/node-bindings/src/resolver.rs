@@ -18,7 +18,7 @@ use napi::Result;
 use napi::bindgen_prelude::Either3;
 use napi_derive::napi;
 
-use atlaspack::file_system::{FileSystemRealPathCache, FileSystemRef};
+use atlaspack::file_system::{DirEntry, FileSystemRealPathCache, FileSystemRef};
 use atlaspack_resolver::ExportsCondition;
 use atlaspack_resolver::Extensions;
 use atlaspack_resolver::Fields;
@@ -154,7 +154,7 @@ impl FileSystem for JsFileSystem {
   fn read(&self, _path: &Path) -> std::io::Result<Vec<u8>> {
     todo!()
   }
-  fn read_dir(&self, _path: &Path) -> std::io::Result<std::fs::ReadDir> {
+  fn read_dir(&self, _path: &Path) -> std::io::Result<Vec<DirEntry>> {
     todo!("JsFileSystem::read_dir")
   }
 }
@@ -1,4 +1,7 @@
-use atlaspack_sourcemap::{Mapping, OriginalLocation, SourceMap, SourceMapError};
+use atlaspack_resolver::OsFileSystem;
+use atlaspack_sourcemap::{
+  Mapping, OriginalLocation, SourceMap, SourceMapError, StackTraceSymbolicator,
+};
 use napi::{
   Env, JsObject, JsTypedArray, JsUnknown, NapiValue, Result,
   bindgen_prelude::{Array, Buffer, ToNapiValue},
@@ -6,6 +9,7 @@ use napi::{
 use napi_derive::napi;
 use rkyv::AlignedVec;
 use std::path::Path;
+use std::sync::Arc;
 
 #[cfg(target_os = "macos")]
 #[global_allocator]
@@ -112,6 +116,38 @@ impl JsSourceMap {
     }
   }
 
+  #[napi]
+  pub fn set_debug_id(&mut self, debug_id: Option<String>) {
+    self.source_map.set_debug_id(debug_id);
+  }
+
+  #[napi]
+  pub fn get_debug_id(&self) -> Option<String> {
+    self.source_map.get_debug_id().map(String::from)
+  }
+
+  #[napi]
+  pub fn add_ignored_source(&mut self, source: String) -> Result<()> {
+    let source_index = self.source_map.add_source(&source);
+    self
+      .source_map
+      .add_ignored_source(source_index)
+      .map_err(to_napi_error)
+  }
+
+  #[napi]
+  pub fn get_ignore_list(&self) -> Vec<u32> {
+    self.source_map.get_ignore_list().clone()
+  }
+
+  #[napi]
+  pub fn populate_ignore_list(&mut self, globs: Vec<String>) -> Result<()> {
+    self
+      .source_map
+      .populate_ignore_list(&globs)
+      .map_err(to_napi_error)
+  }
+
   #[napi]
   pub fn add_name(&mut self, name: String) -> u32 {
     self.source_map.add_name(&name)
@@ -227,6 +263,12 @@ impl JsSourceMap {
         ToNapiValue::to_napi_value(env.raw(), self.get_names())?,
       )
     })?;
+    if let Some(debug_id) = self.source_map.get_debug_id() {
+      result_obj.set_named_property("debugId", env.create_string(debug_id)?)?;
+    }
+    if !self.source_map.get_ignore_list().is_empty() {
+      result_obj.set_named_property("ignoreList", self.get_ignore_list())?;
+    }
     Ok(result_obj)
   }
 
@@ -346,6 +388,41 @@ impl JsSourceMap {
   }
 }
 
+#[napi(object)]
+pub struct SymbolicateStackTraceOptions {
+  pub project_root: String,
+  /// The directory containing the bundles and source maps referenced by the stack trace
+  pub dist_dir: String,
+  /// How many source lines to include around each original location, defaults to 5
+  pub context_lines: Option<u32>,
+}
+
+/// Maps the frames of a production stack trace back to their original source locations
+#[napi]
+pub fn symbolicate_stack_trace(
+  env: Env,
+  stack: String,
+  options: SymbolicateStackTraceOptions,
+) -> Result<JsUnknown> {
+  let mut symbolicator = StackTraceSymbolicator::new(
+    Arc::new(OsFileSystem),
+    Path::new(&options.project_root),
+    Path::new(&options.dist_dir),
+  );
+
+  if let Some(context_lines) = options.context_lines {
+    symbolicator = symbolicator.with_context_lines(context_lines as usize);
+  }
+
+  env.to_js_value(&symbolicator.symbolicate(&stack))
+}
+
+/// Generates the debug ID that links a bundle to its source map from the bundle contents
+#[napi]
+pub fn generate_debug_id(contents: String) -> String {
+  atlaspack_sourcemap::generate_debug_id(contents.as_bytes())
+}
+
 fn mapping_to_js_object(env: &Env, mapping: &Mapping) -> Result<JsObject> {
   let mut mapping_obj = env.create_object()?;
 
@@ -1,6 +1,6 @@
 use std::path::Path;
 
-pub use atlaspack_vcs::{FailureMode, VCSState};
+pub use atlaspack_vcs::{FailureMode, GitFallback, VCSState};
 use napi::{Env, JsObject, JsUnknown};
 use napi_derive::napi;
 use serde::{Deserialize, Serialize};
@@ -43,6 +43,7 @@ pub fn get_vcs_state_snapshot(
       path,
       &exclude_patterns,
       FailureMode::IgnoreMissingNodeModules,
+      GitFallback::Cli,
     )
   })
 }
@@ -77,6 +78,7 @@ pub fn get_events_since(
       &vcs_state,
       new_rev.as_deref(),
       FailureMode::IgnoreMissingNodeModules,
+      GitFallback::Cli,
     )?;
 
     let files: Vec<NodeChangeEvent> = files
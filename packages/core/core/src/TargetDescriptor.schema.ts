@@ -100,6 +100,10 @@ export const PACKAGE_DESCRIPTOR_SCHEMA: SchemaObject = {
             inline: {
               type: 'boolean',
             },
+            ignoreList: {
+              type: 'array',
+              items: {type: 'string'},
+            },
           },
           additionalProperties: false,
         },
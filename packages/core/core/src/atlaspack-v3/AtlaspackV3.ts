@@ -5,6 +5,8 @@ import {
   atlaspackNapiBuildBundleGraph,
   atlaspackNapiRespondToFsEvents,
   atlaspackNapiCompleteSession,
+  atlaspackNapiExplainPlugins,
+  atlaspackNapiPrintConfig,
   atlaspackNapiLoadBundleGraph,
   atlaspackNapiPackage,
   atlaspackNapiUpdateBundleGraph,
@@ -13,9 +15,12 @@ import {
   AtlaspackNapiOptions,
   CacheStats,
   PackageOptions,
+  PluginsExplanation,
+  PrintedConfig,
 } from '@atlaspack/rust';
 import {NapiWorkerPool} from './NapiWorkerPool';
 import ThrowableDiagnostic, {Diagnostic} from '@atlaspack/diagnostic';
+import logger from '@atlaspack/logger';
 import type {Event} from '@parcel/watcher';
 import type {NapiWorkerPool as INapiWorkerPool} from '@atlaspack/types';
 import type BundleGraph from '../BundleGraph';
@@ -35,6 +40,19 @@ export type AtlaspackV3Options = {
   napiWorkerPool?: INapiWorkerPool;
 } & AtlaspackNapiOptions['options'];
 
+/**
+ * Logs the warnings that native plugins reported while producing a result.
+ * Results are `[result, error, warnings]` tuples.
+ */
+function logWarnings<T>(result: T): T {
+  let warnings: Array<Diagnostic> | undefined = (result as any)?.[2];
+  if (warnings != null && warnings.length > 0) {
+    logger.warn(warnings);
+  }
+
+  return result;
+}
+
 export class AtlaspackV3 {
   _atlaspack_napi: AtlaspackNapi;
   _napiWorkerPool: INapiWorkerPool;
@@ -101,29 +119,38 @@ export class AtlaspackV3 {
     }
   }
 
-  buildAssetGraph(
+  async buildAssetGraph(
     progressCallback?: (eventJson: string) => void,
   ): Promise<any> {
-    return atlaspackNapiBuildAssetGraph(
+    let result = atlaspackNapiBuildAssetGraph(
       this._atlaspack_napi,
       progressCallback,
-    ) as Promise<any>;
+    ) as any;
+
+    return {
+      ...result,
+      assetGraphPromise: result.assetGraphPromise.then(logWarnings),
+    };
   }
 
-  buildBundleGraph(
+  async buildBundleGraph(
     progressCallback?: (eventJson: string) => void,
   ): Promise<any> {
-    return atlaspackNapiBuildBundleGraph(
+    let result = atlaspackNapiBuildBundleGraph(
       this._atlaspack_napi,
       progressCallback,
-    ) as Promise<any>;
+    ) as any;
+
+    return {
+      ...result,
+      bundleGraphPromise: result.bundleGraphPromise.then(logWarnings),
+    };
   }
 
   build(progressCallback: (eventJson: string) => void): Promise<any> {
-    return atlaspackNapiBuild(
-      this._atlaspack_napi,
-      progressCallback,
-    ) as Promise<any>;
+    return (
+      atlaspackNapiBuild(this._atlaspack_napi, progressCallback) as Promise<any>
+    ).then(logWarnings);
   }
 
   loadBundleGraph(bundleGraph: BundleGraph): Promise<void> {
@@ -154,18 +181,17 @@ export class AtlaspackV3 {
     bundleId: string,
     options?: PackageOptions,
   ): Promise<[RunPackagerRunnerResult, Diagnostic | null]> {
-    return atlaspackNapiPackage(
-      this._atlaspack_napi,
-      bundleId,
-      options,
-    ) as Promise<[RunPackagerRunnerResult, Diagnostic | null]>;
+    return (
+      atlaspackNapiPackage(this._atlaspack_napi, bundleId, options) as Promise<
+        [RunPackagerRunnerResult, Diagnostic | null]
+      >
+    ).then(logWarnings);
   }
 
   async respondToFsEvents(events: Array<Event>): Promise<boolean> {
     // @ts-expect-error TS2488
-    let [needsRebuild, error] = await atlaspackNapiRespondToFsEvents(
-      this._atlaspack_napi,
-      events,
+    let [needsRebuild, error] = logWarnings(
+      await atlaspackNapiRespondToFsEvents(this._atlaspack_napi, events),
     );
 
     if (error) {
@@ -175,6 +201,21 @@ export class AtlaspackV3 {
     return needsRebuild;
   }
 
+  explainPlugins(
+    filePath: string,
+    pipeline?: string | null,
+  ): Promise<PluginsExplanation> {
+    return atlaspackNapiExplainPlugins(
+      this._atlaspack_napi,
+      filePath,
+      pipeline,
+    );
+  }
+
+  printConfig(): Promise<PrintedConfig> {
+    return atlaspackNapiPrintConfig(this._atlaspack_napi);
+  }
+
   async completeCacheSession(): Promise<CacheStats> {
     return (await atlaspackNapiCompleteSession(
       this._atlaspack_napi,
@@ -0,0 +1,299 @@
+// @ts-expect-error TS2305
+import type {Bundle as NapiBundle} from '@atlaspack/rust';
+import type {FileSystem} from '@atlaspack/types';
+
+import {Dependency} from './dependency';
+import {Environment} from './environment';
+import {MutableAsset} from './mutable-asset';
+import {Target} from './target';
+import {bundleBehaviorMap} from './bitflags';
+
+/**
+ * The part of the bundle graph that is sent to packagers and optimizers, for the bundle that
+ * is being packaged. Asset and inline bundle contents are base64 encoded.
+ */
+export type RpcBundleGraph = {
+  assets: Array<{asset: any; contents: string}>;
+  bundles: Array<NapiBundle>;
+  dependencies: Record<string, Array<any>>;
+  inlineBundles: Record<string, {contents: string; map: string | null}>;
+  publicIds: Record<string, string>;
+  referencedBundleIds: Array<string>;
+  resolvedAssets: Record<string, string>;
+  skippedDependencies: Array<string>;
+};
+
+/**
+ * Dependencies sent from Rust include their id, which the transformer compat layer does not
+ * expose as it is only known once the dependency has been created natively.
+ */
+class BundleGraphDependency extends Dependency {
+  #id: string;
+
+  constructor(inner: any, env: Environment) {
+    super(inner, env);
+    this.#id = inner.id;
+  }
+
+  get id(): string {
+    return this.#id;
+  }
+}
+
+/**
+ * A read-only view of a bundle created by the native bundler, for plugins that run after
+ * bundling. Asset traversal is only available for the bundle that is being packaged.
+ */
+export function createBundle(
+  inner: NapiBundle,
+  assets: Array<MutableAsset> | null = null,
+  dependencies: Map<string, Array<BundleGraphDependency>> | null = null,
+): any {
+  const env = new Environment(inner.env);
+
+  const bundleAssets = () => {
+    if (assets == null) {
+      throw new Error(
+        `The assets of bundle ${inner.id} are not available, as it is not being packaged`,
+      );
+    }
+    return assets;
+  };
+
+  const traverse = (visit: any) => {
+    const enter = typeof visit === 'function' ? visit : visit.enter;
+    const exit = typeof visit === 'function' ? null : visit.exit;
+    let stopped = false;
+    const actions = {
+      skipChildren() {},
+      stop() {
+        stopped = true;
+      },
+    };
+
+    let context;
+    for (const asset of bundleAssets()) {
+      const nodes = [
+        {type: 'asset', value: asset},
+        ...(dependencies?.get(asset.id) ?? []).map((dependency) => ({
+          type: 'dependency',
+          value: dependency,
+        })),
+      ];
+
+      for (const node of nodes) {
+        context = enter?.(node, context, actions);
+        if (stopped) return context;
+        exit?.(node, context, actions);
+        if (stopped) return context;
+      }
+    }
+
+    return context;
+  };
+
+  return Object.freeze({
+    id: inner.id,
+    type: inner.type,
+    env,
+    target: new Target(inner.target, env),
+    name: inner.name,
+    publicId: inner.publicId,
+    hashReference: inner.hashReference,
+    bundleBehavior: bundleBehaviorMap.fromNullable(inner.bundleBehavior),
+    isSplittable: inner.isSplittable,
+    needsStableName: inner.needsStableName,
+    manualSharedBundle: inner.manualSharedBundle,
+    pipeline: inner.pipeline,
+    entryAssetIds: inner.entryAssetIds,
+    mainEntryId: inner.mainEntryId,
+    getEntryAssets() {
+      return bundleAssets().filter((asset) =>
+        inner.entryAssetIds.includes(asset.id),
+      );
+    },
+    getMainEntry() {
+      return bundleAssets().find((asset) => asset.id === inner.mainEntryId);
+    },
+    hasAsset(asset: {id: string}) {
+      return bundleAssets().some(({id}) => id === asset.id);
+    },
+    hasDependency(dependency: {id: string}) {
+      return bundleAssets().some((asset) =>
+        dependencies?.get(asset.id)?.some(({id}) => id === dependency.id),
+      );
+    },
+    traverse,
+    traverseAssets(visit: any) {
+      const visitAsset = (visitor: any) =>
+        visitor == null
+          ? null
+          : (node: any, context: any, actions: any) =>
+              node.type === 'asset'
+                ? visitor(node.value, context, actions)
+                : context;
+
+      return typeof visit === 'function'
+        ? traverse(visitAsset(visit))
+        : traverse({
+            enter: visitAsset(visit.enter),
+            exit: visitAsset(visit.exit),
+          });
+    },
+  });
+}
+
+/**
+ * Creates the bundle graph given to packagers and optimizers from the view collected natively.
+ * Methods that need the rest of the graph fail with an error that names the plugin.
+ */
+export function createBundleGraph(
+  key: string,
+  bundle: NapiBundle,
+  data: RpcBundleGraph,
+  fs: FileSystem,
+  projectRoot: string,
+): {bundle: any; bundleGraph: any} {
+  const assetsById = new Map<string, MutableAsset>();
+  const dependencies = new Map<string, Array<BundleGraphDependency>>();
+
+  for (const {asset: inner, contents} of data.assets) {
+    const env = new Environment(inner.env);
+    const asset = new MutableAsset(
+      inner,
+      Buffer.from(contents, 'base64'),
+      env,
+      fs,
+      null,
+      projectRoot,
+    );
+
+    assetsById.set(inner.id, asset);
+    dependencies.set(
+      inner.id,
+      (data.dependencies[inner.id] ?? []).map(
+        (dependency) =>
+          new BundleGraphDependency(
+            dependency,
+            new Environment(dependency.env),
+          ),
+      ),
+    );
+  }
+
+  const packagedBundle = createBundle(
+    bundle,
+    Array.from(assetsById.values()),
+    dependencies,
+  );
+  const bundles = new Map<string, any>(
+    data.bundles.map((inner) => [
+      inner.id,
+      inner.id === bundle.id ? packagedBundle : createBundle(inner),
+    ]),
+  );
+  const skippedDependencies = new Set(data.skippedDependencies);
+
+  const bundleGraph = {
+    getAssetById(id: string) {
+      const asset = assetsById.get(id);
+      if (asset == null) {
+        throw new Error(`Asset ${id} is not in bundle ${bundle.id}`);
+      }
+      return asset;
+    },
+    getAssetPublicId(asset: {id: string}) {
+      const publicId = data.publicIds[asset.id];
+      if (publicId == null) {
+        throw new Error(`Asset ${asset.id} does not have a public id`);
+      }
+      return publicId;
+    },
+    getBundles() {
+      return Array.from(bundles.values());
+    },
+    getDependencies(asset: {id: string}) {
+      return dependencies.get(asset.id) ?? [];
+    },
+    getReferencedBundles(referencing: {id: string}) {
+      if (referencing.id !== bundle.id) {
+        throw new Error(
+          `Plugin ${key} requested the bundles referenced by ${referencing.id}, but only those of ${bundle.id} are available`,
+        );
+      }
+      return data.referencedBundleIds
+        .map((id) => bundles.get(id))
+        .filter(Boolean);
+    },
+    getResolvedAsset(dependency: {id: string}) {
+      const assetId = data.resolvedAssets[dependency.id];
+      return assetId == null ? null : assetsById.get(assetId) ?? null;
+    },
+    isDependencySkipped(dependency: {id: string}) {
+      return skippedDependencies.has(dependency.id);
+    },
+    traverseBundles(visit: any) {
+      const enter = typeof visit === 'function' ? visit : visit.enter;
+      let stopped = false;
+      const actions = {
+        skipChildren() {},
+        stop() {
+          stopped = true;
+        },
+      };
+
+      let context;
+      for (const visited of bundles.values()) {
+        context = enter?.(visited, context, actions);
+        if (stopped) break;
+      }
+      return context;
+    },
+  };
+
+  return {
+    bundle: packagedBundle,
+    bundleGraph: new Proxy(bundleGraph, {
+      get(target, property, receiver) {
+        // `then` is read when the graph is returned from an async function
+        if (
+          property in target ||
+          typeof property === 'symbol' ||
+          property === 'then'
+        ) {
+          return Reflect.get(target, property, receiver);
+        }
+
+        throw new Error(
+          `Plugin ${key} accessed bundleGraph.${String(
+            property,
+          )}, which is not available to plugins in native builds`,
+        );
+      },
+    }),
+  };
+}
+
+/**
+ * Namers and runtimes run before packaging, so no bundle graph view is sent to them. Accessing it
+ * fails with an error that names the plugin rather than an undefined property error.
+ */
+export function createUnavailableBundleGraph(key: string): any {
+  return new Proxy(
+    {},
+    {
+      get(_target, property) {
+        // `then` is read when the graph is returned from an async function
+        if (property === 'then' || typeof property === 'symbol') {
+          return undefined;
+        }
+
+        throw new Error(
+          `Plugin ${key} accessed bundleGraph.${String(
+            property,
+          )}, but the bundle graph is not available to namers and runtimes in native builds`,
+        );
+      },
+    },
+  );
+}
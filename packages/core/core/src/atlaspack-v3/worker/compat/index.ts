@@ -6,3 +6,4 @@ export * from './plugin-config';
 export * from './plugin-tracer';
 export * from './plugin-options';
 export * from './mutable-asset';
+export * from './bundle';
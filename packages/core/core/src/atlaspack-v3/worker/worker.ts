@@ -6,14 +6,22 @@ const sideEffectDetector = new SideEffectDetector();
 sideEffectDetector.install();
 
 import assert from 'assert';
+import path from 'path';
 import * as napi from '@atlaspack/rust';
 // @ts-expect-error TS2305
 import type {JsCallable} from '@atlaspack/rust';
 import {NodeFS} from '@atlaspack/fs';
 import {NodePackageManager} from '@atlaspack/package-manager';
 import type {
+  Compressor,
+  Namer,
+  Optimizer,
+  Packager,
+  Reporter,
   Resolver,
+  Runtime,
   Transformer,
+  Validator,
   FilePath,
   FileSystem,
 } from '@atlaspack/types';
@@ -21,6 +29,9 @@ import type {FeatureFlags} from '@atlaspack/feature-flags';
 import {parentPort} from 'worker_threads';
 import logger from '@atlaspack/logger';
 import * as module from 'module';
+import {Readable} from 'stream';
+import SourceMap from '@atlaspack/source-map';
+import {blobToBuffer} from '@atlaspack/utils';
 
 import {jsCallable} from '../jsCallable';
 import {PluginLogger} from '@atlaspack/logger';
@@ -32,14 +43,19 @@ import {
   PluginOptions,
   MutableAsset,
   bundleBehaviorMap,
+  createBundle,
+  createBundleGraph,
+  createUnavailableBundleGraph,
   dependencyPriorityMap,
 } from './compat';
+import type {RpcBundleGraph} from './compat';
 
 const CONFIG = Symbol.for('parcel-plugin-config');
 
 export class AtlaspackWorker {
   #resolvers: Map<string, ResolverState<any>>;
   #transformers: Map<string, TransformerState<any>>;
+  #plugins: Map<string, PluginState>;
   #fs: FileSystem;
   #packageManager: NodePackageManager;
   #options: Options | undefined;
@@ -48,6 +64,7 @@ export class AtlaspackWorker {
   constructor() {
     this.#resolvers = new Map();
     this.#transformers = new Map();
+    this.#plugins = new Map();
     this.#fs = new NodeFS();
     this.#packageManager = new NodePackageManager(this.#fs, '/');
     this.#sideEffectDetector = sideEffectDetector; // Use the global detector that was installed before imports
@@ -56,6 +73,7 @@ export class AtlaspackWorker {
   clearState() {
     this.#resolvers.clear();
     this.#transformers.clear();
+    this.#plugins.clear();
     this.#options = undefined;
   }
 
@@ -113,6 +131,8 @@ export class AtlaspackWorker {
         case 'transformer': {
           return this.initializeTransformer(instance, specifier);
         }
+        default:
+          this.#plugins.set(specifier, {kind, plugin: instance});
       }
     },
   );
@@ -386,6 +406,212 @@ export class AtlaspackWorker {
     ];
   });
 
+  runNamerName: JsCallable<[RunNamerNameOptions], Promise<string | null>> =
+    jsCallable(async ({key, bundle}) => {
+      const {plugin, config} = await this.getPlugin<Namer<unknown>>(
+        key,
+        'namer',
+      );
+
+      const name = await plugin.name({
+        bundle: createBundle(bundle),
+        bundleGraph: createUnavailableBundleGraph(key),
+        config,
+        ...this.defaultOptions(key),
+      });
+
+      return name ?? null;
+    });
+
+  runRuntimeApply: JsCallable<
+    [RunRuntimeApplyOptions],
+    Promise<Array<RpcRuntimeAsset>>
+  > = jsCallable(async ({key, bundle}) => {
+    const {plugin, config} = await this.getPlugin<Runtime<unknown>>(
+      key,
+      'runtime',
+    );
+
+    const result = await plugin.apply({
+      bundle: createBundle(bundle),
+      bundleGraph: createUnavailableBundleGraph(key),
+      config,
+      ...this.defaultOptions(key),
+    });
+
+    const runtimeAssets = result == null ? [] : [result].flat();
+
+    return runtimeAssets.map((runtimeAsset) => {
+      assert(
+        runtimeAsset.dependency == null,
+        '[V3] Unimplemented: Runtime assets with a dependency',
+      );
+
+      return {
+        filePath: runtimeAsset.filePath,
+        code: runtimeAsset.code,
+        isEntry: runtimeAsset.isEntry ?? false,
+      };
+    });
+  });
+
+  runPackagerPackage: JsCallable<
+    [RunPackagerPackageOptions],
+    Promise<RpcContentsResult>
+  > = jsCallable(async ({key, bundle: innerBundle, bundleGraph: graphData}) => {
+    const {plugin, config} = await this.getPlugin<Packager<unknown, unknown>>(
+      key,
+      'packager',
+    );
+
+    const {bundle, bundleGraph} = createBundleGraph(
+      key,
+      innerBundle,
+      graphData,
+      this.#fs,
+      this.options.projectRoot,
+    );
+
+    const result = await plugin.package({
+      bundle,
+      bundleGraph,
+      bundleConfig: undefined,
+      config,
+      getInlineBundleContents: (inlineBundle: {id: string}) => {
+        const contents = graphData.inlineBundles[inlineBundle.id];
+        if (contents == null) {
+          throw new Error(
+            `Packager ${key} requested the contents of ${inlineBundle.id}, which is not an inline bundle of ${innerBundle.id}`,
+          );
+        }
+
+        return Promise.resolve({
+          contents: Buffer.from(contents.contents, 'base64'),
+        });
+      },
+      getSourceMapReference: (map: SourceMap | null | undefined) =>
+        this.getSourceMapReference(bundle, map),
+      ...this.defaultOptions(key),
+    });
+
+    return this.intoContentsResult(result.contents, result.map);
+  });
+
+  runOptimizerOptimize: JsCallable<
+    [RunOptimizerOptimizeOptions],
+    Promise<RpcContentsResult>
+  > = jsCallable(async ({key, contents, map, ...options}) => {
+    const {plugin, config} = await this.getPlugin<
+      Optimizer<unknown, unknown>
+    >(key, 'optimizer');
+
+    let sourceMap = null;
+    if (map != null) {
+      sourceMap = new SourceMap(this.options.projectRoot);
+      sourceMap.addVLQMap(JSON.parse(map));
+    }
+
+    const {bundle, bundleGraph} = createBundleGraph(
+      key,
+      options.bundle,
+      options.bundleGraph,
+      this.#fs,
+      this.options.projectRoot,
+    );
+
+    const result = await plugin.optimize({
+      bundle,
+      bundleGraph,
+      bundleConfig: undefined,
+      config,
+      contents: Buffer.from(contents, 'base64'),
+      map: sourceMap,
+      getSourceMapReference: (map: SourceMap | null | undefined) =>
+        this.getSourceMapReference(bundle, map),
+      ...this.defaultOptions(key),
+    });
+
+    return this.intoContentsResult(result.contents, result.map);
+  });
+
+  runCompressorCompress: JsCallable<
+    [RunCompressorCompressOptions],
+    Promise<RpcCompressedFile | null>
+  > = jsCallable(async ({key, contents}) => {
+    const {plugin} = await this.getPlugin<Compressor>(key, 'compressor');
+
+    const result = await plugin.compress({
+      stream: Readable.from(Buffer.from(contents, 'base64')),
+      ...this.defaultOptions(key),
+    });
+
+    if (result == null) {
+      return null;
+    }
+
+    return {
+      contents: (await blobToBuffer(result.stream)).toString('base64'),
+      extension: result.type ?? null,
+    };
+  });
+
+  runReporterReport: JsCallable<[RunReporterReportOptions], Promise<void>> =
+    jsCallable(async ({key, event}) => {
+      const {plugin} = await this.getPlugin<Reporter>(key, 'reporter');
+
+      await plugin.report({
+        event,
+        ...this.defaultOptions(key),
+      });
+    });
+
+  runValidatorValidate: JsCallable<
+    [RunValidatorValidateOptions],
+    Promise<RpcValidateResult>
+  > = jsCallable(async ({key, asset: innerAsset, contents}) => {
+    const {plugin} = await this.getPlugin<Validator>(key, 'validator');
+
+    if (!('validate' in plugin)) {
+      throw new Error(
+        `[V3] Unimplemented: Validator ${key} only implements validateAll`,
+      );
+    }
+
+    const env = new Environment(innerAsset.env);
+    const asset = new MutableAsset(
+      innerAsset,
+      // @ts-expect-error TS2345
+      Buffer.from(contents, 'base64'),
+      env,
+      this.#fs,
+      null,
+      this.options.projectRoot,
+    );
+
+    const defaultOptions = this.defaultOptions(key);
+    const config = await plugin.getConfig?.({
+      // @ts-expect-error TS2322
+      asset,
+      resolveConfig: () => Promise.resolve(null),
+      options: defaultOptions.options,
+    });
+
+    const result = await plugin.validate({
+      // @ts-expect-error TS2322
+      asset,
+      config,
+      ...defaultOptions,
+    });
+
+    const messages = (diagnostics: any) =>
+      [diagnostics ?? []].flat().map((diagnostic: any) => diagnostic.message);
+
+    return {
+      errors: messages(result?.errors),
+      warnings: messages(result?.warnings),
+    };
+  });
+
   get options() {
     if (this.#options == null) {
       throw new Error('Plugin options have not been initialized');
@@ -393,6 +619,83 @@ export class AtlaspackWorker {
     return this.#options;
   }
 
+  defaultOptions(key: string) {
+    return {
+      logger: new PluginLogger({origin: key}),
+      tracer: new PluginTracer(),
+      options: new PluginOptions(this.options),
+    } as const;
+  }
+
+  /**
+   * Returns a plugin loaded by `loadPlugin`, calling its `loadConfig` on first use
+   */
+  async getPlugin<T>(
+    key: string,
+    kind: PluginKind,
+  ): Promise<{plugin: T; config: unknown}> {
+    const state = this.#plugins.get(key);
+    if (!state || state.kind !== kind) {
+      throw new Error(`${kind} not found: ${key}`);
+    }
+
+    if (!('config' in state)) {
+      state.config = await state.plugin.loadConfig?.({
+        config: new PluginConfig(
+          {
+            plugin: key,
+            isSource: true,
+            searchPath: 'index',
+          },
+          this.options,
+        ),
+        ...this.defaultOptions(key),
+      });
+    }
+
+    return {plugin: state.plugin, config: state.config};
+  }
+
+  /**
+   * Returns the `sourceMappingURL` of a bundle, like the `PackagerRunner` does for JS builds
+   */
+  async getSourceMapReference(
+    bundle: any,
+    map: SourceMap | null | undefined,
+  ): Promise<string | null> {
+    if (
+      map == null ||
+      !bundle.env.sourceMap ||
+      bundle.bundleBehavior === 'inline' ||
+      bundle.bundleBehavior === 'inlineIsolated'
+    ) {
+      return null;
+    }
+
+    const fileName = path.basename(bundle.name);
+    if (bundle.env.sourceMap.inline) {
+      return (await map.stringify({
+        file: `${fileName}.map`,
+        fs: this.#fs,
+        rootDir: this.options.projectRoot,
+        inlineSources: true,
+        format: 'inline',
+      })) as string;
+    }
+
+    return `${fileName}.map`;
+  }
+
+  async intoContentsResult(
+    contents: any,
+    map: SourceMap | null | undefined,
+  ): Promise<RpcContentsResult> {
+    return {
+      contents: (await blobToBuffer(contents)).toString('base64'),
+      map: map ? JSON.stringify(map.toVLQ()) : null,
+    };
+  }
+
   async initializeTransformer(instance: Transformer<any>, specifier: string) {
     let transformer = instance;
     let setup, config, allowedEnv;
@@ -481,8 +784,25 @@ type TransformerState<ConfigType> = {
   allowedEnv?: Record<string, string | undefined>;
 };
 
+type PluginKind =
+  | 'compressor'
+  | 'namer'
+  | 'optimizer'
+  | 'packager'
+  | 'reporter'
+  | 'resolver'
+  | 'runtime'
+  | 'transformer'
+  | 'validator';
+
+type PluginState = {
+  kind: PluginKind;
+  plugin: any;
+  config?: unknown;
+};
+
 type LoadPluginOptions = {
-  kind: 'resolver' | 'transformer';
+  kind: PluginKind;
   specifier: string;
   resolveFrom: string;
   options: RpcPluginOptions;
@@ -546,3 +866,69 @@ type RunTransformerTransformResult = [
   string,
   boolean,
 ];
+
+type RunNamerNameOptions = {
+  key: string;
+  // @ts-expect-error TS2694
+  bundle: napi.Bundle;
+};
+
+type RunRuntimeApplyOptions = {
+  key: string;
+  // @ts-expect-error TS2694
+  bundle: napi.Bundle;
+};
+
+type RpcRuntimeAsset = {
+  filePath: FilePath;
+  code: string;
+  isEntry: boolean;
+};
+
+type RunPackagerPackageOptions = {
+  key: string;
+  // @ts-expect-error TS2694
+  bundle: napi.Bundle;
+  bundleGraph: RpcBundleGraph;
+};
+
+type RunOptimizerOptimizeOptions = {
+  key: string;
+  // @ts-expect-error TS2694
+  bundle: napi.Bundle;
+  bundleGraph: RpcBundleGraph;
+  contents: string;
+  map: string | null;
+};
+
+type RpcContentsResult = {
+  contents: string;
+  map: string | null;
+};
+
+type RunCompressorCompressOptions = {
+  key: string;
+  contents: string;
+};
+
+type RpcCompressedFile = {
+  contents: string;
+  extension: string | null;
+};
+
+type RunReporterReportOptions = {
+  key: string;
+  event: any;
+};
+
+type RunValidatorValidateOptions = {
+  key: string;
+  // @ts-expect-error TS2694
+  asset: napi.Asset;
+  contents: string;
+};
+
+type RpcValidateResult = {
+  errors: Array<string>;
+  warnings: Array<string>;
+};
@@ -173,6 +173,10 @@ export class NodeVCSAwareFS extends NodeFS {
           dirtyFilesExecutionTime: vcsState?.dirtyFilesExecutionTime,
           // @ts-expect-error TS2339
           yarnStatesExecutionTime: vcsState?.yarnStatesExecutionTime,
+          // @ts-expect-error TS2339
+          npmStatesExecutionTime: vcsState?.npmStatesExecutionTime,
+          // @ts-expect-error TS2339
+          pnpmStatesExecutionTime: vcsState?.pnpmStatesExecutionTime,
         },
       });
     } catch (err: any) {
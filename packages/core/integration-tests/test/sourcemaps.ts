@@ -1172,6 +1172,27 @@ describe('sourcemaps', function () {
     ]);
   });
 
+  it('should link js bundles to their sourcemaps with a debug id', async function () {
+    let b = await bundle(
+      path.join(__dirname, '/integration/sourcemap/index.js'),
+    );
+
+    let filename = b.getBundles()[0].filePath;
+    let raw = await outputFS.readFile(filename, 'utf8');
+    let debugId = raw.match(/^\/\/# debugId=(.+)$/m)?.[1];
+    assert(debugId != null, 'js bundles should have a debugId comment');
+    assert(
+      raw.includes(`//# debugId=${debugId}\n//# sourceMappingURL=index.js.map`),
+    );
+
+    let mapUrlData = await loadSourceMapUrl(outputFS, filename, raw);
+    if (!mapUrlData) {
+      throw new Error('Could not load map');
+    }
+
+    assert.equal(mapUrlData.map.debugId, debugId);
+  });
+
   it('should respect --no-source-maps', async function () {
     let b = await bundle(
       path.join(__dirname, '/integration/sourcemap/index.js'),
@@ -319,8 +319,10 @@ const {
   atlaspackNapiBuildBundleGraph,
   atlaspackNapiCompleteSession,
   atlaspackNapiCreate,
+  atlaspackNapiExplainPlugins,
   atlaspackNapiLoadBundleGraph,
   atlaspackNapiPackage,
+  atlaspackNapiPrintConfig,
   atlaspackNapiRespondToFsEvents,
   atlaspackNapiUpdateBundleGraph,
   AtlaspackTracer,
@@ -336,6 +338,7 @@ const {
   getAvailableThreads,
   getEnvironment,
   getEventsSince,
+  getHtmlInlineScripts,
   getNativeMemoryStats,
   getVcsStateSnapshot,
   Hash,
@@ -354,9 +357,11 @@ const {
   Resolver,
   runInlineRequiresOptimizer,
   runInlineRequiresOptimizerAsync,
+  runMarkupOptimizer,
   sampleNativeMemory,
   setAllEnvironments,
   SourceMap,
+  symbolicateStackTrace,
   transform,
   transformAsync
 } = nativeBinding
@@ -369,8 +374,10 @@ module.exports.atlaspackNapiBuildAssetGraph = atlaspackNapiBuildAssetGraph
 module.exports.atlaspackNapiBuildBundleGraph = atlaspackNapiBuildBundleGraph
 module.exports.atlaspackNapiCompleteSession = atlaspackNapiCompleteSession
 module.exports.atlaspackNapiCreate = atlaspackNapiCreate
+module.exports.atlaspackNapiExplainPlugins = atlaspackNapiExplainPlugins
 module.exports.atlaspackNapiLoadBundleGraph = atlaspackNapiLoadBundleGraph
 module.exports.atlaspackNapiPackage = atlaspackNapiPackage
+module.exports.atlaspackNapiPrintConfig = atlaspackNapiPrintConfig
 module.exports.atlaspackNapiRespondToFsEvents = atlaspackNapiRespondToFsEvents
 module.exports.atlaspackNapiUpdateBundleGraph = atlaspackNapiUpdateBundleGraph
 module.exports.AtlaspackTracer = AtlaspackTracer
@@ -386,6 +393,7 @@ module.exports.getAllEnvironments = getAllEnvironments
 module.exports.getAvailableThreads = getAvailableThreads
 module.exports.getEnvironment = getEnvironment
 module.exports.getEventsSince = getEventsSince
+module.exports.getHtmlInlineScripts = getHtmlInlineScripts
 module.exports.getNativeMemoryStats = getNativeMemoryStats
 module.exports.getVcsStateSnapshot = getVcsStateSnapshot
 module.exports.Hash = Hash
@@ -404,8 +412,10 @@ module.exports.resetMemoryTracking = resetMemoryTracking
 module.exports.Resolver = Resolver
 module.exports.runInlineRequiresOptimizer = runInlineRequiresOptimizer
 module.exports.runInlineRequiresOptimizerAsync = runInlineRequiresOptimizerAsync
+module.exports.runMarkupOptimizer = runMarkupOptimizer
 module.exports.sampleNativeMemory = sampleNativeMemory
 module.exports.setAllEnvironments = setAllEnvironments
 module.exports.SourceMap = SourceMap
+module.exports.symbolicateStackTrace = symbolicateStackTrace
 module.exports.transform = transform
 module.exports.transformAsync = transformAsync
@@ -0,0 +1,33 @@
+import assert from 'assert';
+import {getHtmlInlineScripts, runMarkupOptimizer} from '..';
+
+describe('runMarkupOptimizer', () => {
+  it('minifies html with the minified inline scripts', () => {
+    const code = '<p>  a  </p><script>\n  const a = 1;\n</script>';
+
+    assert.deepEqual(getHtmlInlineScripts(code), [
+      {code: '\n  const a = 1;\n', isModule: false},
+    ]);
+
+    const result = runMarkupOptimizer({
+      code,
+      isSvg: false,
+      minifiedScripts: {'\n  const a = 1;\n': 'const a=1;'},
+    });
+
+    assert.equal(
+      result,
+      '<html><head></head><body><p> a </p><script>const a=1;</script></body></html>',
+    );
+  });
+
+  it('uses the given config', () => {
+    const result = runMarkupOptimizer({
+      code: '<svg><path d="M0.11111 0"/></svg>',
+      isSvg: true,
+      config: {svg: {floatPrecision: 1}},
+    });
+
+    assert.equal(result, '<svg><path d="M.1 0"></path></svg>');
+  });
+});
@@ -72,6 +72,44 @@ export declare function atlaspackNapiRespondToFsEvents(
   atlaspackNapi: AtlaspackNapi,
   options: object,
 ): object;
+export interface ConfiguredPlugin {
+  packageName: string;
+  configFile: string;
+  pattern: string | null;
+  loc: {
+    filePath: string;
+    start: {line: number; column: number};
+    end: {line: number; column: number};
+  } | null;
+}
+export interface PluginsExplanation {
+  filePath: string;
+  pipeline: string | null;
+  transformers: Array<ConfiguredPlugin>;
+  optimizers: Array<ConfiguredPlugin>;
+  packager: ConfiguredPlugin | null;
+  compressors: Array<ConfiguredPlugin>;
+}
+export declare function atlaspackNapiExplainPlugins(
+  atlaspackNapi: AtlaspackNapi,
+  filePath: string,
+  pipeline?: string | null,
+): Promise<PluginsExplanation>;
+export interface PrintedConfig {
+  bundler: ConfiguredPlugin;
+  compressors: Array<ConfiguredPlugin>;
+  namers: Array<ConfiguredPlugin>;
+  optimizers: Array<ConfiguredPlugin>;
+  packagers: Array<ConfiguredPlugin>;
+  reporters: Array<ConfiguredPlugin>;
+  resolvers: Array<ConfiguredPlugin>;
+  runtimes: Array<ConfiguredPlugin>;
+  transformers: Array<ConfiguredPlugin>;
+  validators: Array<ConfiguredPlugin>;
+}
+export declare function atlaspackNapiPrintConfig(
+  atlaspackNapi: AtlaspackNapi,
+): Promise<PrintedConfig>;
 export interface CacheStats {
   hits: number;
   misses: number;
@@ -79,6 +117,29 @@ export interface CacheStats {
   bailouts: number;
   errors: number;
   validations: number;
+  /** Validations where the cached value did not match the computed result */
+  mismatches: number;
+  /** Per-tier stats when a remote cache is configured, such as `local` and `remote` */
+  tiers: Array<CacheTierStats>;
+  /** Why each cache miss happened, when `ATLASPACK_EXPLAIN_CACHE_MISSES=true` */
+  missExplanations: Array<CacheMissExplanation>;
+}
+export interface CacheTierStats {
+  name: string;
+  hits: number;
+  misses: number;
+  errors: number;
+  writes: number;
+  skipped: number;
+}
+export interface CacheMissExplanation {
+  label: string;
+  reason: 'NewLabel' | 'Evicted' | {Changed: Array<CacheKeyComponentChange>};
+}
+export interface CacheKeyComponentChange {
+  name: string;
+  previous: string | null;
+  current: string | null;
 }
 export declare function atlaspackNapiCompleteSession(
   atlaspackNapi: AtlaspackNapi,
@@ -117,6 +178,25 @@ export declare function runInlineRequiresOptimizer(
 export declare function runInlineRequiresOptimizerAsync(
   input: InlineRequiresOptimizerInput,
 ): object;
+export interface HtmlInlineScript {
+  code: string;
+  isModule: boolean;
+}
+/**
+ * Returns the inline scripts of an HTML document, so they can be minified by the JS optimizers
+ * before the document is passed to `runMarkupOptimizer`
+ */
+export declare function getHtmlInlineScripts(
+  code: string,
+): Array<HtmlInlineScript>;
+export interface MarkupOptimizerInput {
+  code: string;
+  isSvg: boolean;
+  config?: unknown;
+  minifiedScripts?: Record<string, string>;
+}
+/** Minifies an HTML or SVG bundle with the native HTML optimizer */
+export declare function runMarkupOptimizer(input: MarkupOptimizerInput): string;
 export interface JsFileSystemOptions {
   canonicalize: (...args: any[]) => any;
   read: (...args: any[]) => any;
@@ -369,6 +449,11 @@ export class SourceMap {
   getSourceIndex(source: string): number;
   setSourceContentBySource(source: string, sourceContent: string): void;
   getSourceContentBySource(source: string): string;
+  setDebugId(debugId?: string | undefined | null): void;
+  getDebugId(): string | null;
+  addIgnoredSource(source: string): void;
+  getIgnoreList(): Array<number>;
+  populateIgnoreList(globs: Array<string>): void;
   addName(name: string): number;
   getName(nameIndex: number): string;
   getNames(): Array<string>;
@@ -400,6 +485,39 @@ export class SourceMap {
   ): object | null;
   getProjectRoot(): string;
 }
+export interface SymbolicateStackTraceOptions {
+  projectRoot: string;
+  /** The directory containing the bundles and source maps referenced by the stack trace */
+  distDir: string;
+  /** How many source lines to include around each original location, defaults to 5 */
+  contextLines?: number;
+}
+/** A stack frame, with the 1-based line and column printed by the browser */
+export interface StackFrame {
+  functionName: string | null;
+  file: string;
+  line: number;
+  column: number;
+}
+export interface OriginalFrame {
+  source: string;
+  line: number;
+  column: number;
+  functionName: string | null;
+  preContext: Array<string>;
+  contextLine: string | null;
+  postContext: Array<string>;
+}
+export interface SymbolicatedFrame {
+  generated: StackFrame;
+  /** Null when the bundle or its source map could not be found */
+  original: OriginalFrame | null;
+}
+export declare function symbolicateStackTrace(
+  stack: string,
+  options: SymbolicateStackTraceOptions,
+): Array<SymbolicatedFrame>;
+export declare function generateDebugId(contents: string): string;
 
 export declare function atlaspackNapiPackage(
   atlaspackNapi: AtlaspackNapi,
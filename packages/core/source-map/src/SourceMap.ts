@@ -447,6 +447,22 @@ export default class SourceMap {
     this.sourceMapInstance.offsetColumns(line - 1, column, columnOffset);
   }
 
+  /**
+   * Set the debug ID that links this sourcemap to its bundle
+   *
+   * @param debugId the debug ID, which is also written to the bundle as a `//# debugId=` comment
+   */
+  setDebugId(debugId: string | null): void {
+    this.sourceMapInstance.setDebugId(debugId);
+  }
+
+  /**
+   * Get the debug ID that links this sourcemap to its bundle
+   */
+  getDebugId(): string | null {
+    return this.sourceMapInstance.getDebugId();
+  }
+
   /**
    * Returns a buffer that represents this sourcemap, used for caching
    */
@@ -25,6 +25,8 @@ export interface VLQMap {
   version?: number;
   file?: string;
   sourceRoot?: string;
+  debugId?: string;
+  ignoreList?: readonly number[];
 }
 
 export interface SourceMapStringifyOptions {
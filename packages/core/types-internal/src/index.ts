@@ -145,6 +145,8 @@ export type TargetSourceMapOptions = {
   readonly sourceRoot?: string;
   readonly inline?: boolean;
   readonly inlineSources?: boolean;
+  /** Globs of the sources to hide from stack traces, which defaults to sources in node_modules */
+  readonly ignoreList?: Array<string>;
 };
 
 /**
@@ -2217,6 +2219,35 @@ export type NativeCacheStats = {
   bailouts: number;
   errors: number;
   validations: number;
+  /** Validations where the cached value did not match the computed result */
+  mismatches: number;
+  /** Per-tier stats when a remote cache is configured, such as `local` and `remote` */
+  tiers: Array<NativeCacheTierStats>;
+  /** Why each cache miss happened, when `ATLASPACK_EXPLAIN_CACHE_MISSES=true` */
+  missExplanations: Array<NativeCacheMissExplanation>;
+};
+
+export type NativeCacheTierStats = {
+  name: string;
+  hits: number;
+  misses: number;
+  errors: number;
+  writes: number;
+  skipped: number;
+};
+
+export type NativeCacheMissExplanation = {
+  label: string;
+  reason:
+    | 'NewLabel'
+    | 'Evicted'
+    | {Changed: Array<NativeCacheKeyComponentChange>};
+};
+
+export type NativeCacheKeyComponentChange = {
+  name: string;
+  previous: string | null;
+  current: string | null;
 };
 
 /**
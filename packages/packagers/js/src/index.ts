@@ -9,7 +9,7 @@ import {
   debugTools,
 } from '@atlaspack/utils';
 import {encodeJSONKeyComponent} from '@atlaspack/diagnostic';
-import {hashString} from '@atlaspack/rust';
+import {generateDebugId, hashString} from '@atlaspack/rust';
 import nullthrows from 'nullthrows';
 import {DevPackager} from './DevPackager';
 import {
@@ -137,7 +137,8 @@ export default new Packager({
       }
     }
 
-    contents += '\n' + (await getSourceMapSuffix(getSourceMapReference, map));
+    contents +=
+      '\n' + (await getSourceMapSuffix(getSourceMapReference, contents, map));
 
     // For library builds, we need to replace URL references with their final resolved paths.
     // For non-library builds, this is handled in the JS runtime.
@@ -175,11 +176,28 @@ async function getSourceMapSuffix(
   getSourceMapReference: (
     arg1?: SourceMap | null | undefined,
   ) => Async<string | null | undefined>,
+  contents: string,
   map?: SourceMap | null,
 ): Promise<string> {
+  if (map == null) {
+    return '';
+  }
+
+  // The debug ID is set before the reference is created, as inline source
+  // maps are serialised into the reference
+  let debugId = generateDebugId(contents);
+  map.setDebugId(debugId);
+
   let sourcemapReference = await getSourceMapReference(map);
   if (sourcemapReference != null) {
-    return '//# sourceMappingURL=' + sourcemapReference + '\n';
+    return (
+      '//# debugId=' +
+      debugId +
+      '\n' +
+      '//# sourceMappingURL=' +
+      sourcemapReference +
+      '\n'
+    );
   } else {
     return '';
   }
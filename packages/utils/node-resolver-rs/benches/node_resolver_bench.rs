@@ -19,6 +19,7 @@ use std::sync::Arc;
 use criterion::{Criterion, criterion_group, criterion_main};
 use parking_lot::RwLock;
 
+use atlaspack_filesystem::DirEntry;
 use atlaspack_filesystem::FileSystem;
 use atlaspack_filesystem::os_file_system::OsFileSystem;
 use atlaspack_resolver::{Cache, CacheCow, Resolver, SpecifierType};
@@ -119,7 +120,7 @@ impl FileSystem for PreloadingFileSystem {
     todo!()
   }
 
-  fn read_dir(&self, _path: &Path) -> std::io::Result<std::fs::ReadDir> {
+  fn read_dir(&self, _path: &Path) -> std::io::Result<Vec<DirEntry>> {
     todo!("PreloadingFileSystem::read_dir")
   }
 
@@ -1,4 +1,3 @@
-use anyhow::anyhow;
 use rayon::prelude::*;
 use std::borrow::Cow;
 use std::collections::HashMap;
@@ -9,7 +8,7 @@ use std::path::PathBuf;
 use std::sync::Arc;
 
 use atlaspack_core::types::File;
-use atlaspack_filesystem::{FileSystemRealPathCache, FileSystemRef};
+use atlaspack_filesystem::{FileSystemRealPathCache, FileSystemRef, FileType};
 use atlaspack_shared_map::SharedHashMap;
 
 use crate::ResolverError;
@@ -286,37 +285,37 @@ fn find_package_json_files(fs: FileSystemRef, base_path: &Path) -> anyhow::Resul
   });
 
   let entries = fs.read_dir(base_path)?;
-  let entries: Vec<_> = entries.into_iter().collect();
 
   let packages: Vec<Vec<PathBuf>> = entries
     .par_iter()
     .map(|entry| {
-      match entry {
-        Ok(entry) => {
-          let path = entry.path();
-          if path.is_dir() && (should_traverse || path.ends_with("node_modules")) {
-            // Recursively attempt to find package.json files and propagate errors
-            return find_package_json_files(fs.clone(), &path);
-          }
-
-          if path
-            .file_name()
-            .is_some_and(|file_name| file_name == "package.json")
-            &&
-          // Only match on paths that contain multiple node_modules directories
-          path
-              .components()
-              .filter(|component| component.as_os_str().to_string_lossy() == "node_modules")
-              .count()
-              > 1
-          {
-            return Ok(vec![path]);
-          }
-
-          Ok(Vec::new())
-        }
-        Err(error) => Err(anyhow!("Failure reading entry {}", error)),
+      let path = &entry.path;
+      let is_dir = match entry.file_type {
+        FileType::Directory => true,
+        FileType::Symlink => fs.is_dir(path),
+        FileType::File => false,
+      };
+
+      if is_dir && (should_traverse || path.ends_with("node_modules")) {
+        // Recursively attempt to find package.json files and propagate errors
+        return find_package_json_files(fs.clone(), path);
       }
+
+      if path
+        .file_name()
+        .is_some_and(|file_name| file_name == "package.json")
+        &&
+      // Only match on paths that contain multiple node_modules directories
+      path
+          .components()
+          .filter(|component| component.as_os_str().to_string_lossy() == "node_modules")
+          .count()
+          > 1
+      {
+        return Ok(vec![path.clone()]);
+      }
+
+      Ok(Vec::new())
     })
     .collect::<anyhow::Result<Vec<Vec<PathBuf>>>>()?;
 